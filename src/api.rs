@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use lambda_runtime::{Error, LambdaEvent};
+use serde_json::{json, Value};
+use tracing::{error, info, warn};
+
+use crate::daysmart::DaySmart;
+use crate::handler::{self, Request};
+use crate::interactions::{self, HttpRequest};
+use crate::opponents::BenchappNames;
+use crate::preview;
+
+/// Days of games the JSON listing covers when the request sets no `preview_horizon_days`.
+pub const DEFAULT_HORIZON_DAYS: i64 = 30;
+
+/// What a GET asks for, picked from its `Accept` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Upcoming games, as in `Response::preview`.
+    Json,
+    /// The BenchApp CSV, as attached to reminders.
+    Csv,
+}
+
+impl Format {
+    /// The first acceptable type in `accept`, honouring q-values; JSON when the header is missing
+    /// or accepts anything. None when neither format is acceptable.
+    pub fn negotiate(accept: Option<&str>) -> Option<Self> {
+        let Some(accept) = accept.filter(|a| !a.trim().is_empty()) else {
+            return Some(Format::Json);
+        };
+        let mut ranges: Vec<(f32, &str)> = accept
+            .split(',')
+            .map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let media = parts.next().unwrap_or_default();
+                let q = parts.find_map(|p| p.strip_prefix("q=")).and_then(|q| q.parse().ok()).unwrap_or(1.0);
+                (q, media)
+            })
+            .filter(|(q, _)| *q > 0.0)
+            .collect();
+        // Stable, so equally weighted ranges keep the client's order
+        ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+        ranges.iter().find_map(|(_, media)| match media.to_ascii_lowercase().as_str() {
+            "application/json" | "application/*" | "*/*" => Some(Format::Json),
+            "text/csv" | "text/*" => Some(Format::Csv),
+            _ => None,
+        })
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::Csv => "text/csv; charset=utf-8",
+        }
+    }
+}
+
+/// The response body for `format`: the games within the request's preview horizon, or the
+/// BenchApp CSV covering `benchapp_months_ahead`.
+pub fn render(format: Format, day_smart: &DaySmart, request: &Request, names: &BenchappNames, now: DateTime<Utc>) -> String {
+    match format {
+        Format::Json => {
+            let horizon = request.preview_horizon_days.unwrap_or(DEFAULT_HORIZON_DAYS);
+            json!({ "games": preview::games_ahead(day_smart, horizon, now) }).to_string()
+        }
+        Format::Csv => day_smart.to_benchapp_csv_with(now, request.benchapp_months_ahead, names),
+    }
+}
+
+fn response(status: u16, content_type: &str, body: String) -> Value {
+    let mut headers = json!({ "content-type": content_type });
+    if content_type.starts_with("text/csv") {
+        headers["content-disposition"] = json!("attachment; filename=\"games.csv\"");
+    }
+    json!({ "statusCode": status, "headers": headers, "body": body })
+}
+
+fn error_response(status: u16, message: &str) -> Value {
+    response(status, "application/json", json!({ "error": message }).to_string())
+}
+
+/// Entry point for the schedule API (`HANDLER_MODE=api`): a GET returns the upcoming games as
+/// JSON or the BenchApp CSV, whichever `Accept` prefers. No webhook is posted to.
+pub async fn handle(event: LambdaEvent<Value>) -> Result<Value, Error> {
+    let request: HttpRequest = serde_json::from_value(event.payload).map_err(|e| format!("Not an HTTP event: {}", e))?;
+    if request.method() != "GET" {
+        warn!(method = %request.method(), "Rejected schedule API request");
+        let mut refused = error_response(405, "only GET is supported");
+        refused["headers"]["allow"] = json!("GET");
+        return Ok(refused);
+    }
+    let Some(format) = Format::negotiate(request.header("accept")) else {
+        return Ok(error_response(406, "acceptable types are application/json and text/csv"));
+    };
+    let config = match handler::load_valid_request(Value::Null).await {
+        Ok(config) => config,
+        Err(e) => {
+            error!(error = %e, "Failed to load the request for the schedule API");
+            return Ok(error_response(500, "schedule lookup is not configured"));
+        }
+    };
+    let day_smart = match interactions::fetch_daysmart(&config).await {
+        Ok(day_smart) => day_smart,
+        Err(e) => {
+            error!(error = %e, "DaySmart fetch failed for the schedule API");
+            return Ok(error_response(502, "DaySmart is unavailable"));
+        }
+    };
+    let names = match format {
+        Format::Csv => handler::benchapp_names(config.benchapp_names.as_ref()).await,
+        Format::Json => BenchappNames::default(),
+    };
+    info!(?format, "Serving schedule");
+    Ok(response(200, format.content_type(), render(format, &day_smart, &config, &names, Utc::now())))
+}
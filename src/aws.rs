@@ -0,0 +1,22 @@
+use aws_config::SdkConfig;
+use tokio::sync::OnceCell;
+
+// Loaded once per execution environment and reused across warm invocations.
+static SDK_CONFIG: OnceCell<SdkConfig> = OnceCell::const_new();
+
+/// Shared AWS SDK configuration (region and credentials from the Lambda environment).
+pub async fn sdk_config() -> &'static SdkConfig {
+    SDK_CONFIG
+        .get_or_init(|| async { aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await })
+        .await
+}
+
+/// S3 client built from the shared SDK configuration.
+pub async fn s3_client() -> aws_sdk_s3::Client {
+    aws_sdk_s3::Client::new(sdk_config().await)
+}
+
+/// DynamoDB client built from the shared SDK configuration.
+pub async fn dynamodb_client() -> aws_sdk_dynamodb::Client {
+    aws_sdk_dynamodb::Client::new(sdk_config().await)
+}
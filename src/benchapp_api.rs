@@ -0,0 +1,304 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, instrument};
+
+use crate::daysmart::DaySmart;
+use crate::http::{Capture, HttpPolicy};
+use crate::model::game::GameInfo;
+use crate::opponents::BenchappNames;
+use crate::state::StateStore;
+
+/// BenchApp team to keep in sync with DaySmart (benchapp_api workflow).
+/// BenchApp does not publish an API, so there is no default endpoint: `base_url` must serve the
+/// team schedule as `GET/POST {base_url}/teams/{id}/games` and `PUT/DELETE .../games/{game id}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchappConfig {
+    pub base_url: String,
+    pub team_id: String,
+    /// Sent as a bearer token.
+    pub api_token: String,
+    /// Team synced instead of `team_id` in test mode; without it test runs don't sync.
+    #[serde(default)]
+    pub test_team_id: Option<String>,
+}
+
+impl BenchappConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match reqwest::Url::parse(&self.base_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => return Err(format!("benchapp.base_url must be an http(s) URL (got {:?})", self.base_url)),
+        }
+        if self.team_id.trim().is_empty() {
+            return Err("benchapp.team_id must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A game as BenchApp stores it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchappGame {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub date: NaiveDate,
+    /// Local start time, "HH:MM".
+    pub time: String,
+    pub opponent: String,
+    pub home: bool,
+    #[serde(default)]
+    pub location: String,
+    #[serde(default)]
+    pub notes: String,
+    /// "REGULAR" or "PLAYOFF", as in the BenchApp CSV.
+    #[serde(default)]
+    pub game_type: String,
+    /// The DaySmart event the game was made from; not sent to BenchApp.
+    #[serde(skip)]
+    pub daysmart_id: Option<i64>,
+}
+
+impl BenchappGame {
+    /// Games not linked to a DaySmart event yet are matched by date and opponent.
+    fn key(&self) -> (NaiveDate, String) {
+        (self.date, self.opponent.trim().to_lowercase())
+    }
+
+    fn same_details(&self, other: &BenchappGame) -> bool {
+        BenchappGame { id: None, daysmart_id: None, ..self.clone() } == BenchappGame { id: None, daysmart_id: None, ..other.clone() }
+    }
+}
+
+/// BenchApp game ids by the DaySmart event id they were made from, so a game keeps its BenchApp
+/// record when its date or opponent changes.
+pub type Links = BTreeMap<String, String>;
+
+fn links_key(benchapp_team_id: &str) -> String {
+    format!("benchapp_links/{}", benchapp_team_id)
+}
+
+/// The links kept for a BenchApp team.
+pub async fn links(store: &StateStore, benchapp_team_id: &str) -> Result<Links, String> {
+    Ok(store.get(&links_key(benchapp_team_id)).await?.unwrap_or_default())
+}
+
+pub async fn save_links(store: &StateStore, benchapp_team_id: &str, links: &Links) -> Result<(), String> {
+    store.put(&links_key(benchapp_team_id), links).await
+}
+
+/// What has to change in BenchApp.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncAction {
+    Create(BenchappGame),
+    Update { id: String, game: BenchappGame },
+    /// A game the sync made whose DaySmart event is gone.
+    Delete { id: String },
+}
+
+/// The actions bringing BenchApp in line, and the links for the games it already has.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    pub actions: Vec<SyncAction>,
+    pub unchanged: usize,
+    pub links: Links,
+}
+
+/// Counts reported after a sync.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+}
+
+/// The DaySmart games in BenchApp's shape, times in the DaySmart display timezone.
+pub fn games_from_daysmart(day_smart: &DaySmart, games: &[GameInfo]) -> Vec<BenchappGame> {
+    games_from_daysmart_with(day_smart, games, &BenchappNames::default())
+}
+
+/// As [`games_from_daysmart`], with opponents spelled the way BenchApp already has them, so they
+/// match BenchApp's opponent records instead of creating new ones.
+pub fn games_from_daysmart_with(day_smart: &DaySmart, games: &[GameInfo], names: &BenchappNames) -> Vec<BenchappGame> {
+    games
+        .iter()
+        .map(|game| {
+            let (opponent, jersey) = day_smart.matchup(game);
+            let opponent = opponent.name().map_or_else(|| opponent.to_string(), |n| names.rename(n).to_string());
+            let local = game.dt.with_timezone(&day_smart.timezone());
+            let mut notes = format!("{} Jerseys", jersey);
+            if let Some(line) = day_smart.locker_room(game).line() {
+                notes.push_str(&format!("; {}", line));
+            }
+            BenchappGame {
+                id: None,
+                date: local.date_naive(),
+                time: local.format("%H:%M").to_string(),
+                opponent,
+                home: day_smart.team_id().is_some() && day_smart.team_id() == game.h_id,
+                location: day_smart.rink_name(game).to_string(),
+                notes,
+                game_type: if game.is_playoff { "PLAYOFF" } else { "REGULAR" }.to_string(),
+                daysmart_id: Some(game.id),
+            }
+        })
+        .collect()
+}
+
+/// Creates for games BenchApp doesn't have, updates for games whose details differ, and deletes
+/// for games from `today` on that the sync linked to a DaySmart event no longer `wanted`.
+/// A wanted game is found through its link, else by date and opponent among unlinked games.
+/// Unlinked BenchApp games without a DaySmart counterpart are left alone; they may have been
+/// added by hand.
+pub fn plan(existing: &[BenchappGame], wanted: &[BenchappGame], links: &Links, today: NaiveDate) -> Plan {
+    let by_id: HashMap<&str, &BenchappGame> = existing.iter().filter_map(|g| g.id.as_deref().map(|id| (id, g))).collect();
+    let linked: HashSet<&str> = links.values().map(String::as_str).collect();
+    let by_key: HashMap<(NaiveDate, String), &BenchappGame> =
+        existing.iter().filter(|g| g.id.as_deref().is_none_or(|id| !linked.contains(id))).map(|g| (g.key(), g)).collect();
+    let mut plan = Plan::default();
+    for game in wanted {
+        let daysmart_id = game.daysmart_id.map(|id| id.to_string());
+        let current = daysmart_id
+            .as_ref()
+            .and_then(|id| links.get(id))
+            .and_then(|id| by_id.get(id.as_str()))
+            .or_else(|| by_key.get(&game.key()));
+        let Some(current) = current else {
+            plan.actions.push(SyncAction::Create(game.clone()));
+            continue;
+        };
+        if let (Some(daysmart_id), Some(id)) = (daysmart_id, &current.id) {
+            plan.links.insert(daysmart_id, id.clone());
+        }
+        match &current.id {
+            Some(id) if !current.same_details(game) => plan.actions.push(SyncAction::Update { id: id.clone(), game: game.clone() }),
+            // Unchanged, or BenchApp's copy can't be addressed without an id
+            _ => plan.unchanged += 1,
+        }
+    }
+    let wanted_ids: HashSet<String> = wanted.iter().filter_map(|g| g.daysmart_id.map(|id| id.to_string())).collect();
+    for (_, id) in links.iter().filter(|(daysmart_id, _)| !wanted_ids.contains(*daysmart_id)) {
+        // Past games stay as they were played
+        if by_id.get(id.as_str()).is_some_and(|game| game.date >= today) {
+            plan.actions.push(SyncAction::Delete { id: id.clone() });
+        }
+    }
+    plan
+}
+
+/// BenchApp REST client for one team's schedule.
+pub struct BenchappClient {
+    base_url: String,
+    team_id: String,
+    api_token: String,
+    client: reqwest::Client,
+}
+
+impl BenchappClient {
+    /// Client for the configured team, or the test team in test mode (None when there is none).
+    pub fn from_config(config: &BenchappConfig, test_mode: bool, policy: &HttpPolicy) -> Result<Option<Self>, String> {
+        let team_id = if test_mode { config.test_team_id.clone() } else { Some(config.team_id.clone()) };
+        let Some(team_id) = team_id else {
+            return Ok(None);
+        };
+        config.validate()?;
+        Ok(Some(Self {
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            team_id,
+            api_token: config.api_token.clone(),
+            client: policy.client()?,
+        }))
+    }
+
+    /// The BenchApp team this client syncs.
+    pub fn team_id(&self) -> &str {
+        &self.team_id
+    }
+
+    fn games_url(&self) -> String {
+        format!("{}/teams/{}/games", self.base_url, self.team_id)
+    }
+
+    async fn send(&self, method: &'static str, url: &str, body: Option<&BenchappGame>) -> Result<String, String> {
+        let request = match method {
+            "POST" => self.client.post(url),
+            "PUT" => self.client.put(url),
+            "DELETE" => self.client.delete(url),
+            _ => self.client.get(url),
+        };
+        let request = match body {
+            Some(game) => request.json(game),
+            None => request,
+        };
+        let capture = Capture::start(method, url);
+        let resp = request.bearer_auth(&self.api_token).send().await.map_err(|e| {
+            capture.failed(&e);
+            format!("BenchApp request failed: {}", e)
+        })?;
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        capture.finish(status.as_u16(), Some(&text));
+        if status.is_success() {
+            Ok(text)
+        } else {
+            Err(format!("BenchApp {} {}: HTTP {}: {}", method, url, status.as_u16(), text))
+        }
+    }
+
+    /// Games currently on the team's BenchApp schedule.
+    pub async fn list_games(&self) -> Result<Vec<BenchappGame>, String> {
+        let body = self.send("GET", &self.games_url(), None).await?;
+        serde_json::from_str(&body).map_err(|e| format!("Failed to parse BenchApp games: {}", e))
+    }
+
+    /// Create `game`; the id BenchApp gave it, when its answer has one.
+    pub async fn create_game(&self, game: &BenchappGame) -> Result<Option<String>, String> {
+        let body = self.send("POST", &self.games_url(), Some(game)).await?;
+        Ok(serde_json::from_str::<BenchappGame>(&body).ok().and_then(|created| created.id))
+    }
+
+    pub async fn update_game(&self, id: &str, game: &BenchappGame) -> Result<(), String> {
+        self.send("PUT", &self.game_url(id), Some(game)).await.map(|_| ())
+    }
+
+    pub async fn delete_game(&self, id: &str) -> Result<(), String> {
+        self.send("DELETE", &self.game_url(id), None).await.map(|_| ())
+    }
+
+    fn game_url(&self, id: &str) -> String {
+        format!("{}/{}", self.games_url(), crate::links::percent_encode(id))
+    }
+
+    /// Bring the BenchApp schedule in line with `wanted`, updating `links` to the games it now
+    /// has. Every action is attempted; failures are combined, and a failed delete keeps its link
+    /// so the next run tries again.
+    #[instrument(level = "info", skip(self, wanted, links), fields(team_id = %self.team_id, games = wanted.len()))]
+    pub async fn sync(&self, wanted: &[BenchappGame], links: &mut Links, today: NaiveDate) -> Result<SyncSummary, String> {
+        let existing = self.list_games().await?;
+        let plan = plan(&existing, wanted, links, today);
+        let previous = std::mem::replace(links, plan.links);
+        let mut summary = SyncSummary { unchanged: plan.unchanged, ..Default::default() };
+        let mut errors = Vec::new();
+        for action in plan.actions {
+            let result = match &action {
+                SyncAction::Create(game) => self.create_game(game).await.map(|id| {
+                    if let (Some(daysmart_id), Some(id)) = (game.daysmart_id, id) {
+                        links.insert(daysmart_id.to_string(), id);
+                    }
+                    summary.created += 1;
+                }),
+                SyncAction::Update { id, game } => self.update_game(id, game).await.map(|()| summary.updated += 1),
+                SyncAction::Delete { id } => self.delete_game(id).await.map(|()| summary.deleted += 1).inspect_err(|_| {
+                    links.extend(previous.iter().filter(|(_, linked)| *linked == id).map(|(k, v)| (k.clone(), v.clone())));
+                }),
+            };
+            if let Err(e) = result {
+                error!(error = %e, "BenchApp sync action failed");
+                errors.push(e);
+            }
+        }
+        info!(created = summary.created, updated = summary.updated, deleted = summary.deleted, unchanged = summary.unchanged, "Synced BenchApp schedule");
+        if errors.is_empty() { Ok(summary) } else { Err(errors.join("; ")) }
+    }
+}
@@ -0,0 +1,21 @@
+use chrono::{TimeZone, Utc};
+
+use crate::daysmart::DaySmart;
+
+/// DaySmart team document bundled into the binary, so the canary has a game to render even
+/// when the real schedule is empty.
+const SAMPLE_DOCUMENT: &str = include_str!("../tests/sample_response.json");
+
+/// Render the canary post from the bundled document: a reminder for its first game, through the
+/// same formatting as real reminders, plus the BenchApp CSV used as attachment.
+pub fn render(timezone: chrono_tz::Tz) -> Result<(String, String), String> {
+    let day_smart = DaySmart::from_json(SAMPLE_DOCUMENT)?.with_timezone(timezone);
+    // The bundled games are in September 2025; look from just before them
+    let as_of = Utc.with_ymd_and_hms(2025, 9, 1, 0, 0, 0).unwrap();
+    let reminder = day_smart
+        .get_next_game_message(60, as_of)
+        .ok_or_else(|| "Bundled sample has no games to render".to_string())?;
+    let csv = day_smart.to_benchapp_csv(as_of, 2);
+    let message = format!(":bird: Canary: reminder rendering and posting work. Sample reminder:\n\n{}", reminder);
+    Ok((message, csv))
+}
@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::daysmart::DaySmart;
+use crate::destination::Publisher;
+use crate::display::LockerRoom;
+use crate::model::game::{GameInfo, GameStatus};
+use crate::state::StateStore;
+
+/// The parts of a game players plan around.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub start: DateTime<Utc>,
+    pub rink: String,
+    #[serde(default)]
+    pub locker_room: Option<String>,
+    /// Opponent as shown, which may be a bracket placeholder ("Winner of Game 3").
+    #[serde(default)]
+    pub opponent: String,
+    /// Set while the opponent is not decided; older snapshots without it count as decided.
+    #[serde(default)]
+    pub opponent_tbd: bool,
+}
+
+impl GameSnapshot {
+    /// Time, rink and locker room: what a schedule change alert reports.
+    fn schedule(&self) -> (DateTime<Utc>, &str, Option<&str>) {
+        (self.start, &self.rink, self.locker_room.as_deref())
+    }
+}
+
+/// Last-seen snapshot per DaySmart game id.
+pub type ScheduleSnapshot = BTreeMap<i64, GameSnapshot>;
+
+/// A game whose opponent was decided since the previous run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpponentDecided {
+    pub game_id: i64,
+    /// The placeholder shown before, e.g. "Winner of Game 3" or "TBD".
+    pub placeholder: String,
+    pub game: GameSnapshot,
+}
+
+/// A game whose time, rink or locker room differs from the previous run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleChange {
+    pub game_id: i64,
+    pub before: GameSnapshot,
+    pub after: GameSnapshot,
+}
+
+fn state_key(team_id: &str) -> String {
+    format!("schedule_snapshot/{}", team_id)
+}
+
+/// Snapshot of the given games as DaySmart currently lists them.
+pub fn snapshot(day_smart: &DaySmart, games: &[GameInfo]) -> ScheduleSnapshot {
+    games
+        .iter()
+        .map(|game| {
+            let (opponent, _) = day_smart.matchup(game);
+            let snapshot = GameSnapshot {
+                start: game.dt,
+                rink: day_smart.rink_name(game).to_string(),
+                locker_room: day_smart.locker_room(game).name().map(str::to_string),
+                opponent: opponent.to_string(),
+                opponent_tbd: !opponent.is_decided(game.is_playoff),
+            };
+            (game.id, snapshot)
+        })
+        .collect()
+}
+
+/// Games present in both snapshots whose time, rink or locker room changed, in game id order.
+pub fn diff(previous: &ScheduleSnapshot, current: &ScheduleSnapshot) -> Vec<ScheduleChange> {
+    current
+        .iter()
+        .filter_map(|(id, after)| {
+            let before = previous.get(id)?;
+            (before.schedule() != after.schedule())
+                .then(|| ScheduleChange { game_id: *id, before: before.clone(), after: after.clone() })
+        })
+        .collect()
+}
+
+/// Games whose opponent was undecided in `previous` and is known in `current`, in game id order.
+pub fn opponents_decided(previous: &ScheduleSnapshot, current: &ScheduleSnapshot) -> Vec<OpponentDecided> {
+    current
+        .iter()
+        .filter_map(|(id, after)| {
+            let before = previous.get(id)?;
+            (before.opponent_tbd && !after.opponent_tbd)
+                .then(|| OpponentDecided { game_id: *id, placeholder: before.opponent.clone(), game: after.clone() })
+        })
+        .collect()
+}
+
+/// Follow-up to a reminder that went out before the opponent was known.
+pub fn format_opponents_decided(decided: &[OpponentDecided], timezone: chrono_tz::Tz) -> String {
+    let mut out = String::from(":crossed_swords: **Opponent decided**");
+    for decided in decided {
+        let game = &decided.game;
+        out.push_str(&format!(
+            "\n:hockey: {} at {}: vs **{}** (was {})",
+            game.start.with_timezone(&timezone).format("%a %b %e, %-I:%M %p"),
+            game.rink,
+            game.opponent,
+            decided.placeholder
+        ));
+    }
+    out
+}
+
+/// A future game from the previous run that is now cancelled or gone from the schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cancellation {
+    pub game_id: i64,
+    pub game: GameSnapshot,
+    pub status: GameStatus,
+}
+
+/// Games still ahead of `now` in the previous snapshot that DaySmart no longer lists as scheduled.
+/// Games that merely moved outside the snapshot window are still listed and not reported.
+pub fn cancellations(
+    previous: &ScheduleSnapshot,
+    current: &ScheduleSnapshot,
+    now: DateTime<Utc>,
+    status: impl Fn(i64) -> GameStatus,
+) -> Vec<Cancellation> {
+    previous
+        .iter()
+        .filter(|(id, game)| game.start > now && !current.contains_key(id))
+        .filter_map(|(id, game)| {
+            let status = status(*id);
+            (status != GameStatus::Listed).then(|| Cancellation { game_id: *id, game: game.clone(), status })
+        })
+        .collect()
+}
+
+/// Notice listing games that were called off or removed.
+pub fn format_cancellations(cancellations: &[Cancellation], timezone: chrono_tz::Tz) -> String {
+    let mut out = String::from(":no_entry: **Game cancelled**");
+    for cancellation in cancellations {
+        let game = &cancellation.game;
+        let reason = match cancellation.status {
+            GameStatus::Cancelled => "cancelled",
+            _ => "removed from the schedule",
+        };
+        out.push_str(&format!(
+            "\n:hockey: {} at {}: {}",
+            game.start.with_timezone(&timezone).format("%a %b %e, %-I:%M %p"),
+            game.rink,
+            reason
+        ));
+    }
+    out
+}
+
+/// Highlighted alert listing what changed for each game, with `locker_room_placeholder` for a
+/// locker room that was or is no longer assigned.
+pub fn format_alert(changes: &[ScheduleChange], timezone: chrono_tz::Tz, locker_room_placeholder: &str) -> String {
+    let when = |dt: DateTime<Utc>| dt.with_timezone(&timezone).format("%a %b %e, %-I:%M %p").to_string();
+    let mut out = String::from(":warning: **Schedule change**");
+    for change in changes {
+        let (before, after) = (&change.before, &change.after);
+        out.push_str(&format!("\n:hockey: Game on {}:", when(before.start)));
+        if before.start != after.start {
+            out.push_str(&format!("\n    Time: {} → **{}**", when(before.start), when(after.start)));
+        }
+        if before.rink != after.rink {
+            out.push_str(&format!("\n    Rink: {} → **{}**", before.rink, after.rink));
+        }
+        if before.locker_room != after.locker_room {
+            out.push_str(&format!(
+                "\n    Locker room: {} → **{}**",
+                LockerRoom::new(before.locker_room.as_deref(), locker_room_placeholder),
+                LockerRoom::new(after.locker_room.as_deref(), locker_room_placeholder)
+            ));
+        }
+    }
+    out
+}
+
+/// Compare `current` against the stored snapshot, post an alert when anything changed, was
+/// called off or got its opponent decided, and store `current` for the next run. `status` tells whether a game missing from
+/// `current` is still on the schedule. The first run only records the snapshot. Returns a short
+/// summary when a notice was posted or failed.
+#[allow(clippy::too_many_arguments)]
+pub async fn notify_changes(
+    publisher: &Publisher,
+    store: &StateStore,
+    team_id: &str,
+    current: ScheduleSnapshot,
+    now: DateTime<Utc>,
+    status: impl Fn(i64) -> GameStatus,
+    timezone: chrono_tz::Tz,
+    locker_room_placeholder: &str,
+) -> Option<String> {
+    let key = state_key(team_id);
+    let previous: Option<ScheduleSnapshot> = match store.get(&key).await {
+        Ok(previous) => previous,
+        Err(e) => {
+            error!(error = %e, "Failed to load schedule snapshot");
+            return Some(format!("schedule check skipped: {}", e));
+        }
+    };
+    let Some(previous) = previous else {
+        if let Err(e) = store.put(&key, &current).await {
+            error!(error = %e, "Failed to save schedule snapshot");
+        }
+        return None;
+    };
+
+    let mut notices = Vec::new();
+    let changes = diff(&previous, &current);
+    if !changes.is_empty() {
+        info!(changed = changes.len(), "Schedule changed since last run");
+        notices.push(("schedule change alert", format_alert(&changes, timezone, locker_room_placeholder)));
+    }
+    let decided = opponents_decided(&previous, &current);
+    if !decided.is_empty() {
+        info!(decided = decided.len(), "Opponents decided since last run");
+        notices.push(("opponent update", format_opponents_decided(&decided, timezone)));
+    }
+    let cancelled = cancellations(&previous, &current, now, status);
+    if !cancelled.is_empty() {
+        info!(cancelled = cancelled.len(), "Games called off since last run");
+        notices.push(("cancellation notice", format_cancellations(&cancelled, timezone)));
+    }
+
+    let mut summaries = Vec::new();
+    for (what, message) in notices {
+        if let Err(e) = publisher.post(&message).await {
+            error!(error = %e, what, "Failed to post schedule notice");
+            // Keep the old snapshot so the notice is retried on the next run
+            summaries.push(format!("{} failed: {}", what, e));
+            return Some(summaries.join("; "));
+        }
+        summaries.push(format!("{} posted", what));
+    }
+    if let Err(e) = store.put(&key, &current).await {
+        error!(error = %e, "Failed to save schedule snapshot");
+    }
+    (!summaries.is_empty()).then(|| summaries.join("; "))
+}
@@ -0,0 +1,84 @@
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+/// Where an invocation reads the current time.
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock stopped at one instant, for tests.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Source of the throwaway identifiers outbound requests need, such as multipart boundaries.
+/// Nothing security-relevant comes from here; feed tokens use the OS generator directly.
+pub trait IdGenerator: Debug + Send + Sync {
+    fn next_id(&self) -> u64;
+}
+
+/// Random ids from the OS generator.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomIds;
+
+impl IdGenerator for RandomIds {
+    fn next_id(&self) -> u64 {
+        // These only have to be unlikely to appear in a request body, so a fixed fallback will do
+        getrandom::u64().unwrap_or(0xABCD_EF01)
+    }
+}
+
+/// Ids counting up from a seed, so generated requests are the same on every run.
+#[derive(Debug, Default)]
+pub struct SequentialIds(AtomicU64);
+
+impl SequentialIds {
+    pub fn starting_at(seed: u64) -> Self {
+        Self(AtomicU64::new(seed))
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn next_id(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+/// The clock and id generator an invocation uses. The Lambda entry point uses the system ones;
+/// tests pass [`Services::fixed`] so Responses and outbound requests can be snapshotted.
+#[derive(Debug, Clone)]
+pub struct Services {
+    pub clock: Arc<dyn Clock>,
+    pub ids: Arc<dyn IdGenerator>,
+}
+
+impl Default for Services {
+    fn default() -> Self {
+        Self { clock: Arc::new(SystemClock), ids: Arc::new(RandomIds) }
+    }
+}
+
+impl Services {
+    /// Time stopped at `now` and ids counting up from 1.
+    pub fn fixed(now: DateTime<Utc>) -> Self {
+        Self { clock: Arc::new(FixedClock(now)), ids: Arc::new(SequentialIds::starting_at(1)) }
+    }
+}
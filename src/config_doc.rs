@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use serde_json::Value;
+use tracing::info;
+
+use crate::secrets::{SecretRef, SecretsClient};
+
+/// Environment variable naming where the stored request document lives.
+pub const CONFIG_LOCATION_VAR: &str = "CONFIG_LOCATION";
+
+/// Where a stored request document is kept, for invocations (such as a plain EventBridge cron
+/// rule) that don't carry the request themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigLocation {
+    /// `s3://bucket/key`
+    S3 { bucket: String, key: String },
+    /// `ssm:/parameter/name`, read through the Parameters and Secrets Lambda Extension.
+    Parameter(String),
+    /// `file:///path/to/request.json`; for local runs and tests.
+    File(PathBuf),
+}
+
+impl ConfigLocation {
+    pub fn parse(location: &str) -> Result<Self, String> {
+        if let Some(rest) = location.strip_prefix("s3://") {
+            return match rest.split_once('/') {
+                Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => {
+                    Ok(ConfigLocation::S3 { bucket: bucket.to_string(), key: key.to_string() })
+                }
+                _ => Err(format!("{} must look like s3://bucket/key, got {:?}", CONFIG_LOCATION_VAR, location)),
+            };
+        }
+        if let Some(name) = location.strip_prefix("ssm:").filter(|name| !name.is_empty()) {
+            return Ok(ConfigLocation::Parameter(name.to_string()));
+        }
+        if let Some(path) = location.strip_prefix("file://").filter(|path| !path.is_empty()) {
+            return Ok(ConfigLocation::File(PathBuf::from(path)));
+        }
+        Err(format!("{} must start with s3://, ssm: or file://, got {:?}", CONFIG_LOCATION_VAR, location))
+    }
+
+    /// The location in `CONFIG_LOCATION`, if set.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        match std::env::var(CONFIG_LOCATION_VAR) {
+            Ok(location) if !location.trim().is_empty() => Self::parse(location.trim()).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Fetch and parse the stored document.
+    pub async fn load(&self) -> Result<Value, String> {
+        let text = match self {
+            ConfigLocation::S3 { bucket, key } => {
+                let out = crate::aws::s3_client()
+                    .await
+                    .get_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .send()
+                    .await
+                    .map_err(|e| format!("Failed to load config s3://{}/{}: {}", bucket, key, e))?;
+                let bytes = out.body.collect().await.map_err(|e| format!("Failed to read config s3://{}/{}: {}", bucket, key, e))?;
+                String::from_utf8(bytes.into_bytes().to_vec()).map_err(|e| format!("Config s3://{}/{} is not UTF-8: {}", bucket, key, e))?
+            }
+            ConfigLocation::Parameter(name) => SecretsClient::from_env().resolve(&SecretRef::Parameter(name.clone())).await?,
+            ConfigLocation::File(path) => {
+                tokio::fs::read_to_string(path).await.map_err(|e| format!("Failed to load config {}: {}", path.display(), e))?
+            }
+        };
+        info!(location = ?self, "Loaded stored request");
+        serde_json::from_str(&text).map_err(|e| format!("Stored request is not valid JSON: {}", e))
+    }
+}
+
+/// Whether the payload is an EventBridge event (e.g. from a scheduled rule) rather than a request.
+pub fn is_eventbridge_event(payload: &Value) -> bool {
+    payload.get("source").is_some_and(Value::is_string) && payload.get("detail-type").is_some_and(Value::is_string)
+}
+
+/// The stored document with the payload's own fields on top, so an invocation can still
+/// override single fields (e.g. `{"workflows": ["digest"]}`).
+pub fn overlay(document: Value, payload: Value) -> Result<Value, String> {
+    let Value::Object(mut fields) = document else {
+        return Err("Stored request must be a JSON object".to_string());
+    };
+    match payload {
+        Value::Object(overrides) => fields.extend(overrides),
+        Value::Null => {}
+        other => return Err(format!("Payload must be a JSON object, got {}", other)),
+    }
+    Ok(Value::Object(fields))
+}
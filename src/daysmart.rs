@@ -1,98 +1,427 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use tracing::{error, info, instrument, info_span};
+use tracing::{debug, error, info, instrument, info_span, warn, Instrument};
 
+use crate::display::{LockerRoom, Placeholders, RinkName, TeamName, DEFAULT_TEAM};
+use crate::email::DigestEntry;
+use crate::error::Error;
+use crate::explain::{ExplainedGame, GameExplanation, Resolved};
+use crate::facility::{FacilityHoursConfig, OutOfHoursGame};
+use crate::http::{self, HttpPolicy};
+use crate::http_cache::HttpCache;
+use crate::jersey::JerseyConfig;
+use crate::links;
 use crate::model;
-use crate::model::game::{GameInfo, GameCore};
+use crate::model::game::{GameInfo, GameCore, GameStatus};
+use crate::model::event::EventType;
+use crate::model::facility::Facility;
+use crate::model::league::{self as league_model, League, ProgramType, Season};
+use crate::opponents::BenchappNames;
+use crate::streak::GameResult;
+use crate::template::{self, ReminderVars};
+use crate::tournament::{self, TournamentConfig};
+
+/// Map of game event id -> (home_locker_res_id, away_locker_res_id)
+type LockerMap = HashMap<i64, (Option<i64>, Option<i64>)>;
+
+/// Map of game event id -> ids of the locker room events assigning its rooms
+type LockerEvents = HashMap<i64, Vec<i64>>;
+
+/// A locker room event (type L) with no `parent_event_id` linking it to its game.
+struct UnlinkedLocker {
+    id: i64,
+    res_id: i64,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+    /// Team the room is booked for, when the event names one.
+    team_id: Option<i64>,
+    /// Home or visitors' room, from `locker_room_type`.
+    home: Option<bool>,
+}
+
+/// Base URL of the DaySmart Dash JSON:API.
+pub(crate) const API_BASE: &str = "https://apps.daysmartrecreation.com/dash/jsonapi/api/v1";
+
+/// GET a DaySmart JSON:API URL and return the raw response body.
+pub(crate) async fn fetch_body(url: &str, policy: &HttpPolicy) -> Result<String, Error> {
+    // The failure is logged inside the span so a trace shows it failed
+    async {
+        http::get_text(url, policy).await.map_err(|e| {
+            error!(error = %e, url = %url, "DaySmart request failed");
+            Error::Network(format!("DaySmart request failed: {}", e))
+        })
+    }
+    .instrument(info_span!("daysmart_fetch"))
+    .await
+}
+
+/// Parse an event's start time into UTC, preferring `start_gmt` over the local `start`.
+pub(crate) fn parse_event_start(attributes: &model::event::EventAttributes) -> Option<chrono::DateTime<chrono::Utc>> {
+    let dt_str = raw_event_start(attributes)?;
+    let parsed = parse_timestamp(dt_str);
+    if parsed.is_none() {
+        warn!(value = %dt_str, "Unrecognized DaySmart timestamp");
+    }
+    parsed
+}
+
+/// Parse an event's end time, from the same field pair the start was read from so both are on
+/// the same clock (`end_gmt` with `start_gmt`, otherwise `end`).
+pub(crate) fn parse_event_end(attributes: &model::event::EventAttributes) -> Option<chrono::DateTime<chrono::Utc>> {
+    let raw = if attributes.start_gmt.is_some() { attributes.end_gmt.as_deref() } else { attributes.end.as_deref() };
+    raw.and_then(parse_timestamp)
+}
+
+/// The timestamp `parse_event_start` reads.
+fn raw_event_start(attributes: &model::event::EventAttributes) -> Option<&str> {
+    attributes.start_gmt.as_deref().or(attributes.start.as_deref())
+}
+
+/// Timestamp layouts tried after RFC 3339, in order. Values without an offset are taken as UTC.
+const TIMESTAMP_FORMATS: [(&str, bool); 3] = [
+    ("%Y-%m-%dT%H:%M:%S%.f%z", true),
+    ("%Y-%m-%dT%H:%M:%S%.f", false),
+    ("%Y-%m-%dT%H:%M", false),
+];
+
+/// Tolerant parser for DaySmart timestamps. Besides RFC 3339 it accepts a space instead of 'T'
+/// between date and time, optional fractional seconds, offsets without a colon, and values with
+/// no offset or no seconds.
+pub fn parse_timestamp(raw: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let trimmed = raw.trim();
+    let normalized = match trimmed.as_bytes().get(10) {
+        Some(b' ') => format!("{}T{}", &trimmed[..10], trimmed[11..].trim_start()),
+        _ => trimmed.to_string(),
+    };
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(&normalized) {
+        debug!(value = %raw, format = "rfc3339", "Parsed DaySmart timestamp");
+        return Some(dt.with_timezone(&chrono::Utc));
+    }
+    TIMESTAMP_FORMATS.iter().find_map(|&(format, has_offset)| {
+        let dt = if has_offset {
+            chrono::DateTime::parse_from_str(&normalized, format).ok()?.with_timezone(&chrono::Utc)
+        } else {
+            chrono::NaiveDateTime::parse_from_str(&normalized, format).ok()?.and_utc()
+        };
+        debug!(value = %raw, format, "Parsed DaySmart timestamp");
+        Some(dt)
+    })
+}
+
+/// How far ahead the BenchApp CSV looks when no window is configured.
+pub const DEFAULT_BENCHAPP_MONTHS: u32 = 4;
+
+/// Furthest any lookahead window may reach. A season fits, and day counts stay far from the
+/// range where `chrono::Duration::days` panics.
+pub const MAX_LOOKAHEAD_DAYS: i64 = 365;
+
+/// Longest BenchApp CSV window, matching [`MAX_LOOKAHEAD_DAYS`].
+pub const MAX_BENCHAPP_MONTHS: u32 = 12;
+
+/// Timezone used when none is configured (DaySmart's home market).
+pub const DEFAULT_TIMEZONE: chrono_tz::Tz = chrono_tz::America::Los_Angeles;
+
+/// Lookup maps extracted from a team document by `DaySmart::build_maps`.
+struct DocumentMaps {
+    team_names: HashMap<i64, String>,
+    resource_names: HashMap<i64, String>,
+    area_names: HashMap<i64, String>,
+    resource_facilities: HashMap<i64, i64>,
+    facilities: HashMap<i64, Facility>,
+    locker_map: LockerMap,
+    locker_events: LockerEvents,
+    game_map: HashMap<i64, GameCore>,
+    practice_map: HashMap<i64, GameCore>,
+    game_notes: HashMap<i64, Vec<String>>,
+    cancelled_games: HashSet<i64>,
+    league: Option<League>,
+    season: Option<Season>,
+    program_type: Option<ProgramType>,
+}
+
+/// How the request wants games shown: practices, Discord timestamps, arrival time and venue
+/// notes. Applied once to the fetched document, so every workflow shows games the same way.
+#[derive(Debug, Clone, Default)]
+pub struct View {
+    /// Remind about and export our team's practices next to its games. Tournament detection,
+    /// results and schedule changes still only look at games.
+    pub include_practices: bool,
+    /// Write reminder dates and times as Discord timestamp markup, so every reader sees the start
+    /// in their own timezone with a live countdown.
+    pub discord_timestamps: bool,
+    /// Add an "Arrive by" time this many minutes before the start to reminders.
+    pub arrive_minutes_early: Option<u32>,
+    /// The team's note for a venue (e.g. "Park in the north lot; rink door code 4521"), added to
+    /// reminders and BenchApp CSV notes of games there. Keys are arena, rink or facility names.
+    pub venue_notes: HashMap<String, String>,
+}
 
 /// Simple wrapper for the DaySmart API base URL used by this application.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DaySmart {
     // Store our team's id directly to avoid borrowing from the document
     our_team_id: Option<i64>,
     team_names: HashMap<i64, String>,
     resource_names: HashMap<i64, String>,
-    // Map of game event id -> (home_locker_res_id, away_locker_res_id)
-    locker_map: HashMap<i64, (Option<i64>, Option<i64>)>,
+    // Resource area (sheet) names by id
+    area_names: HashMap<i64, String>,
+    // Facility id of each resource, and the facilities by id
+    resource_facilities: HashMap<i64, i64>,
+    facilities: HashMap<i64, Facility>,
+    locker_map: LockerMap,
+    // Locker room event ids per game, for explaining where a locker room came from
+    locker_events: LockerEvents,
     // Map of game event id -> core game data (parsed time and ids)
     game_map: HashMap<i64, GameCore>,
+    // Our team's practices (events of other types listing our team); kept out of game_map
+    practice_map: HashMap<i64, GameCore>,
+    // Whether practices are announced and exported next to games
+    include_practices: bool,
+    // Write reminder dates and times as Discord timestamps instead of text
+    discord_timestamps: bool,
+    // Minutes before the start players should arrive, shown in reminders
+    arrive_minutes_early: Option<u32>,
+    // Team notes (parking, door codes) by arena, rink or facility name
+    venue_notes: HashMap<String, String>,
+    // League notes (event comments) per game, in DaySmart's order
+    game_notes: HashMap<i64, Vec<String>>,
+    // Our games flagged as cancelled or postponed; kept out of game_map
+    cancelled_games: HashSet<i64>,
+    // Our games listed while their rink is closed; kept out of game_map until DaySmart is fixed
+    out_of_hours: Vec<OutOfHoursGame>,
+    // League, season and program type from the document's includes
+    league: Option<League>,
+    season: Option<Season>,
+    program_type: Option<ProgramType>,
+    // Timezone used for all displayed dates and times
+    timezone: chrono_tz::Tz,
+    // Text shown for names DaySmart leaves out
+    placeholders: Placeholders,
+    // Which jersey we wear, home, away or against a given opponent
+    jerseys: JerseyConfig,
 }
 
 impl DaySmart {
     /// Construct a Daysmart instance for a specific team id and populate it with fetched data.
-    #[instrument(level = "info", skip(team_id))]
-    pub fn for_team(team_id: &str, company: &str) -> Result<Self, String> {
-        let daysmart_url = format!("https://apps.daysmartrecreation.com/dash/jsonapi/api/v1/teams/{}?cache[save]=false&include=events.eventType%2Cevents.homeTeam%2Cevents.visitingTeam%2Cevents.resource.facility%2Cevents.resourceArea%2Cevents.comments%2Cleague.playoffEvents.eventType%2Cleague.playoffEvents.homeTeam%2Cleague.playoffEvents.visitingTeam%2Cleague.playoffEvents.resource.facility%2Cleague.playoffEvents.resourceArea%2Cleague.playoffEvents.comments%2Cleague.programType%2Cproduct.locations%2CprogramType%2Cseason%2CskillLevel%2CageRange%2Csport&company={}", team_id, company);
-        let response_result = {
-            let _span = info_span!("daysmart_fetch", url = %daysmart_url).entered();
-            ureq::get(&daysmart_url).call()
-        };
-        match response_result {
-            Ok(response) => {
-                let mut body_reader = response.into_body();
-                match body_reader.read_to_string() {
-                    Ok(body) => match Self::deserialize_team_document(&body) {
-                        Ok(doc) => {
-                            let total_included = doc.included.len();
-                            let event_count = doc
-                                .included
-                                .iter()
-                                .filter(|i| matches!(i, model::team::Included::Event { .. }))
-                                .count();
-                            let our_team_id = doc.data.id.parse::<i64>().ok();
-                            let (team_names, resource_names, locker_map, game_map) = Self::build_maps(doc, our_team_id);
-                            let team_name_str: &str = our_team_id
-                                .and_then(|tid| team_names.get(&tid).map(|s| s.as_str()))
-                                .unwrap_or("Unknown Team");
-                            info!(team_name = %team_name_str, total_included, event_count, "Constructed DaySmart with TeamDocument");
-                            Ok(DaySmart { our_team_id, team_names, resource_names, locker_map, game_map })
-                        }
-                        Err(e) => {
-                            error!(error = %e, "Failed to deserialize into TeamDocument during construction");
-                            Err(format!("Failed to deserialize into TeamDocument: {}", e))
-                        }
-                    },
-                    Err(e) => {
-                        error!(error = %e, "Failed to read response body during construction");
-                        Err(format!("Failed to read response body: {}", e))
-                    }
-                }
+    #[instrument(level = "info", skip(team_id, policy))]
+    pub async fn for_team(team_id: &str, company: &str, policy: &HttpPolicy) -> Result<Self, Error> {
+        let body = fetch_body(&Self::team_url(team_id, company), policy).await?;
+        Self::from_team_body(&body)
+    }
+
+    /// [`DaySmart::for_team`], reusing the last document when DaySmart says it is unchanged.
+    #[instrument(level = "info", skip(team_id, policy, cache))]
+    pub async fn for_team_cached(team_id: &str, company: &str, policy: &HttpPolicy, cache: &HttpCache) -> Result<Self, Error> {
+        let url = Self::team_url(team_id, company);
+        async {
+            cache.fetch(&url, policy, Self::from_team_body).await.map_err(|e| {
+                error!(error = %e, url = %url, "DaySmart request failed");
+                Error::Network(format!("DaySmart request failed: {}", e))
+            })
+        }
+        .instrument(info_span!("daysmart_fetch"))
+        .await?
+    }
+
+    fn team_url(team_id: &str, company: &str) -> String {
+        format!("{}/teams/{}?cache[save]=false&include=events.eventType%2Cevents.homeTeam%2Cevents.visitingTeam%2Cevents.resource.facility%2Cevents.resourceArea%2Cevents.comments%2Cleague.playoffEvents.eventType%2Cleague.playoffEvents.homeTeam%2Cleague.playoffEvents.visitingTeam%2Cleague.playoffEvents.resource.facility%2Cleague.playoffEvents.resourceArea%2Cleague.playoffEvents.comments%2Cleague.programType%2Cproduct.locations%2CprogramType%2Cseason%2CskillLevel%2CageRange%2Csport&company={}", API_BASE, team_id, crate::links::percent_encode(company))
+    }
+
+    fn from_team_body(body: &str) -> Result<Self, Error> {
+        match Self::deserialize_team_document(body) {
+            Ok(doc) => {
+                let total_included = doc.included.len();
+                let event_count = doc
+                    .included
+                    .iter()
+                    .filter(|i| matches!(i, model::team::Included::Event { .. }))
+                    .count();
+                let our_team_id = doc.data.id.parse::<i64>().ok();
+                let day_smart = Self::from_document(doc, our_team_id);
+                let team_name_str = TeamName::new(our_team_id.and_then(|tid| day_smart.team_names.get(&tid).map(|s| s.as_str())), DEFAULT_TEAM);
+                info!(team_name = %team_name_str, total_included, event_count, "Constructed DaySmart with TeamDocument");
+                Ok(day_smart)
             }
             Err(e) => {
-                error!(error = %e, url = %daysmart_url, "Request failed during construction");
-                Err(format!("Request failed: {}", e))
+                error!(error = %e, "Failed to deserialize into TeamDocument during construction");
+                Err(Error::Parse(format!("Failed to deserialize into TeamDocument: {}", e)))
             }
         }
     }
 
+    /// Display dates and times in the given timezone instead of the default Pacific time.
+    pub fn with_timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Show these placeholders for names DaySmart leaves out.
+    pub fn with_placeholders(mut self, placeholders: Placeholders) -> Self {
+        self.placeholders = placeholders;
+        self
+    }
+
+    /// Pick jersey colors with these rules instead of light at home, dark away.
+    pub fn with_jerseys(mut self, jerseys: JerseyConfig) -> Self {
+        self.jerseys = jerseys;
+        self
+    }
+
+    /// Apply every setting of `view`.
+    pub fn with_view(mut self, view: View) -> Self {
+        self.include_practices = view.include_practices;
+        self.discord_timestamps = view.discord_timestamps;
+        self.arrive_minutes_early = view.arrive_minutes_early;
+        self.venue_notes = view.venue_notes;
+        self
+    }
+
+    /// Hold back games that start while their rink is closed, so an impossible time (a
+    /// recurring DaySmart data-entry slip) is reported to the captains instead of announced.
+    /// Uses the display timezone, so apply it after `with_timezone`.
+    pub fn with_facility_hours(mut self, config: &FacilityHoursConfig) -> Self {
+        let mut flagged: Vec<OutOfHoursGame> = self
+            .game_map
+            .iter()
+            .filter_map(|(gid, core)| {
+                let rink = self.resource_name(core.res_id);
+                let hours = config.hours_for(rink)?;
+                let local = core.dt.with_timezone(&self.timezone);
+                (!hours.allows(local.time())).then(|| OutOfHoursGame {
+                    game_id: *gid,
+                    start: local.format("%a %b %-d %-I:%M %p").to_string(),
+                    rink: RinkName::new(rink, &self.placeholders.rink).to_string(),
+                    hours: hours.clone(),
+                })
+            })
+            .collect();
+        flagged.sort_by_key(|game| game.game_id);
+        for game in &flagged {
+            warn!(game_id = game.game_id, rink = %game.rink, start = %game.start, "Game listed outside facility hours; not announcing it");
+            self.game_map.remove(&game.game_id);
+        }
+        self.out_of_hours = flagged;
+        self
+    }
+
+    /// Games held back by [`DaySmart::with_facility_hours`].
+    pub fn out_of_hours_games(&self) -> &[OutOfHoursGame] {
+        &self.out_of_hours
+    }
+
     /// Construct a DaySmart instance from a raw JSON response body (no network).
     #[allow(dead_code)]
-    pub fn from_json(body: &str) -> Result<Self, String> {
-        match Self::deserialize_team_document(body) {
-            Ok(doc) => {
-                let our_team_id = doc.data.id.parse::<i64>().ok();
-                let (team_names, resource_names, locker_map, game_map) = Self::build_maps(doc, our_team_id);
-                Ok(DaySmart { our_team_id, team_names, resource_names, locker_map, game_map })
-            }
-            Err(e) => Err(format!("Failed to deserialize into TeamDocument: {}", e)),
+    pub fn from_json(body: &str) -> Result<Self, Error> {
+        let doc = Self::deserialize_team_document(body).map_err(|e| Error::Parse(format!("Failed to deserialize into TeamDocument: {}", e)))?;
+        let our_team_id = doc.data.id.parse::<i64>().ok();
+        Ok(Self::from_document(doc, our_team_id))
+    }
+
+    /// Every game in a league, from one league document: events of all its teams plus the
+    /// playoff events. There is no "our" team; use [`DaySmart::team_view`] for one team's side.
+    #[instrument(level = "info", skip(policy))]
+    pub async fn for_league(league_id: i64, company: &str, policy: &HttpPolicy) -> Result<Self, Error> {
+        // Leagues relate to their teams and playoff events; the regular-season games hang off the teams
+        let url = format!("{}/leagues/{}?cache[save]=false&include=teams.events.eventType%2Cteams.events.homeTeam%2Cteams.events.visitingTeam%2Cteams.events.resource.facility%2Cteams.events.resourceArea%2CplayoffEvents.eventType%2CplayoffEvents.homeTeam%2CplayoffEvents.visitingTeam%2CplayoffEvents.resource.facility%2CplayoffEvents.resourceArea%2CprogramType%2Cseason&company={}", API_BASE, league_id, crate::links::percent_encode(company));
+        let body = fetch_body(&url, policy).await?;
+        let day_smart = Self::from_league_json(&body)?;
+        info!(games = day_smart.game_map.len(), teams = day_smart.league_teams().len(), "Constructed DaySmart for league");
+        Ok(day_smart)
+    }
+
+    /// Construct a league-wide instance from a raw league document (no network).
+    pub fn from_league_json(body: &str) -> Result<Self, Error> {
+        let doc = Self::deserialize_team_document(body).map_err(|e| Error::Parse(format!("Failed to deserialize league document: {}", e)))?;
+        Ok(Self::from_document(doc, None))
+    }
+
+    fn from_document(doc: model::team::TeamDocument, our_team_id: Option<i64>) -> Self {
+        let DocumentMaps { team_names, resource_names, area_names, resource_facilities, facilities, locker_map, locker_events, game_map, practice_map, game_notes, cancelled_games, league, season, program_type } =
+            Self::build_maps(doc, our_team_id);
+        DaySmart {
+            our_team_id,
+            team_names,
+            resource_names,
+            area_names,
+            resource_facilities,
+            facilities,
+            locker_map,
+            locker_events,
+            game_map,
+            practice_map,
+            include_practices: false,
+            discord_timestamps: false,
+            arrive_minutes_early: None,
+            venue_notes: HashMap::new(),
+            game_notes,
+            cancelled_games,
+            out_of_hours: Vec::new(),
+            league,
+            season,
+            program_type,
+            timezone: DEFAULT_TIMEZONE,
+            placeholders: Placeholders::default(),
+            jerseys: JerseyConfig::default(),
         }
     }
 
+    /// Teams playing in this document's games, by name.
+    pub fn league_teams(&self) -> Vec<(i64, TeamName<'_>)> {
+        let mut ids: Vec<i64> = self.game_map.values().flat_map(|core| [core.h_id, core.v_id]).flatten().collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let mut teams: Vec<(i64, TeamName)> = ids.into_iter().map(|id| (id, self.team(Some(id), DEFAULT_TEAM))).collect();
+        teams.sort_by(|a, b| a.1.as_str().cmp(b.1.as_str()).then(a.0.cmp(&b.0)));
+        teams
+    }
+
+    /// The document as `team_id` sees it: only its games, with it as our team.
+    pub fn team_view(&self, team_id: i64) -> DaySmart {
+        let plays = |core: &GameCore| core.h_id == Some(team_id) || core.v_id == Some(team_id);
+        let mut view = self.clone();
+        view.our_team_id = Some(team_id);
+        view.game_map.retain(|_, core| plays(core));
+        view.out_of_hours.retain(|game| self.game_map.get(&game.game_id).is_none_or(plays));
+        view
+    }
+
     /// Build lookup maps in a single pass: team names, resource names, locker room assignments, and game core data.
-    fn build_maps(doc: model::team::TeamDocument, our_team_id: Option<i64>) -> (
-        HashMap<i64, String>,                // team_names
-        HashMap<i64, String>,                // resource_names
-        HashMap<i64, (Option<i64>, Option<i64>)>, // locker_map: game_id -> (home_res_id, away_res_id)
-        HashMap<i64, GameCore>,              // game_map: game_id -> core
-    ) {
+    fn build_maps(doc: model::team::TeamDocument, our_team_id: Option<i64>) -> DocumentMaps {
         let mut team_names: HashMap<i64, String> = HashMap::new();
         let mut resource_names: HashMap<i64, String> = HashMap::new();
-        let mut locker_map: HashMap<i64, (Option<i64>, Option<i64>)> = HashMap::new();
+        let mut locker_map: LockerMap = HashMap::new();
+        let mut locker_events: LockerEvents = HashMap::new();
         let mut game_map: HashMap<i64, GameCore> = HashMap::new();
+        let mut practice_map: HashMap<i64, GameCore> = HashMap::new();
+        let mut cancelled_games: HashSet<i64> = HashSet::new();
+        let mut leagues: Vec<League> = Vec::new();
+        let mut seasons: Vec<Season> = Vec::new();
+        let mut program_type: Option<ProgramType> = None;
+        let mut playoff_event_ids: Vec<i64> = Vec::new();
+        let mut area_names: HashMap<i64, String> = HashMap::new();
+        let mut resource_facilities: HashMap<i64, i64> = HashMap::new();
+        let mut facilities: HashMap<i64, Facility> = HashMap::new();
+        let mut unlinked_lockers: Vec<UnlinkedLocker> = Vec::new();
+        let mut comments: HashMap<i64, (Option<i64>, String)> = HashMap::new();
+        let mut event_comments: Vec<(i64, i64)> = Vec::new();
+        let (team_league_id, team_season_id) = (doc.data.attributes.league_id, doc.data.attributes.season_id);
 
-        // Insert our own team name from root data (move, no clone)
-        if let Ok(tid) = doc.data.id.parse::<i64>() {
-            team_names.insert(tid, doc.data.attributes.name);
+        // Insert our own team name from root data (move, no clone); a league document's root is the league
+        match (doc.data.type_field.as_str(), doc.data.id.parse::<i64>()) {
+            ("leagues", Ok(id)) => {
+                playoff_event_ids.extend(Self::relationship_ids(doc.data.relationships.as_ref(), "playoffEvents"));
+                leagues.push(League {
+                    id,
+                    name: doc.data.attributes.name,
+                    season_id: doc.data.attributes.season_id,
+                    start_date: league_model::parse_date(doc.data.attributes.start_date.as_deref()),
+                    end_date: None,
+                });
+            }
+            (_, Ok(tid)) => {
+                team_names.insert(tid, doc.data.attributes.name);
+            }
+            _ => {}
         }
 
         for item in doc.included.into_iter() {
@@ -103,70 +432,243 @@ impl DaySmart {
                     }
                 }
                 model::team::Included::Resource { id, attributes, .. } => {
-                    if let Ok(rid) = id.parse::<i64>() {
-                        if let Some(name) = attributes.name {
-                            resource_names.insert(rid, name);
-                        }
+                    if let (Ok(rid), Some(facility_id)) = (id.parse::<i64>(), attributes.facility_id) {
+                        resource_facilities.insert(rid, facility_id);
+                    }
+                    if let (Ok(rid), Some(name)) = (id.parse::<i64>(), attributes.name) {
+                        resource_names.insert(rid, name);
+                    }
+                }
+                model::team::Included::ResourceArea { id, attributes, .. } => {
+                    if let (Ok(aid), Some(name)) = (id.parse::<i64>(), attributes.name) {
+                        area_names.insert(aid, name);
                     }
                 }
-                model::team::Included::Event { id, attributes, .. } => {
+                model::team::Included::Comment { id, attributes, .. } => {
+                    let text = attributes.comment.as_deref().map(str::trim).filter(|t| !t.is_empty());
+                    if let (Ok(cid), Some(text)) = (id.parse::<i64>(), text) {
+                        comments.insert(cid, (attributes.event_id, text.to_string()));
+                    }
+                }
+                model::team::Included::Facility { id, attributes, .. } => {
+                    if let Ok(fid) = id.parse::<i64>() {
+                        let address = attributes.street_address();
+                        facilities.insert(fid, Facility { id: fid, name: attributes.name, address });
+                    }
+                }
+                model::team::Included::Event { id, attributes, relationships } => {
+                    if let Ok(eid) = id.parse::<i64>() {
+                        event_comments.extend(Self::relationship_ids(relationships.as_ref(), "comments").into_iter().map(|cid| (eid, cid)));
+                    }
+                    let event_type = attributes.event_type(our_team_id);
                     // Build locker map from locker room events (type L)
-                    let is_locker = attributes
-                        .event_type_id
-                        .as_deref()
-                        .map(|s| s.eq_ignore_ascii_case("L"))
-                        .unwrap_or(false);
-                    if is_locker {
-                        if let (Some(game_id), Some(res_id)) = (attributes.parent_event_id, attributes.resource_id) {
-                            let is_home = attributes
-                                .locker_room_type
-                                .as_deref()
-                                .map(|s| s.eq_ignore_ascii_case("h"))
-                                .unwrap_or(false);
-
-                            let entry = locker_map.entry(game_id).or_insert((None, None));
-                            if is_home {
-                                entry.0 = Some(res_id);
-                            } else {
-                                entry.1 = Some(res_id);
-                            }
+                    let is_locker = event_type == EventType::LockerRoom;
+                    if is_locker
+                        && let (Some(game_id), Some(res_id)) = (attributes.parent_event_id, attributes.resource_id)
+                    {
+                        let is_home = attributes
+                            .locker_room_type
+                            .as_deref()
+                            .map(|s| s.eq_ignore_ascii_case("h"))
+                            .unwrap_or(false);
+
+                        if let Ok(eid) = id.parse::<i64>() {
+                            locker_events.entry(game_id).or_default().push(eid);
+                        }
+                        let entry = locker_map.entry(game_id).or_insert((None, None));
+                        if is_home {
+                            entry.0 = Some(res_id);
+                        } else {
+                            entry.1 = Some(res_id);
                         }
+                    } else if is_locker
+                        && let (Some(res_id), Some(start), Ok(eid)) = (attributes.resource_id, parse_event_start(&attributes), id.parse::<i64>())
+                    {
+                        unlinked_lockers.push(UnlinkedLocker {
+                            id: eid,
+                            res_id,
+                            start,
+                            end: parse_event_end(&attributes).unwrap_or(start + chrono::Duration::minutes(model::game::DEFAULT_GAME_MINUTES)),
+                            team_id: attributes.hteam_id.or(attributes.vteam_id),
+                            home: attributes.locker_room_type.as_deref().map(|s| s.eq_ignore_ascii_case("h")),
+                        });
+                    }
+
+                    if event_type == EventType::Practice
+                        && !attributes.is_called_off()
+                        && let (Some(dt), Ok(pid)) = (parse_event_start(&attributes), id.parse::<i64>())
+                    {
+                        let raw_start = raw_event_start(&attributes).unwrap_or_default().to_string();
+                        let area_id = attributes.resource_area_id.filter(|id| *id != 0);
+                        let practice = GameCore { dt, raw_start, h_id: attributes.hteam_id, v_id: attributes.vteam_id, res_id: attributes.resource_id, area_id, is_playoff: false, score: None, end: parse_event_end(&attributes) };
+                        practice_map.insert(pid, practice);
                     }
 
                     // Also build game map from game events (type G)
-                    let is_game = attributes
-                        .event_type_id
-                        .as_deref()
-                        .map(|s| s.eq_ignore_ascii_case("g"))
-                        .unwrap_or(false);
-                    
-                    if is_game {
+                    if event_type == EventType::Game {
                         let matches_team = our_team_id.map(|our| {
                             attributes.hteam_id.map(|h| h == our).unwrap_or(false) ||
                             attributes.vteam_id.map(|v| v == our).unwrap_or(false)
                         }).unwrap_or(true);
 
-                        if matches_team {
-                            let date_str_opt = attributes.start_gmt.as_deref().or(attributes.start.as_deref());
-                            if let Some(dt_str) = date_str_opt {
-                                let parsed_dt_utc = chrono::DateTime::parse_from_rfc3339(dt_str)
-                                    .map(|dt| dt.with_timezone(&chrono::Utc))
-                                    .or_else(|_| {
-                                        chrono::NaiveDateTime::parse_from_str(dt_str, "%Y-%m-%dT%H:%M:%S")
-                                            .map(|naive| chrono::TimeZone::from_utc_datetime(&chrono::Utc, &naive))
-                                    });
-                                if let (Ok(dt), Ok(gid)) = (parsed_dt_utc, id.parse::<i64>()) {
-                                    game_map.insert(gid, GameCore { dt, h_id: attributes.hteam_id, v_id: attributes.vteam_id, res_id: attributes.resource_id });
+                        if matches_team
+                            && attributes.is_called_off()
+                            && let Ok(gid) = id.parse::<i64>()
+                        {
+                            info!(game_id = gid, "Game is cancelled or postponed");
+                            cancelled_games.insert(gid);
+                        } else if matches_team
+                            && let (Some(dt), Ok(gid)) = (parse_event_start(&attributes), id.parse::<i64>())
+                        {
+                            let is_playoff = attributes.sub_type.as_deref().is_some_and(|t| t.to_ascii_lowercase().starts_with("playoff"));
+                            let raw_start = raw_event_start(&attributes).unwrap_or_default().to_string();
+                            let game = GameCore { dt, raw_start, h_id: attributes.hteam_id, v_id: attributes.vteam_id, res_id: attributes.resource_id, area_id: attributes.resource_area_id.filter(|id| *id != 0), is_playoff, score: attributes.score(), end: parse_event_end(&attributes) };
+                            match game_map.get(&gid) {
+                                // The same event can appear in more than one include section (e.g. regular
+                                // and playoff events); keep whichever copy carries more details
+                                Some(existing) => {
+                                    let mut game = game;
+                                    game.is_playoff |= existing.is_playoff;
+                                    game.score = game.score.or(existing.score);
+                                    game.end = game.end.or(existing.end);
+                                    game.area_id = game.area_id.or(existing.area_id);
+                                    let replace = game.completeness() > existing.completeness();
+                                    warn!(
+                                        game_id = gid,
+                                        kept = if replace { "later" } else { "earlier" },
+                                        "Duplicate game event in DaySmart document"
+                                    );
+                                    if replace {
+                                        game_map.insert(gid, game);
+                                    }
+                                }
+                                None => {
+                                    game_map.insert(gid, game);
                                 }
                             }
                         }
                     }
                 }
+                model::team::Included::League { id, attributes, relationships } => {
+                    playoff_event_ids.extend(Self::relationship_ids(relationships.as_ref(), "playoffEvents"));
+                    if let Ok(id) = id.parse::<i64>() {
+                        leagues.push(League {
+                            id,
+                            name: attributes.name.unwrap_or_default(),
+                            season_id: attributes.season_id,
+                            start_date: league_model::parse_date(attributes.start_date.as_deref()),
+                            end_date: league_model::parse_date(attributes.end_date.as_deref()),
+                        });
+                    }
+                }
+                model::team::Included::Season { id, attributes, .. } => {
+                    if let Ok(id) = id.parse::<i64>() {
+                        seasons.push(Season {
+                            id,
+                            name: attributes.name.unwrap_or_default(),
+                            start_date: league_model::parse_date(attributes.start_date.as_deref()),
+                            end_date: league_model::parse_date(attributes.end_date.as_deref()),
+                        });
+                    }
+                }
+                model::team::Included::ProgramType { id, attributes, .. } => {
+                    if program_type.is_none()
+                        && let Ok(id) = id.parse::<i64>()
+                    {
+                        program_type = Some(ProgramType { id, name: attributes.name.unwrap_or_default() });
+                    }
+                }
                 _ => {}
             }
         }
 
-        (team_names, resource_names, locker_map, game_map)
+        // Events listed under the league's playoffEvents are playoff games even without a playoff sub_type
+        for gid in playoff_event_ids {
+            if let Some(game) = game_map.get_mut(&gid) {
+                game.is_playoff = true;
+            }
+        }
+
+        // Prefer the league and season the team document points at; fall back to the first included
+        let league = match leagues.iter().position(|l| Some(l.id) == team_league_id) {
+            Some(i) => Some(leagues.swap_remove(i)),
+            None => leagues.into_iter().next(),
+        };
+        let season_id = team_season_id.or(league.as_ref().and_then(|l| l.season_id));
+        let season = match seasons.iter().position(|s| Some(s.id) == season_id) {
+            Some(i) => Some(seasons.swap_remove(i)),
+            None => seasons.into_iter().next(),
+        };
+
+        Self::assign_unlinked_lockers(unlinked_lockers, &game_map, &resource_facilities, &mut locker_map, &mut locker_events);
+
+        // Comments listed under a game's relationships come first, in that order; then any that
+        // only name their event. Each comment is used once, even when its event is included twice
+        let mut game_notes: HashMap<i64, Vec<String>> = HashMap::new();
+        let mut comment_ids: Vec<i64> = comments.keys().copied().collect();
+        comment_ids.sort_unstable();
+        let by_event_id = comment_ids.into_iter().filter_map(|cid| Some((comments[&cid].0?, cid)));
+        let mut seen: HashSet<i64> = HashSet::new();
+        for (gid, cid) in event_comments.into_iter().chain(by_event_id) {
+            if (game_map.contains_key(&gid) || practice_map.contains_key(&gid))
+                && let Some((_, text)) = comments.get(&cid)
+                && seen.insert(cid)
+            {
+                game_notes.entry(gid).or_default().push(text.clone());
+            }
+        }
+
+        // A cancelled copy of an event does not cancel a live copy listed elsewhere
+        cancelled_games.retain(|gid| !game_map.contains_key(gid));
+        DocumentMaps { team_names, resource_names, area_names, resource_facilities, facilities, locker_map, locker_events, game_map, practice_map, game_notes, cancelled_games, league, season, program_type }
+    }
+
+    /// Fallback for locker room events DaySmart lists without the game they belong to: each is
+    /// given to the one game at the same facility whose time overlaps it, narrowed to its team's
+    /// game when it names one. Rooms already assigned by a linked event are kept.
+    fn assign_unlinked_lockers(
+        lockers: Vec<UnlinkedLocker>,
+        game_map: &HashMap<i64, GameCore>,
+        resource_facilities: &HashMap<i64, i64>,
+        locker_map: &mut LockerMap,
+        locker_events: &mut LockerEvents,
+    ) {
+        for locker in lockers {
+            let Some(facility) = resource_facilities.get(&locker.res_id) else { continue };
+            let candidates: Vec<(&i64, &GameCore)> = game_map
+                .iter()
+                .filter(|(_, game)| game.res_id.and_then(|rid| resource_facilities.get(&rid)) == Some(facility))
+                .filter(|(_, game)| game.dt < locker.end && locker.start < game.end.unwrap_or(game.dt + chrono::Duration::minutes(model::game::DEFAULT_GAME_MINUTES)))
+                .filter(|(_, game)| locker.team_id.is_none_or(|team| game.h_id == Some(team) || game.v_id == Some(team)))
+                .collect();
+            let [(gid, game)] = candidates[..] else {
+                if !candidates.is_empty() {
+                    warn!(locker_event_id = locker.id, games = candidates.len(), "Unlinked locker room event overlaps several games; not assigning it");
+                }
+                continue;
+            };
+            let home = match locker.team_id {
+                Some(team) => game.h_id == Some(team),
+                None => locker.home.unwrap_or(false),
+            };
+            let entry = locker_map.entry(*gid).or_insert((None, None));
+            let side = if home { &mut entry.0 } else { &mut entry.1 };
+            if side.is_none() {
+                info!(game_id = gid, locker_event_id = locker.id, home, "Assigned locker room from an unlinked locker room event");
+                *side = Some(locker.res_id);
+                locker_events.entry(*gid).or_default().push(locker.id);
+            }
+        }
+    }
+
+    /// Ids of a JSON:API to-many relationship, e.g. `{"playoffEvents": {"data": [{"id": "1", "type": "events"}]}}`.
+    fn relationship_ids(relationships: Option<&serde_json::Value>, name: &str) -> Vec<i64> {
+        relationships
+            .and_then(|r| r.get(name))
+            .and_then(|r| r.get("data"))
+            .and_then(|d| d.as_array())
+            .map(|items| items.iter().filter_map(|item| item.get("id")?.as_str()?.parse().ok()).collect())
+            .unwrap_or_default()
     }
 
     /// Deserialize the Daysmart team document from a JSON string.
@@ -176,24 +678,20 @@ impl DaySmart {
     }
 
     /// Format a Discord-friendly game message using stored document and name maps.
-    fn format_game_message(&self, game: &GameInfo) -> String {
+    pub(crate) fn format_game_message(&self, game: &GameInfo) -> String {
+        let wording = if game.is_practice { template::DEFAULT_PRACTICE_TEMPLATE } else { template::DEFAULT_REMINDER_TEMPLATE };
+        // Both built-in wordings are rendered with every optional value set and unset in tests
+        template::render(wording, &self.reminder_vars(game)).expect("built-in reminder templates render")
+    }
+
+    /// Values for the reminder template placeholders, resolved for a game.
+    pub fn reminder_vars(&self, game: &GameInfo) -> ReminderVars {
         // Use stored team id (extracted at construction time)
         let our_team_id_i64 = self.our_team_id;
 
         // Resolve names (borrow to avoid allocations)
-        let h_name: &str = game
-            .h_id
-            .and_then(|id| self.team_names.get(&id).map(|s| s.as_str()))
-            .unwrap_or("Home");
-        let v_name: &str = game
-            .v_id
-            .and_then(|id| self.team_names.get(&id).map(|s| s.as_str()))
-            .unwrap_or("Visitor");
-
-        let resource_name: &str = game
-            .res_id
-            .and_then(|rid| self.resource_names.get(&rid).map(|s| s.as_str()))
-            .unwrap_or("Unknown Arena");
+        let h_name = self.team(game.h_id, &self.placeholders.home_team);
+        let v_name = self.team(game.v_id, &self.placeholders.visiting_team);
 
         // Home vs away determines jersey color
         let is_home = match (our_team_id_i64, game.h_id) {
@@ -201,149 +699,497 @@ impl DaySmart {
             _ => false,
         };
 
-        // Localize to Pacific time
-        use chrono_tz::America::Los_Angeles;
-        let local_dt = game.dt.with_timezone(&Los_Angeles);
-        let date_str = local_dt.format("%a %b %e, %Y").to_string();
-        let time_str = local_dt.format("%-I:%M %p").to_string();
-        let jersey_color = if is_home { "Light" } else { "Dark" };
-
-        // Use only the pre-computed locker room for our team; no fallback search here.
-        let our_locker_room_name: Option<&str> = {
-            let rid_opt = if is_home { game.home_locker_res_id } else { game.away_locker_res_id };
-            rid_opt.and_then(|rid| self.resource_names.get(&rid).map(|s| s.as_str()))
-        };
-        let locker_line = if let Some(lr) = our_locker_room_name {
-            let mut s = String::with_capacity(12 + lr.len());
-            s.push_str("\nLocker Room: ");
-            s.push_str(lr);
-            s
-        } else {
-            String::new()
+        // Localize to the configured timezone
+        let local_dt = game.dt.with_timezone(&self.timezone);
+        let timestamp = game.dt.timestamp();
+        let time_of = |dt: chrono::DateTime<chrono::Utc>| {
+            if self.discord_timestamps { format!("<t:{}:t>", dt.timestamp()) } else { dt.with_timezone(&self.timezone).format("%-I:%M %p").to_string() }
         };
+        let date_str = if self.discord_timestamps { format!("<t:{0}:D> (<t:{0}:R>)", timestamp) } else { local_dt.format("%a %b %e, %Y").to_string() };
+        let time_str = time_of(game.dt);
+        let arrive_by = self.arrive_minutes_early.map(|minutes| time_of(game.dt - chrono::Duration::minutes(i64::from(minutes))));
+        let opponent = if is_home { v_name } else { h_name };
+        let jersey_color = self.jersey(is_home, opponent);
 
-        format!(
-            ":hockey: Kraken Hockey League Game :goal:\n{}\n{} at {}\n{} vs {}{}\n:shirt: {} Jerseys",
-            date_str, time_str, resource_name, h_name, v_name, locker_line, jersey_color
-        )
+        let header = match (game.is_practice, game.is_playoff) {
+            (true, _) => ":hockey: Team Practice",
+            (false, true) => ":trophy: PLAYOFF GAME :trophy:",
+            (false, false) => ":hockey: Kraken Hockey League Game :goal:",
+        };
+        ReminderVars {
+            header: header.to_string(),
+            date: date_str,
+            time: time_str,
+            arrive_by,
+            arrive_minutes_early: self.arrive_minutes_early,
+            timestamp,
+            arena: self.arena(game),
+            address: self.address(game).map(str::to_string),
+            maps_url: self.address(game).map(links::maps_search_url),
+            home: h_name.to_string(),
+            visitor: v_name.to_string(),
+            opponent: opponent.to_string(),
+            opponent_decided: opponent.is_decided(game.is_playoff),
+            jersey: jersey_color.to_string(),
+            // Use only the pre-computed locker room for our team; no fallback search here.
+            locker_room: self.locker_room(game).name().map(str::to_string),
+            playoff: game.is_playoff,
+            practice: game.is_practice,
+            notes: self.notes(game).to_vec(),
+            venue_note: self.venue_note(game).map(str::to_string),
+        }
     }
 
 
     /// Find upcoming games within the next `days_ahead` days using the stored document.
     /// Accepts a specific current time `now_utc` to make this function easier to test.
     fn find_upcoming_games(&self, days_ahead: i64, now_utc: chrono::DateTime<chrono::Utc>) -> Vec<GameInfo> {
-        use chrono::Duration;
+        self.games_between(now_utc, now_utc + chrono::Duration::days(days_ahead))
+    }
 
-        let window_end = now_utc + Duration::days(days_ahead);
-        let mut games: Vec<GameInfo> = Vec::new();
+    /// One of our games by id, if it is listed and hasn't started by `now_utc`.
+    pub fn upcoming_game(&self, game_id: i64, now_utc: chrono::DateTime<chrono::Utc>) -> Option<GameInfo> {
+        match self.game_map.get(&game_id) {
+            Some(core) => Some(self.game_info(game_id, core)),
+            None => self.practices().find(|(pid, _)| **pid == game_id).map(|(pid, core)| self.practice_info(*pid, core)),
+        }
+        .filter(|game| game.dt > now_utc)
+    }
 
-        for (gid, core) in &self.game_map {
-            let dt = core.dt;
-            if dt < now_utc || dt > window_end {
-                continue;
-            }
+    /// Practices, when they are included.
+    fn practices(&self) -> impl Iterator<Item = (&i64, &GameCore)> {
+        self.practice_map.iter().filter(|_| self.include_practices)
+    }
 
-            let (home_lr_id, away_lr_id) = if let Some((home_rid_opt, away_rid_opt)) = self.locker_map.get(gid) {
-                (*home_rid_opt, *away_rid_opt)
-            } else {
-                (None, None)
-            };
+    /// Games starting within `[from, to]`, in no particular order.
+    fn games_between(&self, from: chrono::DateTime<chrono::Utc>, to: chrono::DateTime<chrono::Utc>) -> Vec<GameInfo> {
+        let in_window = |core: &GameCore| core.dt >= from && core.dt <= to;
+        let games = self.game_map.iter().filter(|(_, core)| in_window(core)).map(|(gid, core)| self.game_info(*gid, core));
+        let practices = self.practices().filter(|(_, core)| in_window(core)).map(|(pid, core)| self.practice_info(*pid, core));
+        games.chain(practices).collect()
+    }
 
-            games.push(GameInfo {
-                dt,
-                h_id: core.h_id,
-                v_id: core.v_id,
-                res_id: core.res_id,
-                home_locker_res_id: home_lr_id,
-                away_locker_res_id: away_lr_id,
-            });
+    /// A game with its locker room assignments looked up.
+    fn game_info(&self, gid: i64, core: &GameCore) -> GameInfo {
+        let (home_lr_id, away_lr_id) = self.locker_map.get(&gid).copied().unwrap_or((None, None));
+        GameInfo {
+            id: gid,
+            dt: core.dt,
+            h_id: core.h_id,
+            v_id: core.v_id,
+            res_id: core.res_id,
+            area_id: core.area_id,
+            home_locker_res_id: home_lr_id,
+            away_locker_res_id: away_lr_id,
+            is_playoff: core.is_playoff,
+            score: core.score,
+            end: core.end,
+            is_practice: false,
         }
+    }
+
+    /// A practice, with any locker room booked for it.
+    fn practice_info(&self, pid: i64, core: &GameCore) -> GameInfo {
+        GameInfo { is_practice: true, ..self.game_info(pid, core) }
+    }
 
+    /// Everything derived about one event: parsed times, resolved names, locker rooms and the
+    /// included records they came from, and the reminder it renders to (with `message_template`
+    /// when given).
+    pub fn explain(&self, game_id: i64, message_template: Option<&str>) -> GameExplanation {
+        let status = match self.game_status(game_id) {
+            GameStatus::Listed => "listed",
+            GameStatus::Cancelled => "cancelled",
+            GameStatus::Missing => "missing",
+        };
+        let game = self.game_map.get(&game_id).map(|core| {
+            let game = self.game_info(game_id, core);
+            let is_home = matches!((self.our_team_id, game.h_id), (Some(our), Some(h)) if our == h);
+            let mut includes = vec![format!("events/{} (game)", game_id)];
+            let mut missing_includes = Vec::new();
+            let mut resolve = |id: Option<i64>, kind: &str, role: &str, name: Option<&str>, shown: &str| {
+                if let Some(id) = id {
+                    let used = format!("{}/{} ({})", kind, id, role);
+                    if name.is_some() { includes.push(used) } else { missing_includes.push(used) }
+                }
+                Resolved { id, name: name.map(str::to_string), shown: shown.to_string() }
+            };
+            let home_team = self.team(game.h_id, &self.placeholders.home_team);
+            let visiting_team = self.team(game.v_id, &self.placeholders.visiting_team);
+            let rink = self.rink_name(&game);
+            let home_team = resolve(game.h_id, "teams", "home team", home_team.name(), home_team.as_str());
+            let visiting_team = resolve(game.v_id, "teams", "visiting team", visiting_team.name(), visiting_team.as_str());
+            let rink = resolve(game.res_id, "resources", "rink", rink.name(), rink.as_str());
+            let locker = |rid: Option<i64>| {
+                let name = self.resource_name(rid);
+                (name, LockerRoom::new(name, &self.placeholders.locker_room).to_string())
+            };
+            let (name, shown) = locker(game.home_locker_res_id);
+            let home_locker_room = resolve(game.home_locker_res_id, "resources", "home locker room", name, &shown);
+            let (name, shown) = locker(game.away_locker_res_id);
+            let away_locker_room = resolve(game.away_locker_res_id, "resources", "away locker room", name, &shown);
+            for eid in self.locker_events.get(&game_id).into_iter().flatten() {
+                includes.push(format!("events/{} (locker room assignment)", eid));
+            }
+            let (opponent, jersey) = self.matchup(&game);
+            let reminder = match message_template {
+                Some(message_template) => self.format_game_message_with(message_template, &game).unwrap_or_else(|e| e.to_string()),
+                None => self.format_game_message(&game),
+            };
+            ExplainedGame {
+                raw_start: core.raw_start.clone(),
+                start_utc: game.dt.to_rfc3339(),
+                start_local: game.dt.with_timezone(&self.timezone).to_rfc3339(),
+                timezone: self.timezone.name().to_string(),
+                home: is_home,
+                playoff: game.is_playoff,
+                score: game.score,
+                home_team,
+                visiting_team,
+                opponent: opponent.to_string(),
+                jersey: jersey.to_string(),
+                rink,
+                home_locker_room,
+                away_locker_room,
+                includes,
+                missing_includes,
+                reminder,
+            }
+        });
+        GameExplanation { game_id, status: status.to_string(), game }
+    }
+
+    /// Every game in the document, past and future, earliest first.
+    pub fn all_games(&self) -> Vec<GameInfo> {
+        let mut games = self.games_between(chrono::DateTime::<chrono::Utc>::MIN_UTC, chrono::DateTime::<chrono::Utc>::MAX_UTC);
+        games.sort_by_key(|g| g.dt);
+        games
+    }
+
+    /// Games on local date `date`, earliest first.
+    pub fn games_on(&self, date: chrono::NaiveDate) -> Vec<GameInfo> {
+        // Generous UTC window around the local day; the exact local date is checked below
+        let midnight = date.and_time(chrono::NaiveTime::MIN).and_utc();
+        let mut games: Vec<GameInfo> = self
+            .games_between(midnight - chrono::Duration::days(1), midnight + chrono::Duration::days(2))
+            .into_iter()
+            .filter(|g| g.dt.with_timezone(&self.timezone).date_naive() == date)
+            .collect();
+        games.sort_by_key(|g| g.dt);
         games
     }
 
+    /// Games played on local date `date` that already have a final score, earliest first.
+    pub fn completed_games_on(&self, date: chrono::NaiveDate) -> Vec<GameInfo> {
+        self.games_on(date).into_iter().filter(|g| g.score.is_some()).collect()
+    }
+
+    /// Our results in every game with a final score, in the order they were played.
+    pub fn results(&self) -> Vec<GameResult> {
+        let mut played: Vec<GameInfo> =
+            self.games_between(chrono::DateTime::<chrono::Utc>::MIN_UTC, chrono::DateTime::<chrono::Utc>::MAX_UTC);
+        played.retain(|g| g.score.is_some());
+        played.sort_by_key(|g| g.dt);
+        played
+            .iter()
+            .filter_map(|game| {
+                let (home, visitor) = game.score?;
+                let is_home = matches!((self.our_team_id, game.h_id), (Some(our), Some(h)) if our == h);
+                let (ours, theirs) = if is_home { (home, visitor) } else { (visitor, home) };
+                Some(match ours.cmp(&theirs) {
+                    std::cmp::Ordering::Greater => GameResult::Win,
+                    std::cmp::Ordering::Less => GameResult::Loss,
+                    std::cmp::Ordering::Equal => GameResult::Tie,
+                })
+            })
+            .collect()
+    }
+
+    /// Score recap for a played game, e.g. "Final: Yacht Flippers 5 – 3 Blackbirds".
+    pub fn format_recap(&self, game: &GameInfo) -> Option<String> {
+        let (home_score, visitor_score) = game.score?;
+        let prefix = if game.is_playoff { ":trophy:" } else { ":hockey:" };
+        Some(format!(
+            "{} Final: {} {} – {} {}",
+            prefix,
+            self.team(game.h_id, &self.placeholders.home_team),
+            home_score,
+            visitor_score,
+            self.team(game.v_id, &self.placeholders.visiting_team)
+        ))
+    }
+
     /// Determine the next game within `days_ahead` and return a formatted message if one exists.
     /// Returns Some(String) with the formatted message when a game is found, or None if not.
     /// Accepts a specific current time `now_utc` to make this function easier to test.
     pub fn get_next_game_message(&self, days_ahead: i64, now_utc: chrono::DateTime<chrono::Utc>) -> Option<String> {
+        self.next_game(days_ahead, now_utc).map(|game| self.format_game_message(&game))
+    }
+
+    /// One game's reminder worded by a custom template.
+    pub fn format_game_message_with(&self, message_template: &str, game: &GameInfo) -> Result<String, Error> {
+        template::render(message_template, &self.reminder_vars(game)).map_err(Error::Config)
+    }
+
+    /// The earliest game within `days_ahead` days of `now_utc`, if any.
+    pub fn next_game(&self, days_ahead: i64, now_utc: chrono::DateTime<chrono::Utc>) -> Option<GameInfo> {
+        self.find_upcoming_games(days_ahead, now_utc).into_iter().min_by_key(|g| g.dt)
+    }
+
+    /// All games within `days_ahead` days of `now_utc`, earliest first.
+    pub fn upcoming_games(&self, days_ahead: i64, now_utc: chrono::DateTime<chrono::Utc>) -> Vec<GameInfo> {
         let mut games = self.find_upcoming_games(days_ahead, now_utc);
-        if games.is_empty() {
+        games.sort_by_key(|g| g.dt);
+        games
+    }
+
+    /// Opponent name and jersey color for one of our games.
+    pub(crate) fn matchup(&self, game: &GameInfo) -> (TeamName<'_>, &str) {
+        let is_home = match (self.our_team_id, game.h_id) {
+            (Some(our), Some(h)) => our == h,
+            _ => false,
+        };
+        let opponent_id = if is_home { game.v_id } else { game.h_id };
+        let opponent = self.team(opponent_id, &self.placeholders.opponent);
+        (opponent, self.jersey(is_home, opponent))
+    }
+
+    fn jersey(&self, is_home: bool, opponent: TeamName<'_>) -> &str {
+        self.jerseys.color(is_home, opponent.name())
+    }
+
+    fn team<'a>(&'a self, id: Option<i64>, placeholder: &'a str) -> TeamName<'a> {
+        TeamName::new(id.and_then(|id| self.team_names.get(&id).map(|s| s.as_str())), placeholder)
+    }
+
+    fn resource_name(&self, res_id: Option<i64>) -> Option<&str> {
+        res_id.and_then(|rid| self.resource_names.get(&rid).map(|s| s.as_str()))
+    }
+
+    /// League notes DaySmart attached to a game as comments, e.g. "Arrive 30 min early".
+    pub fn notes(&self, game: &GameInfo) -> &[String] {
+        self.game_notes.get(&game.id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The team's note for the venue of a game: the one for its arena as shown in reminders, else
+    /// its rink, else its facility. Names match ignoring case and surrounding spaces.
+    pub fn venue_note(&self, game: &GameInfo) -> Option<&str> {
+        if self.venue_notes.is_empty() {
             return None;
         }
-        games.sort_by_key(|g| g.dt);
-        Some(self.format_game_message(&games[0]))
+        let arena = self.arena(game);
+        let facility = self.facility(game.res_id).and_then(|facility| facility.name.as_deref());
+        [Some(arena.as_str()), self.resource_name(game.res_id), facility].into_iter().flatten().find_map(|name| {
+            self.venue_notes.iter().find(|(venue, _)| venue.trim().eq_ignore_ascii_case(name.trim())).map(|(_, note)| note.as_str())
+        })
+    }
+
+    /// Rink name with the sheet appended for multi-sheet rinks, e.g. "Olympic View Arena – North Rink".
+    pub fn arena(&self, game: &GameInfo) -> String {
+        let rink = self.rink_name(game);
+        match game.area_id.and_then(|aid| self.area_names.get(&aid)) {
+            Some(area) if rink.name().is_some() => format!("{} – {}", rink, area),
+            _ => rink.to_string(),
+        }
+    }
+
+    /// Facility the rink with `res_id` is in, when DaySmart included it.
+    fn facility(&self, res_id: Option<i64>) -> Option<&Facility> {
+        res_id.and_then(|rid| self.resource_facilities.get(&rid)).and_then(|fid| self.facilities.get(fid))
+    }
+
+    /// Street address of the facility a game is played at.
+    pub fn address(&self, game: &GameInfo) -> Option<&str> {
+        self.facility(game.res_id).and_then(|facility| facility.address.as_deref())
+    }
+
+    /// Name of the rink a game is played at.
+    pub fn rink_name(&self, game: &GameInfo) -> RinkName<'_> {
+        RinkName::new(self.resource_name(game.res_id), &self.placeholders.rink)
+    }
+
+    /// Title of a game's discussion thread, e.g. "Sun Sep 28 vs Blackbirds" or "Tue Sep 30 practice".
+    pub fn thread_name(&self, game: &GameInfo) -> String {
+        let date = game.dt.with_timezone(&self.timezone).format("%a %b %-d");
+        if game.is_practice {
+            return format!("{} practice", date);
+        }
+        let (opponent, _) = self.matchup(game);
+        format!("{} vs {}", date, opponent)
+    }
+
+    /// Home and visiting team of a game.
+    pub(crate) fn home_and_visitor(&self, game: &GameInfo) -> (TeamName<'_>, TeamName<'_>) {
+        (self.team(game.h_id, &self.placeholders.home_team), self.team(game.v_id, &self.placeholders.visiting_team))
+    }
+
+    /// Text shown for names DaySmart leaves out.
+    pub fn placeholders(&self) -> &Placeholders {
+        &self.placeholders
+    }
+
+    /// Timezone dates and times are displayed in.
+    pub fn timezone(&self) -> chrono_tz::Tz {
+        self.timezone
+    }
+
+    /// Our team's DaySmart id.
+    pub fn team_id(&self) -> Option<i64> {
+        self.our_team_id
+    }
+
+    /// The league the team plays in, if the document includes it.
+    pub fn league(&self) -> Option<&League> {
+        self.league.as_ref()
+    }
+
+    /// The team's season, if the document includes it.
+    pub fn season(&self) -> Option<&Season> {
+        self.season.as_ref()
+    }
+
+    /// The team's program type, if the document includes it.
+    pub fn program_type(&self) -> Option<&ProgramType> {
+        self.program_type.as_ref()
+    }
+
+    /// Whether a game is still scheduled, was called off, or has disappeared from the document.
+    pub fn game_status(&self, game_id: i64) -> GameStatus {
+        // A game held back for its time is still on the schedule, just not announced
+        if self.game_map.contains_key(&game_id) || self.out_of_hours.iter().any(|game| game.game_id == game_id) {
+            GameStatus::Listed
+        } else if self.cancelled_games.contains(&game_id) {
+            GameStatus::Cancelled
+        } else {
+            GameStatus::Missing
+        }
     }
 
-    /// Generate a BenchApp-compatible CSV containing all games in the next ~4 months
-    /// (120 days) from the provided current UTC time.
+    /// Name of our locker room for a game, when DaySmart has assigned one.
+    pub fn locker_room(&self, game: &GameInfo) -> LockerRoom<'_> {
+        let is_home = matches!((self.our_team_id, game.h_id), (Some(our), Some(h)) if our == h);
+        let rid = if is_home { game.home_locker_res_id } else { game.away_locker_res_id };
+        LockerRoom::new(self.resource_name(rid), &self.placeholders.locker_room)
+    }
+
+    /// Condensed tournament message: every game with the break before the next one, followed by
+    /// the hotel and notes for the rinks being played at.
+    pub fn format_itinerary(&self, games: &[GameInfo], config: &TournamentConfig) -> String {
+        let mut out = format!(":trophy: Tournament weekend: {} games", games.len());
+        for (i, game) in games.iter().enumerate() {
+            let local_dt = game.dt.with_timezone(&self.timezone);
+            let (opponent, jersey) = self.matchup(game);
+            out.push_str(&format!(
+                "\n:hockey: {} at {}: vs {} ({} Jerseys)",
+                local_dt.format("%a %b %e, %-I:%M %p"),
+                self.rink_name(game),
+                opponent,
+                jersey
+            ));
+            if let Some(next) = games.get(i + 1) {
+                out.push_str(&format!("\n    :hourglass: {} break", tournament::format_gap(next.dt - game.dt)));
+            }
+        }
+        if let Some(hotel) = &config.hotel {
+            out.push_str(&format!("\n:hotel: {}", hotel));
+        }
+        let rinks: std::collections::BTreeSet<&str> = games.iter().map(|g| self.rink_name(g).as_str()).collect();
+        for rink in rinks {
+            if let Some(note) = config.rink_notes.get(rink) {
+                out.push_str(&format!("\n:round_pushpin: {}: {}", rink, note));
+            }
+        }
+        out
+    }
+
+    /// Resolved names for the given games, for the weekly email digest.
+    pub fn digest_entries(&self, games: &[GameInfo]) -> Vec<DigestEntry> {
+        games
+            .iter()
+            .map(|game| {
+                let (opponent, jersey) = self.matchup(game);
+                DigestEntry {
+                    start: game.dt,
+                    end: game.ends(),
+                    opponent: opponent.to_string(),
+                    jersey: jersey.to_string(),
+                    rink: self.rink_name(game).to_string(),
+                    locker_room: self.locker_room(game).name().map(str::to_string),
+                }
+            })
+            .collect()
+    }
+
+    /// A single calendar containing the given games, for attaching to the tournament itinerary.
+    pub fn to_ics(&self, games: &[GameInfo]) -> String {
+        crate::ical::writer::write_calendar(self, games)
+    }
+
+    /// Generate a BenchApp-compatible CSV containing all games in the next `months_ahead`
+    /// calendar months from the provided current UTC time.
     ///
     /// Columns: Type,Game Type,Title (Optional),Away,Home,Date,Time,Duration,Location (Optional),Address (Optional),Notes (Optional)
-    pub fn to_benchapp_csv(&self, now_utc: chrono::DateTime<chrono::Utc>) -> String {
-        use chrono::{Duration, Datelike};
-        use chrono_tz::America::Los_Angeles;
+    pub fn to_benchapp_csv(&self, now_utc: chrono::DateTime<chrono::Utc>, months_ahead: u32) -> String {
+        self.to_benchapp_csv_with(now_utc, months_ahead, &BenchappNames::default())
+    }
+
+    /// As [`DaySmart::to_benchapp_csv`], with team names spelled the way BenchApp already has them.
+    pub fn to_benchapp_csv_with(
+        &self,
+        now_utc: chrono::DateTime<chrono::Utc>,
+        months_ahead: u32,
+        names: &BenchappNames,
+    ) -> String {
+        use chrono::{Datelike, Months};
 
         // Helper to escape quotes for CSV values (match BenchAppCsv behavior)
-        fn escape_quotes(s: &str) -> String { s.replace('"', "\"") }
+        fn escape_quotes(s: &str) -> String { s.replace('"', "\"\"") }
 
         let mut out = String::new();
         out.push_str("Type,Game Type,Title (Optional),Away,Home,Date,Time,Duration,Location (Optional),Address (Optional),Notes (Optional)\n");
 
-        let window_end = now_utc + Duration::days(120);
-        // Collect and sort upcoming games (with ids)
-        let mut games: Vec<(&i64, &GameCore)> = self
-            .game_map
-            .iter()
-            .filter(|(_, core)| core.dt >= now_utc && core.dt <= window_end)
-            .collect();
-        games.sort_by_key(|(_, g)| g.dt);
-
-        for (gid, core) in games.into_iter() {
-            let h_name: &str = core
-                .h_id
-                .and_then(|id| self.team_names.get(&id).map(|s| s.as_str()))
-                .unwrap_or("");
-            let v_name: &str = core
-                .v_id
-                .and_then(|id| self.team_names.get(&id).map(|s| s.as_str()))
-                .unwrap_or("");
-            let location_name: &str = core
-                .res_id
-                .and_then(|rid| self.resource_names.get(&rid).map(|s| s.as_str()))
-                .unwrap_or("");
-
-            let local_dt = core.dt.with_timezone(&Los_Angeles);
+        let window_end = now_utc.checked_add_months(Months::new(months_ahead)).unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC);
+        // Collect and sort upcoming games (and practices, when included)
+        let mut games = self.games_between(now_utc, window_end);
+        games.sort_by_key(|g| g.dt);
+
+        for game in games.into_iter() {
+            // Missing names are left empty: BenchApp would take a placeholder for a team or rink
+            let benchapp_name = |team: TeamName<'_>| team.name().map(|n| names.rename(n).to_string()).unwrap_or_default();
+            let home = self.team(game.h_id, &self.placeholders.home_team);
+            let visitor = self.team(game.v_id, &self.placeholders.visiting_team);
+            // Practices have no teams to list
+            let (h_name, v_name) = if game.is_practice { (String::new(), String::new()) } else { (benchapp_name(home), benchapp_name(visitor)) };
+
+            let local_dt = game.dt.with_timezone(&self.timezone);
             let date_str = format!("{}/{}/{}", local_dt.day(), local_dt.month(), local_dt.year());
             let time_str = local_dt.format("%I:%M %p").to_string();
-            // Default to 1 hour duration when we don't have explicit end time in DaySmart core
-            let duration_str = "1:00";
-
-            // Notes: jersey color (Light/Dark) and locker room if available
-            let is_home = match (self.our_team_id, core.h_id) { (Some(our), Some(h)) => our == h, _ => false };
-            let jersey = if is_home { "Light Jerseys" } else { "Dark Jerseys" };
-            let locker_opt: Option<String> = self
-                .locker_map
-                .get(gid)
-                .and_then(|(home_lr, away_lr)| {
-                    let pick = if is_home { *home_lr } else { *away_lr };
-                    pick
-                })
-                .and_then(|rid| self.resource_names.get(&rid).map(|s| format!("Locker Room: {}", s)));
-            let notes_str: String = match locker_opt {
-                Some(lr) => format!("{}; {}", jersey, lr),
-                None => jersey.to_string(),
-            };
+            let duration = game.duration();
+            let duration_str = format!("{}:{:02}", duration.num_hours(), duration.num_minutes() % 60);
+
+            // Notes: jersey color (games only), locker room, league notes and the venue note if available
+            let is_home = match (self.our_team_id, game.h_id) { (Some(our), Some(h)) => our == h, _ => false };
+            let jersey = (!game.is_practice).then(|| format!("{} Jerseys", self.jersey(is_home, if is_home { visitor } else { home })));
+            let locker_opt = self.locker_room(&game).line();
+            let venue_note = self.venue_note(&game).map(str::to_string);
+            let notes_str = jersey.into_iter().chain(locker_opt).chain(self.notes(&game).iter().cloned()).chain(venue_note).collect::<Vec<_>>().join("; ");
 
+            let (event_type, game_type) = match (game.is_practice, game.is_playoff) {
+                (true, _) => ("PRACTICE", ""),
+                (false, true) => ("GAME", "PLAYOFF"),
+                (false, false) => ("GAME", "REGULAR"),
+            };
             let row = vec![
-                "GAME".to_string(),              // Type
-                "REGULAR".to_string(),           // Game Type
+                event_type.to_string(),          // Type
+                game_type.to_string(),           // Game Type
                 String::new(),                    // Title (Optional)
-                v_name.to_string(),               // Away
-                h_name.to_string(),               // Home
+                v_name,                           // Away
+                h_name,                           // Home
                 date_str,                         // Date
                 time_str,                         // Time
-                duration_str.to_string(),         // Duration
-                location_name.to_string(),        // Location (Optional)
-                String::new(),                    // Address (Optional) - unknown from DaySmart
+                duration_str,                     // Duration
+                self.rink_name(&game).name().map(|_| self.arena(&game)).unwrap_or_default(), // Location (Optional)
+                self.address(&game).unwrap_or_default().to_string(), // Address (Optional)
                 notes_str,                        // Notes (Optional)
             ]
                 .into_iter()
@@ -359,3 +1205,11 @@ impl DaySmart {
     }
 }
 
+/// Join per-game reminders into one post, with a count heading when there is more than one game.
+pub fn games_digest(messages: Vec<String>) -> Option<String> {
+    match messages.len() {
+        0 => None,
+        1 => messages.into_iter().next(),
+        count => Some(format!(":calendar_spiral: {} upcoming games\n\n{}", count, messages.join("\n\n"))),
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::discord::PostedMessage;
+use crate::state::StateStore;
+
+/// What was last posted for a game, so an unchanged reminder is not posted again on the next run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostedRecord {
+    pub content_hash: String,
+    pub posted_at: DateTime<Utc>,
+    /// The main webhook's message, edited when the reminder changes instead of posting a new one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    /// Channel (or thread) of that message, for reading its reactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    /// The reminder offset (hours before the game) this post was for, with `reminder_offsets_hours`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset_hours: Option<i64>,
+    /// The game's first reminder, kept when later offsets post new messages: with game_threads
+    /// its channel is the game's thread, and its reactions are the RSVPs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_message: Option<PostedMessage>,
+}
+
+/// How long a posted record is kept after its game starts. It is keyed off the game rather than
+/// the post, since days_ahead can announce a game months before it is played.
+pub const POSTED_RECORD_TTL_DAYS: i64 = 30;
+
+fn state_key(team_id: &str, game_id: &str) -> String {
+    format!("posted/{}/{}", team_id, game_id)
+}
+
+/// Stable fingerprint of a message (64-bit FNV-1a, hex). Unlike std's hasher it does not change
+/// between Rust releases, so stored hashes stay comparable across deployments.
+pub fn content_hash(content: &str) -> String {
+    let hash = content.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// What was last posted for the game, if anything.
+pub async fn posted(store: &StateStore, team_id: &str, game_id: &str) -> Result<Option<PostedRecord>, String> {
+    store.get(&state_key(team_id, game_id)).await
+}
+
+impl PostedRecord {
+    /// True when this record is for exactly `content`.
+    pub fn matches(&self, content: &str) -> bool {
+        self.content_hash == content_hash(content)
+    }
+
+    /// The main webhook's message for the game, when Discord returned one.
+    pub fn message(&self) -> Option<PostedMessage> {
+        self.message_id.clone().map(|id| PostedMessage { id, channel_id: self.channel_id.clone() })
+    }
+
+    /// The first reminder posted for the game; records from before offsets kept it only have
+    /// the latest.
+    pub fn first_message(&self) -> Option<PostedMessage> {
+        self.first_message.clone().or_else(|| self.message())
+    }
+}
+
+/// The latest of `offsets` (hours before the game) that `now` has passed, while the game has not
+/// started yet; e.g. 24 with offsets [72, 24, 2] ten hours before the game.
+pub fn crossed_offset(offsets: &[i64], game_start: DateTime<Utc>, now: DateTime<Utc>) -> Option<i64> {
+    if now >= game_start {
+        return None;
+    }
+    offsets.iter().copied().filter(|hours| now >= game_start - chrono::Duration::hours(*hours)).min()
+}
+
+/// Remember that `content` was posted for the game, as `message` on the main webhook when
+/// Discord returned one, and for which reminder offset. The game's first message is kept; the
+/// record lapses [`POSTED_RECORD_TTL_DAYS`] after `game_start`.
+#[allow(clippy::too_many_arguments)]
+pub async fn record_posted(
+    store: &StateStore,
+    team_id: &str,
+    game_id: &str,
+    content: &str,
+    message: Option<&PostedMessage>,
+    offset_hours: Option<i64>,
+    now: DateTime<Utc>,
+    game_start: DateTime<Utc>,
+) -> Result<(), String> {
+    let first_message = posted(store, team_id, game_id).await?.and_then(|record| record.first_message()).or_else(|| message.cloned());
+    let record = PostedRecord {
+        content_hash: content_hash(content),
+        posted_at: now,
+        message_id: message.map(|m| m.id.clone()),
+        channel_id: message.and_then(|m| m.channel_id.clone()),
+        offset_hours,
+        first_message,
+    };
+    store.put_expiring(&state_key(team_id, game_id), &record, game_start + chrono::Duration::days(POSTED_RECORD_TTL_DAYS)).await?;
+    info!(game_id, hash = %record.content_hash, "Recorded posted reminder");
+    Ok(())
+}
+
+/// A reminder whose post failed, so the next run announces it even if its schedule wouldn't run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingAnnouncement {
+    pub game_id: i64,
+    pub failed_at: DateTime<Utc>,
+    pub error: String,
+}
+
+/// Minimum time between attempts at a pending announcement outside the workflow's schedule, so
+/// frequent invocations don't hammer a webhook that keeps failing.
+pub const PENDING_RETRY_INTERVAL_MINUTES: i64 = 60;
+
+/// Note added to a reminder posted late because the earlier post failed.
+pub const DELAYED_NOTE: &str = ":hourglass: Delayed announcement: the earlier post for this game failed.";
+
+fn pending_key(team_id: &str) -> String {
+    format!("pending/{}", team_id)
+}
+
+impl PendingAnnouncement {
+    /// Whether a retry outside the schedule is allowed at `now`.
+    pub fn retry_due(&self, now: DateTime<Utc>) -> bool {
+        now - self.failed_at >= chrono::Duration::minutes(PENDING_RETRY_INTERVAL_MINUTES)
+    }
+}
+
+/// The team's unannounced reminder, if the last post failed.
+pub async fn pending(store: &StateStore, team_id: &str) -> Result<Option<PendingAnnouncement>, String> {
+    store.get(&pending_key(team_id)).await
+}
+
+/// Remember that the reminder for `game_id` failed to post; the record lapses when the game starts.
+pub async fn record_failed(
+    store: &StateStore,
+    team_id: &str,
+    game_id: i64,
+    error: &str,
+    now: DateTime<Utc>,
+    game_start: DateTime<Utc>,
+) -> Result<(), String> {
+    let record = PendingAnnouncement { game_id, failed_at: now, error: error.to_string() };
+    store.put_expiring(&pending_key(team_id), &record, game_start).await?;
+    info!(game_id, "Recorded failed reminder for re-announcement");
+    Ok(())
+}
+
+/// Forget the pending announcement once a reminder went out.
+pub async fn clear_pending(store: &StateStore, team_id: &str) -> Result<(), String> {
+    store.delete(&pending_key(team_id)).await
+}
@@ -0,0 +1,350 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono_tz::Tz;
+use futures::future::join_all;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::discord::{Mentions, PostOptions, PostedMessage};
+use crate::error::{Error, ErrorInfo};
+use crate::workflow::Workflow;
+use crate::notifier::Notifier;
+use crate::secrets::Secret;
+use crate::slack::SlackConfig;
+
+fn default_true() -> bool { true }
+
+/// Where workflow results are posted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Destinations {
+    /// Post to the Discord webhook from the request (default true).
+    #[serde(default = "default_true")]
+    pub discord: bool,
+    #[serde(default)]
+    pub slack: Option<SlackConfig>,
+    /// Further Discord webhooks (e.g. other league channels) that receive every production post.
+    #[serde(default)]
+    pub discord_hooks: Vec<Secret>,
+    /// Pacing of posts when there are many destinations.
+    #[serde(default)]
+    pub fan_out: Option<FanOutConfig>,
+}
+
+impl Default for Destinations {
+    fn default() -> Self {
+        Self { discord: true, slack: None, discord_hooks: Vec::new(), fan_out: None }
+    }
+}
+
+/// Posts to the same host go out in batches of `batch_size`, with `batch_delay_ms` between
+/// batches, so a long list of webhooks doesn't trip the host's rate limit. Different hosts are
+/// posted to concurrently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FanOutConfig {
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    #[serde(default = "default_batch_delay_ms")]
+    pub batch_delay_ms: u64,
+}
+
+fn default_batch_size() -> usize { 5 }
+
+fn default_batch_delay_ms() -> u64 { 1_000 }
+
+impl Default for FanOutConfig {
+    fn default() -> Self {
+        Self { batch_size: default_batch_size(), batch_delay_ms: default_batch_delay_ms() }
+    }
+}
+
+/// Result of one post to one destination.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct DeliveryReport {
+    /// Workflow that made the post, when the publisher was given one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<Workflow>,
+    /// Kind and redacted webhook URL, e.g. "discord https://discord.com/api/webhooks/1/REDACTED".
+    pub destination: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The same error, machine-readable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_info: Option<ErrorInfo>,
+}
+
+/// A file attached to a post, e.g. the BenchApp CSV.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PostedFile {
+    pub filename: String,
+    /// File contents as text (CSV and ICS), or a note giving the size of a binary file such as a zip.
+    pub content: String,
+}
+
+/// One message posted by a publisher, as written (before rendering for each sink), with how
+/// each destination took it. A dry run has no deliveries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Post {
+    pub workflow: Option<Workflow>,
+    pub content: String,
+    pub attachment: Option<PostedFile>,
+    pub deliveries: Vec<DeliveryReport>,
+}
+
+/// How a post went. The lead destination (the main webhook, or the first destination when
+/// Discord posting is off) decides whether the post was made; the others are reported apart, so a
+/// failing extra destination doesn't get the lead's post repeated.
+#[derive(Debug)]
+pub struct Delivery {
+    /// The lead destination's result, with the main webhook's message to edit next time.
+    pub lead: Result<Option<PostedMessage>, Error>,
+    /// Every other destination, in configuration order.
+    pub others: Vec<DeliveryReport>,
+}
+
+impl Delivery {
+    /// The other destinations' failures as one error.
+    pub fn others_result(&self) -> Result<(), Error> {
+        combine(self.others.clone())
+    }
+}
+
+// A host's notifiers with their position among all targets
+type HostGroup<'a> = Vec<(usize, &'a dyn Notifier)>;
+
+/// Every post made by a publisher and its clones, in the order they finished.
+pub type DeliveryLog = Arc<Mutex<Vec<Post>>>;
+
+/// Fans a post out to every configured destination.
+#[derive(Debug, Clone, Default)]
+pub struct Publisher {
+    /// The request's main webhook; a workflow's own webhook replaces it.
+    pub primary: Option<Arc<dyn Notifier>>,
+    /// Every other destination, in configuration order.
+    pub notifiers: Vec<Arc<dyn Notifier>>,
+    pub fan_out: FanOutConfig,
+    /// Workflow the posts are made for, recorded in delivery reports.
+    pub workflow: Option<Workflow>,
+    pub log: Option<DeliveryLog>,
+    /// Record posts in the log without sending them anywhere.
+    pub dry_run: bool,
+    /// Timezone plain-text destinations write Discord timestamps out in.
+    pub timezone: Tz,
+}
+
+impl Publisher {
+    pub fn new(primary: Option<Arc<dyn Notifier>>) -> Self {
+        Self { primary, notifiers: Vec::new(), fan_out: FanOutConfig::default(), workflow: None, log: None, dry_run: false, timezone: Tz::UTC }
+    }
+
+    /// Record every delivery in `log`.
+    pub fn with_log(mut self, log: DeliveryLog) -> Self {
+        self.log = Some(log);
+        self
+    }
+
+    /// Only record posts in the log; nothing is sent.
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Write timestamps out in `timezone` for destinations that render plain text.
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Mark deliveries as made for `workflow`.
+    pub fn for_workflow(mut self, workflow: Workflow) -> Self {
+        self.workflow = Some(workflow);
+        self
+    }
+
+    /// Also post to `notifiers`, after those already added.
+    pub fn with_notifiers(mut self, notifiers: impl IntoIterator<Item = Arc<dyn Notifier>>) -> Self {
+        self.notifiers.extend(notifiers);
+        self
+    }
+
+    /// Pace posts to the same host per `fan_out`.
+    pub fn with_fan_out(mut self, fan_out: FanOutConfig) -> Self {
+        self.fan_out = fan_out;
+        self
+    }
+
+    /// Post to `hook_url` instead of the main webhook. Has no effect when there is no main
+    /// webhook (Discord posting switched off).
+    pub fn with_discord_hook(mut self, hook_url: &str) -> Self {
+        self.primary = self.primary.take().map(|primary| primary.retarget(hook_url));
+        self
+    }
+
+    /// Ping `mentions` with each post, on the destinations that can.
+    pub fn mentioning(mut self, mentions: &Mentions) -> Self {
+        let ping = |notifier: Arc<dyn Notifier>| notifier.mentioning(mentions).unwrap_or(notifier);
+        self.primary = self.primary.take().map(ping);
+        self.notifiers = self.notifiers.into_iter().map(ping).collect();
+        self
+    }
+
+    /// Post with `options` (name, avatar, allowed mentions, link previews) on the destinations
+    /// that have them.
+    pub fn with_post_options(mut self, options: &PostOptions) -> Self {
+        let brand = |notifier: Arc<dyn Notifier>| notifier.with_post_options(options).unwrap_or(notifier);
+        self.primary = self.primary.take().map(brand);
+        self.notifiers = self.notifiers.into_iter().map(brand).collect();
+        self
+    }
+
+    /// Open a thread named `name` with the main webhook's posts, which must then be a forum
+    /// channel's. Other destinations post as usual.
+    pub fn in_thread(mut self, name: &str) -> Self {
+        self.primary = self.primary.take().map(|primary| primary.in_thread(name).unwrap_or(primary));
+        self
+    }
+
+    /// Post the main webhook's messages into the thread `thread_id`, opened by an earlier
+    /// [`Publisher::in_thread`] post. Other destinations post as usual.
+    pub fn in_existing_thread(mut self, thread_id: &str) -> Self {
+        self.primary = self.primary.take().map(|primary| primary.in_existing_thread(thread_id).unwrap_or(primary));
+        self
+    }
+
+    /// Post a text message everywhere. Every destination is attempted; failures are combined.
+    pub async fn post(&self, content: &str) -> Result<(), Error> {
+        combine(self.deliver(content, None).await)
+    }
+
+    /// Post a message with an attachment everywhere. Every destination is attempted; failures are combined.
+    pub async fn post_with_attachment(&self, content: &str, filename: &str, bytes: &[u8]) -> Result<(), Error> {
+        combine(self.deliver(content, Some((filename, bytes))).await)
+    }
+
+    /// Post a text message everywhere, replacing the main webhook's `previous` message where it
+    /// can instead of posting a new one. Every destination is attempted; the delivery carries the
+    /// main webhook's message, to edit next time.
+    pub async fn post_or_edit(&self, previous: Option<&PostedMessage>, content: &str) -> Delivery {
+        let (reports, primary_message) = self.deliver_or_edit(content, None, previous).await;
+        split_lead(reports, primary_message)
+    }
+
+    /// [`Publisher::post_or_edit`] with an attachment, which an edit replaces too.
+    pub async fn post_or_edit_with_attachment(
+        &self,
+        previous: Option<&PostedMessage>,
+        content: &str,
+        filename: &str,
+        bytes: &[u8],
+    ) -> Delivery {
+        let (reports, primary_message) = self.deliver_or_edit(content, Some((filename, bytes)), previous).await;
+        split_lead(reports, primary_message)
+    }
+
+    /// Post to every destination and report how each went, in configuration order.
+    pub async fn deliver(&self, content: &str, attachment: Option<(&str, &[u8])>) -> Vec<DeliveryReport> {
+        self.deliver_or_edit(content, attachment, None).await.0
+    }
+
+    /// [`Publisher::deliver`], editing message `edit` on the main webhook, plus the main webhook's
+    /// message.
+    async fn deliver_or_edit(&self, content: &str, attachment: Option<(&str, &[u8])>, edit: Option<&PostedMessage>) -> (Vec<DeliveryReport>, Option<PostedMessage>) {
+        if self.dry_run {
+            info!(workflow = ?self.workflow, "Dry run; not posting");
+            self.record(content, attachment, Vec::new());
+            return (Vec::new(), None);
+        }
+        // The message to edit was posted through the main webhook, the first target
+        let edit = edit.filter(|_| self.primary.is_some());
+        let targets: Vec<&dyn Notifier> = self.primary.iter().chain(&self.notifiers).map(|n| n.as_ref()).collect();
+
+        // Group by host, keeping each target's position so the report stays in order
+        let mut by_host: Vec<(String, HostGroup)> = Vec::new();
+        for (i, target) in targets.iter().enumerate() {
+            let host = target.host();
+            match by_host.iter_mut().find(|(h, _)| *h == host) {
+                Some((_, group)) => group.push((i, *target)),
+                None => by_host.push((host, vec![(i, *target)])),
+            }
+        }
+
+        let batch_size = self.fan_out.batch_size.max(1);
+        let delay = Duration::from_millis(self.fan_out.batch_delay_ms);
+        let hosts = by_host.iter().map(|(_, group)| async move {
+            let mut results = Vec::with_capacity(group.len());
+            for (n, batch) in group.chunks(batch_size).enumerate() {
+                if n > 0 {
+                    tokio::time::sleep(delay).await;
+                }
+                let sent = join_all(batch.iter().map(|(i, target)| async move { (*i, send(*target, content, attachment, edit.filter(|_| *i == 0), self.timezone).await) })).await;
+                results.extend(sent);
+            }
+            results
+        });
+        let mut results: Vec<(usize, Result<Option<PostedMessage>, Error>)> = join_all(hosts).await.into_iter().flatten().collect();
+        results.sort_by_key(|(i, _)| *i);
+        let primary_message = results.first().filter(|_| self.primary.is_some()).and_then(|(_, result)| result.as_ref().ok().cloned().flatten());
+
+        let reports: Vec<DeliveryReport> = results
+            .into_iter()
+            .map(|(i, result)| {
+                let error = result.err();
+                DeliveryReport { workflow: self.workflow, destination: targets[i].label(), error: error.as_ref().map(Error::to_string), error_info: error.map(|e| e.info()) }
+            })
+            .collect();
+        if reports.len() > 2 {
+            let failed = reports.iter().filter(|r| r.error.is_some()).count();
+            for report in &reports {
+                match &report.error {
+                    None => info!(destination = %report.destination, "Delivered"),
+                    Some(e) => warn!(destination = %report.destination, error = %e, "Delivery failed"),
+                }
+            }
+            info!(destinations = reports.len(), failed, "Fan-out finished");
+        }
+        self.record(content, attachment, reports.clone());
+        (reports, primary_message)
+    }
+
+    fn record(&self, content: &str, attachment: Option<(&str, &[u8])>, deliveries: Vec<DeliveryReport>) {
+        if let Some(log) = &self.log {
+            let attachment = attachment.map(|(filename, bytes)| PostedFile {
+                filename: filename.to_string(),
+                content: String::from_utf8(bytes.to_vec()).unwrap_or_else(|_| format!("<{} bytes of binary data>", bytes.len())),
+            });
+            let post = Post { workflow: self.workflow, content: content.to_string(), attachment, deliveries };
+            log.lock().expect("delivery log lock").push(post);
+        }
+    }
+}
+
+async fn send(notifier: &dyn Notifier, content: &str, attachment: Option<(&str, &[u8])>, edit: Option<&PostedMessage>, timezone: Tz) -> Result<Option<PostedMessage>, Error> {
+    let rendered = notifier.profile().render(content, timezone);
+    if let Some(previous) = edit
+        && let Some(edited) = notifier.edit_message(&previous.id, &rendered, attachment)
+    {
+        match edited.await {
+            Ok(()) => return Ok(Some(previous.clone())),
+            // Deleted since, or grown too long for one message: post it afresh
+            Err(e) => warn!(error = %e, message_id = %previous.id, "Failed to edit earlier message; posting a new one"),
+        }
+    }
+    notifier.post_message(&rendered, attachment).await
+}
+
+/// The first report as the lead's result; nothing sent (a dry run) counts as delivered.
+fn split_lead(mut reports: Vec<DeliveryReport>, primary_message: Option<PostedMessage>) -> Delivery {
+    let lead = if reports.is_empty() { Ok(None) } else { combine(vec![reports.remove(0)]).map(|()| primary_message) };
+    Delivery { lead, others: reports }
+}
+
+/// The failures as one error, classified like the first of them.
+fn combine(reports: Vec<DeliveryReport>) -> Result<(), Error> {
+    let failed: Vec<ErrorInfo> = reports.into_iter().filter_map(|r| r.error_info).collect();
+    let Some(first) = failed.first() else {
+        return Ok(());
+    };
+    let message = failed.iter().map(|e| e.message.as_str()).collect::<Vec<_>>().join("; ");
+    Err(Error::from_info(first, message))
+}
@@ -0,0 +1,95 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use tracing::{error, info};
+
+use crate::changes::{self, Cancellation, ScheduleChange, ScheduleSnapshot};
+use crate::daysmart::DaySmart;
+use crate::email::DigestEntry;
+use crate::model::game::GameStatus;
+use crate::state::StateStore;
+
+/// Days of games the digest looks ahead.
+pub const DIGEST_DAYS: i64 = 7;
+
+/// What changed since the previous digest.
+#[derive(Debug, Clone, Default)]
+pub struct DigestChanges {
+    pub changed: Vec<ScheduleChange>,
+    pub cancelled: Vec<Cancellation>,
+}
+
+/// Kept apart from the daysmart workflow's snapshot, so the digest covers the whole week
+/// regardless of which alerts the daily reminders already posted.
+fn state_key(team_id: &str) -> String {
+    format!("digest_snapshot/{}", team_id)
+}
+
+/// Compare `current` with the snapshot taken by the previous digest and store `current` for the
+/// next one. The first digest has nothing to compare with and reports no changes.
+pub async fn changes_since_last(
+    store: &StateStore,
+    team_id: &str,
+    current: ScheduleSnapshot,
+    now: DateTime<Utc>,
+    status: impl Fn(i64) -> GameStatus,
+) -> Result<DigestChanges, String> {
+    let key = state_key(team_id);
+    let previous: Option<ScheduleSnapshot> = store.get(&key).await?;
+    let found = match &previous {
+        Some(previous) => DigestChanges {
+            changed: changes::diff(previous, &current),
+            cancelled: changes::cancellations(previous, &current, now, status),
+        },
+        None => DigestChanges::default(),
+    };
+    if let Err(e) = store.put(&key, &current).await {
+        error!(error = %e, "Failed to save digest snapshot");
+    }
+    info!(changed = found.changed.len(), cancelled = found.cancelled.len(), "Compared schedule for digest");
+    Ok(found)
+}
+
+/// The weekly summary: the coming week's games, changes since the last digest (when known),
+/// and how far the ICS schedule currently runs.
+pub fn format_digest(
+    entries: &[DigestEntry],
+    changes: Option<&DigestChanges>,
+    schedule_until: Option<NaiveDate>,
+    timezone: chrono_tz::Tz,
+    locker_room_placeholder: &str,
+) -> String {
+    let mut out = String::from(":calendar_spiral: **Week ahead**");
+    if entries.is_empty() {
+        out.push_str("\nNo games this week.");
+    }
+    for entry in entries {
+        out.push_str(&format!(
+            "\n:hockey: {} at {}: vs {} ({} Jerseys)",
+            entry.start.with_timezone(&timezone).format("%a %b %e, %-I:%M %p"),
+            entry.rink,
+            entry.opponent,
+            entry.jersey
+        ));
+        if let Some(locker_room) = &entry.locker_room {
+            out.push_str(&format!(", Locker Room: {}", locker_room));
+        }
+    }
+    if let Some(changes) = changes {
+        if !changes.changed.is_empty() {
+            out.push_str("\n\n");
+            out.push_str(&changes::format_alert(&changes.changed, timezone, locker_room_placeholder));
+        }
+        if !changes.cancelled.is_empty() {
+            out.push_str("\n\n");
+            out.push_str(&changes::format_cancellations(&changes.cancelled, timezone));
+        }
+    }
+    if let Some(until) = schedule_until {
+        out.push_str(&format!("\n\n:date: Games scheduled until {}", until.format("%a %b %e, %Y")));
+    }
+    out
+}
+
+/// Games in the digest window, resolved for display.
+pub fn week_entries(day_smart: &DaySmart, now: DateTime<Utc>) -> Vec<DigestEntry> {
+    day_smart.digest_entries(&day_smart.upcoming_games(DIGEST_DAYS, now))
+}
@@ -1,90 +1,573 @@
-use tracing::{error, info};
+use std::borrow::Cow;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, instrument, warn};
+
+use crate::clock::{IdGenerator, RandomIds};
+use crate::http::Capture;
+use crate::multipart::MultipartBody;
+use crate::notifier::Embed;
+
+/// Attachments larger than this are zipped before upload. Discord rejects webhook
+/// uploads over 8 MiB; the headroom covers multipart overhead and the message body.
+pub const COMPRESS_THRESHOLD: usize = 7 * 1024 * 1024;
+
+/// Why a Discord post failed, after any retries.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum DiscordError {
+    /// Still rate limited (HTTP 429) after the last attempt, or asked to wait longer than the policy allows.
+    #[error("Discord rate limited the webhook (retry after {retry_after:?}, {attempts} attempts)")]
+    RateLimited { retry_after: Duration, attempts: u32 },
+    /// Non-success HTTP status; 5xx responses are only reported once retries are exhausted.
+    #[error("Discord webhook returned HTTP {status} after {attempts} attempts: {body}")]
+    Status { status: u16, body: String, attempts: u32 },
+    /// The request could not be sent or the response could not be read.
+    #[error("Failed to reach Discord webhook after {attempts} attempts: {message}")]
+    Transport { message: String, attempts: u32 },
+    /// The attachment could not be prepared.
+    #[error("{0}")]
+    Attachment(String),
+}
+
+/// How failed posts are retried: 429s wait for Discord's `Retry-After`, 5xx responses and
+/// transport errors back off exponentially with jitter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    /// Upper bound for a single wait. A 429 asking for longer than this is not retried.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 4, base_delay: Duration::from_millis(500), max_delay: Duration::from_secs(30) }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (1-based): half of `base_delay * 2^(retry-1)`, capped
+    /// at `max_delay`, plus up to the same amount again of random jitter.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exp = self.base_delay.saturating_mul(1u32 << (retry.saturating_sub(1)).min(16)).min(self.max_delay);
+        let half = exp / 2;
+        let jitter = getrandom::u32().unwrap_or(0) as f64 / u32::MAX as f64;
+        half + half.mul_f64(jitter)
+    }
+}
+
+/// Parse a `Retry-After` header value (seconds, possibly fractional).
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<f64>().ok().filter(|s| s.is_finite() && *s >= 0.0).map(Duration::from_secs_f64)
+}
+
+/// Longest message content Discord accepts, in characters.
+pub const MAX_CONTENT_CHARS: usize = 2000;
+
+/// Name of the file carrying the whole text of a message over Discord's length limit.
+pub const OVERFLOW_FILENAME: &str = "message.txt";
+
+/// Room kept in every part for closing and reopening a code block split across parts.
+const FENCE_ROOM: usize = 8;
+
+/// Split `content` into parts of at most `limit` characters, breaking between lines. A line too
+/// long for a part of its own is cut wherever it reaches the limit. A code block split across
+/// parts is closed at the end of one and reopened at the start of the next, so tables stay
+/// formatted.
+pub fn split_content(content: &str, limit: usize) -> Vec<String> {
+    if content.chars().count() <= limit {
+        return vec![content.to_string()];
+    }
+    let room = limit.saturating_sub(FENCE_ROOM).max(1);
+    let mut parts = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+    let mut length = 0;
+    // The line that opened the code block we're in, if any
+    let mut fence: Option<String> = None;
+    for line in content.split('\n') {
+        let chars: Vec<char> = line.chars().collect();
+        let pieces: Vec<String> = if chars.is_empty() { vec![String::new()] } else { chars.chunks(room).map(|c| c.iter().collect()).collect() };
+        for piece in pieces {
+            let added = piece.chars().count() + usize::from(!lines.is_empty());
+            if !lines.is_empty() && length + added > room {
+                if fence.is_some() {
+                    lines.push("```".to_string());
+                }
+                parts.push(lines.join("\n"));
+                lines.clear();
+                length = 0;
+                if let Some(open) = &fence {
+                    lines.push(open.clone());
+                    length = open.chars().count();
+                }
+            }
+            length += piece.chars().count() + usize::from(!lines.is_empty());
+            lines.push(piece);
+        }
+        if line.trim_start().starts_with("```") {
+            fence = match fence {
+                Some(_) => None,
+                None => Some(line.trim_start().to_string()),
+            };
+        }
+    }
+    parts.push(lines.join("\n"));
+    parts
+}
+
+/// `content` as one message of at most `limit` characters: as-is when it fits, otherwise its
+/// opening part (see [`split_content`]) pointing to the whole text, which is returned to attach
+/// as [`OVERFLOW_FILENAME`].
+pub fn fit_content(content: &str, limit: usize) -> (String, Option<String>) {
+    if content.chars().count() <= limit {
+        return (content.to_string(), None);
+    }
+    let note = format!(":page_facing_up: Full message in {}", OVERFLOW_FILENAME);
+    let opening = split_content(content, limit.saturating_sub(note.chars().count() + 1)).into_iter().next().unwrap_or_default();
+    (format!("{}\n{}", opening, note), Some(content.to_string()))
+}
+
+/// The files of a message: its attachment, if any, then the overflow text.
+fn with_overflow<'a>(attachment: Option<(&'a str, &'a [u8])>, overflow: Option<&'a str>) -> Vec<(&'a str, &'a [u8])> {
+    attachment.into_iter().chain(overflow.map(|text| (OVERFLOW_FILENAME, text.as_bytes()))).collect()
+}
+
+/// Message flag that hides link previews.
+const SUPPRESS_EMBEDS: u64 = 1 << 2;
+
+/// Discord's `allowed_mentions`: which mentions in a message actually ping.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowedMentions {
+    /// Kinds pinged wherever they appear: "roles", "users" and/or "everyone".
+    #[serde(default)]
+    pub parse: Vec<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub users: Vec<String>,
+}
+
+/// Webhook settings for a post: who it appears to come from, what it may ping, and whether link
+/// previews show. Unset fields keep the webhook's own settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostOptions {
+    /// Name shown instead of the webhook's.
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Avatar shown instead of the webhook's.
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub allowed_mentions: Option<AllowedMentions>,
+    /// Hide link previews (Discord's SUPPRESS_EMBEDS flag).
+    #[serde(default)]
+    pub suppress_embeds: bool,
+}
+
+impl PostOptions {
+    /// Add the options to a webhook payload.
+    fn apply(&self, payload: &mut serde_json::Value) {
+        if let Some(username) = &self.username {
+            payload["username"] = serde_json::json!(username);
+        }
+        if let Some(avatar_url) = &self.avatar_url {
+            payload["avatar_url"] = serde_json::json!(avatar_url);
+        }
+        if let Some(allowed_mentions) = &self.allowed_mentions {
+            payload["allowed_mentions"] = serde_json::json!(allowed_mentions);
+        }
+        if self.suppress_embeds {
+            payload["flags"] = serde_json::json!(SUPPRESS_EMBEDS);
+        }
+    }
+}
+
+/// Who a post pings: a role and individual users, by Discord id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mentions {
+    pub role_id: Option<String>,
+    pub user_ids: Vec<String>,
+}
+
+impl Mentions {
+    pub fn is_empty(&self) -> bool {
+        self.role_id.is_none() && self.user_ids.is_empty()
+    }
+
+    /// "<@&role> <@user> ...", the markup Discord pings.
+    pub fn markup(&self) -> String {
+        let role = self.role_id.iter().map(|id| format!("<@&{}>", id));
+        let users = self.user_ids.iter().map(|id| format!("<@{}>", id));
+        role.chain(users).collect::<Vec<_>>().join(" ")
+    }
+
+    /// `content` with the mentions on a line of their own before it.
+    pub fn prepend(&self, content: &str) -> String {
+        if self.is_empty() { content.to_string() } else { format!("{}\n{}", self.markup(), content) }
+    }
+
+    /// The `allowed_mentions` object pinging exactly these role and users. Anything else the
+    /// text happens to mention (@everyone, a team name written as a role) stays silent.
+    pub fn allowed_mentions(&self) -> AllowedMentions {
+        AllowedMentions { parse: Vec::new(), roles: self.role_id.iter().cloned().collect(), users: self.user_ids.clone() }
+    }
+}
+
+/// Longest thread name Discord accepts, in characters.
+const MAX_THREAD_NAME_CHARS: usize = 100;
+
+/// A message posted through a webhook, from what Discord answers to a post made with `wait=true`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostedMessage {
+    pub id: String,
+    /// The channel or thread the message landed in.
+    #[serde(default)]
+    pub channel_id: Option<String>,
+}
+
+/// `url` with the query pairs appended; left as it is when it doesn't parse.
+fn with_query(url: &str, pairs: &[(&str, &str)]) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut url) => {
+            url.query_pairs_mut().extend_pairs(pairs);
+            url.into()
+        }
+        Err(_) => url.to_string(),
+    }
+}
 
 /// Simple Discord webhook client encapsulating the hook URL.
 #[derive(Debug, Clone)]
 pub struct Discord {
     hook_url: String,
+    retry: RetryPolicy,
+    ids: Arc<dyn IdGenerator>,
+    client: reqwest::Client,
+    mentions: Mentions,
+    options: PostOptions,
+    thread_name: Option<String>,
+    thread_id: Option<String>,
 }
 
 impl Discord {
     /// Create a new Discord client with the provided webhook URL.
     pub fn new(hook_url: String) -> Self {
-        Self { hook_url }
+        Self {
+            hook_url,
+            retry: RetryPolicy::default(),
+            ids: Arc::new(RandomIds),
+            client: crate::http::shared_client(),
+            mentions: Mentions::default(),
+            options: PostOptions::default(),
+            thread_name: None,
+            thread_id: None,
+        }
+    }
+
+    /// The webhook this client posts to.
+    pub fn hook_url(&self) -> &str {
+        &self.hook_url
     }
 
-    /// Post a simple text message to the webhook URL.
-    /// Returns Ok(()) on success, or Err(String) with a description on failure.
-    pub fn post(&self, content: &str) -> Result<(), String> {
-        let payload = serde_json::json!({ "content": content });
-        match ureq::post(&self.hook_url).send_json(payload) {
-            Ok(resp) => {
-                info!(status = resp.status().as_u16(), "Posted message to Discord webhook");
-                Ok(())
+    /// Take multipart boundaries from `ids` instead of random ones.
+    pub fn with_ids(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// The same client posting to `hook_url`.
+    pub fn with_hook_url(mut self, hook_url: String) -> Self {
+        self.hook_url = hook_url;
+        self
+    }
+
+    /// Ping `mentions` with every message this client posts.
+    pub fn with_mentions(mut self, mentions: Mentions) -> Self {
+        self.mentions = mentions;
+        self
+    }
+
+    /// Post everything with `options` (e.g. a workflow's own name and avatar).
+    pub fn with_options(mut self, options: PostOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Open a thread named `name` with each post, for webhooks of forum channels. Edits go to the
+    /// thread of the message they replace.
+    pub fn with_thread_name(mut self, name: &str) -> Self {
+        self.thread_name = Some(name.chars().take(MAX_THREAD_NAME_CHARS).collect());
+        self
+    }
+
+    /// Post into the existing thread `thread_id` (e.g. one opened by [`Discord::with_thread_name`]
+    /// for an earlier post) instead of opening one. Edits are addressed in it too.
+    pub fn with_thread_id(mut self, thread_id: &str) -> Self {
+        self.thread_id = Some(thread_id.to_string());
+        self
+    }
+
+    /// `content` with the mentions before it, cut to what Discord accepts, and the whole text to
+    /// attach when it was cut.
+    fn message_content(&self, content: &str) -> (String, Option<String>) {
+        let (text, overflow) = fit_content(&self.mentions.prepend(content), MAX_CONTENT_CHARS);
+        if let Some(whole) = &overflow {
+            info!(chars = whole.chars().count(), "Message is over Discord's length limit; attaching the whole text");
+        }
+        (text, overflow)
+    }
+
+    /// The message payload for `content` with `options`, plus the `allowed_mentions` that make the
+    /// mentions ping.
+    fn message_payload(&self, content: &str, options: &PostOptions) -> serde_json::Value {
+        let mut payload = serde_json::json!({ "content": content });
+        options.apply(&mut payload);
+        if !self.mentions.is_empty() {
+            payload["allowed_mentions"] = serde_json::json!(self.mentions.allowed_mentions());
+        }
+        payload
+    }
+
+    /// Where a message goes, with `wait=true` so Discord answers with the message it created. With
+    /// a thread name the message opens the thread; with a thread id it goes into that thread.
+    fn route(&self, payload: &mut serde_json::Value) -> String {
+        let url = with_query(&self.hook_url, &[("wait", "true")]);
+        if let Some(thread_id) = &self.thread_id {
+            return with_query(&url, &[("thread_id", thread_id)]);
+        }
+        if let Some(name) = &self.thread_name {
+            payload["thread_name"] = serde_json::json!(name);
+        }
+        url
+    }
+
+    /// The URL of message `message_id`, posted through this webhook. A thread id in the query is
+    /// kept; a message that opened a forum thread has the thread's id, so it is addressed in it.
+    fn message_url(&self, message_id: &str) -> String {
+        let url = match reqwest::Url::parse(&self.hook_url) {
+            Ok(mut url) => {
+                if let Ok(mut segments) = url.path_segments_mut() {
+                    segments.pop_if_empty().push("messages").push(message_id);
+                }
+                url.into()
+            }
+            Err(_) => format!("{}/messages/{}", self.hook_url, message_id),
+        };
+        match (&self.thread_id, &self.thread_name) {
+            (Some(thread_id), _) => with_query(&url, &[("thread_id", thread_id)]),
+            (None, Some(_)) => with_query(&url, &[("thread_id", message_id)]),
+            (None, None) => url,
+        }
+    }
+
+    /// The payload replacing a message's text with `content`, and the whole text to attach when it
+    /// was cut. The mentions stay in the text but only ping when the message is first posted,
+    /// which Discord takes care of.
+    fn edit_payload(&self, content: &str) -> (serde_json::Value, Option<String>) {
+        let (text, overflow) = self.message_content(content);
+        let mut payload = serde_json::json!({ "content": text });
+        if let Some(allowed_mentions) = &self.options.allowed_mentions {
+            payload["allowed_mentions"] = serde_json::json!(allowed_mentions);
+        }
+        if !self.mentions.is_empty() {
+            payload["allowed_mentions"] = serde_json::json!(self.mentions.allowed_mentions());
+        }
+        (payload, overflow)
+    }
+
+    /// Override how failed posts are retried.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Send the request built by `request` until it succeeds, retrying per the policy. Returns the
+    /// message Discord answered with, if any.
+    #[instrument(level = "info", name = "discord_post", skip(self, url, request), fields(status = tracing::field::Empty))]
+    async fn send_with_retry<F>(&self, what: &str, method: &'static str, url: &str, request: F) -> Result<Option<PostedMessage>, DiscordError>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let capture = Capture::start(method, url);
+            let response = request().send().await;
+            if let Ok(resp) = &response {
+                tracing::Span::current().record("status", resp.status().as_u16());
             }
-            Err(e) => {
-                error!(error = %e, "Failed to post to Discord webhook");
-                Err(format!("Failed to post to Discord webhook: {}", e))
+            let (error, wait) = match response {
+                Ok(resp) if resp.status().is_success() => {
+                    let status = resp.status().as_u16();
+                    capture.finish(status, None);
+                    info!(status, attempt, "Posted {} to Discord webhook", what);
+                    // 204 without `wait=true`; otherwise the message, whose id allows editing it later
+                    let body = resp.text().await.unwrap_or_default();
+                    return Ok(serde_json::from_str(&body).ok());
+                }
+                Ok(resp) if resp.status().as_u16() == 429 => {
+                    capture.finish(429, None);
+                    let retry_after = resp
+                        .headers()
+                        .get("retry-after")
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(parse_retry_after)
+                        .unwrap_or_else(|| self.retry.backoff(attempt));
+                    let error = DiscordError::RateLimited { retry_after, attempts: attempt };
+                    // Don't sit out a long rate limit inside a Lambda invocation
+                    let wait = (retry_after <= self.retry.max_delay).then_some(retry_after);
+                    (error, wait)
+                }
+                Ok(resp) => {
+                    let status = resp.status();
+                    let body = resp.text().await.unwrap_or_default();
+                    capture.finish(status.as_u16(), Some(&body));
+                    let retryable = status.is_server_error();
+                    let error = DiscordError::Status { status: status.as_u16(), body, attempts: attempt };
+                    (error, retryable.then(|| self.retry.backoff(attempt)))
+                }
+                Err(e) => {
+                    capture.failed(&e);
+                    (DiscordError::Transport { message: e.to_string(), attempts: attempt }, Some(self.retry.backoff(attempt)))
+                }
+            };
+            match wait {
+                Some(wait) if attempt < max_attempts => {
+                    warn!(error = %error, attempt, wait_ms = wait.as_millis() as u64, "Retrying Discord post");
+                    tokio::time::sleep(wait).await;
+                    attempt += 1;
+                }
+                _ => {
+                    error!(error = %error, "Failed to post {} to Discord webhook", what);
+                    return Err(error);
+                }
             }
         }
     }
 
+    /// Post a simple text message to the webhook URL, retrying rate limits and server errors.
+    /// Returns the message, for [`Discord::edit`].
+    pub async fn post(&self, content: &str) -> Result<Option<PostedMessage>, DiscordError> {
+        self.post_with_options(content, &self.options).await
+    }
+
+    /// [`Discord::post`] with `options` instead of the client's own.
+    pub async fn post_with_options(&self, content: &str, options: &PostOptions) -> Result<Option<PostedMessage>, DiscordError> {
+        self.post_message(content, options, None).await
+    }
+
+    /// Post `content` as one message, with the file when there is one. Content over Discord's
+    /// limit is cut between lines and the whole text attached as [`OVERFLOW_FILENAME`], so a
+    /// failed post never leaves part of a message behind to be posted again.
+    async fn post_message(&self, content: &str, options: &PostOptions, attachment: Option<(&str, &[u8])>) -> Result<Option<PostedMessage>, DiscordError> {
+        let (text, overflow) = self.message_content(content);
+        let mut payload = self.message_payload(&text, options);
+        let url = self.route(&mut payload);
+        let files = with_overflow(attachment, overflow.as_deref());
+        if files.is_empty() {
+            return self.send_with_retry("message", "POST", &url, || self.client.post(&url).json(&payload)).await;
+        }
+        let (content_type, body) = self.multipart_body(payload, &files);
+        self.send_with_retry("message with attachment", "POST", &url, || {
+            self.client.post(&url).header(reqwest::header::CONTENT_TYPE, &content_type).body(body.clone())
+        })
+        .await
+    }
+
+    /// Post a single embed with no message text.
+    pub async fn post_embed(&self, embed: &Embed) -> Result<(), DiscordError> {
+        let mut card = serde_json::json!({ "title": embed.title, "description": embed.description });
+        if let Some(url) = &embed.url {
+            card["url"] = serde_json::json!(url);
+        }
+        if let Some(color) = embed.color {
+            card["color"] = serde_json::json!(color);
+        }
+        let mut payload = serde_json::json!({ "embeds": [card] });
+        self.options.apply(&mut payload);
+        let url = self.route(&mut payload);
+        self.send_with_retry("embed", "POST", &url, || self.client.post(&url).json(&payload)).await.map(drop)
+    }
+
+    /// Replace the text of message `message_id`, posted earlier through this webhook, with
+    /// `content`. Content over Discord's limit is cut like a new post, with the whole text attached.
+    pub async fn edit(&self, message_id: &str, content: &str) -> Result<(), DiscordError> {
+        self.edit_message(message_id, content, None).await
+    }
+
+    /// [`Discord::edit`], also replacing the message's attachment with `filename`.
+    pub async fn edit_with_attachment(&self, message_id: &str, content: &str, filename: &str, bytes: &[u8]) -> Result<(), DiscordError> {
+        let (filename, bytes) = self.prepare_attachment(filename, bytes)?;
+        self.edit_message(message_id, content, Some((&filename, &bytes))).await
+    }
+
+    async fn edit_message(&self, message_id: &str, content: &str, attachment: Option<(&str, &[u8])>) -> Result<(), DiscordError> {
+        let (payload, overflow) = self.edit_payload(content);
+        let url = self.message_url(message_id);
+        let files = with_overflow(attachment, overflow.as_deref());
+        if files.is_empty() {
+            return self.send_with_retry("message edit", "PATCH", &url, || self.client.patch(&url).json(&payload)).await.map(drop);
+        }
+        let (content_type, body) = self.multipart_body(payload, &files);
+        self.send_with_retry("message edit with attachment", "PATCH", &url, || {
+            self.client.patch(&url).header(reqwest::header::CONTENT_TYPE, &content_type).body(body.clone())
+        })
+        .await
+        .map(drop)
+    }
+
+    /// The file as uploaded: zipped as `<filename>.zip` when over [`COMPRESS_THRESHOLD`].
+    fn prepare_attachment<'a>(&self, filename: &'a str, bytes: &'a [u8]) -> Result<(Cow<'a, str>, Cow<'a, [u8]>), DiscordError> {
+        if bytes.len() > COMPRESS_THRESHOLD {
+            let (zip_name, zipped) = zip_attachment(filename, bytes)?;
+            info!(original_bytes = bytes.len(), zipped_bytes = zipped.len(), "Compressed oversized attachment");
+            Ok((Cow::Owned(zip_name), Cow::Owned(zipped)))
+        } else {
+            Ok((Cow::Borrowed(filename), Cow::Borrowed(bytes)))
+        }
+    }
+
+    /// Content type and multipart/form-data body carrying `payload` and `files` as its attachments.
+    fn multipart_body(&self, mut payload: serde_json::Value, files: &[(&str, &[u8])]) -> (String, Vec<u8>) {
+        // Build payload_json for Discord attachments metadata
+        let attachments: Vec<serde_json::Value> =
+            files.iter().enumerate().map(|(id, (filename, _))| serde_json::json!({ "id": id, "filename": filename })).collect();
+        payload["attachments"] = serde_json::json!(attachments);
+        let payload_json = payload.to_string();
+
+        let mut multipart = MultipartBody::new(self.ids.as_ref());
+        multipart.text("payload_json", Some("application/json"), &payload_json);
+        for (id, (filename, bytes)) in files.iter().enumerate() {
+            multipart.file(&format!("files[{}]", id), filename, bytes);
+        }
+        multipart.finish()
+    }
+
     /// Post a message with a single file attachment to a Discord webhook using multipart/form-data.
     /// See: https://discord.com/developers/docs/resources/webhook#execute-webhook
     /// The filename is what will appear in Discord; bytes are the file content.
-    pub fn post_with_attachment(&self, content: &str, filename: &str, bytes: &[u8]) -> Result<(), String> {
-        // Build payload_json for Discord attachments metadata
-        let payload_json = serde_json::json!({
-            "content": content,
-            "attachments": [ { "id": 0, "filename": filename } ]
-        }).to_string();
-
-        // Build a simple multipart/form-data body manually to avoid extra crate features
-        let boundary = format!("---------------------------{:x}{:x}", rand_seed(), rand_seed());
-        let mut body: Vec<u8> = Vec::new();
-        let crlf = b"\r\n";
-
-        // Part 1: payload_json
-        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
-        body.extend_from_slice(b"Content-Disposition: form-data; name=\"payload_json\"\r\n");
-        body.extend_from_slice(b"Content-Type: application/json\r\n\r\n");
-        body.extend_from_slice(payload_json.as_bytes());
-        body.extend_from_slice(crlf);
-
-        // Part 2: file as files[0]
-        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
-        body.extend_from_slice(format!("Content-Disposition: form-data; name=\"files[0]\"; filename=\"{}\"\r\n", escape_header_value(filename)).as_bytes());
-        body.extend_from_slice(b"Content-Type: application/octet-stream\r\n\r\n");
-        body.extend_from_slice(bytes);
-        body.extend_from_slice(crlf);
-
-        // Close boundary
-        body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
-
-        let content_type = format!("multipart/form-data; boundary={}", boundary);
-
-        let req = ureq::post(&self.hook_url).content_type(&content_type);
-        match req.send(&body) {
-            Ok(resp) => {
-                info!(status = resp.status().as_u16(), "Posted message with attachment to Discord webhook");
-                Ok(())
-            }
-            Err(e) => {
-                error!(error = %e, "Failed to post attachment to Discord webhook");
-                Err(format!("Failed to post attachment to Discord webhook: {}", e))
-            }
-        }
+    /// Attachments over the compression threshold are sent as `<filename>.zip` containing the original file.
+    /// Content over Discord's limit is cut, with the whole text attached next to the file.
+    /// Returns the message, for [`Discord::edit_with_attachment`].
+    pub async fn post_with_attachment(&self, content: &str, filename: &str, bytes: &[u8]) -> Result<Option<PostedMessage>, DiscordError> {
+        let (filename, bytes) = self.prepare_attachment(filename, bytes)?;
+        self.post_message(content, &self.options, Some((&filename, &bytes))).await
     }
 }
 
-// Tiny helper to make a boundary that's unlikely to collide; not cryptographically strong.
-fn rand_seed() -> u64 {
-    // Use a simple time-based seed; if std::time errors, fall back to a constant.
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_nanos() as u64)
-        .unwrap_or(0xABCDEF01)
+/// Wrap a single file in a deflate-compressed zip archive, preserving its name inside the archive.
+/// Returns the archive filename (`<filename>.zip`) and its bytes.
+pub fn zip_attachment(filename: &str, bytes: &[u8]) -> Result<(String, Vec<u8>), DiscordError> {
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    writer
+        .start_file(filename, options)
+        .and_then(|_| writer.write_all(bytes).map_err(zip::result::ZipError::from))
+        .map_err(|e| DiscordError::Attachment(format!("Failed to zip attachment: {}", e)))?;
+    let cursor = writer.finish().map_err(|e| DiscordError::Attachment(format!("Failed to zip attachment: {}", e)))?;
+    Ok((format!("{}.zip", filename), cursor.into_inner()))
 }
-
-// Escape double quotes in header values if any
-fn escape_header_value(s: &str) -> String { s.replace('"', "'") }
-
@@ -0,0 +1,273 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::{error, info};
+
+use crate::http::Capture;
+
+const API_BASE: &str = "https://discord.com/api/v10";
+
+/// Discord allows at most five buttons per action row.
+const MAX_BUTTONS_PER_ROW: usize = 5;
+
+/// Longest scheduled event name and location Discord accepts, in characters.
+const MAX_EVENT_NAME_CHARS: usize = 100;
+/// Longest scheduled event description Discord accepts, in characters.
+const MAX_EVENT_DESCRIPTION_CHARS: usize = 1000;
+
+/// A link-style message button.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkButton {
+    pub label: String,
+    pub url: String,
+}
+
+/// Message `components` holding the given link buttons, five per action row.
+/// See: https://discord.com/developers/docs/interactions/message-components
+pub fn link_components(buttons: &[LinkButton]) -> serde_json::Value {
+    let rows: Vec<serde_json::Value> = buttons
+        .chunks(MAX_BUTTONS_PER_ROW)
+        .map(|row| {
+            let buttons: Vec<serde_json::Value> = row
+                .iter()
+                // type 2 = button, style 5 = link
+                .map(|b| serde_json::json!({ "type": 2, "style": 5, "label": b.label, "url": b.url }))
+                .collect();
+            serde_json::json!({ "type": 1, "components": buttons })
+        })
+        .collect();
+    serde_json::Value::Array(rows)
+}
+
+/// A Discord Scheduled Event held somewhere outside Discord, listed in the server's events with
+/// "Interested" RSVPs.
+/// See: https://discord.com/developers/docs/resources/guild-scheduled-event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledEvent {
+    pub name: String,
+    pub description: String,
+    pub location: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Someone who reacted to a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reactor {
+    pub user_id: String,
+    /// Display name, or the username when there is none.
+    pub name: String,
+}
+
+/// A Discord user as listed among a message's reactions.
+#[derive(Deserialize)]
+struct User {
+    id: String,
+    username: String,
+    #[serde(default)]
+    global_name: Option<String>,
+    #[serde(default)]
+    bot: bool,
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+/// Minimal Discord REST client authenticated with a bot token, for the things a webhook cannot do.
+#[derive(Debug, Clone)]
+pub struct DiscordBot {
+    token: String,
+    api_base: String,
+    client: reqwest::Client,
+}
+
+impl DiscordBot {
+    /// Create a new bot client with the provided bot token (without the "Bot " prefix).
+    pub fn new(token: String) -> Self {
+        Self { token, api_base: API_BASE.to_string(), client: crate::http::shared_client() }
+    }
+
+    /// Send requests to `api_base` instead of Discord's API, e.g. a local server in tests.
+    pub fn with_api_base(mut self, api_base: &str) -> Self {
+        self.api_base = api_base.trim_end_matches('/').to_string();
+        self
+    }
+
+    fn authorization(&self) -> String {
+        format!("Bot {}", self.token)
+    }
+
+    /// Send a direct message to a user by opening (or reusing) their DM channel, with optional link buttons.
+    /// Returns Ok(()) on success, or Err(String) with a description on failure.
+    pub async fn send_dm(&self, user_id: &str, content: &str, buttons: &[LinkButton]) -> Result<(), String> {
+        let url = format!("{}/users/@me/channels", self.api_base);
+        let capture = Capture::start("POST", &url);
+        let channel: serde_json::Value = self
+            .client
+            .post(&url)
+            .header("Authorization", self.authorization())
+            .json(&serde_json::json!({ "recipient_id": user_id }))
+            .send()
+            .await
+            .inspect(|resp| capture.finish(resp.status().as_u16(), None))
+            .inspect_err(|e| capture.failed(e))
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| format!("Failed to open DM channel: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to read DM channel response: {}", e))?;
+        let channel_id = channel
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "DM channel response had no id".to_string())?;
+
+        let url = format!("{}/channels/{}/messages", self.api_base, channel_id);
+        let capture = Capture::start("POST", &url);
+        let result = self
+            .client
+            .post(&url)
+            .header("Authorization", self.authorization())
+            .json(&serde_json::json!({ "content": content, "components": link_components(buttons) }))
+            .send()
+            .await
+            .inspect(|resp| capture.finish(resp.status().as_u16(), None))
+            .inspect_err(|e| capture.failed(e))
+            .and_then(|resp| resp.error_for_status());
+        match result {
+            Ok(resp) => {
+                info!(status = resp.status().as_u16(), "Sent Discord direct message");
+                Ok(())
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to send Discord direct message");
+                Err(format!("Failed to send direct message: {}", e))
+            }
+        }
+    }
+
+    /// Users who reacted to message `message_id` in `channel_id` with `emoji` (e.g. "✅"), bots
+    /// left out. Discord lists at most 100, plenty for a team.
+    pub async fn reactors(&self, channel_id: &str, message_id: &str, emoji: &str) -> Result<Vec<Reactor>, String> {
+        let mut url = reqwest::Url::parse(&self.api_base).map_err(|e| format!("Invalid Discord API base: {}", e))?;
+        url.path_segments_mut()
+            .map_err(|_| "Invalid Discord API base".to_string())?
+            .extend(["channels", channel_id, "messages", message_id, "reactions", emoji]);
+        url.query_pairs_mut().append_pair("limit", "100");
+        let capture = Capture::start("GET", url.as_str());
+        let users: Vec<User> = self
+            .client
+            .get(url.clone())
+            .header("Authorization", self.authorization())
+            .send()
+            .await
+            .inspect(|resp| capture.finish(resp.status().as_u16(), None))
+            .inspect_err(|e| capture.failed(e))
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| format!("Failed to read {} reactions: {}", emoji, e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to read {} reactions response: {}", emoji, e))?;
+        Ok(users
+            .into_iter()
+            .filter(|user| !user.bot)
+            .map(|user| Reactor { name: user.global_name.unwrap_or(user.username), user_id: user.id })
+            .collect())
+    }
+
+    /// React to message `message_id` in `channel_id` with `emoji` as the bot. Needs the Add
+    /// Reactions permission in the channel.
+    pub async fn add_reaction(&self, channel_id: &str, message_id: &str, emoji: &str) -> Result<(), String> {
+        let mut url = reqwest::Url::parse(&self.api_base).map_err(|e| format!("Invalid Discord API base: {}", e))?;
+        url.path_segments_mut()
+            .map_err(|_| "Invalid Discord API base".to_string())?
+            .extend(["channels", channel_id, "messages", message_id, "reactions", emoji, "@me"]);
+        let capture = Capture::start("PUT", url.as_str());
+        self.client
+            .put(url.clone())
+            .header("Authorization", self.authorization())
+            .header("Content-Length", "0")
+            .send()
+            .await
+            .inspect(|resp| capture.finish(resp.status().as_u16(), None))
+            .inspect_err(|e| capture.failed(e))
+            .and_then(|resp| resp.error_for_status())
+            .map(|_| ())
+            .map_err(|e| format!("Failed to add {} reaction: {}", emoji, e))
+    }
+
+    /// Create `event` in the server `guild_id` and return its id. The bot needs the Manage Events
+    /// permission there; text over Discord's limits is cut short.
+    pub async fn create_scheduled_event(&self, guild_id: &str, event: &ScheduledEvent) -> Result<String, String> {
+        let url = format!("{}/guilds/{}/scheduled-events", self.api_base, guild_id);
+        let capture = Capture::start("POST", &url);
+        let created: serde_json::Value = self
+            .client
+            .post(&url)
+            .header("Authorization", self.authorization())
+            .json(&event_body(event))
+            .send()
+            .await
+            .inspect(|resp| capture.finish(resp.status().as_u16(), None))
+            .inspect_err(|e| capture.failed(e))
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| format!("Failed to create scheduled event: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to read scheduled event response: {}", e))?;
+        created
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| "Scheduled event response had no id".to_string())
+    }
+
+    /// Replace the name, description, location and times of the scheduled event `event_id`.
+    pub async fn update_scheduled_event(&self, guild_id: &str, event_id: &str, event: &ScheduledEvent) -> Result<(), String> {
+        let url = format!("{}/guilds/{}/scheduled-events/{}", self.api_base, guild_id, event_id);
+        let capture = Capture::start("PATCH", &url);
+        self.client
+            .patch(&url)
+            .header("Authorization", self.authorization())
+            .json(&event_body(event))
+            .send()
+            .await
+            .inspect(|resp| capture.finish(resp.status().as_u16(), None))
+            .inspect_err(|e| capture.failed(e))
+            .and_then(|resp| resp.error_for_status())
+            .map(|_| ())
+            .map_err(|e| format!("Failed to update scheduled event: {}", e))
+    }
+
+    /// Delete the scheduled event `event_id`. An event already gone counts as deleted.
+    pub async fn delete_scheduled_event(&self, guild_id: &str, event_id: &str) -> Result<(), String> {
+        let url = format!("{}/guilds/{}/scheduled-events/{}", self.api_base, guild_id, event_id);
+        let capture = Capture::start("DELETE", &url);
+        let resp = self
+            .client
+            .delete(&url)
+            .header("Authorization", self.authorization())
+            .send()
+            .await
+            .inspect(|resp| capture.finish(resp.status().as_u16(), None))
+            .inspect_err(|e| capture.failed(e))
+            .map_err(|e| format!("Failed to delete scheduled event: {}", e))?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        resp.error_for_status().map(|_| ()).map_err(|e| format!("Failed to delete scheduled event: {}", e))
+    }
+}
+
+/// Create/update body for `event`, with text over Discord's limits cut short.
+fn event_body(event: &ScheduledEvent) -> serde_json::Value {
+    // privacy_level 2 = server members only, entity_type 3 = external (a location, not a channel)
+    serde_json::json!({
+        "name": truncate(&event.name, MAX_EVENT_NAME_CHARS),
+        "description": truncate(&event.description, MAX_EVENT_DESCRIPTION_CHARS),
+        "privacy_level": 2,
+        "entity_type": 3,
+        "entity_metadata": { "location": truncate(&event.location, MAX_EVENT_NAME_CHARS) },
+        "scheduled_start_time": event.start.to_rfc3339(),
+        "scheduled_end_time": event.end.to_rfc3339(),
+    })
+}
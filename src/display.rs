@@ -0,0 +1,170 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_HOME_TEAM: &str = "Home";
+pub const DEFAULT_VISITING_TEAM: &str = "Visitor";
+pub const DEFAULT_OPPONENT: &str = "TBD";
+pub const DEFAULT_TEAM: &str = "Unknown Team";
+pub const DEFAULT_RINK: &str = "Unknown Arena";
+pub const DEFAULT_LOCKER_ROOM: &str = "none";
+
+/// Text shown in place of names DaySmart leaves out. Reminders, recaps, alerts and calendar
+/// exports all read it from here, so a missing name reads the same everywhere. The BenchApp CSV
+/// leaves missing names empty instead, so BenchApp doesn't take a placeholder for a real team.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placeholders {
+    #[serde(default = "default_home_team")]
+    pub home_team: String,
+    #[serde(default = "default_visiting_team")]
+    pub visiting_team: String,
+    /// Our opponent, when the other side of a game has not been set yet.
+    #[serde(default = "default_opponent")]
+    pub opponent: String,
+    #[serde(default = "default_rink")]
+    pub rink: String,
+    /// Shown where a missing locker room has to be spelled out, e.g. schedule change alerts.
+    /// Elsewhere the locker room line is left out.
+    #[serde(default = "default_locker_room")]
+    pub locker_room: String,
+}
+
+fn default_home_team() -> String { DEFAULT_HOME_TEAM.to_string() }
+
+fn default_visiting_team() -> String { DEFAULT_VISITING_TEAM.to_string() }
+
+fn default_opponent() -> String { DEFAULT_OPPONENT.to_string() }
+
+fn default_rink() -> String { DEFAULT_RINK.to_string() }
+
+fn default_locker_room() -> String { DEFAULT_LOCKER_ROOM.to_string() }
+
+impl Default for Placeholders {
+    fn default() -> Self {
+        Self {
+            home_team: default_home_team(),
+            visiting_team: default_visiting_team(),
+            opponent: default_opponent(),
+            rink: default_rink(),
+            locker_room: default_locker_room(),
+        }
+    }
+}
+
+/// A team name, or the placeholder for the role the team has in the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TeamName<'a> {
+    name: Option<&'a str>,
+    placeholder: &'a str,
+}
+
+impl<'a> TeamName<'a> {
+    pub fn new(name: Option<&'a str>, placeholder: &'a str) -> Self {
+        Self { name, placeholder }
+    }
+
+    /// The name DaySmart gave, if any.
+    pub fn name(&self) -> Option<&'a str> {
+        self.name
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.name.unwrap_or(self.placeholder)
+    }
+
+    /// Whether an actual team is set, rather than no team or, in a `playoff` game, a bracket
+    /// placeholder. Regular season teams are taken at their name, "Bye Bye Birdies" included.
+    pub fn is_decided(&self, playoff: bool) -> bool {
+        self.name.is_some_and(|name| !(playoff && is_bracket_placeholder(name)))
+    }
+}
+
+/// Words a playoff bracket uses in place of a team that isn't known yet.
+const BRACKET_PLACEHOLDERS: [&str; 7] = ["winner", "loser", "tbd", "tba", "to be determined", "seed", "bye"];
+
+/// Whether a team name is a bracket placeholder such as "Winner of Game 3", "Loser G2", "TBD" or
+/// "#2 Seed" rather than a team. Team names that merely start with such a word
+/// ("Winners Circle") are not.
+pub fn is_bracket_placeholder(name: &str) -> bool {
+    let name = name.trim().to_ascii_lowercase();
+    let whole_word = |word: &str| name[word.len()..].chars().next().is_none_or(|c| !c.is_alphanumeric());
+    name.is_empty()
+        || name.ends_with(" seed")
+        || BRACKET_PLACEHOLDERS.iter().any(|word| name.starts_with(word) && whole_word(word))
+}
+
+impl fmt::Display for TeamName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<&str> for TeamName<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// A rink name, or the rink placeholder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RinkName<'a> {
+    name: Option<&'a str>,
+    placeholder: &'a str,
+}
+
+impl<'a> RinkName<'a> {
+    pub fn new(name: Option<&'a str>, placeholder: &'a str) -> Self {
+        Self { name, placeholder }
+    }
+
+    /// The name DaySmart gave, if any.
+    pub fn name(&self) -> Option<&'a str> {
+        self.name
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.name.unwrap_or(self.placeholder)
+    }
+}
+
+impl fmt::Display for RinkName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<&str> for RinkName<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// Our locker room for a game. Displays as the placeholder when none is assigned; use
+/// [`LockerRoom::line`] where the locker room should simply be left out instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockerRoom<'a> {
+    name: Option<&'a str>,
+    placeholder: &'a str,
+}
+
+impl<'a> LockerRoom<'a> {
+    pub fn new(name: Option<&'a str>, placeholder: &'a str) -> Self {
+        Self { name, placeholder }
+    }
+
+    /// The assigned locker room, if any.
+    pub fn name(&self) -> Option<&'a str> {
+        self.name
+    }
+
+    /// "Locker Room: <name>" when one is assigned.
+    pub fn line(&self) -> Option<String> {
+        self.name.map(|name| format!("Locker Room: {}", name))
+    }
+}
+
+impl fmt::Display for LockerRoom<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name.unwrap_or(self.placeholder))
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::discord_bot::{DiscordBot, LinkButton};
+use crate::state::StateStore;
+
+/// Players who opted in to game-day direct messages for a team.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DmSubscribers {
+    pub user_ids: BTreeSet<String>,
+}
+
+fn subscribers_key(team_id: &str) -> String {
+    format!("dm_subscribers/{}", team_id)
+}
+
+fn sent_key(team_id: &str, game_id: i64) -> String {
+    format!("dm_sent/{}/{}", team_id, game_id)
+}
+
+/// Load the opted-in Discord user ids for a team.
+pub async fn subscribers(store: &StateStore, team_id: &str) -> Result<DmSubscribers, String> {
+    Ok(store.get(&subscribers_key(team_id)).await?.unwrap_or_default())
+}
+
+/// Opt a user in. Returns true if they were not already registered.
+pub async fn subscribe(store: &StateStore, team_id: &str, user_id: &str) -> Result<bool, String> {
+    let mut subs = subscribers(store, team_id).await?;
+    let added = subs.user_ids.insert(user_id.to_string());
+    if added {
+        store.put(&subscribers_key(team_id), &subs).await?;
+    }
+    Ok(added)
+}
+
+/// Opt a user out. Returns true if they were registered.
+pub async fn unsubscribe(store: &StateStore, team_id: &str, user_id: &str) -> Result<bool, String> {
+    let mut subs = subscribers(store, team_id).await?;
+    let removed = subs.user_ids.remove(user_id);
+    if removed {
+        store.put(&subscribers_key(team_id), &subs).await?;
+    }
+    Ok(removed)
+}
+
+/// True when the game starts on the same local calendar day as `now` in `timezone`.
+pub fn is_game_day(game_dt: DateTime<Utc>, now: DateTime<Utc>, timezone: chrono_tz::Tz) -> bool {
+    game_dt.with_timezone(&timezone).date_naive() == now.with_timezone(&timezone).date_naive()
+}
+
+/// DM the game-day reminder, with its link buttons, to every subscriber and return a short summary.
+/// The DMs go out once per game: later runs on game day find the marker left for `game_id`, which
+/// expires at `game_start`.
+pub async fn send_game_day_dms(
+    bot: &DiscordBot,
+    store: &StateStore,
+    team_id: &str,
+    game_id: i64,
+    game_start: DateTime<Utc>,
+    content: &str,
+    buttons: &[LinkButton],
+) -> String {
+    match store.get::<usize>(&sent_key(team_id, game_id)).await {
+        Ok(Some(_)) => return "game-day DMs already sent".to_string(),
+        Ok(None) => {}
+        Err(e) => {
+            error!(error = %e, "Failed to check game-day DMs");
+            return format!("DMs skipped: {}", e);
+        }
+    }
+    let subs = match subscribers(store, team_id).await {
+        Ok(subs) => subs,
+        Err(e) => {
+            error!(error = %e, "Failed to load DM subscribers");
+            return format!("DMs skipped: {}", e);
+        }
+    };
+    if subs.user_ids.is_empty() {
+        return "no DM subscribers".to_string();
+    }
+
+    let mut failed = 0usize;
+    for user_id in &subs.user_ids {
+        if let Err(e) = bot.send_dm(user_id, content, buttons).await {
+            error!(error = %e, "Failed to DM subscriber");
+            failed += 1;
+        }
+    }
+    let sent = subs.user_ids.len() - failed;
+    info!(sent, failed, "Sent game-day DMs");
+    // Failed DMs aren't retried, as that would DM everyone else again
+    if sent > 0
+        && let Err(e) = store.put_expiring(&sent_key(team_id, game_id), &sent, game_start).await
+    {
+        error!(error = %e, "Failed to record game-day DMs");
+    }
+    if failed == 0 { format!("{} DMs sent", sent) } else { format!("{} DMs sent, {} failed", sent, failed) }
+}
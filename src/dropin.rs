@@ -0,0 +1,149 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+use crate::daysmart::{fetch_body, parse_event_start, API_BASE, MAX_LOOKAHEAD_DAYS};
+use crate::display::{RinkName, DEFAULT_RINK};
+use crate::http::HttpPolicy;
+use crate::links::percent_encode;
+use crate::model::event::EventsDocument;
+use crate::model::team::Included;
+
+/// Which drop-in/pickup sessions to watch for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropInConfig {
+    /// Extra DaySmart `filter[...]` query parameters selecting the facility/program,
+    /// e.g. {"resource.facility_id": "1", "event_type_id": "d"}.
+    #[serde(default)]
+    pub filters: BTreeMap<String, String>,
+    /// Local weekdays to announce (e.g. ["mon", "tue"]); empty means any day.
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>,
+    /// Earliest local start time to announce, as "HH:MM".
+    #[serde(default)]
+    pub earliest_start: Option<String>,
+    /// How far ahead to look for sessions.
+    #[serde(default = "default_days_ahead")]
+    pub days_ahead: i64,
+}
+
+fn default_days_ahead() -> i64 { 14 }
+
+impl DropInConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_LOOKAHEAD_DAYS).contains(&self.days_ahead) {
+            return Err(format!("dropin.days_ahead must be between 1 and {} (got {})", MAX_LOOKAHEAD_DAYS, self.days_ahead));
+        }
+        if let Some(earliest_start) = &self.earliest_start
+            && NaiveTime::parse_from_str(earliest_start, "%H:%M").is_err()
+        {
+            return Err(format!("dropin.earliest_start must be a time such as \"17:00\" (got {:?})", earliest_start));
+        }
+        Ok(())
+    }
+
+    /// State key for the sessions already announced. The filters are part of it, so two
+    /// drop-in configs for one company don't overwrite each other's seen set.
+    pub fn seen_key(&self, company: &str) -> String {
+        if self.filters.is_empty() {
+            return format!("dropin_seen/{}", company);
+        }
+        let filters: Vec<String> = self.filters.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        format!("dropin_seen/{}/{}", company, percent_encode(&filters.join("&")))
+    }
+}
+
+/// A single drop-in session slot.
+#[derive(Debug, Clone)]
+pub struct DropInSlot {
+    pub id: String,
+    pub dt: DateTime<Utc>,
+    pub name: Option<String>,
+    pub rink: Option<String>,
+}
+
+/// Parse an `events` collection document into slots, resolving rink names from included resources.
+pub fn parse_slots(body: &str) -> Result<Vec<DropInSlot>, String> {
+    let doc: EventsDocument = serde_json::from_str(body).map_err(|e| format!("Failed to deserialize events document: {}", e))?;
+    let rinks: HashMap<String, String> = doc
+        .included
+        .into_iter()
+        .filter_map(|item| match item {
+            Included::Resource { id, attributes, .. } => attributes.name.map(|name| (id, name)),
+            _ => None,
+        })
+        .collect();
+
+    let mut slots: Vec<DropInSlot> = doc
+        .data
+        .into_iter()
+        .filter_map(|event| {
+            let dt = parse_event_start(&event.attributes)?;
+            let rink = event.attributes.resource_id.and_then(|rid| rinks.get(&rid.to_string()).cloned());
+            let name = event.attributes.desc.filter(|d| !d.trim().is_empty());
+            Some(DropInSlot { id: event.id, dt, name, rink })
+        })
+        .collect();
+    slots.sort_by_key(|s| s.dt);
+    Ok(slots)
+}
+
+/// DaySmart `events` URL for sessions in the lookahead window, with the configured filters
+/// percent-encoded.
+pub fn events_url(config: &DropInConfig, company: &str, now: DateTime<Utc>) -> String {
+    let from = now.date_naive();
+    let to = (now + chrono::Duration::days(config.days_ahead)).date_naive();
+    let mut url = format!(
+        "{}/events?cache[save]=false&filter[start_date__gte]={}&filter[start_date__lte]={}&include=resource",
+        API_BASE, from, to
+    );
+    for (key, value) in &config.filters {
+        url.push_str(&format!("&filter[{}]={}", percent_encode(key), percent_encode(value)));
+    }
+    url.push_str(&format!("&company={}", percent_encode(company)));
+    url
+}
+
+/// Fetch upcoming sessions for the configured facility/program.
+#[instrument(level = "info", skip(config, policy))]
+pub async fn fetch_slots(config: &DropInConfig, company: &str, now: DateTime<Utc>, policy: &HttpPolicy) -> Result<Vec<DropInSlot>, String> {
+    let body = fetch_body(&events_url(config, company, now), policy).await?;
+    let slots = parse_slots(&body)?;
+    info!(count = slots.len(), "Fetched drop-in sessions");
+    Ok(slots)
+}
+
+/// True when the slot is in the future and falls on a configured weekday at or after the earliest start time.
+pub fn matches(slot: &DropInSlot, config: &DropInConfig, now: DateTime<Utc>, timezone: chrono_tz::Tz) -> bool {
+    if slot.dt < now {
+        return false;
+    }
+    let local = slot.dt.with_timezone(&timezone);
+    if !config.weekdays.is_empty() && !config.weekdays.contains(&local.weekday()) {
+        return false;
+    }
+    match config.earliest_start.as_deref().and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok()) {
+        Some(earliest) => local.time() >= earliest,
+        None => true,
+    }
+}
+
+/// Slots not announced before, per the set of previously seen slot ids.
+pub fn unseen<'a>(slots: &'a [DropInSlot], seen: &BTreeSet<String>) -> Vec<&'a DropInSlot> {
+    slots.iter().filter(|s| !seen.contains(&s.id)).collect()
+}
+
+/// Discord announcement listing the given slots.
+pub fn format_announcement(slots: &[&DropInSlot], timezone: chrono_tz::Tz) -> String {
+    let mut out = String::from(":ice_cube: New drop-in sessions posted:");
+    for slot in slots {
+        let local = slot.dt.with_timezone(&timezone);
+        out.push_str(&format!("\n• {} at {}", local.format("%a %b %e, %-I:%M %p"), RinkName::new(slot.rink.as_deref(), DEFAULT_RINK)));
+        if let Some(name) = &slot.name {
+            out.push_str(&format!(" ({})", name));
+        }
+    }
+    out
+}
@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+
+use crate::links;
+
+/// One game in the weekly digest, with names already resolved.
+#[derive(Debug, Clone)]
+pub struct DigestEntry {
+    pub start: DateTime<Utc>,
+    /// Scheduled end, from the booked ice slot.
+    pub end: DateTime<Utc>,
+    pub opponent: String,
+    pub jersey: String,
+    pub rink: String,
+    pub locker_room: Option<String>,
+}
+
+/// A rendered email: HTML body plus a plain-text alternative for clients that don't show HTML.
+#[derive(Debug, Clone)]
+pub struct EmailBody {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+/// Render the week's games as an email: a table with one row per game and an "Add to calendar"
+/// link for each, and the same information as plain text without any Discord markup.
+///
+/// No notifier sends email yet; this is for library users who deliver the digest themselves.
+pub fn render_weekly_digest(team_name: &str, entries: &[DigestEntry], timezone: chrono_tz::Tz) -> EmailBody {
+    let subject = match entries.len() {
+        0 => format!("{}: no games this week", team_name),
+        1 => format!("{}: 1 game this week", team_name),
+        n => format!("{}: {} games this week", team_name, n),
+    };
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html><body style=\"font-family: sans-serif\">\n<h2>{}</h2>\n",
+        escape_html(&subject)
+    );
+    let mut text = format!("{}\n", subject);
+    if entries.is_empty() {
+        html.push_str("<p>No games are scheduled this week.</p>\n");
+        text.push_str("\nNo games are scheduled this week.\n");
+    } else {
+        html.push_str(
+            "<table cellpadding=\"6\" style=\"border-collapse: collapse\">\n\
+             <tr><th align=\"left\">Date</th><th align=\"left\">Time</th><th align=\"left\">Opponent</th>\
+             <th align=\"left\">Jerseys</th><th align=\"left\">Rink</th><th align=\"left\">Locker room</th><th></th></tr>\n",
+        );
+        for entry in entries {
+            let local = entry.start.with_timezone(&timezone);
+            let date = local.format("%a %b %e").to_string();
+            let time = local.format("%-I:%M %p").to_string();
+            let locker_room = entry.locker_room.as_deref().unwrap_or("");
+            let calendar_url = links::google_calendar_url(&format!("vs {}", entry.opponent), entry.start, entry.end, &entry.rink);
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td><a href=\"{}\">Add to calendar</a></td></tr>\n",
+                escape_html(&date),
+                escape_html(&time),
+                escape_html(&entry.opponent),
+                escape_html(&entry.jersey),
+                escape_html(&entry.rink),
+                escape_html(locker_room),
+                escape_html(&calendar_url)
+            ));
+            text.push_str(&format!("\n{} {} vs {} ({} jerseys) at {}", date, time, entry.opponent, entry.jersey, entry.rink));
+            if !locker_room.is_empty() {
+                text.push_str(&format!(", locker room {}", locker_room));
+            }
+            text.push_str(&format!("\n  Add to calendar: {}\n", calendar_url));
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</body></html>\n");
+
+    EmailBody { subject, html, text }
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
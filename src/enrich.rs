@@ -0,0 +1,163 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::model::game::GameInfo;
+use crate::roster;
+use crate::rotation::{self, RotationConfig};
+use crate::state::StateStore;
+use crate::streak::{self, Streak, StreakConfig};
+
+/// Everything an enricher may look at for the game a reminder is about.
+#[derive(Debug, Clone)]
+pub struct GameContext {
+    pub game: GameInfo,
+    pub team_id: String,
+    pub now: DateTime<Utc>,
+    pub timezone: chrono_tz::Tz,
+    /// Our current streak going into the game, if any games have been played.
+    pub streak: Option<Streak>,
+}
+
+pub type EnrichFuture<'a> = Pin<Box<dyn Future<Output = Result<Option<String>, String>> + Send + 'a>>;
+
+/// A message add-on. Each enricher contributes at most one line, appended below the formatted
+/// game in pipeline order.
+pub trait Enricher: Send + Sync {
+    /// Short name used in logs.
+    fn name(&self) -> &'static str;
+
+    fn enrich<'a>(&'a self, ctx: &'a GameContext) -> EnrichFuture<'a>;
+}
+
+/// Enrichers that can be switched on per deployment via `Request::enrichers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnricherKind {
+    Scorekeeper,
+    Beverages,
+    Attendance,
+    Streak,
+    Notes,
+}
+
+impl EnricherKind {
+    /// Order used when a request does not list enrichers explicitly.
+    pub const DEFAULT_ORDER: [EnricherKind; 5] = [
+        EnricherKind::Scorekeeper,
+        EnricherKind::Beverages,
+        EnricherKind::Attendance,
+        EnricherKind::Streak,
+        EnricherKind::Notes,
+    ];
+}
+
+/// Ordered list of enrichers applied to a reminder.
+#[derive(Default)]
+pub struct Pipeline {
+    enrichers: Vec<Box<dyn Enricher>>,
+}
+
+impl Pipeline {
+    pub fn new(enrichers: Vec<Box<dyn Enricher>>) -> Self {
+        Self { enrichers }
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.enrichers.iter().map(|e| e.name()).collect()
+    }
+
+    /// Append each enricher's line to `message`. A failing enricher is logged and skipped so
+    /// one broken add-on never blocks the reminder.
+    pub async fn apply(&self, message: &mut String, ctx: &GameContext) {
+        for enricher in &self.enrichers {
+            match enricher.enrich(ctx).await {
+                Ok(Some(line)) => {
+                    message.push('\n');
+                    message.push_str(&line);
+                }
+                Ok(None) => {}
+                Err(e) => error!(error = %e, enricher = enricher.name(), "Enricher failed"),
+            }
+        }
+    }
+}
+
+/// Duty rotation (scorekeeper, beverages, ...): names whose turn it is for this game.
+pub struct DutyEnricher {
+    /// Name of the duty, also the default state key.
+    pub key: &'static str,
+    /// Line prefix, e.g. ":clipboard: Scorekeeper: ".
+    pub prefix: &'static str,
+    pub config: RotationConfig,
+    pub store: StateStore,
+}
+
+impl Enricher for DutyEnricher {
+    fn name(&self) -> &'static str {
+        self.key
+    }
+
+    fn enrich<'a>(&'a self, ctx: &'a GameContext) -> EnrichFuture<'a> {
+        Box::pin(async move {
+            let player = rotation::assignee(&self.store, &ctx.team_id, self.key, &self.config, &ctx.game.id.to_string()).await?;
+            Ok(player.map(|p| format!("{}{}", self.prefix, p)))
+        })
+    }
+}
+
+/// Fixed note appended to every reminder.
+pub struct NotesEnricher {
+    pub note: String,
+}
+
+impl Enricher for NotesEnricher {
+    fn name(&self) -> &'static str {
+        "notes"
+    }
+
+    fn enrich<'a>(&'a self, _ctx: &'a GameContext) -> EnrichFuture<'a> {
+        Box::pin(async move { Ok(Some(format!(":pushpin: {}", self.note))) })
+    }
+}
+
+/// The team's current win/loss/tie streak.
+pub struct StreakEnricher {
+    pub config: StreakConfig,
+}
+
+impl Enricher for StreakEnricher {
+    fn name(&self) -> &'static str {
+        "streak"
+    }
+
+    fn enrich<'a>(&'a self, ctx: &'a GameContext) -> EnrichFuture<'a> {
+        Box::pin(async move { Ok(streak::streak_line(ctx.streak, &self.config)) })
+    }
+}
+
+/// Expected turnout by position, from the player registry and the answers given for the game.
+/// Adds nothing until players are registered.
+pub struct AttendanceEnricher {
+    pub store: StateStore,
+}
+
+impl Enricher for AttendanceEnricher {
+    fn name(&self) -> &'static str {
+        "attendance"
+    }
+
+    fn enrich<'a>(&'a self, ctx: &'a GameContext) -> EnrichFuture<'a> {
+        Box::pin(async move {
+            let players = roster::roster(&self.store, &ctx.team_id).await?;
+            if players.players.is_empty() {
+                return Ok(None);
+            }
+            let answers = roster::rsvps(&self.store, &ctx.team_id, &ctx.game.id.to_string()).await?;
+            Ok(Some(format!(":busts_in_silhouette: {}", roster::attendance(&players, &answers).line())))
+        })
+    }
+}
@@ -0,0 +1,105 @@
+use serde_json::{Map, Value};
+
+/// Request fields that can be set from the environment, with whether the variable is taken as
+/// plain text. Everything else is JSON (numbers, booleans, arrays and objects), e.g.
+/// `WORKFLOWS=["ical","daysmart"]` or `STATE={"bucket":"hockey-state"}`.
+pub const ENV_FIELDS: &[(&str, bool)] = &[
+    ("schema_version", false),
+    ("mode", true),
+    ("discord_hook_url", true),
+    ("discord_hook_secret_arn", true),
+    ("discord_hook_parameter", true),
+    ("test_discord_hook_url", true),
+    ("destinations", false),
+    ("discord_hook_urls", false),
+    ("ical_url", true),
+    ("team_id", true),
+    ("company", true),
+    ("workflows", false),
+    ("now_override", true),
+    ("days_ahead", false),
+    ("announce_all", false),
+    ("reminder_offsets_hours", false),
+    ("quiet_hours", false),
+    ("arrive_minutes_early", false),
+    ("venue_notes", false),
+    ("include_practices", false),
+    ("plain_times", false),
+    ("game_threads", false),
+    ("mention_role_id", true),
+    ("mention_user_ids", false),
+    ("benchapp_months_ahead", false),
+    ("http", false),
+    ("http_cache", false),
+    ("timezone", true),
+    ("state", false),
+    ("bot_token", true),
+    ("scheduled_events", false),
+    ("rsvp_reactions", false),
+    ("goalie_escalation", false),
+    ("links", false),
+    ("scorekeeper", false),
+    ("beverages", false),
+    ("notes", true),
+    ("message_template", true),
+    ("streak", false),
+    ("enrichers", false),
+    ("fee_split", false),
+    ("dropin", false),
+    ("no_games_notice", false),
+    ("tournament", false),
+    ("facility_hours", false),
+    ("feed", false),
+    ("benchapp", false),
+    ("benchapp_names", false),
+    ("jerseys", false),
+    ("placeholders", false),
+    ("ics_publish", false),
+    ("league_bundle", false),
+    ("deadline_ms", false),
+    ("webhook_rotation", false),
+    ("canary_hook_url", true),
+    ("admin_hook_url", true),
+    ("failure_budget", false),
+    ("error_hook_url", true),
+    ("explain_game_id", false),
+    ("dry_run", false),
+    ("preview_horizon_days", false),
+];
+
+/// Ways of giving the production webhook; the payload naming one of them rules out the others.
+const HOOK_FIELDS: [&str; 3] = ["discord_hook_url", "discord_hook_secret_arn", "discord_hook_parameter"];
+
+/// Environment variable for a request field: the field name upper-cased, e.g. `TEAM_ID`.
+pub fn env_var(field: &str) -> String {
+    field.to_ascii_uppercase()
+}
+
+/// Fill the fields the payload leaves out (or sets to null) from the environment, looked up with
+/// `env`. A missing or null payload counts as an empty one, so the function can run on
+/// environment variables alone.
+pub fn with_env_defaults(payload: Value, env: impl Fn(&str) -> Option<String>) -> Result<Value, String> {
+    let mut fields = match payload {
+        Value::Object(fields) => fields,
+        Value::Null => Map::new(),
+        other => return Err(format!("Payload must be a JSON object, got {}", other)),
+    };
+    fields.retain(|_, value| !value.is_null());
+    let payload_names_hook = HOOK_FIELDS.iter().any(|field| fields.contains_key(*field));
+    for (field, plain_text) in ENV_FIELDS {
+        if fields.contains_key(*field) || (payload_names_hook && HOOK_FIELDS.contains(field)) {
+            continue;
+        }
+        let name = env_var(field);
+        let Some(raw) = env(&name).filter(|raw| !raw.is_empty()) else {
+            continue;
+        };
+        let value = if *plain_text {
+            Value::String(raw)
+        } else {
+            serde_json::from_str(&raw).map_err(|e| format!("{} is not valid JSON: {}", name, e))?
+        };
+        fields.insert(field.to_string(), value);
+    }
+    Ok(Value::Object(fields))
+}
@@ -0,0 +1,88 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::discord::DiscordError;
+
+/// Why fetching, parsing or posting failed, for the modules that talk to DaySmart, iCal feeds and
+/// Discord. Callers still returning `Result<_, String>` can use `?` on it directly.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// An upstream (DaySmart, an iCal feed) could not be reached or answered with an error.
+    #[error("{0}")]
+    Network(String),
+    /// A response or document could not be read.
+    #[error("{0}")]
+    Parse(String),
+    /// Discord refused a post; `status` is the HTTP status when it answered at all.
+    #[error("{message}")]
+    Discord { status: Option<u16>, message: String },
+    /// The request's own settings are wrong, e.g. a reminder template that doesn't render.
+    #[error("{0}")]
+    Config(String),
+}
+
+/// Machine-readable error code in the Response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Network,
+    Parse,
+    Discord,
+    Config,
+}
+
+/// An [`Error`] as reported in the Response, e.g. `{"code": "discord", "status": 404, "message": "..."}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorInfo {
+    pub code: ErrorCode,
+    /// HTTP status behind a Discord error, when there was one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+impl Error {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Network(_) => ErrorCode::Network,
+            Error::Parse(_) => ErrorCode::Parse,
+            Error::Discord { .. } => ErrorCode::Discord,
+            Error::Config(_) => ErrorCode::Config,
+        }
+    }
+
+    pub fn info(&self) -> ErrorInfo {
+        let status = match self {
+            Error::Discord { status, .. } => *status,
+            _ => None,
+        };
+        ErrorInfo { code: self.code(), status, message: self.to_string() }
+    }
+
+    /// An error of the same kind as `info` with another message, e.g. several failures combined.
+    pub fn from_info(info: &ErrorInfo, message: String) -> Self {
+        match info.code {
+            ErrorCode::Network => Error::Network(message),
+            ErrorCode::Parse => Error::Parse(message),
+            ErrorCode::Discord => Error::Discord { status: info.status, message },
+            ErrorCode::Config => Error::Config(message),
+        }
+    }
+}
+
+impl From<DiscordError> for Error {
+    fn from(error: DiscordError) -> Self {
+        let status = match &error {
+            DiscordError::Status { status, .. } => Some(*status),
+            DiscordError::RateLimited { .. } => Some(429),
+            DiscordError::Transport { .. } | DiscordError::Attachment(_) => None,
+        };
+        Error::Discord { status, message: error.to_string() }
+    }
+}
+
+impl From<Error> for String {
+    fn from(error: Error) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,75 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A DaySmart id and the name it resolved to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Resolved {
+    pub id: Option<i64>,
+    /// Name found in the document's includes; None when the id is unset or not included.
+    pub name: Option<String>,
+    /// What messages show: the name, or the placeholder when there is none.
+    pub shown: String,
+}
+
+/// Everything derived about one DaySmart event, for checking whether a wrong reminder comes
+/// from the feed or from how it was read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct GameExplanation {
+    pub game_id: i64,
+    /// "listed", "cancelled" (called off in DaySmart) or "missing" (not one of our games in the document).
+    pub status: String,
+    /// Details of a listed game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game: Option<ExplainedGame>,
+}
+
+/// How a listed game was read from the document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ExplainedGame {
+    /// Timestamp as DaySmart sent it (start_gmt, else start).
+    pub raw_start: String,
+    /// Parsed start, RFC 3339 in UTC.
+    pub start_utc: String,
+    /// Parsed start, RFC 3339 in `timezone`.
+    pub start_local: String,
+    pub timezone: String,
+    /// Whether we are the home team.
+    pub home: bool,
+    pub playoff: bool,
+    /// Final (home, visitor) score, once played.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<(i64, i64)>,
+    pub home_team: Resolved,
+    pub visiting_team: Resolved,
+    pub opponent: String,
+    pub jersey: String,
+    pub rink: Resolved,
+    pub home_locker_room: Resolved,
+    pub away_locker_room: Resolved,
+    /// Included records the game was built from, e.g. "teams/45 (home team)".
+    pub includes: Vec<String>,
+    /// Records the game refers to that the document doesn't include.
+    pub missing_includes: Vec<String>,
+    /// The reminder as it would be posted.
+    pub reminder: String,
+}
+
+impl GameExplanation {
+    /// One-line summary for the workflow report.
+    pub fn summary(&self) -> String {
+        match &self.game {
+            Some(game) => format!(
+                "Game {}: {} at {} ({}), {} vs {}, {} includes used, {} missing",
+                self.game_id,
+                game.start_local,
+                game.rink.shown,
+                game.raw_start,
+                game.home_team.shown,
+                game.visiting_team.shown,
+                game.includes.len(),
+                game.missing_includes.len()
+            ),
+            None => format!("Game {}: {}", self.game_id, self.status),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+/// When rinks are open, for catching games DaySmart lists at impossible times (e.g. 3 AM after
+/// a data-entry slip). Times are local, "HH:MM".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FacilityHoursConfig {
+    /// Hours for rinks not listed in `rinks`; without it, unlisted rinks aren't checked.
+    #[serde(default)]
+    pub default: Option<Hours>,
+    /// Hours by rink name as DaySmart shows it, e.g. {"Starbucks Rink 1": {"open": "05:30", "close": "01:00"}}.
+    #[serde(default)]
+    pub rinks: BTreeMap<String, Hours>,
+}
+
+/// Opening hours. A close time at or before the open time means the rink closes after midnight.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hours {
+    pub open: String,
+    pub close: String,
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    // "24:00" is the natural way to write closing at midnight
+    if value.trim() == "24:00" {
+        return Ok(NaiveTime::MIN);
+    }
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| format!("Invalid facility time {:?}; expected \"HH:MM\"", value))
+}
+
+impl Hours {
+    pub fn validate(&self) -> Result<(), String> {
+        parse_time(&self.open)?;
+        parse_time(&self.close)?;
+        Ok(())
+    }
+
+    /// Whether a game may start at `start` (local). Unparseable hours never flag a game.
+    pub fn allows(&self, start: NaiveTime) -> bool {
+        let (Ok(open), Ok(close)) = (parse_time(&self.open), parse_time(&self.close)) else {
+            return true;
+        };
+        if open < close { start >= open && start < close } else { start >= open || start < close }
+    }
+}
+
+impl FacilityHoursConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        self.default.iter().chain(self.rinks.values()).try_for_each(Hours::validate)
+    }
+
+    /// Hours for `rink`, falling back to the default.
+    pub fn hours_for(&self, rink: Option<&str>) -> Option<&Hours> {
+        rink.and_then(|name| self.rinks.get(name)).or(self.default.as_ref())
+    }
+}
+
+/// A game held back from announcements because it starts while its rink is closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfHoursGame {
+    pub game_id: i64,
+    /// Local start, e.g. "Sun Sep 21 3:00 AM".
+    pub start: String,
+    pub rink: String,
+    pub hours: Hours,
+}
+
+impl OutOfHoursGame {
+    /// Line for the captains' run summary.
+    pub fn warning(&self) -> String {
+        format!(
+            "Game {} at {} is listed for {}, outside its hours ({}–{}); not announced until DaySmart is corrected",
+            self.game_id, self.rink, self.start, self.hours.open, self.hours.close
+        )
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::destination::DeliveryReport;
+use crate::state::StateStore;
+use crate::summary::{Outcome, WorkflowReport};
+
+/// Keeps ops quiet about transient failures: instead of a summary after every run, failures are
+/// counted in state and reported in one digest per period. Runs only alert right away when
+/// `consecutive_runs` runs in a row failed or no post got through at all. Needs `state`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureBudgetConfig {
+    /// Failed runs in a row that trigger an immediate alert (default 3).
+    #[serde(default = "default_consecutive_runs")]
+    pub consecutive_runs: u32,
+    /// Days covered by each digest (default 7).
+    #[serde(default = "default_digest_days")]
+    pub digest_days: i64,
+}
+
+fn default_consecutive_runs() -> u32 { 3 }
+
+fn default_digest_days() -> i64 { 7 }
+
+impl Default for FailureBudgetConfig {
+    fn default() -> Self {
+        Self { consecutive_runs: default_consecutive_runs(), digest_days: default_digest_days() }
+    }
+}
+
+/// Failures counted since the last digest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureLedger {
+    /// Start of the period the next digest covers; set by the first run.
+    pub period_start: Option<DateTime<Utc>>,
+    pub runs: u32,
+    pub failed_runs: u32,
+    /// Failures by category, e.g. {"posting": 2, "timeouts": 1}.
+    pub counts: BTreeMap<String, u32>,
+    /// Failed runs since the last good one.
+    pub consecutive_failed_runs: u32,
+    /// Whether the current failure streak was already alerted on.
+    pub alerted: bool,
+}
+
+/// What a run tells ops under the failure budget.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Notices {
+    pub alert: Option<String>,
+    pub digest: Option<String>,
+}
+
+/// Bucket for a failed workflow, from its detail: posting, timeouts and fetches are the usual
+/// transient kinds; anything else is counted under the workflow's name.
+pub fn category(report: &WorkflowReport) -> String {
+    let detail = report.detail.to_ascii_lowercase();
+    if detail.contains("post failed") {
+        "posting".to_string()
+    } else if detail.ends_with("timed out") {
+        "timeouts".to_string()
+    } else if detail.contains("fetch failed") || detail.contains("init error") {
+        "fetches".to_string()
+    } else {
+        report.workflow.name().to_string()
+    }
+}
+
+/// Whether the run tried to post and every delivery failed.
+pub fn posting_stopped(deliveries: &[DeliveryReport]) -> bool {
+    !deliveries.is_empty() && deliveries.iter().all(|d| d.error.is_some())
+}
+
+impl FailureLedger {
+    /// Count a run's failures and decide what, if anything, to post now.
+    pub fn record(&mut self, reports: &[WorkflowReport], deliveries: &[DeliveryReport], now: DateTime<Utc>, config: &FailureBudgetConfig) -> Notices {
+        let period_start = *self.period_start.get_or_insert(now);
+        let failed: Vec<&WorkflowReport> = reports.iter().filter(|r| r.outcome == Outcome::Failed).collect();
+        let stopped = posting_stopped(deliveries);
+        self.runs += 1;
+        let mut notices = Notices::default();
+        if failed.is_empty() && !stopped {
+            self.consecutive_failed_runs = 0;
+            self.alerted = false;
+        } else {
+            self.failed_runs += 1;
+            self.consecutive_failed_runs += 1;
+            for report in &failed {
+                *self.counts.entry(category(report)).or_default() += 1;
+            }
+            let streak = self.consecutive_failed_runs >= config.consecutive_runs.max(1);
+            if (streak || stopped) && !self.alerted {
+                self.alerted = true;
+                notices.alert = Some(format_alert(&failed, stopped, self.consecutive_failed_runs));
+            }
+        }
+        if now - period_start >= chrono::Duration::days(config.digest_days.max(1)) {
+            notices.digest = Some(self.format_digest(period_start, now));
+            *self = FailureLedger { period_start: Some(now), consecutive_failed_runs: self.consecutive_failed_runs, alerted: self.alerted, ..Default::default() };
+        }
+        notices
+    }
+
+    fn format_digest(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+        let mut out = format!(
+            ":bookkeeping: Ops digest {} – {}: {} of {} runs had failures",
+            from.format("%b %-d"),
+            to.format("%b %-d"),
+            self.failed_runs,
+            self.runs
+        );
+        let mut counts: Vec<(&String, &u32)> = self.counts.iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (category, count) in counts {
+            out.push_str(&format!("\n• {}: {}", category, count));
+        }
+        out
+    }
+}
+
+fn format_alert(failed: &[&WorkflowReport], posting_stopped: bool, consecutive: u32) -> String {
+    let mut out = if posting_stopped {
+        ":rotating_light: No posts are getting through: every delivery in the last run failed".to_string()
+    } else {
+        format!(":rotating_light: {} runs in a row have failed", consecutive)
+    };
+    for report in failed {
+        out.push_str(&format!("\n:x: {}: {}", report.workflow.name(), report.detail));
+    }
+    out
+}
+
+fn state_key(team_id: &str) -> String {
+    format!("failures/{}", team_id)
+}
+
+/// Load the team's ledger, count this run and save it. Returns what to post to ops.
+pub async fn track(
+    store: &StateStore,
+    team_id: &str,
+    reports: &[WorkflowReport],
+    deliveries: &[DeliveryReport],
+    now: DateTime<Utc>,
+    config: &FailureBudgetConfig,
+) -> Result<Notices, String> {
+    let mut ledger: FailureLedger = store.get(&state_key(team_id)).await?.unwrap_or_default();
+    let notices = ledger.record(reports, deliveries, now, config);
+    store.put(&state_key(team_id), &ledger).await?;
+    info!(failed_runs = ledger.failed_runs, consecutive = ledger.consecutive_failed_runs, alert = notices.alert.is_some(), digest = notices.digest.is_some(), "Recorded run in failure budget");
+    Ok(notices)
+}
@@ -0,0 +1,197 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, instrument};
+
+/// Where team calendar feeds are hosted. Feeds live under `<prefix>/<token>/<team_id>.ics`, so
+/// only people holding the current token can subscribe. Token manifests are kept under
+/// `manifest_prefix`, which must not be exposed by the CloudFront distribution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedConfig {
+    pub bucket: String,
+    #[serde(default = "default_prefix")]
+    pub prefix: String,
+    #[serde(default = "default_manifest_prefix")]
+    pub manifest_prefix: String,
+    /// Public base URL (e.g. a CloudFront domain) used to build subscription links.
+    #[serde(default)]
+    pub public_base_url: Option<String>,
+}
+
+fn default_prefix() -> String { "feeds".to_string() }
+
+fn default_manifest_prefix() -> String { "_private/feeds".to_string() }
+
+/// Current token for a team's feed, persisted next to (but outside) the public feed path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedManifest {
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Generate an unguessable 256-bit token, hex encoded.
+pub fn generate_token() -> Result<String, String> {
+    let mut buf = [0u8; 32];
+    getrandom::fill(&mut buf).map_err(|e| format!("Failed to generate feed token: {}", e))?;
+    Ok(buf.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Object key of a team's feed for a given token.
+pub fn feed_key(prefix: &str, token: &str, team_id: &str) -> String {
+    format!("{}/{}/{}.ics", prefix.trim_end_matches('/'), token, team_id)
+}
+
+/// Public subscription URL for a feed key, falling back to the S3 virtual-hosted URL.
+pub fn feed_url(config: &FeedConfig, key: &str) -> String {
+    match &config.public_base_url {
+        Some(base) => format!("{}/{}", base.trim_end_matches('/'), key),
+        None => format!("https://{}.s3.amazonaws.com/{}", config.bucket, key),
+    }
+}
+
+/// Fixed S3 location for the calendar generated from DaySmart (ics_publish workflow). Unlike the
+/// tokenized feed, the URL never changes, so anyone who has it can subscribe.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcsPublishConfig {
+    pub bucket: String,
+    pub key: String,
+    /// Public base URL (e.g. a CloudFront domain) used to build the subscription link.
+    #[serde(default)]
+    pub public_base_url: Option<String>,
+}
+
+/// webcal:// subscription URL for a published calendar; calendar apps open these as subscriptions.
+pub fn webcal_url(config: &IcsPublishConfig) -> String {
+    let base = match &config.public_base_url {
+        Some(base) => base.trim_end_matches('/').to_string(),
+        None => format!("https://{}.s3.amazonaws.com", config.bucket),
+    };
+    let base = base.strip_prefix("https://").or_else(|| base.strip_prefix("http://")).unwrap_or(&base);
+    format!("webcal://{}/{}", base, config.key.trim_start_matches('/'))
+}
+
+/// Write the calendar to its fixed location. Returns the webcal subscription URL.
+#[instrument(level = "info", skip(ics), fields(bytes = ics.len()))]
+pub async fn publish_ics(config: &IcsPublishConfig, ics: &str) -> Result<String, String> {
+    let client = crate::aws::s3_client().await;
+    upload_calendar(&client, &config.bucket, config.key.trim_start_matches('/'), ics).await?;
+    info!("Published team calendar");
+    Ok(webcal_url(config))
+}
+
+async fn upload_calendar(client: &aws_sdk_s3::Client, bucket: &str, key: &str, ics: &str) -> Result<(), String> {
+    client
+        .put_object()
+        .bucket(bucket)
+        .key(key)
+        .content_type("text/calendar; charset=utf-8")
+        .cache_control("max-age=300")
+        .body(ics.as_bytes().to_vec().into())
+        .send()
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to upload calendar feed: {}", e))
+}
+
+/// S3-backed host for a single team's tokenized calendar feed.
+pub struct FeedHost {
+    client: aws_sdk_s3::Client,
+    config: FeedConfig,
+    team_id: String,
+}
+
+impl FeedHost {
+    pub async fn new(config: FeedConfig, team_id: &str) -> Self {
+        Self { client: crate::aws::s3_client().await, config, team_id: team_id.to_string() }
+    }
+
+    fn manifest_key(&self) -> String {
+        format!("{}/{}.json", self.config.manifest_prefix.trim_end_matches('/'), self.team_id)
+    }
+
+    /// Load the current manifest, if one has been written.
+    async fn load_manifest(&self) -> Result<Option<FeedManifest>, String> {
+        let resp = self.client.get_object().bucket(&self.config.bucket).key(self.manifest_key()).send().await;
+        match resp {
+            Ok(out) => {
+                let bytes = out
+                    .body
+                    .collect()
+                    .await
+                    .map_err(|e| format!("Failed to read feed manifest: {}", e))?
+                    .into_bytes();
+                serde_json::from_slice(&bytes)
+                    .map(Some)
+                    .map_err(|e| format!("Failed to parse feed manifest: {}", e))
+            }
+            Err(e) if e.as_service_error().map(|se| se.is_no_such_key()).unwrap_or(false) => Ok(None),
+            Err(e) => Err(format!("Failed to load feed manifest: {}", e)),
+        }
+    }
+
+    async fn save_manifest(&self, manifest: &FeedManifest) -> Result<(), String> {
+        let body = serde_json::to_vec(manifest).map_err(|e| format!("Failed to serialize feed manifest: {}", e))?;
+        self.client
+            .put_object()
+            .bucket(&self.config.bucket)
+            .key(self.manifest_key())
+            .content_type("application/json")
+            .body(body.into())
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to save feed manifest: {}", e))
+    }
+
+    async fn upload(&self, key: &str, ics: &str) -> Result<(), String> {
+        upload_calendar(&self.client, &self.config.bucket, key, ics).await
+    }
+
+    /// Subscription URL of the currently published feed, if one has been published.
+    pub async fn current_url(&self) -> Result<Option<String>, String> {
+        Ok(self
+            .load_manifest()
+            .await?
+            .map(|m| feed_url(&self.config, &feed_key(&self.config.prefix, &m.token, &self.team_id))))
+    }
+
+    /// Upload the calendar under the current token, creating a token on first publish.
+    /// Returns the subscription URL.
+    #[instrument(level = "info", skip(self, ics), fields(bytes = ics.len()))]
+    pub async fn publish(&self, ics: &str, now: DateTime<Utc>) -> Result<String, String> {
+        let manifest = match self.load_manifest().await? {
+            Some(m) => m,
+            None => {
+                let m = FeedManifest { token: generate_token()?, created_at: now };
+                self.save_manifest(&m).await?;
+                info!("Created initial feed token");
+                m
+            }
+        };
+        let key = feed_key(&self.config.prefix, &manifest.token, &self.team_id);
+        self.upload(&key, ics).await?;
+        info!("Published calendar feed");
+        Ok(feed_url(&self.config, &key))
+    }
+
+    /// Move the feed to a fresh token and delete the old object, cutting off old subscribers.
+    /// Returns the new subscription URL.
+    #[instrument(level = "info", skip(self, ics), fields(bytes = ics.len()))]
+    pub async fn rotate(&self, ics: &str, now: DateTime<Utc>) -> Result<String, String> {
+        let previous = self.load_manifest().await?;
+        let manifest = FeedManifest { token: generate_token()?, created_at: now };
+        let key = feed_key(&self.config.prefix, &manifest.token, &self.team_id);
+        // Upload first so the new URL works before anything points at it
+        self.upload(&key, ics).await?;
+        self.save_manifest(&manifest).await?;
+
+        if let Some(old) = previous {
+            let old_key = feed_key(&self.config.prefix, &old.token, &self.team_id);
+            if let Err(e) = self.client.delete_object().bucket(&self.config.bucket).key(&old_key).send().await {
+                // The manifest already points at the new token; report but don't fail the rotation
+                error!(error = %e, "Failed to delete previous calendar feed");
+            }
+        }
+        info!("Rotated calendar feed token");
+        Ok(feed_url(&self.config, &key))
+    }
+}
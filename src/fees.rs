@@ -0,0 +1,54 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::roster;
+use crate::state::StateStore;
+
+/// Ice cost split for pickup/rental games.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeSplitConfig {
+    /// Total ice rental cost in dollars.
+    pub ice_cost: f64,
+}
+
+impl FeeSplitConfig {
+    /// The cost must be a positive amount; anything else would post a nonsense share such as
+    /// "$NaN/skater".
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.ice_cost.is_finite() || self.ice_cost <= 0.0 {
+            return Err(format!("fee_split.ice_cost must be a positive amount (got {})", self.ice_cost));
+        }
+        Ok(())
+    }
+}
+
+/// Per-player share, rounded up to the cent so the total is always covered.
+/// Returns None when nobody has confirmed.
+pub fn per_player_cost(ice_cost: f64, confirmed: usize) -> Option<f64> {
+    if confirmed == 0 {
+        return None;
+    }
+    Some((ice_cost * 100.0 / confirmed as f64).ceil() / 100.0)
+}
+
+/// Message line such as "$14/skater at 12 confirmed".
+pub fn fee_line(ice_cost: f64, confirmed: usize) -> Option<String> {
+    let share = per_player_cost(ice_cost, confirmed)?;
+    let amount = if share.fract() == 0.0 { format!("${}", share as u64) } else { format!("${:.2}", share) };
+    Some(format!("{}/skater at {} confirmed", amount, confirmed))
+}
+
+/// Pickup games from the iCal feed have no DaySmart id, so their answers are kept under the
+/// local date, e.g. "2025-10-05".
+pub fn rsvp_key(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+/// Skaters (defense included) who answered yes for the pickup game on `day`. Goalies don't
+/// share the ice cost.
+pub async fn confirmed(store: &StateStore, team_id: &str, day: NaiveDate) -> Result<usize, String> {
+    let roster = roster::roster(store, team_id).await?;
+    let answers = roster::rsvps(store, team_id, &rsvp_key(day)).await?;
+    let attendance = roster::attendance(&roster, &answers);
+    Ok(attendance.skaters + attendance.defense)
+}
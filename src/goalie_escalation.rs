@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::daysmart::MAX_LOOKAHEAD_DAYS;
+use crate::discord::{AllowedMentions, PostOptions};
+use crate::roster::{self, Position, Rsvp};
+use crate::secrets::Secret;
+use crate::state::StateStore;
+
+/// Calling for a spare goalie closer to game time (goalie_escalation workflow).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoalieEscalationConfig {
+    /// Discord webhook of the spares channel that gets the calls.
+    pub spares_hook_url: Secret,
+    /// Only call for the next game once it starts within this many hours.
+    #[serde(default = "default_hours_before")]
+    pub hours_before: i64,
+    /// Fewest hours between two calls for the same game, however often the workflow runs.
+    #[serde(default = "default_min_hours_between_calls")]
+    pub min_hours_between_calls: i64,
+    /// Calls for one game before giving up.
+    #[serde(default = "default_max_calls")]
+    pub max_calls: u32,
+    /// A goalie is lined up outside Discord; set it to stop the calls.
+    #[serde(default)]
+    pub goalie_confirmed: bool,
+}
+
+fn default_hours_before() -> i64 { 48 }
+
+fn default_min_hours_between_calls() -> i64 { 6 }
+
+fn default_max_calls() -> u32 { 3 }
+
+impl GoalieEscalationConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.spares_hook_url.is_empty() {
+            return Err("goalie_escalation.spares_hook_url must not be empty".to_string());
+        }
+        if !(1..=MAX_LOOKAHEAD_DAYS * 24).contains(&self.hours_before) {
+            return Err(format!("goalie_escalation.hours_before must be between 1 and {} (got {})", MAX_LOOKAHEAD_DAYS * 24, self.hours_before));
+        }
+        if !(0..=self.hours_before).contains(&self.min_hours_between_calls) {
+            return Err(format!(
+                "goalie_escalation.min_hours_between_calls must be between 0 and hours_before ({}) (got {})",
+                self.hours_before, self.min_hours_between_calls
+            ));
+        }
+        if self.max_calls == 0 {
+            return Err("goalie_escalation.max_calls must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The calls made so far for one game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Calls {
+    pub count: u32,
+    /// When the latest call was posted.
+    #[serde(default)]
+    pub last_at: Option<DateTime<Utc>>,
+}
+
+impl Calls {
+    /// Why no call is due at `now`, or None when one is.
+    pub fn not_due(&self, config: &GoalieEscalationConfig, now: DateTime<Utc>) -> Option<String> {
+        if self.count >= config.max_calls {
+            return Some(format!("all {} calls made", config.max_calls));
+        }
+        self.last_at
+            .filter(|last| now - *last < chrono::Duration::hours(config.min_hours_between_calls))
+            .map(|_| format!("last call less than {} hours ago", config.min_hours_between_calls))
+    }
+
+    /// These calls plus one posted at `now`.
+    pub fn and_one_more(&self, now: DateTime<Utc>) -> Calls {
+        Calls { count: self.count + 1, last_at: Some(now) }
+    }
+}
+
+/// The call for a goalie, more urgent with each call already made for the game (`level`).
+/// `day` is the weekday of the game ("Sunday"), `game` its name and time.
+pub fn message(level: u32, day: &str, game: &str) -> String {
+    match level {
+        0 => format!("@here Still need a goalie for {} ({}). Reply here if you can play!", day, game),
+        1 => format!("@here Still no goalie for {} ({}). Can anyone cover?", day, game),
+        _ => format!("@here :rotating_light: Last call: no goalie for {} yet ({}). We can't play without one!", day, game),
+    }
+}
+
+/// Post options that let the call's @here ping the channel.
+pub fn post_options() -> PostOptions {
+    PostOptions {
+        allowed_mentions: Some(AllowedMentions { parse: vec!["everyone".to_string()], ..Default::default() }),
+        ..Default::default()
+    }
+}
+
+fn state_key(team_id: &str, game_id: i64) -> String {
+    format!("goalie_escalation/{}/{}", team_id, game_id)
+}
+
+/// The calls already made for a game.
+pub async fn calls_made(store: &StateStore, team_id: &str, game_id: i64) -> Result<Calls, String> {
+    Ok(store.get(&state_key(team_id, game_id)).await?.unwrap_or_default())
+}
+
+/// Remember the calls made for a game starting at `game_start`; they are forgotten a day after
+/// the game.
+pub async fn record_calls(store: &StateStore, team_id: &str, game_id: i64, calls: Calls, game_start: DateTime<Utc>) -> Result<(), String> {
+    store.put_expiring(&state_key(team_id, game_id), &calls, game_start + chrono::Duration::days(1)).await
+}
+
+/// Whether a goalie on the roster (see /roster) answered yes for the game with /rsvp.
+pub async fn goalie_answered(store: &StateStore, team_id: &str, game_id: i64) -> Result<bool, String> {
+    let players = roster::roster(store, team_id).await?;
+    let answers = roster::rsvps(store, team_id, &game_id.to_string()).await?;
+    Ok(answers
+        .iter()
+        .any(|(user_id, rsvp)| *rsvp == Rsvp::Yes && players.players.get(user_id).is_some_and(|player| player.position == Position::Goalie)))
+}
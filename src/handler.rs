@@ -1,10 +1,53 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use lambda_runtime::{Error, LambdaEvent};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tracing::{error, info, instrument};
+use tracing::{error, info, instrument, warn};
+
+use crate::clock::Services;
+use crate::daysmart::{self, DEFAULT_BENCHAPP_MONTHS, DEFAULT_TIMEZONE, MAX_BENCHAPP_MONTHS, MAX_LOOKAHEAD_DAYS};
+use crate::destination::{DeliveryLog, DeliveryReport, Destinations, Publisher};
+use crate::discord::{Discord, Mentions, PostOptions};
+use crate::notifier::Notifier;
+use crate::display::Placeholders;
+use crate::benchapp_api::BenchappConfig;
+use crate::config_doc::{self, ConfigLocation};
+use crate::dedup;
+use crate::dropin::DropInConfig;
+use crate::enrich::{AttendanceEnricher, DutyEnricher, Enricher, EnricherKind, NotesEnricher, Pipeline, StreakEnricher};
+use crate::env_config;
+use crate::fees::FeeSplitConfig;
+use crate::http::HttpPolicy;
+use crate::http_cache::HttpCache;
+use crate::jersey::JerseyConfig;
+use crate::links::LinksConfig;
+use crate::metrics;
+use crate::no_games::NoGamesNoticeConfig;
+use crate::opponents::{self, BenchappNames, BenchappNamesConfig};
+use crate::preview::{self, GamePreview};
+use crate::rotation::RotationConfig;
+use crate::schedule::{QuietHours, Schedule};
+use crate::rsvp_reactions::RsvpReactionsConfig;
+use crate::goalie_escalation::GoalieEscalationConfig;
+use crate::scheduled_events::ScheduledEventsConfig;
+use crate::secrets::{Secret, SecretRef, SecretsClient};
+use crate::slack::Slack;
+use crate::state::{StateConfig, StateStore};
+use crate::streak::StreakConfig;
+use crate::template;
+use crate::tournament::TournamentConfig;
+use crate::facility::FacilityHoursConfig;
+use crate::failure_budget::{self, FailureBudgetConfig};
+use crate::feed::{FeedConfig, IcsPublishConfig};
+use crate::league_bundle::LeagueBundleConfig;
+use crate::sources::{FetchReport, Sources};
+use crate::summary::{self, Outcome, RunSummary, WorkflowReport};
+use crate::webhook_rotation::{self, WebhookRotationConfig};
+use crate::workflows;
 
-use crate::ical::Ical;
-use crate::daysmart::DaySmart;
-use crate::discord::Discord;
+pub use crate::workflow::Workflow;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -13,153 +56,912 @@ pub enum Mode {
     Production,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Workflow {
-    Ical,
-    Daysmart,
+/// Latest request schema understood by the handler. Payloads without a
+/// `schema_version` are treated as version 1.
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+fn default_schema_version() -> u32 { 1 }
+
+fn default_true() -> bool { true }
+
+fn default_days_ahead() -> i64 { 5 }
+
+fn default_benchapp_months_ahead() -> u32 { DEFAULT_BENCHAPP_MONTHS }
+
+/// A single entry in `Request::workflows`.
+/// Version 1 payloads list bare workflow names (`"daysmart"`); version 2 payloads may
+/// instead use an object carrying settings for that workflow only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WorkflowEntry {
+    Name(Workflow),
+    Configured(WorkflowConfig),
+}
+
+/// Per-workflow settings (schema version 2).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowConfig {
+    pub workflow: Workflow,
+    /// Allows keeping a workflow in the payload while switching it off.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Only run on matching days, so one daily trigger can drive workflows with different cadences.
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
+    /// Production Discord webhook for this workflow's posts (e.g. BenchApp CSVs to a managers'
+    /// channel), used instead of `discord_hook_url` and any rotated webhook.
+    #[serde(default)]
+    pub discord_hook_url: Option<Secret>,
+    /// Longest this workflow may run, in milliseconds, before it is reported as timed out while
+    /// the others carry on. `deadline_ms` still applies.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Discord name, avatar, allowed mentions and link previews for this workflow's posts.
+    #[serde(default)]
+    pub discord_options: Option<Box<PostOptions>>,
+}
+
+impl WorkflowEntry {
+    pub fn workflow(&self) -> Workflow {
+        match self {
+            WorkflowEntry::Name(wf) => *wf,
+            WorkflowEntry::Configured(cfg) => cfg.workflow,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        match self {
+            WorkflowEntry::Name(_) => true,
+            WorkflowEntry::Configured(cfg) => cfg.enabled,
+        }
+    }
+
+    /// The entry's own production webhook, if it has one.
+    pub fn discord_hook_url(&self) -> Option<&str> {
+        match self {
+            WorkflowEntry::Name(_) => None,
+            WorkflowEntry::Configured(cfg) => cfg.discord_hook_url.as_ref().map(Secret::value),
+        }
+    }
+
+    /// The entry's own Discord post options, if it has them.
+    pub fn discord_options(&self) -> Option<&PostOptions> {
+        match self {
+            WorkflowEntry::Name(_) => None,
+            WorkflowEntry::Configured(cfg) => cfg.discord_options.as_deref(),
+        }
+    }
+
+    /// The entry's own time limit, if it has one.
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        match self {
+            WorkflowEntry::Name(_) => None,
+            WorkflowEntry::Configured(cfg) => cfg.timeout_ms.map(std::time::Duration::from_millis),
+        }
+    }
+
+    /// Whether the entry's schedule (if any) allows running at `now`.
+    pub fn is_due(&self, now: chrono::DateTime<chrono::Utc>, timezone: chrono_tz::Tz) -> bool {
+        match self {
+            WorkflowEntry::Configured(WorkflowConfig { schedule: Some(schedule), .. }) => schedule.is_due(now, timezone),
+            _ => true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Request {
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
     pub mode: Mode,
-    pub discord_hook_url: String,
+    /// Production webhook. May be left out when `discord_hook_secret_arn` or
+    /// `discord_hook_parameter` says where to look it up.
+    #[serde(default)]
+    pub discord_hook_url: Secret,
+    /// Secrets Manager secret holding the production webhook, so it stays out of the payload.
+    #[serde(default)]
+    pub discord_hook_secret_arn: Option<String>,
+    /// SSM parameter holding the production webhook.
+    #[serde(default)]
+    pub discord_hook_parameter: Option<String>,
     #[serde(default)]
-    pub test_discord_hook_url: Option<String>,
+    pub test_discord_hook_url: Option<Secret>,
+    /// Where results are posted: Discord (default), Slack, or both.
+    #[serde(default)]
+    pub destinations: Option<Destinations>,
+    /// Further production Discord webhooks (e.g. a spares league's server) that receive every
+    /// post; same as `destinations.discord_hooks`.
+    #[serde(default)]
+    pub discord_hook_urls: Vec<Secret>,
     #[serde(default)]
     pub ical_url: Option<String>,
     pub team_id: String,
     pub company: String,
     #[serde(default)]
-    pub workflows: Vec<Workflow>,
+    pub workflows: Vec<WorkflowEntry>,
+    /// Simulated invocation time (RFC 3339). When set, every workflow evaluates its
+    /// windows and cutoffs against this instant instead of the real clock.
+    #[serde(default)]
+    pub now_override: Option<chrono::DateTime<chrono::Utc>>,
+    /// How many days ahead the daysmart workflow looks for the next game.
+    #[serde(default = "default_days_ahead")]
+    pub days_ahead: i64,
+    /// Remind about every game within `days_ahead` in one post instead of only the next one.
+    #[serde(default)]
+    pub announce_all: bool,
+    /// Hours before a game at which the daysmart workflow reminds about it (e.g. [72, 24, 2]);
+    /// each run posts only once `now` passes an offset not yet reminded for. Needs `state`.
+    #[serde(default)]
+    pub reminder_offsets_hours: Vec<i64>,
+    /// Local hours (e.g. 22:00–08:00) in which workflows that post to the team are skipped, so a
+    /// cron firing at night doesn't ping everyone; the next run after them posts instead.
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    /// Minutes before the start to be at the rink; reminders add "Arrive by 5:45 PM (30 min before puck drop)".
+    #[serde(default)]
+    pub arrive_minutes_early: Option<u32>,
+    /// Notes by arena, rink or facility name (e.g. parking, door codes) added to reminders and
+    /// BenchApp CSV notes of games there.
+    #[serde(default)]
+    pub venue_notes: Option<HashMap<String, String>>,
+    /// Remind about our team's practices like games, and add them to the BenchApp CSV.
+    #[serde(default)]
+    pub include_practices: bool,
+    /// Write reminder dates and times as text in the request timezone instead of Discord
+    /// timestamps.
+    #[serde(default)]
+    pub plain_times: bool,
+    /// Open a thread per game with the reminder, named after the date and opponent.
+    #[serde(default)]
+    pub game_threads: bool,
+    /// Discord role pinged by game reminders.
+    #[serde(default)]
+    pub mention_role_id: Option<String>,
+    /// Discord users pinged by game reminders.
+    #[serde(default)]
+    pub mention_user_ids: Vec<String>,
+    /// How many months of games go into the DaySmart BenchApp CSV.
+    #[serde(default = "default_benchapp_months_ahead")]
+    pub benchapp_months_ahead: u32,
+    /// Timeouts and retries for fetching DaySmart and iCal sources.
+    #[serde(default)]
+    pub http: Option<HttpPolicy>,
+    /// Where DaySmart and iCal bodies are kept with their ETag / Last-Modified, so unchanged feeds
+    /// are neither downloaded nor parsed again, e.g. {"backend": "file", "dir": "/tmp/http-cache"}
+    /// for warm invocations or an S3 backend to survive cold starts.
+    #[serde(default)]
+    pub http_cache: Option<StateConfig>,
+    /// IANA timezone (e.g. "America/Denver") for displayed dates and times and for deciding
+    /// what "today" is. Defaults to America/Los_Angeles.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Persistent state backend for features that remember things between runs.
+    #[serde(default)]
+    pub state: Option<StateConfig>,
+    /// Discord bot token, used for features a webhook cannot do (e.g. direct messages).
+    #[serde(default)]
+    pub bot_token: Option<Secret>,
+    /// Discord Scheduled Events for upcoming games, kept by the scheduled_events workflow; needs
+    /// `bot_token` and `state`.
+    #[serde(default)]
+    pub scheduled_events: Option<ScheduledEventsConfig>,
+    /// Attendance minimums for the rsvp_reactions workflow; defaults to 10 skaters and 1 goalie.
+    #[serde(default)]
+    pub rsvp_reactions: Option<RsvpReactionsConfig>,
+    /// Spare goalie calls for the goalie_escalation workflow.
+    #[serde(default)]
+    pub goalie_escalation: Option<GoalieEscalationConfig>,
+    /// Link buttons ("Directions", "Full schedule", "League standings") on reminders sent with the bot token.
+    #[serde(default)]
+    pub links: Option<LinksConfig>,
+    /// Scorekeeper / penalty-box duty rotation; needs `state` to remember whose turn it is.
+    #[serde(default)]
+    pub scorekeeper: Option<RotationConfig>,
+    /// Post-game beverage/snack rotation; persisted independently of the scorekeeper rotation.
+    #[serde(default)]
+    pub beverages: Option<RotationConfig>,
+    /// Free-text note appended to the reminder by the notes enricher.
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Handlebars template for the DaySmart reminder, e.g. "{{home}} vs {{visitor}} at {{arena}}".
+    /// See `template::ReminderVars` for the variables; defaults to the built-in wording.
+    #[serde(default)]
+    pub message_template: Option<String>,
+    /// Current win/loss streak line in reminders and score recaps.
+    #[serde(default)]
+    pub streak: Option<StreakConfig>,
+    /// Message add-ons to apply to the DaySmart reminder, in order. Defaults to every
+    /// configured add-on (scorekeeper, beverages, attendance, streak, notes).
+    #[serde(default)]
+    pub enrichers: Option<Vec<EnricherKind>>,
+    /// Per-player ice cost for pickup/rental games from the iCal feed, shown on game day.
+    #[serde(default)]
+    pub fee_split: Option<FeeSplitConfig>,
+    /// Drop-in session monitoring (drop_in workflow). With `state`, only new sessions are announced.
+    #[serde(default)]
+    pub dropin: Option<DropInConfig>,
+    /// Post an occasional "still no games" notice during breaks instead of staying silent; needs `state`.
+    #[serde(default)]
+    pub no_games_notice: Option<NoGamesNoticeConfig>,
+    /// Tournament itinerary thresholds plus hotel and rink notes. Detection is on by default.
+    #[serde(default)]
+    pub tournament: Option<TournamentConfig>,
+    /// Rink opening hours; games DaySmart lists outside them are held back and reported in the
+    /// captains' summary instead of announced.
+    #[serde(default)]
+    pub facility_hours: Option<FacilityHoursConfig>,
+    /// S3/CloudFront hosting for the tokenized calendar feed (feed workflows only).
+    #[serde(default)]
+    pub feed: Option<FeedConfig>,
+    /// BenchApp team and API token (benchapp_api workflow only).
+    #[serde(default)]
+    pub benchapp: Option<BenchappConfig>,
+    /// S3 location of a BenchApp opponents/roster export; DaySmart team names in the BenchApp CSV
+    /// and the benchapp_api sync are replaced by the matching BenchApp names.
+    #[serde(default)]
+    pub benchapp_names: Option<BenchappNamesConfig>,
+    /// Jersey colors: home, away, and per-opponent overrides. Defaults to light at home, dark away.
+    #[serde(default)]
+    pub jerseys: Option<JerseyConfig>,
+    /// Text shown for team, rink and locker room names DaySmart leaves out.
+    #[serde(default)]
+    pub placeholders: Option<Placeholders>,
+    /// Fixed S3 location for the calendar generated from DaySmart (ics_publish workflow only).
+    #[serde(default)]
+    pub ics_publish: Option<IcsPublishConfig>,
+    /// Per-team and combined league calendars, zipped (league_bundle workflow only).
+    #[serde(default)]
+    pub league_bundle: Option<LeagueBundleConfig>,
+    /// Time budget for the workflows, in milliseconds from the start of the invocation. Workflows
+    /// still running then (e.g. waiting on a slow iCal feed) are reported as timed out; finished
+    /// ones keep their results.
+    #[serde(default)]
+    pub deadline_ms: Option<u64>,
+    /// New production webhook for the webhook_rotate workflow; needs `state` to remember it.
+    #[serde(default)]
+    pub webhook_rotation: Option<WebhookRotationConfig>,
+    /// Private ops Discord webhook for the canary workflow.
+    #[serde(default)]
+    pub canary_hook_url: Option<Secret>,
+    /// Separate low-traffic Discord webhook (e.g. a captains channel) that gets a short summary
+    /// of every run: workflows executed, games found, and anything skipped or failed.
+    #[serde(default)]
+    pub admin_hook_url: Option<Secret>,
+    /// Count failures in state and send admin_hook_url a weekly digest instead of a summary after
+    /// every run; needs `state`.
+    #[serde(default)]
+    pub failure_budget: Option<FailureBudgetConfig>,
+    /// Discord webhook (e.g. the team manager's) that gets a short report of whatever failed in a
+    /// run: workflows, panics included, source fetches and posts.
+    #[serde(default)]
+    pub error_hook_url: Option<Secret>,
+    /// DaySmart event id the explain workflow reports on.
+    #[serde(default)]
+    pub explain_game_id: Option<i64>,
+    /// Run the workflows and return what they would post in the Response, without posting,
+    /// sending DMs or saving state.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// List every game in this many days ahead in `Response::preview`, for dashboards.
+    #[serde(default)]
+    pub preview_horizon_days: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Request {
+    /// Reject payloads this build cannot interpret: future schema versions, or
+    /// version 1 payloads that use per-workflow settings.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.schema_version == 0 || self.schema_version > CURRENT_SCHEMA_VERSION {
+            return Err(format!(
+                "Unsupported schema_version {} (supported: 1..={})",
+                self.schema_version, CURRENT_SCHEMA_VERSION
+            ));
+        }
+        if self.schema_version < 2
+            && self.workflows.iter().any(|w| matches!(w, WorkflowEntry::Configured(_)))
+        {
+            return Err("Per-workflow settings require schema_version 2".to_string());
+        }
+        for entry in &self.workflows {
+            if let WorkflowEntry::Configured(WorkflowConfig { schedule: Some(schedule), .. }) = entry {
+                schedule.validate()?;
+            }
+        }
+        if let Some(message_template) = &self.message_template {
+            template::validate(message_template)?;
+        }
+        if let Some(facility_hours) = &self.facility_hours {
+            facility_hours.validate()?;
+        }
+        // The slug names the rink's DaySmart site; it is percent-encoded into query strings, so
+        // this only catches typos
+        if self.company.is_empty() || !self.company.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(format!("company must be a DaySmart company slug such as \"kraken\" (got {:?})", self.company));
+        }
+        // The id goes into the DaySmart URL path and state keys as is
+        if self.team_id.is_empty() || !self.team_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("team_id must be a DaySmart team id such as \"11007\" (got {:?})", self.team_id));
+        }
+        if let Some(id) = self.mention_role_id.iter().chain(&self.mention_user_ids).find(|id| id.is_empty() || !id.chars().all(|c| c.is_ascii_digit())) {
+            return Err(format!("mention_role_id and mention_user_ids must be Discord ids such as \"123456789012345678\" (got {:?})", id));
+        }
+        if !(1..=MAX_LOOKAHEAD_DAYS).contains(&self.days_ahead) {
+            return Err(format!("days_ahead must be between 1 and {} (got {})", MAX_LOOKAHEAD_DAYS, self.days_ahead));
+        }
+        if self.benchapp_months_ahead > MAX_BENCHAPP_MONTHS {
+            return Err(format!("benchapp_months_ahead must be at most {} (got {})", MAX_BENCHAPP_MONTHS, self.benchapp_months_ahead));
+        }
+        if let Some(tournament) = &self.tournament {
+            tournament.validate()?;
+        }
+        if let Some(minutes) = self.arrive_minutes_early.filter(|minutes| !(1..=180).contains(minutes)) {
+            return Err(format!("arrive_minutes_early must be between 1 and 180 (got {})", minutes));
+        }
+        if let Some(quiet_hours) = &self.quiet_hours {
+            quiet_hours.validate()?;
+        }
+        if !self.reminder_offsets_hours.is_empty() && self.state.is_none() {
+            return Err("reminder_offsets_hours needs state to remember which reminders went out".to_string());
+        }
+        if let Some(hours) = self.reminder_offsets_hours.iter().find(|hours| **hours < 1 || **hours > self.days_ahead * 24) {
+            return Err(format!("reminder_offsets_hours must be between 1 and days_ahead * 24 = {} (got {})", self.days_ahead * 24, hours));
+        }
+        if let Some(scheduled_events) = &self.scheduled_events {
+            scheduled_events.validate()?;
+        }
+        if let Some(goalie_escalation) = &self.goalie_escalation {
+            goalie_escalation.validate()?;
+        }
+        if let Some(dropin) = &self.dropin {
+            dropin.validate()?;
+        }
+        if let Some(benchapp) = &self.benchapp {
+            benchapp.validate()?;
+        }
+        if let Some(fee_split) = &self.fee_split {
+            fee_split.validate()?;
+        }
+        if let Some(webhook_rotation) = &self.webhook_rotation {
+            webhook_rotation.validate()?;
+        }
+        if let Some(days) = self.preview_horizon_days
+            && !(1..=MAX_LOOKAHEAD_DAYS).contains(&days)
+        {
+            return Err(format!("preview_horizon_days must be between 1 and {} (got {})", MAX_LOOKAHEAD_DAYS, days));
+        }
+        let hook_sources = [!self.discord_hook_url.is_empty(), self.discord_hook_secret_arn.is_some(), self.discord_hook_parameter.is_some()];
+        match hook_sources.iter().filter(|set| **set).count() {
+            0 => return Err("One of discord_hook_url, discord_hook_secret_arn or discord_hook_parameter is required".to_string()),
+            1 => {}
+            _ => return Err("Give only one of discord_hook_url, discord_hook_secret_arn and discord_hook_parameter".to_string()),
+        }
+        self.timezone()?;
+        Ok(())
+    }
+
+    /// The request in an invoke payload, with fields it leaves out taken from environment
+    /// variables (see `env_config`).
+    pub fn from_payload(payload: serde_json::Value) -> Result<Self, String> {
+        Self::from_payload_with_env(payload, |name| std::env::var(name).ok())
+    }
+
+    /// `from_payload` with the environment looked up through `env`.
+    pub fn from_payload_with_env(payload: serde_json::Value, env: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let merged = env_config::with_env_defaults(payload, env)?;
+        serde_json::from_value(merged).map_err(|e| format!("Invalid request: {}", e))
+    }
+
+    /// Where the production webhook is kept, when it isn't in the payload.
+    pub fn discord_hook_secret(&self) -> Option<SecretRef> {
+        match (&self.discord_hook_secret_arn, &self.discord_hook_parameter) {
+            (Some(arn), _) => Some(SecretRef::SecretsManager(arn.clone())),
+            (None, Some(name)) => Some(SecretRef::Parameter(name.clone())),
+            (None, None) => None,
+        }
+    }
+
+    /// Look up every webhook and token given as a reference, so the run only sees values. A
+    /// production webhook kept in `discord_hook_secret_arn` / `discord_hook_parameter` ends up in
+    /// `discord_hook_url`.
+    pub async fn resolve_secrets(&mut self, client: &SecretsClient) -> Result<(), String> {
+        if let Some(secret) = self.discord_hook_secret() {
+            self.discord_hook_url = Secret::Stored(secret);
+            self.discord_hook_secret_arn = None;
+            self.discord_hook_parameter = None;
+        }
+        let mut secrets: Vec<&mut Secret> = vec![&mut self.discord_hook_url];
+        secrets.extend(self.test_discord_hook_url.as_mut());
+        secrets.extend(self.discord_hook_urls.iter_mut());
+        secrets.extend(self.bot_token.as_mut());
+        secrets.extend(self.canary_hook_url.as_mut());
+        secrets.extend(self.admin_hook_url.as_mut());
+        secrets.extend(self.error_hook_url.as_mut());
+        if let Some(destinations) = &mut self.destinations {
+            secrets.extend(destinations.discord_hooks.iter_mut());
+            if let Some(slack) = &mut destinations.slack {
+                secrets.push(&mut slack.webhook_url);
+                secrets.extend(slack.test_webhook_url.as_mut());
+                secrets.extend(slack.bot_token.as_mut());
+            }
+        }
+        if let Some(escalation) = &mut self.goalie_escalation {
+            secrets.push(&mut escalation.spares_hook_url);
+        }
+        for entry in &mut self.workflows {
+            if let WorkflowEntry::Configured(config) = entry {
+                secrets.extend(config.discord_hook_url.as_mut());
+            }
+        }
+        for secret in secrets {
+            secret.resolve(client).await?;
+        }
+        Ok(())
+    }
+
+    /// The configured timezone, if any, parsed from its IANA name.
+    pub fn timezone(&self) -> Result<Option<chrono_tz::Tz>, String> {
+        self.timezone
+            .as_deref()
+            .map(|name| name.parse::<chrono_tz::Tz>().map_err(|_| format!("Unknown timezone: {}", name)))
+            .transpose()
+    }
+
+    /// Workflows to run, in request order. Defaults to Daysmart when none are listed
+    /// for backward compatibility; disabled entries are dropped.
+    pub fn selected_workflows(&self) -> Vec<Workflow> {
+        if self.workflows.is_empty() {
+            return vec![Workflow::Daysmart];
+        }
+        self.workflows.iter().filter(|w| w.enabled()).map(|w| w.workflow()).collect()
+    }
+
+    /// Production webhook configured on the workflow's own entry, if any; the first enabled entry
+    /// for the workflow that sets one wins.
+    pub fn workflow_hook_url(&self, workflow: Workflow) -> Option<&str> {
+        self.workflows
+            .iter()
+            .filter(|w| w.enabled() && w.workflow() == workflow)
+            .find_map(|w| w.discord_hook_url())
+    }
+
+    /// Discord post options configured on the workflow's own entry, if any.
+    pub fn workflow_discord_options(&self, workflow: Workflow) -> Option<&PostOptions> {
+        self.workflows
+            .iter()
+            .filter(|w| w.enabled() && w.workflow() == workflow)
+            .find_map(|w| w.discord_options())
+    }
+
+    /// The cache for conditional source fetches, when configured.
+    pub async fn http_cache(&self) -> Option<HttpCache> {
+        match &self.http_cache {
+            Some(config) => Some(HttpCache::new(StateStore::from_config(config).await)),
+            None => None,
+        }
+    }
+
+    /// How DaySmart games are shown, for every workflow.
+    pub fn daysmart_view(&self) -> daysmart::View {
+        daysmart::View {
+            include_practices: self.include_practices,
+            discord_timestamps: !self.plain_times,
+            arrive_minutes_early: self.arrive_minutes_early,
+            venue_notes: self.venue_notes.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Who game reminders ping.
+    pub fn mentions(&self) -> Mentions {
+        Mentions { role_id: self.mention_role_id.clone(), user_ids: self.mention_user_ids.clone() }
+    }
+
+    /// Time limit configured on the workflow's own entry, if any; the first enabled entry for the
+    /// workflow that sets one wins.
+    pub fn workflow_timeout(&self, workflow: Workflow) -> Option<std::time::Duration> {
+        self.workflows.iter().filter(|w| w.enabled() && w.workflow() == workflow).find_map(|w| w.timeout())
+    }
+
+    /// Selected workflows split into those due at `now` and those held back by their schedule.
+    pub fn due_workflows(&self, now: chrono::DateTime<chrono::Utc>, timezone: chrono_tz::Tz) -> (Vec<Workflow>, Vec<Workflow>) {
+        if self.workflows.is_empty() {
+            return (self.selected_workflows(), Vec::new());
+        }
+        let (due, not_due): (Vec<&WorkflowEntry>, Vec<&WorkflowEntry>) =
+            self.workflows.iter().filter(|w| w.enabled()).partition(|w| w.is_due(now, timezone));
+        (due.iter().map(|w| w.workflow()).collect(), not_due.iter().map(|w| w.workflow()).collect())
+    }
+}
+
+/// Build the enrichment pipeline for the DaySmart reminder. Enrichers that are listed but not
+/// configured (or need state that isn't available) are left out.
+pub(crate) fn enrichment_pipeline(payload: &Request, state: &Option<StateStore>) -> Pipeline {
+    let kinds = payload.enrichers.clone().unwrap_or_else(|| EnricherKind::DEFAULT_ORDER.to_vec());
+    let duty = |config: &Option<RotationConfig>, key: &'static str, prefix: &'static str| -> Option<Box<dyn Enricher>> {
+        let (config, store) = (config.clone()?, state.clone()?);
+        Some(Box::new(DutyEnricher { key, prefix, config, store }))
+    };
+    let enrichers = kinds
+        .into_iter()
+        .filter_map(|kind| {
+            let enricher = match kind {
+                EnricherKind::Scorekeeper => duty(&payload.scorekeeper, "scorekeeper", ":clipboard: Scorekeeper: "),
+                EnricherKind::Beverages => duty(&payload.beverages, "beverages", ":beer: Post-game beverages: "),
+                EnricherKind::Attendance => state
+                    .clone()
+                    .map(|store| Box::new(AttendanceEnricher { store }) as Box<dyn Enricher>),
+                EnricherKind::Streak => payload.streak.map(|config| Box::new(StreakEnricher { config }) as Box<dyn Enricher>),
+                EnricherKind::Notes => payload
+                    .notes
+                    .clone()
+                    .map(|note| Box::new(NotesEnricher { note }) as Box<dyn Enricher>),
+            };
+            if enricher.is_none() && payload.enrichers.is_some() {
+                info!(?kind, "Enricher listed but not configured; skipping");
+            }
+            enricher
+        })
+        .collect();
+    Pipeline::new(enrichers)
+}
+
+/// Destinations besides the main webhook, in delivery order. A new kind of destination (Telegram,
+/// email, ...) is a `Notifier` implementation added here; workflows only see the `Publisher`.
+fn notifiers(payload: &Request, destinations: &Destinations, services: &Services) -> Vec<Arc<dyn Notifier>> {
+    let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+    if let Some(config) = &destinations.slack {
+        notifiers.push(Arc::new(Slack::from_config(config, matches!(payload.mode, Mode::Test)).with_ids(services.ids.clone())));
+    }
+    // Extra channels are production-only; test runs stay in the test channel
+    if matches!(payload.mode, Mode::Production) {
+        for hook_url in destinations.discord_hooks.iter().chain(&payload.discord_hook_urls) {
+            notifiers.push(Arc::new(Discord::new(hook_url.value().to_string()).with_ids(services.ids.clone())));
+        }
+    }
+    notifiers
+}
+
+/// BenchApp's spelling of team names, when a names CSV is configured. A CSV that can't be loaded
+/// is logged and DaySmart's names are used, so exports still go out.
+pub(crate) async fn benchapp_names(config: Option<&BenchappNamesConfig>) -> BenchappNames {
+    let Some(config) = config else {
+        return BenchappNames::default();
+    };
+    match opponents::load(config).await {
+        Ok(names) => {
+            info!(names = names.names().len(), "Loaded BenchApp names");
+            names
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to load BenchApp names; using DaySmart names");
+            BenchappNames::default()
+        }
+    }
+}
+
+/// Version of the Response shape, emitted in every Response. Bump it when a field is removed or
+/// changes meaning; new optional fields don't need a bump.
+pub const RESPONSE_SCHEMA_VERSION: u32 = 1;
+
+/// What the handler returns to the invoker.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Response {
+    /// RESPONSE_SCHEMA_VERSION of the emitting function.
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
     pub message: String,
+    /// Per-source fetch timing for this invocation.
+    #[serde(default)]
+    pub fetches: Vec<FetchReport>,
+    /// Outcome of each selected workflow.
+    #[serde(default)]
+    pub workflows: Vec<WorkflowReport>,
+    /// Result of every post to every destination, in the order they finished.
+    #[serde(default)]
+    pub deliveries: Vec<DeliveryReport>,
+    /// Games in the next `preview_horizon_days` days, when requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preview: Vec<GamePreview>,
+}
+
+/// JSON Schema of the Response, generated from the Rust types.
+pub fn response_schema() -> schemars::Schema {
+    schemars::schema_for!(Response)
+}
+
+/// Lambda entry point: builds the request from the raw payload, the stored request document
+/// (`CONFIG_LOCATION`) and the environment, then runs it. An EventBridge event such as a plain
+/// cron rule's carries no request fields, so it counts as an empty payload.
+pub async fn handle_event(event: LambdaEvent<serde_json::Value>) -> Result<Response, Error> {
+    let (payload, context) = event.into_parts();
+    let request = load_request(payload).await?;
+    handler(LambdaEvent::new(request, context)).await
+}
+
+/// The request for an invocation payload: its own fields, then the stored request document, then
+/// environment variables.
+pub async fn load_request(mut payload: serde_json::Value) -> Result<Request, String> {
+    if config_doc::is_eventbridge_event(&payload) {
+        info!(source = payload["source"].as_str().unwrap_or(""), time = payload["time"].as_str().unwrap_or(""), "Invoked by an EventBridge event");
+        payload = serde_json::Value::Null;
+    }
+    if let Some(location) = ConfigLocation::from_env()? {
+        payload = config_doc::overlay(location.load().await?, payload)?;
+    }
+    Request::from_payload(payload)
+}
+
+/// [`load_request`], checked like the scheduled handler checks its payload, for the entry points
+/// that fetch from DaySmart without running the handler (slash commands, the schedule API).
+pub async fn load_valid_request(payload: serde_json::Value) -> Result<Request, String> {
+    let request = load_request(payload).await?;
+    request.validate()?;
+    Ok(request)
 }
 
-#[instrument(skip(event))]
 pub async fn handler(event: LambdaEvent<Request>) -> Result<Response, Error> {
+    handler_with(event, &Services::default()).await
+}
+
+/// [`handler`] reading the time and outbound request ids from `services`, so a test can pin both.
+#[instrument(skip(event, services))]
+pub async fn handler_with(event: LambdaEvent<Request>, services: &Services) -> Result<Response, Error> {
+    // Every webhook client shares the invocation's id generator
+    let discord = |hook_url: String| Discord::new(hook_url).with_ids(services.ids.clone());
     // Extract config from the request payload instead of environment variables
-    let payload = event.payload; // Derived from the Lambda event
+    let mut payload = event.payload; // Derived from the Lambda event
+    payload.validate()?;
+    let secrets = SecretsClient::from_env();
+    payload.resolve_secrets(&secrets).await?;
+    let payload = Arc::new(payload);
+    let deadline = payload.deadline_ms.map(|ms| tokio::time::Instant::now() + std::time::Duration::from_millis(ms));
+    let discord_hook_url = payload.discord_hook_url.value().to_string();
 
-    // Select destination based on request mode
-    let message_destination = match payload.mode {
-        Mode::Test => payload
-            .test_discord_hook_url
-            .unwrap_or_else(|| payload.discord_hook_url.clone()),
-        Mode::Production => payload.discord_hook_url.clone(),
+    let state = match &payload.state {
+        // A dry run reads state so the preview matches a real run, but leaves it as it was
+        Some(config) if payload.dry_run => Some(StateStore::from_config(config).await.read_only()),
+        Some(config) => Some(StateStore::from_config(config).await),
+        None => None,
     };
-    let discord = Discord::new(message_destination);
 
-    // Decide workflows: default to Daysmart if none specified for backward compatibility
-    let workflows = if payload.workflows.is_empty() {
-        vec![Workflow::Daysmart]
-    } else {
-        payload.workflows.clone()
+    // Select destination based on request mode; a production webhook rotated via webhook_rotate
+    // replaces discord_hook_url
+    let message_destination = match (payload.mode, &state) {
+        (Mode::Test, _) => payload
+            .test_discord_hook_url
+            .as_ref()
+            .map(|hook_url| hook_url.value().to_string())
+            .unwrap_or_else(|| discord_hook_url.clone()),
+        (Mode::Production, Some(store)) => {
+            webhook_rotation::effective_hook_url(store, &secrets, &payload.team_id, &discord_hook_url).await
+        }
+        (Mode::Production, None) => discord_hook_url.clone(),
     };
+    let timezone = payload.timezone()?;
+    let local_tz = timezone.unwrap_or(DEFAULT_TIMEZONE);
 
-    let mut handles: Vec<tokio::task::JoinHandle<String>> = Vec::new();
+    let destinations = payload.destinations.clone().unwrap_or_default();
+    let deliveries = DeliveryLog::default();
+    let publisher = Publisher::new(destinations.discord.then(|| Arc::new(discord(message_destination.clone())) as Arc<dyn Notifier>))
+        .with_notifiers(notifiers(&payload, &destinations, services))
+        .with_fan_out(destinations.fan_out.unwrap_or_default())
+        .with_log(deliveries.clone())
+        .with_timezone(local_tz);
+    let publisher = if payload.dry_run { publisher.dry_run() } else { publisher };
 
-    for wf in workflows {
-        let discord = discord.clone();
-        match wf {
-            Workflow::Daysmart => {
-                // Clone because spawn_blocking's 'move' closure requires 'static owned data
-                // and we cannot borrow from `payload` across await/join points. Each task
-                // must own its inputs.
-                let team_id = payload.team_id.clone();
-                let company = payload.company.clone();
-                let handle = tokio::task::spawn_blocking(move || {
-                    let day_smart = match DaySmart::for_team(&team_id, &company) {
-                        Ok(ds) => ds,
-                        Err(e) => {
-                            let msg = format!("DaySmart init error: {}", e);
-                            error!(error = %msg, "DaySmart init failed");
-                            return msg;
-                        }
-                    };
-                    let csv_schedule = day_smart.to_benchapp_csv(chrono::Utc::now());
-                    match day_smart.get_next_game_message(5, chrono::Utc::now()) {
-                        Some(message) => {
-                            info!(message = %message, "Prepared DaySmart message");
-                            if let Err(e) = discord.post_with_attachment(&message, "games.csv", csv_schedule.as_bytes()) {
-                                error!(error = %e, "Failed to post DaySmart message to Discord");
-                                format!("DaySmart post failed: {}", e)
-                            } else {
-                                "DaySmart message posted".to_string()
-                            }
-                        }
-                        None => {
-                            use chrono::Utc;
-                            let msg = format!("No games in the next 5 days from {}. Skipping Discord post.", Utc::now());
-                            info!("{}", msg);
-                            // Skip sending a Discord message when there are no upcoming games
-                            "DaySmart: no upcoming games (skipped)".to_string()
-                        }
-                    }
-                });
-
-                handles.push(handle);
-            }
-            Workflow::Ical => {
-                // If the iCal URL is not provided, skip BenchApp workflow gracefully
-                if let Some(ical_url) = payload.ical_url.clone() {
-                    // Clone for the same reason: the spawned blocking task needs to own a 'static String.
-                    let handle = tokio::task::spawn_blocking(move || {
-                        // Generate BenchApp CSV from the provided iCal URL and post as an attachment
-                        let generator = Ical::from_url(&ical_url);
-                        let cutoff = chrono::Utc::now().naive_utc();
-                        match generator.to_bench_app_csv(cutoff) {
-                            Ok(csv) => {
-                                // If the CSV contains only the header (no data rows), skip posting to Discord
-                                let has_rows = csv.lines().skip(1).any(|l| !l.trim().is_empty());
-                                if !has_rows {
-                                    info!("No upcoming BenchApp events after cutoff; skipping Discord post");
-                                    return "BenchApp: no upcoming games (skipped)".to_string();
-                                }
-
-                                let filename = "benchapp_schedule.csv";
-                                let content = generator
-                                    .discord_message(cutoff)
-                                    .unwrap_or_else(|_| "BenchApp import schedule attached.".to_string());
-                                if let Err(e) = discord.post_with_attachment(&content, filename, csv.as_bytes()) {
-                                    error!(error = %e, "Failed to post BenchApp CSV to Discord");
-                                    format!("BenchApp post failed: {}", e)
-                                } else {
-                                    "BenchApp CSV posted".to_string()
-                                }
-                            }
-                            Err(e) => {
-                                error!(error = %e, "Failed to generate BenchApp CSV");
-                                format!("BenchApp CSV generation failed: {}", e)
-                            }
-                        }
-                    });
-                    handles.push(handle);
-                } else {
-                    info!("No ical_url provided; skipping ical workflow");
-                }
+    // Single clock reading shared by all workflows so they agree on "now"
+    let now = match payload.now_override {
+        Some(simulated) => {
+            info!(now = %simulated, "Using simulated invocation time");
+            simulated
+        }
+        None => services.clock.now(),
+    };
+
+    let (mut workflows, mut not_due) = payload.due_workflows(now, local_tz);
+    // A reminder whose post failed is announced by the next run, whatever the schedule says
+    if let Some(store) = &state
+        && not_due.contains(&Workflow::Daysmart)
+    {
+        match dedup::pending(store, &payload.team_id).await {
+            Ok(Some(pending)) if pending.retry_due(now) => {
+                info!(game_id = pending.game_id, "Re-announcing a reminder whose post failed");
+                not_due.retain(|wf| *wf != Workflow::Daysmart);
+                workflows.push(Workflow::Daysmart);
             }
+            Ok(_) => {}
+            Err(e) => error!(error = %e, "Failed to check for a pending announcement"),
         }
     }
+    let mut reports: Vec<WorkflowReport> = not_due
+        .into_iter()
+        .map(|wf| {
+            info!(workflow = ?wf, "Workflow not scheduled for today; skipping");
+            WorkflowReport::new(wf, Outcome::Skipped, format!("{:?}: not scheduled today (skipped)", wf))
+        })
+        .collect();
+    // Nobody gets pinged at 3 AM; a dry run pings nobody anyway
+    if let Some(quiet_hours) = payload.quiet_hours.as_ref().filter(|quiet_hours| !payload.dry_run && quiet_hours.contains(now, local_tz)) {
+        let (held, rest): (Vec<Workflow>, Vec<Workflow>) = workflows.into_iter().partition(Workflow::posts_to_team);
+        workflows = rest;
+        reports.extend(held.into_iter().map(|wf| {
+            info!(workflow = ?wf, "Inside quiet hours; skipping");
+            WorkflowReport::new(wf, Outcome::Skipped, format!("{:?}: quiet hours until {} (skipped)", wf, quiet_hours.end.trim()))
+        }));
+    }
+
+    let http_policy = payload.http.unwrap_or_default();
+
+    // Fetch each upstream source once, concurrently, for all workflows that need it; each
+    // workflow waits only for the sources it reads
+    // Feeds are built from DaySmart when there is no iCal feed to republish
+    let feed_from_daysmart = payload.ical_url.is_none();
+    let needs_daysmart = payload.preview_horizon_days.is_some() || workflows.iter().any(|wf| match wf {
+        Workflow::Daysmart
+        | Workflow::Scores
+        | Workflow::Standings
+        | Workflow::IcsPublish
+        | Workflow::BenchappApi
+        | Workflow::Digest
+        | Workflow::Explain
+        | Workflow::RsvpReactions
+        | Workflow::GoalieEscalation
+        | Workflow::ScheduledEvents => true,
+        Workflow::Feed | Workflow::FeedRotate => feed_from_daysmart,
+        // Only to find the league when none is configured
+        Workflow::LeagueBundle => payload.league_bundle.as_ref().is_some_and(|config| config.league_id.is_none()),
+        _ => false,
+    });
+    let needs_ical = workflows.iter().any(|wf| matches!(wf, Workflow::Ical | Workflow::Feed | Workflow::FeedRotate | Workflow::Digest));
+    let sources = Sources::fetch(
+        needs_daysmart.then(|| (payload.team_id.clone(), payload.company.clone())),
+        if needs_ical { payload.ical_url.clone() } else { None },
+        timezone,
+        payload.placeholders.clone().unwrap_or_default(),
+        payload.jerseys.clone().unwrap_or_default(),
+        payload.daysmart_view(),
+        http_policy,
+        payload.facility_hours.clone(),
+        payload.http_cache().await,
+    );
+
+    let ctx = Arc::new(workflows::Context {
+        request: payload.clone(),
+        sources: sources.clone(),
+        state: state.clone(),
+        now,
+        timezone,
+        local_tz,
+        http_policy,
+        ids: services.ids.clone(),
+        hook_url: message_destination.clone(),
+        deadline,
+    });
 
-    let mut summaries: Vec<String> = Vec::new();
-    for h in handles {
-        match h.await {
-            Ok(summary) => summaries.push(summary),
-            Err(e) => summaries.push(format!("Workflow task join error: {}", e)),
+    let mut planned = Vec::new();
+    for wf in workflows {
+        if payload.dry_run && wf.acts_outside_publisher() {
+            info!(workflow = ?wf, "Dry run; skipping workflow");
+            reports.push(WorkflowReport::new(wf, Outcome::Skipped, format!("{:?}: dry run (skipped)", wf)));
+            continue;
+        }
+        // A workflow's own webhook replaces the request-wide one; test runs stay in the test channel
+        let publisher = match (payload.mode, payload.workflow_hook_url(wf)) {
+            (Mode::Production, Some(hook_url)) => publisher.clone().with_discord_hook(hook_url),
+            _ => publisher.clone(),
         }
+        .for_workflow(wf);
+        let publisher = match payload.workflow_discord_options(wf) {
+            Some(options) => publisher.with_post_options(options),
+            None => publisher,
+        };
+        planned.push((wf, publisher));
     }
+    reports.extend(workflows::run_all(&ctx, planned).await);
+
+    // The preview only needs the DaySmart document the workflows already waited for, if any
+    let preview = match payload.preview_horizon_days {
+        Some(days) => {
+            let loaded = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, sources.daysmart()).await.ok().flatten(),
+                None => sources.daysmart().await,
+            };
+            match loaded {
+                Some(Ok(day_smart)) => preview::games_ahead(&day_smart, days, now),
+                _ => {
+                    warn!("DaySmart not available; no game preview");
+                    Vec::new()
+                }
+            }
+        }
+        None => Vec::new(),
+    };
+    let fetches = sources.fetches();
 
-    let summary = if summaries.is_empty() {
+    let summary = if reports.is_empty() {
         "No workflows executed".to_string()
     } else {
-        summaries.join("; ")
+        reports.iter().map(|r| r.detail.as_str()).collect::<Vec<_>>().join("; ")
     };
+    let summary = if payload.dry_run { format!("[dry run] {}", summary) } else { summary };
 
-    Ok(Response { message: summary })
-}
+    let posts = std::mem::take(&mut *deliveries.lock().expect("delivery log lock"));
+    let mut deliveries = Vec::new();
+    for post in posts {
+        if let Some(report) = reports.iter_mut().find(|r| Some(r.workflow) == post.workflow) {
+            report.messages.push(post.content);
+            report.attachments.extend(post.attachment);
+        }
+        deliveries.extend(post.deliveries);
+    }
 
+    let ready = sources.daysmart_if_ready();
+    let games_found = match &ready {
+        Some(Ok(ds)) => Some(ds.upcoming_games(payload.days_ahead, now).len()),
+        _ => None,
+    };
+    // Dry runs would count posts that were never sent. Metrics are stamped with the real time:
+    // CloudWatch drops documents far from it, as a now_override would be
+    if !payload.dry_run {
+        metrics::emit(&metrics::documents(&payload.team_id, services.clock.now(), games_found, &reports, &deliveries));
+    }
+
+    // Under a failure budget the run is counted in state, and ops hear about failures only through
+    // its alerts and digest. None without a budget, or when the ledger can't be read or saved, so
+    // every run reports as if there were none
+    let ops_hooked = payload.admin_hook_url.is_some() || payload.error_hook_url.is_some();
+    let notices = match payload.failure_budget.as_ref().zip(state.as_ref()).filter(|_| ops_hooked && !payload.dry_run) {
+        Some((config, store)) => match failure_budget::track(store, &payload.team_id, &reports, &deliveries, now, config).await {
+            Ok(notices) => Some(notices),
+            Err(e) => {
+                error!(error = %e, "Failure budget unavailable; reporting this run in full");
+                None
+            }
+        },
+        None => None,
+    };
+
+    // Anything that broke goes to the error channel, so breakage is noticed without reading the
+    // logs; under a failure budget only once the budget raises an alert
+    if let Some(error_hook_url) = payload.error_hook_url.as_ref().filter(|_| !payload.dry_run)
+        && notices.as_ref().is_none_or(|notices| notices.alert.is_some())
+        && let Some(report) = summary::format_error_report(&payload.team_id, &reports, &fetches, &deliveries)
+        && let Err(e) = discord(error_hook_url.value().to_string()).post(&report).await
+    {
+        error!(error = %e, "Failed to post error report");
+    }
 
+    // Captains get a short run report on their own channel; failures here never fail the run
+    if let Some(admin_hook_url) = payload.admin_hook_url.as_ref().filter(|_| !payload.dry_run) {
+        let warnings: Vec<String> = match &ready {
+            Some(Ok(ds)) => ds.out_of_hours_games().iter().map(|game| game.warning()).collect(),
+            _ => Vec::new(),
+        };
+        let report = summary::format_run_summary(&RunSummary {
+            team_id: &payload.team_id,
+            now,
+            timezone: local_tz,
+            test_mode: matches!(payload.mode, Mode::Test),
+            games_found,
+            days_ahead: payload.days_ahead,
+            reports: &reports,
+            fetches: &fetches,
+            warnings: &warnings,
+        });
+        // Under a failure budget only alerts, the periodic digest and summaries with out-of-hours
+        // warnings go out
+        let messages = match notices {
+            Some(notices) => {
+                let summary = Some(report.clone()).filter(|_| !warnings.is_empty());
+                notices.alert.into_iter().chain(notices.digest).chain(summary).collect()
+            }
+            None => vec![report],
+        };
+        for message in messages {
+            if let Err(e) = discord(admin_hook_url.value().to_string()).post(&message).await {
+                error!(error = %e, "Failed to post run summary");
+            }
+        }
+    }
+
+    Ok(Response { schema_version: RESPONSE_SCHEMA_VERSION, message: summary, fetches, workflows: reports, deliveries, preview })
+}
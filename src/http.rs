@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::Read;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, instrument, warn};
+
+/// User-Agent sent with every outbound request, so upstreams can tell who is calling.
+pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// How long an idle pooled connection is kept for the next request or warm invocation.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Limits for [`shared_client`] calls, so a webhook that stops answering can't hold the
+/// invocation until Lambda kills it.
+const SHARED_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const SHARED_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Clients by policy, so calls with the same timeouts share connections across workflows and
+// warm invocations
+static CLIENTS: OnceLock<Mutex<HashMap<HttpPolicy, reqwest::Client>>> = OnceLock::new();
+
+/// The client for webhook and API calls made without an [`HttpPolicy`] (Discord, Slack, the
+/// secrets extension): one connection pool and the crate's User-Agent.
+pub fn shared_client() -> reqwest::Client {
+    static SHARED: OnceLock<reqwest::Client> = OnceLock::new();
+    SHARED
+        .get_or_init(|| {
+            reqwest::Client::builder()
+                .user_agent(USER_AGENT)
+                .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+                .connect_timeout(SHARED_CONNECT_TIMEOUT)
+                .timeout(SHARED_REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default()
+        })
+        .clone()
+}
+
+/// Timeouts and retries for fetching upstream sources (DaySmart, iCal feeds).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HttpPolicy {
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+    /// Limit for waiting on the response headers and, separately, for reading the body.
+    #[serde(default = "default_read_timeout_ms")]
+    pub read_timeout_ms: u64,
+    /// Extra attempts after a 5xx response or transport error.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// Wait before the first retry; doubles for each further retry.
+    #[serde(default = "default_backoff_ms")]
+    pub backoff_ms: u64,
+}
+
+fn default_connect_timeout_ms() -> u64 { 5_000 }
+
+fn default_read_timeout_ms() -> u64 { 20_000 }
+
+fn default_retries() -> u32 { 2 }
+
+fn default_backoff_ms() -> u64 { 500 }
+
+impl Default for HttpPolicy {
+    fn default() -> Self {
+        Self {
+            connect_timeout_ms: default_connect_timeout_ms(),
+            read_timeout_ms: default_read_timeout_ms(),
+            retries: default_retries(),
+            backoff_ms: default_backoff_ms(),
+        }
+    }
+}
+
+impl HttpPolicy {
+    /// Wait before retry number `retry` (1-based).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        Duration::from_millis(self.backoff_ms.saturating_mul(1u64 << retry.saturating_sub(1).min(16)))
+    }
+
+    /// HTTP client applying this policy's timeouts, shared by every call with the same policy.
+    pub fn client(&self) -> Result<reqwest::Client, String> {
+        let mut clients = CLIENTS.get_or_init(Default::default).lock().expect("HTTP client lock");
+        if let Some(client) = clients.get(self) {
+            return Ok(client.clone());
+        }
+        let client = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .connect_timeout(Duration::from_millis(self.connect_timeout_ms))
+            .read_timeout(Duration::from_millis(self.read_timeout_ms))
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        clients.insert(*self, client.clone());
+        Ok(client)
+    }
+}
+
+/// GET `url` and return the body, retrying 5xx responses and transport errors per `policy`.
+/// Other non-success statuses fail immediately.
+pub async fn get_text(url: &str, policy: &HttpPolicy) -> Result<String, String> {
+    match get_conditional(url, policy, None).await? {
+        Conditional::Modified { body, .. } => Ok(body),
+        Conditional::NotModified => Err("HTTP 304 for an unconditional request".to_string()),
+    }
+}
+
+/// What a server sent to identify a body, for asking later whether it changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        Validators { etag: header(reqwest::header::ETAG), last_modified: header(reqwest::header::LAST_MODIFIED) }
+    }
+}
+
+/// Result of a conditional GET.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conditional {
+    Modified { body: String, validators: Validators },
+    /// The server answered 304: the body is the one `known` came with.
+    NotModified,
+}
+
+/// [`get_text`], asking the server to answer 304 Not Modified when the body still matches `known`
+/// (If-None-Match / If-Modified-Since).
+#[instrument(level = "info", name = "get_text", skip(policy, known), fields(status = tracing::field::Empty))]
+pub async fn get_conditional(url: &str, policy: &HttpPolicy, known: Option<&Validators>) -> Result<Conditional, String> {
+    let client = policy.client()?;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let capture = Capture::start("GET", url);
+        let mut request = client.get(url).header(reqwest::header::ACCEPT_ENCODING, "gzip, deflate");
+        if let Some(etag) = known.and_then(|v| v.etag.as_deref()) {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = known.and_then(|v| v.last_modified.as_deref()) {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+        let (message, retryable) = match request.send().await {
+            Ok(resp) if resp.status() == reqwest::StatusCode::NOT_MODIFIED && known.is_some() => {
+                tracing::Span::current().record("status", 304);
+                capture.finish(304, None);
+                return Ok(Conditional::NotModified);
+            }
+            Ok(resp) if resp.status().is_success() => {
+                let status = resp.status().as_u16();
+                tracing::Span::current().record("status", status);
+                let validators = Validators::from_headers(resp.headers());
+                return match read_body(resp).await {
+                    Ok(body) => {
+                        capture.finish(status, Some(&body));
+                        Ok(Conditional::Modified { body, validators })
+                    }
+                    Err(e) => {
+                        capture.failed(&e);
+                        error!(error = %e, "Failed to read response body");
+                        Err(format!("Failed to read response body: {}", e))
+                    }
+                };
+            }
+            Ok(resp) => {
+                let status = resp.status();
+                tracing::Span::current().record("status", status.as_u16());
+                let body = read_body(resp).await.unwrap_or_default();
+                capture.finish(status.as_u16(), Some(&body));
+                (format!("HTTP {}: {}", status.as_u16(), body), status.is_server_error())
+            }
+            Err(e) => {
+                capture.failed(&e);
+                (format!("Request failed: {}", e), true)
+            }
+        };
+        if retryable && attempt <= policy.retries {
+            let wait = policy.backoff(attempt);
+            warn!(error = %message, attempt, wait_ms = wait.as_millis() as u64, "Retrying GET");
+            tokio::time::sleep(wait).await;
+            continue;
+        }
+        error!(error = %message, attempt, "GET failed");
+        return Err(message);
+    }
+}
+
+/// The body as text, decompressed when the server sent it gzip- or deflate-encoded.
+/// Decoded here rather than with reqwest's `gzip`/`deflate` features, which would add
+/// async-compression and tower-http to the dependency tree for this one call site.
+async fn read_body(resp: reqwest::Response) -> Result<String, String> {
+    let encoding = resp
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_ascii_lowercase());
+    let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+    let decoded = match encoding.as_deref() {
+        Some("gzip" | "x-gzip") => decompress(flate2::read::GzDecoder::new(&bytes[..]))?,
+        // "deflate" should be zlib-wrapped, but some servers send a raw stream
+        Some("deflate") => decompress(flate2::read::ZlibDecoder::new(&bytes[..])).or_else(|_| decompress(flate2::read::DeflateDecoder::new(&bytes[..])))?,
+        _ => bytes.to_vec(),
+    };
+    Ok(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+fn decompress(mut reader: impl Read) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::new();
+    reader.read_to_end(&mut decoded).map_err(|e| format!("Invalid compressed body: {}", e))?;
+    Ok(decoded)
+}
+
+/// Tracing target of the outbound call capture. It logs at debug level, so it is off unless
+/// enabled with e.g. `RUST_LOG=info,http_capture=debug`.
+pub const CAPTURE_TARGET: &str = "http_capture";
+
+/// Longest response body included in a capture event, in bytes.
+const CAPTURE_BODY_LIMIT: usize = 512;
+
+/// One outbound HTTP call, logged as a single `http_capture` event when it finishes:
+/// method, redacted URL, status (or error), latency and the start of the response body.
+pub struct Capture {
+    method: &'static str,
+    // None when capture is disabled, so nothing is redacted or formatted
+    url: Option<String>,
+    started: Instant,
+}
+
+impl Capture {
+    pub fn start(method: &'static str, url: &str) -> Self {
+        let enabled = tracing::enabled!(target: CAPTURE_TARGET, tracing::Level::DEBUG);
+        Self { method, url: enabled.then(|| redact_url(url)), started: Instant::now() }
+    }
+
+    /// The call got a response; `body` is given when it was read.
+    pub fn finish(&self, status: u16, body: Option<&str>) {
+        if let Some(url) = &self.url {
+            let body = body.map(truncate_body);
+            debug!(target: CAPTURE_TARGET, method = self.method, url, status, latency_ms = self.latency_ms(), body, "Outbound HTTP call");
+        }
+    }
+
+    /// The call failed without a response.
+    pub fn failed(&self, error: &dyn Display) {
+        if let Some(url) = &self.url {
+            debug!(target: CAPTURE_TARGET, method = self.method, url, error = %error, latency_ms = self.latency_ms(), "Outbound HTTP call");
+        }
+    }
+
+    fn latency_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+}
+
+/// URL safe to log: credentials, token-like path segments (webhook and feed tokens) and the
+/// values of secret-looking query parameters are replaced with "REDACTED".
+pub fn redact_url(url: &str) -> String {
+    let Ok(mut parsed) = reqwest::Url::parse(url) else {
+        return "<invalid url>".to_string();
+    };
+    let _ = parsed.set_username("");
+    let _ = parsed.set_password(None);
+    let path: Vec<String> = parsed
+        .path_segments()
+        .map(|segments| segments.map(|s| if s.len() >= 20 { "REDACTED".to_string() } else { s.to_string() }).collect())
+        .unwrap_or_default();
+    if !path.is_empty() {
+        parsed.set_path(&path.join("/"));
+    }
+    if parsed.query().is_some() {
+        let secret = |key: &str| {
+            let key = key.to_ascii_lowercase();
+            ["token", "key", "secret", "sig", "password", "auth"].iter().any(|s| key.contains(s))
+        };
+        let pairs: Vec<(String, String)> = parsed
+            .query_pairs()
+            .map(|(k, v)| {
+                let v = if secret(&k) { "REDACTED".to_string() } else { v.into_owned() };
+                (k.into_owned(), v)
+            })
+            .collect();
+        parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    parsed.to_string()
+}
+
+fn truncate_body(body: &str) -> String {
+    if body.len() <= CAPTURE_BODY_LIMIT {
+        return body.to_string();
+    }
+    let mut end = CAPTURE_BODY_LIMIT;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}… ({} bytes)", &body[..end], body.len())
+}
@@ -0,0 +1,99 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::http::{self, Conditional, HttpPolicy, Validators};
+use crate::state::StateStore;
+
+/// A fetched body and the validators it came with, as stored under `http-cache/<hash of URL>`.
+/// The URL itself is left out, since feed URLs can carry tokens.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedBody {
+    #[serde(flatten)]
+    validators: Validators,
+    body: String,
+}
+
+type Parsed = (Validators, Arc<dyn Any + Send + Sync>);
+
+// Parsed documents by URL with the validators of the body they came from, kept across warm
+// invocations so an unchanged feed is not parsed again.
+static PARSED: OnceLock<Mutex<HashMap<String, Parsed>>> = OnceLock::new();
+
+fn parsed() -> &'static Mutex<HashMap<String, Parsed>> {
+    PARSED.get_or_init(Default::default)
+}
+
+/// Conditional fetches for feeds that rarely change (the DaySmart team document, iCal feeds):
+/// bodies and their ETag / Last-Modified are kept in a state store, and the next fetch asks the
+/// server whether the body changed. When it didn't, the document parsed from it last time is
+/// reused, or the stored body is parsed after a cold start.
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    store: StateStore,
+}
+
+impl HttpCache {
+    pub fn new(store: StateStore) -> Self {
+        Self { store }
+    }
+
+    fn key(url: &str) -> String {
+        let digest = ring::digest::digest(&ring::digest::SHA256, url.as_bytes());
+        format!("http-cache/{}", &hex::encode(digest.as_ref())[..32])
+    }
+
+    /// GET `url` and parse its body with `parse`, skipping both the download and the parse when
+    /// the server says the body is unchanged. The outer error is a failed fetch.
+    pub async fn fetch<T, E>(&self, url: &str, policy: &HttpPolicy, parse: impl FnOnce(&str) -> Result<T, E>) -> Result<Result<T, E>, String>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let key = Self::key(url);
+        let stored = self.store.get::<CachedBody>(&key).await.unwrap_or_else(|e| {
+            warn!(error = %e, "HTTP cache unreadable; fetching in full");
+            None
+        });
+        let remembered = parsed().lock().expect("parsed cache lock").get(url).cloned();
+        // Without a stored body only the parsed document can stand in for a 304
+        let known = stored.as_ref().map(|entry| entry.validators.clone()).or_else(|| remembered.as_ref().map(|(validators, _)| validators.clone()));
+        match http::get_conditional(url, policy, known.as_ref().filter(|v| !v.is_empty())).await? {
+            Conditional::NotModified => {
+                if let Some((validators, document)) = &remembered
+                    && Some(validators) == known.as_ref()
+                    && let Some(document) = document.downcast_ref::<T>()
+                {
+                    info!(%key, "Feed unchanged; reusing the parsed document");
+                    return Ok(Ok(document.clone()));
+                }
+                let Some(entry) = stored else {
+                    return Err("HTTP 304 but the cached body is gone".to_string());
+                };
+                info!(%key, "Feed unchanged; parsing the cached body");
+                Ok(remember(url, entry.validators, parse(&entry.body)))
+            }
+            Conditional::Modified { body, validators } => {
+                let result = parse(&body);
+                if result.is_ok() && !validators.is_empty() {
+                    let entry = CachedBody { validators: validators.clone(), body };
+                    if let Err(e) = self.store.put(&key, &entry).await {
+                        warn!(error = %e, "Failed to save to the HTTP cache");
+                    }
+                    return Ok(remember(url, validators, result));
+                }
+                Ok(result)
+            }
+        }
+    }
+}
+
+/// Keep a successfully parsed document for the next warm invocation.
+fn remember<T: Clone + Send + Sync + 'static, E>(url: &str, validators: Validators, result: Result<T, E>) -> Result<T, E> {
+    if let Ok(document) = &result {
+        parsed().lock().expect("parsed cache lock").insert(url.to_string(), (validators, Arc::new(document.clone())));
+    }
+    result
+}
@@ -1,71 +1,87 @@
+use std::sync::Arc;
+
 use chrono::{Datelike, NaiveDateTime};
 use icalendar::Component;
+use tracing::{error, instrument};
+
+use crate::error::Error;
+use crate::http::HttpPolicy;
+use crate::http_cache::HttpCache;
+
+pub mod ingest;
+pub mod writer;
+
+use ingest::QuarantinedEvent;
 
 /// Minimal BenchAppCsv type for future CSV/ICS ingestion from KHL
+#[derive(Clone)]
 pub struct Ical {
-    pub calendar: Option<icalendar::Calendar>,
+    /// Shared so a calendar reused from the HTTP cache isn't copied.
+    pub calendar: Option<Arc<icalendar::Calendar>>,
+    /// When set, UTC timestamps ("...Z") are converted to this timezone; otherwise they are used as-is.
+    pub timezone: Option<chrono_tz::Tz>,
+    /// Events left out of `calendar` because they could not be parsed or have no usable start.
+    pub quarantined: Vec<QuarantinedEvent>,
+    /// Why there is no `calendar`: the feed couldn't be fetched, or couldn't be parsed.
+    pub error: Option<Error>,
 }
 
 impl Ical {
-    /// Construct from the provided KHL schedule URL.
-    /// Performs a GET to the URL and attempts to parse the ICS into a Calendar; errors are logged.
-    pub fn from_url(url: &str) -> Self {
-        let mut calendar: Option<icalendar::Calendar> = None;
-
-        // Attempt to fetch and print the body; report any errors, but keep constructor infallible.
-        match ureq::get(url).call() {
-            Ok(resp) => {
-                // Note: ureq returns Ok(Response) even for HTTP error codes; warn if not 2xx.
-                let status = resp.status();
-                let code = status.as_u16();
-                let mut body_reader = resp.into_body();
-                match body_reader.read_to_string() {
-                    Ok(body) => {
-                        if code < 200 || code >= 300 {
-                            eprintln!("BenchAppCsv GET non-success status: {}. Body: {}", code, body);
-                        } else {
-                            // Try to parse ICS into an icalendar::Calendar
-                            match icalendar::parser::read_calendar(&body) {
-                                Ok(parsed) => {
-                                    let cal: icalendar::Calendar = parsed.into();
-                                    calendar = Some(cal);
-                                }
-                                Err(e) => {
-                                    eprintln!("BenchAppCsv ICS parse error: {}", e);
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("BenchAppCsv read body error: {}", e);
-                    }
-                }
-            }
+    /// Construct from the provided KHL schedule URL, fetched per the HTTP policy.
+    /// Attempts to parse the ICS into a Calendar; errors are logged.
+    #[instrument(level = "info", name = "ical_fetch", skip(url, policy))]
+    pub async fn from_url(url: &str, policy: &HttpPolicy) -> Self {
+        Self::from_fetch(crate::http::get_text(url, policy).await.map(|body| Self::from_ics(&body)))
+    }
+
+    /// [`Ical::from_url`], reusing the last calendar when the feed says it is unchanged.
+    #[instrument(level = "info", name = "ical_fetch", skip(url, policy, cache))]
+    pub async fn from_url_cached(url: &str, policy: &HttpPolicy, cache: &HttpCache) -> Self {
+        let fetched = cache.fetch(url, policy, |body| Ok(Self::from_ics(body))).await;
+        Self::from_fetch(fetched.and_then(|parsed| parsed))
+    }
+
+    fn from_fetch(fetched: Result<Self, String>) -> Self {
+        // Report any errors, but keep constructor infallible.
+        match fetched {
+            Ok(ical) => ical,
             Err(e) => {
-                eprintln!("BenchAppCsv GET error: {}", e);
+                error!(error = %e, "iCal feed request failed");
+                Self { calendar: None, timezone: None, quarantined: Vec::new(), error: Some(Error::Network(format!("iCal feed request failed: {}", e))) }
             }
         }
-
-        Self { calendar }
     }
 
-    /// Build from a raw ICS string (no network).
-    #[allow(dead_code)]
+    /// Build from a raw ICS string (no network). Malformed events are quarantined rather than
+    /// failing the whole calendar.
     pub fn from_ics(ics: &str) -> Self {
-        let calendar = match icalendar::parser::read_calendar(ics) {
-            Ok(parsed) => Some(parsed.into()),
+        match ingest::parse_isolated(ics, |start| parse_dt(start, None).is_some()) {
+            Ok((calendar, quarantined)) => Self { calendar: Some(Arc::new(calendar)), timezone: None, quarantined, error: None },
             Err(e) => {
-                eprintln!("BenchAppCsv ICS parse error (from_ics): {}", e);
-                None
+                error!(error = %e, "Invalid iCal feed");
+                Self { calendar: None, timezone: None, quarantined: Vec::new(), error: Some(Error::Parse(format!("Invalid iCal feed: {}", e))) }
             }
-        };
-        Self { calendar }
+        }
+    }
+
+    fn unavailable(&self) -> Error {
+        self.error.clone().unwrap_or_else(|| Error::Parse("No ICS available".to_string()))
+    }
+
+    /// Interpret UTC event times in the given timezone.
+    pub fn with_timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    fn parse_dt(&self, s: &str) -> Option<NaiveDateTime> {
+        parse_dt(s, self.timezone)
     }
 
     /// Generate a BenchApp import CSV representing all VEVENT entries in the ICS that start AFTER the provided cutoff datetime.
     /// Columns: Type,Game Type,Title (Optional),Away,Home,Date,Time,Duration,Location (Optional),Address (Optional),Notes (Optional)
-    pub fn to_bench_app_csv(&self, cutoff: NaiveDateTime) -> Result<String, String> {
-        let cal = self.calendar.as_ref().ok_or_else(|| "No ICS available".to_string())?;
+    pub fn to_bench_app_csv(&self, cutoff: NaiveDateTime) -> Result<String, Error> {
+        let cal = self.calendar.as_ref().ok_or_else(|| self.unavailable())?;
 
         let mut out = String::new();
         out.push_str("Type,Game Type,Title (Optional),Away,Home,Date,Time,Duration,Location (Optional),Address (Optional),Notes (Optional)\n");
@@ -79,12 +95,12 @@ impl Ical {
                 let dtstart_s = e.property_value("DTSTART").unwrap_or("").to_string();
                 let dtend_s = e.property_value("DTEND").map(|s| s.to_string());
 
-                let start = parse_dt(&dtstart_s).ok_or_else(|| format!("Invalid DTSTART: {}", dtstart_s))?;
+                let start = self.parse_dt(&dtstart_s).ok_or_else(|| Error::Parse(format!("Invalid DTSTART: {}", dtstart_s)))?;
                 // Only include events strictly after the cutoff
-                if !(start > cutoff) {
+                if start <= cutoff {
                     continue;
                 }
-                let end = dtend_s.and_then(|s| parse_dt(&s)).unwrap_or_else(|| start + chrono::Duration::minutes(60));
+                let end = dtend_s.and_then(|s| self.parse_dt(&s)).unwrap_or_else(|| start + chrono::Duration::minutes(60));
 
                 let date_str = format!("{}/{}/{}", start.day(), start.month(), start.year());
                 let time_str = start.format("%I:%M %p").to_string();
@@ -127,37 +143,50 @@ impl Ical {
     /// Build a concise Discord message indicating the latest scheduled game date
     /// among events strictly after the provided cutoff. Falls back to a generic
     /// message when none are found.
-    pub fn discord_message(&self, cutoff: NaiveDateTime) -> Result<String, String> {
-        let cal = self.calendar.as_ref().ok_or_else(|| "No ICS available".to_string())?;
-        let mut latest: Option<NaiveDateTime> = None;
-        for comp in &cal.components {
-            if let icalendar::CalendarComponent::Event(e) = comp {
-                let dtstart_s = e.property_value("DTSTART").unwrap_or("").to_string();
-                if let Some(start) = parse_dt(&dtstart_s) {
-                    if start > cutoff {
-                        latest = Some(match latest { Some(cur) => cur.max(start), None => start });
-                    }
-                }
-            }
+    pub fn discord_message(&self, cutoff: NaiveDateTime) -> Result<String, Error> {
+        if self.calendar.is_none() {
+            return Err(self.unavailable());
         }
-        if let Some(dt) = latest {
+        if let Some(dt) = self.last_event_start(cutoff) {
             Ok(format!("BenchApp import schedule attached. Games scheduled until {}.", dt.date()))
         } else {
             Ok("BenchApp import schedule attached. No upcoming games found.".to_string())
         }
     }
+
+    /// Start of the latest event strictly after the provided cutoff, if any.
+    pub fn last_event_start(&self, cutoff: NaiveDateTime) -> Option<NaiveDateTime> {
+        self.event_starts().filter(|start| *start > cutoff).max()
+    }
+
+    fn event_starts(&self) -> impl Iterator<Item = NaiveDateTime> + '_ {
+        self.calendar.iter().flat_map(|cal| &cal.components).filter_map(|comp| match comp {
+            icalendar::CalendarComponent::Event(e) => self.parse_dt(e.property_value("DTSTART").unwrap_or("")),
+            _ => None,
+        })
+    }
+
+    /// Start of the earliest event strictly after the provided cutoff, if any.
+    pub fn next_event_start(&self, cutoff: NaiveDateTime) -> Option<NaiveDateTime> {
+        self.event_starts().filter(|start| *start > cutoff).min()
+    }
 }
 
-fn parse_dt(s: &str) -> Option<NaiveDateTime> {
+fn parse_dt(s: &str, timezone: Option<chrono_tz::Tz>) -> Option<NaiveDateTime> {
     if s.is_empty() { return None; }
-    // Strip trailing Z if present (treat as local/naive for CSV)
-    let s2 = if s.ends_with('Z') { &s[..s.len()-1] } else { s };
+    // Strip trailing Z if present (treat as local/naive for CSV unless a timezone is configured)
+    let (s2, is_utc) = match s.strip_suffix('Z') { Some(stripped) => (stripped, true), None => (s, false) };
     for pat in ["%Y%m%dT%H%M%S", "%Y%m%dT%H%M"].iter() {
-        if let Ok(dt) = NaiveDateTime::parse_from_str(s2, pat) { return Some(dt); }
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s2, pat) {
+            return Some(match timezone {
+                Some(tz) if is_utc => dt.and_utc().with_timezone(&tz).naive_local(),
+                _ => dt,
+            });
+        }
     }
     // All-day dates (no time)
     if let Ok(date) = chrono::NaiveDate::parse_from_str(s2, "%Y%m%d") {
-        return Some(date.and_hms_opt(0, 0, 0)?);
+        return date.and_hms_opt(0, 0, 0);
     }
     None
 }
@@ -195,5 +224,5 @@ fn split_location_address(location: &str) -> (String, String) {
     }
 }
 
-fn escape_quotes(s: &str) -> String { s.replace('"', "\"") }
+fn escape_quotes(s: &str) -> String { s.replace('"', "\"\"") }
 
@@ -0,0 +1,117 @@
+use icalendar::{Calendar, CalendarComponent, Component};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// A VEVENT left out of the calendar because it could not be used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct QuarantinedEvent {
+    /// 1-based line of the event's BEGIN:VEVENT in the feed.
+    pub line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    pub reason: String,
+}
+
+/// One VEVENT's lines, as found in the feed.
+struct EventBlock<'a> {
+    line: usize,
+    lines: Vec<&'a str>,
+    closed: bool,
+}
+
+impl EventBlock<'_> {
+    /// UID as written, for reporting events that don't parse.
+    fn uid(&self) -> Option<String> {
+        self.lines.iter().find_map(|l| l.strip_prefix("UID:")).map(|uid| uid.trim().to_string())
+    }
+
+    /// Why the block doesn't parse, pointing at the first line that isn't a property when there is
+    /// one (the parser's own message refers to its position in the isolated event).
+    fn parse_error(&self) -> String {
+        let not_a_property = self
+            .lines
+            .iter()
+            .enumerate()
+            .find(|(_, l)| !l.starts_with([' ', '\t']) && !l.trim().is_empty() && !l.contains(':'));
+        match not_a_property {
+            Some((offset, line)) => format!("Line {} is not a property: {:?}", self.line + offset, line.trim_end()),
+            None => "VEVENT could not be parsed".to_string(),
+        }
+    }
+}
+
+/// Split the feed into its VEVENT blocks and everything else (calendar properties, time zones).
+fn split_events(ics: &str) -> (String, Vec<EventBlock<'_>>) {
+    let mut rest = String::new();
+    let mut events: Vec<EventBlock> = Vec::new();
+    let mut open: Option<EventBlock> = None;
+    for (index, line) in ics.lines().enumerate() {
+        let keyword = line.trim_end().to_ascii_uppercase();
+        if keyword == "BEGIN:VEVENT" {
+            // A VEVENT that never ended is cut off by the next one
+            events.extend(open.take());
+            open = Some(EventBlock { line: index + 1, lines: vec![line], closed: false });
+        } else if let Some(block) = open.as_mut() {
+            if keyword == "END:VCALENDAR" {
+                events.extend(open.take());
+                rest.push_str(line);
+                rest.push('\n');
+                continue;
+            }
+            block.lines.push(line);
+            if keyword == "END:VEVENT" {
+                block.closed = true;
+                events.extend(open.take());
+            }
+        } else {
+            rest.push_str(line);
+            rest.push('\n');
+        }
+    }
+    events.extend(open);
+    (rest, events)
+}
+
+/// Parse one VEVENT on its own, checking it has a usable start.
+fn parse_event(block: &EventBlock, valid_start: &impl Fn(&str) -> bool) -> Result<CalendarComponent, String> {
+    if !block.closed {
+        return Err("VEVENT is not closed with END:VEVENT".to_string());
+    }
+    let wrapped = format!("BEGIN:VCALENDAR\n{}\nEND:VCALENDAR\n", block.lines.join("\n"));
+    let parsed: Calendar = icalendar::parser::read_calendar(&wrapped).map_err(|_| block.parse_error())?.into();
+    let event = parsed
+        .components
+        .into_iter()
+        .find(|c| matches!(c, CalendarComponent::Event(_)))
+        .ok_or_else(|| "VEVENT could not be read".to_string())?;
+    if let CalendarComponent::Event(e) = &event {
+        let start = e.property_value("DTSTART").unwrap_or("");
+        if !valid_start(start) {
+            return Err(format!("Invalid DTSTART: {:?}", start));
+        }
+    }
+    Ok(event)
+}
+
+/// Parse a feed one VEVENT at a time, so a malformed event is quarantined (and logged) instead of
+/// failing or silently shortening the whole calendar. `valid_start` says whether a DTSTART value
+/// can be used. Errors only when the calendar around the events can't be parsed.
+pub fn parse_isolated(ics: &str, valid_start: impl Fn(&str) -> bool) -> Result<(Calendar, Vec<QuarantinedEvent>), String> {
+    let (rest, blocks) = split_events(ics);
+    let mut calendar: Calendar = icalendar::parser::read_calendar(&rest)?.into();
+    let mut quarantined = Vec::new();
+    for block in &blocks {
+        match parse_event(block, &valid_start) {
+            Ok(event) => {
+                calendar.push(event);
+            }
+            Err(reason) => {
+                let uid = block.uid();
+                warn!(line = block.line, uid = uid.as_deref().unwrap_or(""), reason = %reason, "Quarantined ICS event");
+                quarantined.push(QuarantinedEvent { line: block.line, uid, reason });
+            }
+        }
+    }
+    Ok((calendar, quarantined))
+}
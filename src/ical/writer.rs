@@ -0,0 +1,53 @@
+use icalendar::{Calendar, Component, Event, EventLike};
+
+use crate::daysmart::DaySmart;
+use crate::model::game::GameInfo;
+
+/// Build a VCALENDAR with one VEVENT per game. UIDs are derived from the DaySmart game id, so
+/// calendar apps update a rescheduled game in place instead of adding a second copy.
+pub fn write_calendar(day_smart: &DaySmart, games: &[GameInfo]) -> String {
+    let mut calendar = Calendar::new();
+    for game in games {
+        calendar.push(game_event(day_smart, game));
+    }
+    calendar.done().to_string()
+}
+
+/// A VCALENDAR of every game in a league document, titled "Visitor @ Home" since it belongs to
+/// no one team. UIDs match the team calendars', so a game is the same event in both.
+pub fn write_league_calendar(day_smart: &DaySmart, games: &[GameInfo]) -> String {
+    let mut calendar = Calendar::new();
+    for game in games {
+        let (home, visitor) = day_smart.home_and_visitor(game);
+        let matchup = format!("{} @ {}", visitor, home);
+        let summary = if game.is_playoff { format!("Playoff: {}", matchup) } else { matchup };
+        calendar.push(
+            Event::new()
+                .uid(&format!("daysmart-{}", game.id))
+                .summary(&summary)
+                .location(day_smart.rink_name(game).as_str())
+                .starts(game.dt)
+                .ends(game.ends())
+                .done(),
+        );
+    }
+    calendar.done().to_string()
+}
+
+fn game_event(day_smart: &DaySmart, game: &GameInfo) -> Event {
+    let (opponent, jersey) = day_smart.matchup(game);
+    let summary = if game.is_playoff { format!("Playoff: vs {}", opponent) } else { format!("vs {}", opponent) };
+    let mut description = format!("{} Jerseys", jersey);
+    if let Some(line) = day_smart.locker_room(game).line() {
+        description.push('\n');
+        description.push_str(&line);
+    }
+    Event::new()
+        .uid(&format!("daysmart-{}", game.id))
+        .summary(&summary)
+        .description(&description)
+        .location(day_smart.rink_name(game).as_str())
+        .starts(game.dt)
+        .ends(game.ends())
+        .done()
+}
@@ -0,0 +1,441 @@
+use std::collections::HashMap;
+
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, Utc};
+use lambda_runtime::{Error, LambdaEvent};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{error, info, warn};
+
+use crate::daysmart::{self, DaySmart};
+use crate::dm;
+use crate::error;
+use crate::fees;
+use crate::handler::{self, Request};
+use crate::model::game::GameInfo;
+use crate::roster::{self, Player, Position, Rsvp};
+use crate::rotation;
+use crate::sources::Sources;
+use crate::state::StateStore;
+
+/// Environment variable holding the application's public key (hex), from the Discord developer portal.
+pub const PUBLIC_KEY_VAR: &str = "DISCORD_PUBLIC_KEY";
+
+/// How far `/nextgame` looks for the next game.
+pub const NEXT_GAME_DAYS: i64 = 60;
+
+/// Days of games `/schedule` lists.
+pub const SCHEDULE_DAYS: i64 = 14;
+
+// Interaction and response types from the Discord API
+const PING: u64 = 1;
+const APPLICATION_COMMAND: u64 = 2;
+const PONG: u64 = 1;
+const CHANNEL_MESSAGE: u64 = 4;
+// Message flag showing a reply only to the user who asked
+const EPHEMERAL: u64 = 1 << 6;
+
+/// The parts of a Function URL / API Gateway (v1 or v2) event an interaction needs.
+#[derive(Debug, Deserialize)]
+pub struct HttpRequest {
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default, rename = "isBase64Encoded")]
+    pub is_base64_encoded: bool,
+    /// API Gateway v1 method.
+    #[serde(default, rename = "httpMethod")]
+    pub http_method: Option<String>,
+    /// Function URL / API Gateway v2 request context, carrying the method as `http.method`.
+    #[serde(default, rename = "requestContext")]
+    pub request_context: Value,
+}
+
+impl HttpRequest {
+    /// Header value by case-insensitive name (API Gateway v1 keeps the client's casing).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+
+    /// HTTP method, upper-case; empty when the event doesn't say.
+    pub fn method(&self) -> String {
+        self.http_method
+            .as_deref()
+            .or_else(|| self.request_context.pointer("/http/method").and_then(Value::as_str))
+            .unwrap_or_default()
+            .to_ascii_uppercase()
+    }
+
+    fn body(&self) -> Result<String, String> {
+        let body = self.body.clone().unwrap_or_default();
+        if !self.is_base64_encoded {
+            return Ok(body);
+        }
+        let bytes = base64::engine::general_purpose::STANDARD.decode(body).map_err(|e| format!("Invalid base64 body: {}", e))?;
+        String::from_utf8(bytes).map_err(|e| format!("Body is not UTF-8: {}", e))
+    }
+}
+
+/// Check Discord's Ed25519 signature over timestamp + body against the application's public key.
+pub fn verify_signature(public_key_hex: &str, signature_hex: &str, timestamp: &str, body: &str) -> bool {
+    let (Ok(public_key), Ok(signature)) = (hex::decode(public_key_hex.trim()), hex::decode(signature_hex.trim())) else {
+        return false;
+    };
+    let message = [timestamp.as_bytes(), body.as_bytes()].concat();
+    ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key).verify(&message, &signature).is_ok()
+}
+
+/// A slash command this endpoint answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    NextGame,
+    Schedule,
+}
+
+impl Command {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "nextgame" => Some(Command::NextGame),
+            "schedule" => Some(Command::Schedule),
+            _ => None,
+        }
+    }
+}
+
+/// A slash command that changes what is stored for the team, with the user who ran it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// `/remindme`: game-day direct messages for the user; with `off: true`, no more of them.
+    RemindMe { user_id: String, off: bool },
+    /// `/swap duty first second`: exchange who has a rotation duty ("scorekeeper" or
+    /// "beverages") at the games on two local dates, given as YYYY-MM-DD.
+    Swap { duty: String, first: String, second: String },
+    /// `/roster position`: register the user (as `name`) at "skater", "defense" or "goalie";
+    /// with `leave: true`, take them off the roster.
+    Roster { user_id: String, name: String, position: String, leave: bool },
+    /// `/rsvp answer [date]`: the user's "yes" or "no" for the game on a local YYYY-MM-DD date,
+    /// or the next game without one.
+    Rsvp { user_id: String, answer: String, date: Option<String> },
+}
+
+impl Action {
+    fn parse(name: &str, interaction: &Value) -> Option<Self> {
+        // Guild interactions carry the user under member, direct messages at the top level
+        let user_id = interaction
+            .pointer("/member/user/id")
+            .or_else(|| interaction.pointer("/user/id"))
+            .and_then(Value::as_str)?
+            .to_string();
+        let option = |option_name: &str| {
+            interaction["data"]["options"]
+                .as_array()
+                .and_then(|options| options.iter().find(|option| option["name"] == option_name))
+                .map(|option| &option["value"])
+        };
+        let text = |option_name: &str| option(option_name).and_then(Value::as_str).unwrap_or_default().to_string();
+        let flag = |option_name: &str| option(option_name).and_then(Value::as_bool).unwrap_or(false);
+        match name {
+            "remindme" => Some(Action::RemindMe { user_id, off: flag("off") }),
+            "swap" => Some(Action::Swap { duty: text("duty"), first: text("first"), second: text("second") }),
+            "roster" => {
+                // The server nickname, else the account's display name, else its username
+                let name = ["/member/nick", "/member/user/global_name", "/member/user/username", "/user/global_name", "/user/username"]
+                    .iter()
+                    .find_map(|pointer| interaction.pointer(pointer).and_then(Value::as_str))
+                    .unwrap_or(&user_id)
+                    .to_string();
+                Some(Action::Roster { user_id, name, position: text("position"), leave: flag("leave") })
+            }
+            "rsvp" => Some(Action::Rsvp { user_id, answer: text("answer"), date: option("date").and_then(Value::as_str).map(str::to_string) }),
+            _ => None,
+        }
+    }
+
+    /// Whether carrying it out needs the team's schedule.
+    pub fn needs_schedule(&self) -> bool {
+        matches!(self, Action::Swap { .. } | Action::Rsvp { .. })
+    }
+}
+
+/// What a verified interaction asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Interaction {
+    Ping,
+    Command(Command),
+    Action(Action),
+    /// A command (or interaction type) this endpoint doesn't handle, by name.
+    Unsupported(String),
+}
+
+/// Read the interaction from a verified request body.
+pub fn parse_interaction(body: &str) -> Result<Interaction, String> {
+    let interaction: Value = serde_json::from_str(body).map_err(|e| format!("Invalid interaction: {}", e))?;
+    match interaction["type"].as_u64() {
+        Some(PING) => Ok(Interaction::Ping),
+        Some(APPLICATION_COMMAND) => {
+            let name = interaction["data"]["name"].as_str().unwrap_or_default();
+            if let Some(command) = Command::parse(name) {
+                return Ok(Interaction::Command(command));
+            }
+            Ok(Action::parse(name, &interaction).map_or_else(|| Interaction::Unsupported(name.to_string()), Interaction::Action))
+        }
+        other => Ok(Interaction::Unsupported(format!("interaction type {}", other.map_or("?".to_string(), |t| t.to_string())))),
+    }
+}
+
+/// The reply to `command`, formatted like the scheduled reminders.
+pub fn command_reply(command: Command, day_smart: &DaySmart, message_template: Option<&str>, now: DateTime<Utc>) -> String {
+    let format = |game| match message_template {
+        Some(template) => day_smart.format_game_message_with(template, game).unwrap_or_else(|e| {
+            error!(error = %e, "Custom reminder template failed; using the default");
+            day_smart.format_game_message(game)
+        }),
+        None => day_smart.format_game_message(game),
+    };
+    match command {
+        Command::NextGame => match day_smart.next_game(NEXT_GAME_DAYS, now) {
+            Some(game) => format(&game),
+            None => format!("No games in the next {} days.", NEXT_GAME_DAYS),
+        },
+        Command::Schedule => {
+            let messages = day_smart.upcoming_games(SCHEDULE_DAYS, now).iter().map(format).collect();
+            daysmart::games_digest(messages).unwrap_or_else(|| format!("No games in the next {} days.", SCHEDULE_DAYS))
+        }
+    }
+}
+
+/// Carry out `action` for the team in `request` and say how it went. `day_smart` is the team's
+/// schedule, for actions that [need it](Action::needs_schedule).
+pub async fn action_reply(
+    action: &Action,
+    store: &StateStore,
+    request: &Request,
+    day_smart: Option<&DaySmart>,
+    now: DateTime<Utc>,
+) -> String {
+    let team_id = request.team_id.as_str();
+    let result = match action {
+        Action::RemindMe { user_id, off: false } => dm::subscribe(store, team_id, user_id).await.map(|added| {
+            if added {
+                "You'll get a direct message on game days. Use /remindme off to stop.".to_string()
+            } else {
+                "You're already getting game-day messages.".to_string()
+            }
+        }),
+        Action::RemindMe { user_id, off: true } => dm::unsubscribe(store, team_id, user_id).await.map(|removed| {
+            if removed {
+                "No more game-day messages.".to_string()
+            } else {
+                "You weren't getting game-day messages.".to_string()
+            }
+        }),
+        Action::Swap { duty, first, second } => {
+            let Some(day_smart) = day_smart else {
+                return "Couldn't reach DaySmart right now; try again in a minute.".to_string();
+            };
+            let config = match duty.as_str() {
+                "scorekeeper" => request.scorekeeper.as_ref(),
+                "beverages" => request.beverages.as_ref(),
+                _ => return format!("Unknown duty {:?}; use scorekeeper or beverages.", duty),
+            };
+            let Some(config) = config else {
+                return format!("No {} rotation is configured.", duty);
+            };
+            let (a, b) = match (game_on(day_smart, first), game_on(day_smart, second)) {
+                (Ok(a), Ok(b)) => (a, b),
+                (Err(e), _) | (_, Err(e)) => return e,
+            };
+            let key = config.key.as_deref().unwrap_or(duty);
+            // Both games need someone assigned; assigning here also covers games not announced yet
+            for game in [&a, &b] {
+                if let Err(e) = rotation::assignee(store, team_id, key, config, &game.id.to_string()).await {
+                    error!(error = %e, "Failed to load the rotation for a swap");
+                    return "Couldn't save that right now; try again in a minute.".to_string();
+                }
+            }
+            rotation::swap(store, team_id, key, &a.id.to_string(), &b.id.to_string()).await.map(|()| {
+                let tz = day_smart.timezone();
+                format!(
+                    "Swapped {} duty between {} and {}.",
+                    duty,
+                    a.dt.with_timezone(&tz).format("%a %b %-d"),
+                    b.dt.with_timezone(&tz).format("%a %b %-d")
+                )
+            })
+        }
+        Action::Roster { user_id, leave: true, .. } => roster::unregister(store, team_id, user_id).await.map(|removed| {
+            if removed {
+                "You're off the roster.".to_string()
+            } else {
+                "You weren't on the roster.".to_string()
+            }
+        }),
+        Action::Roster { user_id, name, position, leave: false } => {
+            let position = match position.as_str() {
+                "skater" => Position::Skater,
+                "defense" => Position::Defense,
+                "goalie" => Position::Goalie,
+                _ => return format!("Unknown position {:?}; use skater, defense or goalie.", position),
+            };
+            let player = Player { name: name.clone(), position };
+            roster::register(store, team_id, user_id, player).await.map(|added| {
+                let verb = if added { "You're on the roster" } else { "Your roster entry is updated" };
+                format!("{} as {}, {}.", verb, name, position_name(position))
+            })
+        }
+        Action::Rsvp { user_id, answer, date } => {
+            let rsvp = match answer.as_str() {
+                "yes" => Rsvp::Yes,
+                "no" => Rsvp::No,
+                _ => return format!("Unknown answer {:?}; use yes or no.", answer),
+            };
+            let keyed = |day_smart: &DaySmart, game: GameInfo| (game.id.to_string(), game.dt.with_timezone(&day_smart.timezone()).date_naive());
+            let found = match (day_smart, date) {
+                (Some(day_smart), Some(date)) => game_on(day_smart, date).map(|game| keyed(day_smart, game)),
+                (Some(day_smart), None) => day_smart
+                    .next_game(NEXT_GAME_DAYS, now)
+                    .map(|game| keyed(day_smart, game))
+                    .ok_or_else(|| format!("No games in the next {} days.", NEXT_GAME_DAYS)),
+                (None, _) => Err("Couldn't reach DaySmart right now; try again in a minute.".to_string()),
+            };
+            // Pickup games (fee_split) aren't in DaySmart: an answer for a day without a DaySmart
+            // game is kept under the date
+            let pickup_day = date.as_deref().filter(|_| request.fee_split.is_some()).and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+            let (key, day) = match (found, pickup_day) {
+                (Ok(found), _) => found,
+                (Err(_), Some(day)) => (fees::rsvp_key(day), day),
+                (Err(e), None) => return e,
+            };
+            roster::respond(store, team_id, &key, user_id, rsvp, now).await.map(|()| {
+                let day = day.format("%a %b %-d");
+                match rsvp {
+                    Rsvp::Yes => format!("Got it: you're in for {}.", day),
+                    Rsvp::No => format!("Got it: you're out for {}.", day),
+                }
+            })
+        }
+    };
+    result.unwrap_or_else(|e| {
+        error!(error = %e, ?action, "Failed to save a slash command's change");
+        "Couldn't save that right now; try again in a minute.".to_string()
+    })
+}
+
+/// The first game on a local date given as YYYY-MM-DD, or the reply saying why there is none.
+fn game_on(day_smart: &DaySmart, date: &str) -> Result<GameInfo, String> {
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("{:?} is not a date like 2025-09-28.", date))?;
+    day_smart.games_on(day).into_iter().next().ok_or_else(|| format!("There is no game on {}.", day.format("%a %b %-d")))
+}
+
+fn position_name(position: Position) -> &'static str {
+    match position {
+        Position::Skater => "skater",
+        Position::Defense => "defense",
+        Position::Goalie => "goalie",
+    }
+}
+
+/// Function URL / API Gateway response.
+fn http_response(status: u16, body: Value) -> Value {
+    json!({ "statusCode": status, "headers": { "content-type": "application/json" }, "body": body.to_string() })
+}
+
+fn message(content: &str) -> Value {
+    json!({ "type": CHANNEL_MESSAGE, "data": { "content": content } })
+}
+
+/// A reply only the user who ran the command sees.
+fn private_message(content: &str) -> Value {
+    json!({ "type": CHANNEL_MESSAGE, "data": { "content": content, "flags": EPHEMERAL } })
+}
+
+/// Entry point for the interactions endpoint (`HANDLER_MODE=interactions`). Discord needs an answer
+/// within 3 seconds, so keep the stored request's `http` timeouts short.
+pub async fn handle(event: LambdaEvent<Value>) -> Result<Value, Error> {
+    let public_key = std::env::var(PUBLIC_KEY_VAR).map_err(|_| format!("{} is not set", PUBLIC_KEY_VAR))?;
+    let request: HttpRequest = serde_json::from_value(event.payload).map_err(|e| format!("Not an HTTP event: {}", e))?;
+    let body = match request.body() {
+        Ok(body) => body,
+        Err(e) => return Ok(http_response(400, json!({ "error": e }))),
+    };
+    let signed = match (request.header("x-signature-ed25519"), request.header("x-signature-timestamp")) {
+        (Some(signature), Some(timestamp)) => verify_signature(&public_key, signature, timestamp, &body),
+        _ => false,
+    };
+    // Discord probes the endpoint with bad signatures and expects them refused
+    if !signed {
+        warn!("Rejected interaction with a missing or invalid signature");
+        return Ok(http_response(401, json!({ "error": "invalid request signature" })));
+    }
+    let reply = match parse_interaction(&body) {
+        Ok(Interaction::Ping) => json!({ "type": PONG }),
+        Ok(Interaction::Command(command)) => {
+            info!(?command, "Answering slash command");
+            message(&answer(command).await)
+        }
+        Ok(Interaction::Action(action)) => {
+            info!(?action, "Carrying out slash command");
+            private_message(&carry_out(&action).await)
+        }
+        Ok(Interaction::Unsupported(name)) => {
+            warn!(name = %name, "Unsupported interaction");
+            message(&format!("Unknown command: {}", name))
+        }
+        Err(e) => return Ok(http_response(400, json!({ "error": e }))),
+    };
+    Ok(http_response(200, reply))
+}
+
+/// Load the team's state and carry out `action`; failures become the reply so the user sees them.
+async fn carry_out(action: &Action) -> String {
+    let request = match handler::load_valid_request(Value::Null).await {
+        Ok(request) => request,
+        Err(e) => {
+            error!(error = %e, "Failed to load the request for a slash command");
+            return "This command is not configured.".to_string();
+        }
+    };
+    let Some(config) = &request.state else {
+        return "This command needs the team's state, which is not configured.".to_string();
+    };
+    let day_smart = if action.needs_schedule() {
+        fetch_daysmart(&request).await.inspect_err(|e| error!(error = %e, "DaySmart fetch failed for a slash command")).ok()
+    } else {
+        None
+    };
+    action_reply(action, &StateStore::from_config(config).await, &request, day_smart.as_deref(), Utc::now()).await
+}
+
+/// Fetch the schedule and reply to `command`; failures become the reply so the user sees them.
+async fn answer(command: Command) -> String {
+    let request = match handler::load_valid_request(Value::Null).await {
+        Ok(request) => request,
+        Err(e) => {
+            error!(error = %e, "Failed to load the request for a slash command");
+            return "Schedule lookup is not configured.".to_string();
+        }
+    };
+    match fetch_daysmart(&request).await {
+        Ok(day_smart) => command_reply(command, &day_smart, request.message_template.as_deref(), Utc::now()),
+        Err(e) => {
+            error!(error = %e, "DaySmart fetch failed for a slash command");
+            "Couldn't reach DaySmart right now; try again in a minute.".to_string()
+        }
+    }
+}
+
+pub(crate) async fn fetch_daysmart(request: &Request) -> Result<std::sync::Arc<DaySmart>, error::Error> {
+    let sources = Sources::fetch(
+        Some((request.team_id.clone(), request.company.clone())),
+        None,
+        request.timezone().map_err(error::Error::Config)?,
+        request.placeholders.clone().unwrap_or_default(),
+        request.jerseys.clone().unwrap_or_default(),
+        request.daysmart_view(),
+        request.http.unwrap_or_default(),
+        request.facility_hours.clone(),
+        request.http_cache().await,
+    );
+    sources.daysmart().await.unwrap_or_else(|| Err(error::Error::Config("DaySmart not fetched".to_string())))
+}
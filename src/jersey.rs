@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_HOME_JERSEY: &str = "Light";
+pub const DEFAULT_AWAY_JERSEY: &str = "Dark";
+
+/// Which jersey we wear. Most leagues have the home team in light jerseys; leagues that wear dark
+/// at home swap the two, and `opponents` covers one-off clashes (e.g. an opponent whose only
+/// jerseys are white).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JerseyConfig {
+    #[serde(default = "default_home_jersey")]
+    pub home_jersey: String,
+    #[serde(default = "default_away_jersey")]
+    pub away_jersey: String,
+    /// Jersey to wear against a given opponent, home or away. Opponent names match ignoring case.
+    #[serde(default)]
+    pub opponents: BTreeMap<String, String>,
+}
+
+fn default_home_jersey() -> String { DEFAULT_HOME_JERSEY.to_string() }
+
+fn default_away_jersey() -> String { DEFAULT_AWAY_JERSEY.to_string() }
+
+impl Default for JerseyConfig {
+    fn default() -> Self {
+        Self { home_jersey: default_home_jersey(), away_jersey: default_away_jersey(), opponents: BTreeMap::new() }
+    }
+}
+
+impl JerseyConfig {
+    /// Our jersey for a game, given whether we are home and the opponent's name (if known).
+    pub fn color(&self, is_home: bool, opponent: Option<&str>) -> &str {
+        let overridden = opponent.and_then(|opponent| {
+            let opponent = opponent.trim();
+            self.opponents.iter().find(|(name, _)| name.trim().eq_ignore_ascii_case(opponent)).map(|(_, color)| color)
+        });
+        match overridden {
+            Some(color) => color,
+            None if is_home => &self.home_jersey,
+            None => &self.away_jersey,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+use crate::daysmart::DaySmart;
+use crate::ical;
+
+/// Combined calendar's name inside the bundle.
+pub const LEAGUE_CALENDAR: &str = "league.ics";
+
+fn default_filename() -> String { "league-calendars.zip".to_string() }
+
+fn default_true() -> bool { true }
+
+/// Every team's calendar plus a combined league calendar in one zip (league_bundle workflow), for
+/// league admins handing calendars out to each team.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeagueBundleConfig {
+    /// DaySmart league to export; defaults to the league of `team_id`.
+    #[serde(default)]
+    pub league_id: Option<i64>,
+    /// Upload the zip to this bucket, at `key`.
+    #[serde(default)]
+    pub bucket: Option<String>,
+    /// Object key; defaults to `filename`.
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Name of the zip, default "league-calendars.zip".
+    #[serde(default = "default_filename")]
+    pub filename: String,
+    /// Attach the zip to a post (default true).
+    #[serde(default = "default_true")]
+    pub post: bool,
+}
+
+/// One calendar per team in the league document, named after the team, then the league calendar.
+pub fn calendars(league: &DaySmart) -> Vec<(String, String)> {
+    let mut files: Vec<(String, String)> = Vec::new();
+    for (team_id, name) in league.league_teams() {
+        let view = league.team_view(team_id);
+        let mut filename = format!("{}.ics", file_stem(name.as_str()));
+        // Two teams can share a name across divisions
+        if files.iter().any(|(existing, _)| *existing == filename) {
+            filename = format!("{}-{}.ics", file_stem(name.as_str()), team_id);
+        }
+        files.push((filename, ical::writer::write_calendar(&view, &view.all_games())));
+    }
+    files.push((LEAGUE_CALENDAR.to_string(), ical::writer::write_league_calendar(league, &league.all_games())));
+    files
+}
+
+/// Lower-case file name stem: letters and digits, everything else collapsed to single dashes.
+pub fn file_stem(name: &str) -> String {
+    let stem = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if stem.is_empty() { "team".to_string() } else { stem }
+}
+
+/// Zip `files` (name, content), deflate-compressed.
+pub fn zip_files(files: &[(String, String)]) -> Result<Vec<u8>, String> {
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, content) in files {
+        writer
+            .start_file(name.as_str(), options)
+            .and_then(|_| writer.write_all(content.as_bytes()).map_err(zip::result::ZipError::from))
+            .map_err(|e| format!("Failed to zip {}: {}", name, e))?;
+    }
+    let cursor = writer.finish().map_err(|e| format!("Failed to zip league calendars: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+/// Upload the zip to `bucket`/`key`. Returns the s3:// location.
+#[instrument(level = "info", skip(bytes), fields(bytes = bytes.len()))]
+pub async fn upload(bucket: &str, key: &str, bytes: Vec<u8>) -> Result<String, String> {
+    let key = key.trim_start_matches('/');
+    crate::aws::s3_client()
+        .await
+        .put_object()
+        .bucket(bucket)
+        .key(key)
+        .content_type("application/zip")
+        .body(bytes.into())
+        .send()
+        .await
+        .map_err(|e| format!("Failed to upload league calendars: {}", e))?;
+    info!("Uploaded league calendars");
+    Ok(format!("s3://{}/{}", bucket, key))
+}
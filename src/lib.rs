@@ -1,5 +1,61 @@
 pub mod model;
+pub mod api;
+pub mod benchapp_api;
+pub mod canary;
+pub mod changes;
+pub mod clock;
+pub mod config_doc;
 pub mod daysmart;
+pub mod dedup;
+pub mod digest;
 pub mod ical;
+pub mod destination;
 pub mod discord;
+pub mod discord_bot;
+pub mod display;
+pub mod email;
+pub mod explain;
+pub mod facility;
+pub mod failure_budget;
+pub mod dm;
+pub mod dropin;
+pub mod enrich;
+pub mod env_config;
+pub mod error;
 pub mod handler;
+pub mod http;
+pub mod http_cache;
+pub mod interactions;
+pub mod jersey;
+pub mod league_bundle;
+pub mod aws;
+pub mod feed;
+pub mod goalie_escalation;
+pub mod links;
+pub mod metrics;
+pub mod fees;
+mod multipart;
+pub mod no_games;
+pub mod notifier;
+pub mod opponents;
+pub mod preview;
+pub mod render;
+pub mod roster;
+pub mod rotation;
+pub mod rsvp_reactions;
+pub mod schedule;
+pub mod scheduled_events;
+pub mod scores;
+pub mod secrets;
+pub mod sources;
+pub mod slack;
+pub mod standings;
+pub mod state;
+pub mod streak;
+pub mod summary;
+pub mod template;
+pub mod tournament;
+pub mod webhook_rotation;
+pub mod workflow;
+pub mod workflows;
+pub mod xray;
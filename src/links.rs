@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::discord_bot::LinkButton;
+
+/// URLs for the link buttons attached to reminders sent with the bot token.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LinksConfig {
+    /// Directions URL per rink name; rinks not listed get a Google Maps search link.
+    #[serde(default)]
+    pub directions: BTreeMap<String, String>,
+    /// Full schedule page. Falls back to the hosted calendar feed when one is published.
+    #[serde(default)]
+    pub schedule_url: Option<String>,
+    #[serde(default)]
+    pub standings_url: Option<String>,
+}
+
+/// Google Maps search link for a place name.
+pub fn maps_search_url(query: &str) -> String {
+    format!("https://www.google.com/maps/search/?api=1&query={}", percent_encode(query))
+}
+
+/// Buttons for a game reminder, in display order. Buttons without a URL are left out.
+pub fn reminder_buttons(config: &LinksConfig, rink: Option<&str>, hosted_schedule_url: Option<String>) -> Vec<LinkButton> {
+    let directions = rink.map(|rink| config.directions.get(rink).cloned().unwrap_or_else(|| maps_search_url(rink)));
+    [
+        ("Directions", directions),
+        ("Full schedule", config.schedule_url.clone().or(hosted_schedule_url)),
+        ("League standings", config.standings_url.clone()),
+    ]
+    .into_iter()
+    .filter_map(|(label, url)| url.map(|url| LinkButton { label: label.to_string(), url }))
+    .collect()
+}
+
+/// Google Calendar "add event" link with the event details filled in.
+pub fn google_calendar_url(title: &str, start: DateTime<Utc>, end: DateTime<Utc>, location: &str) -> String {
+    format!(
+        "https://calendar.google.com/calendar/render?action=TEMPLATE&text={}&dates={}/{}&location={}",
+        percent_encode(title),
+        start.format("%Y%m%dT%H%M%SZ"),
+        end.format("%Y%m%dT%H%M%SZ"),
+        percent_encode(location)
+    )
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters.
+pub fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
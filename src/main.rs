@@ -1,25 +1,33 @@
-mod model;
-mod daysmart;
-mod discord;
-mod ical;
-mod handler;
-
+use hockey_reminder_lambda_rust::{api, handler, interactions, xray};
 use lambda_runtime::{service_fn, Error};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     // Initialize structured logging with tracing
-    let _ = tracing_subscriber::fmt()
-        .json()
-        .with_max_level(tracing::Level::INFO)
-        // Emit a closing event for each span, which includes its total duration
-        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
-        .with_current_span(false)
-        .with_target(false)
-        .with_ansi(false)
+    let _ = tracing_subscriber::registry()
+        // INFO by default; RUST_LOG overrides, e.g. "info,http_capture=debug" to capture outbound calls
+        .with(tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .json()
+                // Emit a closing event for each span, which includes its total duration
+                .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+                .with_current_span(false)
+                .with_target(false)
+                .with_ansi(false),
+        )
+        // TRACE_EXPORTER=xray also sends the spans to X-Ray
+        .with(xray::XrayLayer::from_env())
         .try_init();
 
-    //let _ = handler(LambdaEvent::new(serde_json::from_str("{\"mode\":\"production\",\"date\":\"2026-03-28\", \"discord_hook_url\":\"https://discord.com/api/webhooks/1300382433241464854/B4pFABQURfSy-eZIzX8RaOyEuyJVhXtyd-6iYEGVHsKJfzGyCr5PwTATvpuMBzx6f9IY\",\"team_id\":\"11007\",\"company\":\"kraken\"}")?, Context::default())).await.expect("TODO: panic message");
-
-    lambda_runtime::run(service_fn(handler::handler)).await
+    // The same binary can serve Discord slash commands or the schedule API behind a Function URL / API Gateway
+    match std::env::var("HANDLER_MODE").as_deref() {
+        Ok("interactions") => return lambda_runtime::run(service_fn(|event| Box::pin(interactions::handle(event)))).await,
+        Ok("api") => return lambda_runtime::run(service_fn(|event| Box::pin(api::handle(event)))).await,
+        _ => {}
+    }
+    // Boxed so the runtime's future doesn't embed the handler's (deeply nested) state machine
+    lambda_runtime::run(service_fn(|event| Box::pin(handler::handle_event(event)))).await
 }
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::destination::DeliveryReport;
+use crate::summary::{Outcome, WorkflowReport};
+
+/// CloudWatch namespace the metrics are published under.
+pub const NAMESPACE: &str = "HockeyReminder";
+
+/// CloudWatch embedded metric format (EMF) documents for one invocation: one with the run's
+/// `games_found` and `messages_posted` by team, and one per workflow with its
+/// `workflow_duration_ms` and `workflow_failures` by team and workflow. Lambda turns each one
+/// written to stdout into metrics, so dashboards and alarms need no log parsing.
+pub fn documents(team_id: &str, now: DateTime<Utc>, games_found: Option<usize>, reports: &[WorkflowReport], deliveries: &[DeliveryReport]) -> Vec<Value> {
+    let messages_posted = deliveries.iter().filter(|d| d.error.is_none()).count();
+    let mut run = vec![("messages_posted", "Count", json!(messages_posted))];
+    // Unknown when DaySmart wasn't fetched, which is not the same as no games
+    if let Some(games) = games_found {
+        run.push(("games_found", "Count", json!(games)));
+    }
+    let mut documents = vec![document(now, &[("TeamId", team_id)], &run)];
+    for report in reports {
+        let workflow = serde_json::to_value(report.workflow).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+        documents.push(document(
+            now,
+            &[("TeamId", team_id), ("Workflow", &workflow)],
+            &[
+                ("workflow_duration_ms", "Milliseconds", json!(report.duration_ms)),
+                ("workflow_failures", "Count", json!(u8::from(report.outcome == Outcome::Failed))),
+            ],
+        ));
+    }
+    documents
+}
+
+/// One EMF document: `metrics` as (name, unit, value), recorded under all of `dimensions`.
+fn document(now: DateTime<Utc>, dimensions: &[(&str, &str)], metrics: &[(&str, &str, Value)]) -> Value {
+    let mut doc = json!({
+        "_aws": {
+            "Timestamp": now.timestamp_millis(),
+            "CloudWatchMetrics": [{
+                "Namespace": NAMESPACE,
+                "Dimensions": [dimensions.iter().map(|(name, _)| name).collect::<Vec<_>>()],
+                "Metrics": metrics.iter().map(|(name, unit, _)| json!({ "Name": name, "Unit": unit })).collect::<Vec<_>>(),
+            }],
+        },
+    });
+    for (name, value) in dimensions {
+        doc[*name] = json!(value);
+    }
+    for (name, _, value) in metrics {
+        doc[*name] = value.clone();
+    }
+    doc
+}
+
+/// Write the documents to stdout, one per line, where the Lambda log agent picks them up. They
+/// bypass tracing so no log formatting wraps them.
+pub fn emit(documents: &[Value]) {
+    for doc in documents {
+        println!("{}", doc);
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentAttributes {
+    /// Note text, e.g. "Wear alternate jerseys".
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub event_id: Option<i64>,
+}
@@ -5,14 +5,95 @@ pub struct EventAttributes {
     pub event_type_id: Option<String>,
     pub start: Option<String>,
     pub end: Option<String>,
+    pub end_gmt: Option<String>,
     pub start_date: Option<String>,
     pub event_start_time: Option<String>,
     pub start_gmt: Option<String>,
     pub hteam_id: Option<i64>,
     pub vteam_id: Option<i64>,
     pub resource_id: Option<i64>,
+    // Sheet of a multi-rink resource; 0 when the whole resource is booked
+    #[serde(default)]
+    pub resource_area_id: Option<i64>,
     pub sub_type: Option<String>,
+    // Free-text description (used as the session name for drop-in events)
+    #[serde(default)]
+    pub desc: Option<String>,
     // Additional fields used for locker room events to associate with a game
     pub parent_event_id: Option<i64>,
     pub locker_room_type: Option<String>,
+    // Cancellation markers. Kept loosely typed: DaySmart has sent flags as booleans and as 0/1,
+    // and an unexpected shape must not fail the whole document
+    #[serde(default)]
+    pub is_canceled: Option<serde_json::Value>,
+    #[serde(default)]
+    pub status: Option<serde_json::Value>,
+    // Final score, filled in once a game has been played (number or numeric string)
+    #[serde(default)]
+    pub home_score: Option<serde_json::Value>,
+    #[serde(default)]
+    pub visiting_score: Option<serde_json::Value>,
+}
+
+/// What an event is booked for, from its `event_type_id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventType {
+    /// "G"
+    Game,
+    /// "L": a locker room, linked to its game by `parent_event_id`.
+    LockerRoom,
+    /// Any other type booked for our team, i.e. practice ice.
+    Practice,
+    Other,
+}
+
+impl EventAttributes {
+    /// Classify the event; only events listing `our_team_id` as a team can be practices.
+    pub fn event_type(&self, our_team_id: Option<i64>) -> EventType {
+        match self.event_type_id.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            Some("g") => EventType::Game,
+            Some("l") => EventType::LockerRoom,
+            Some(_) if our_team_id.is_some_and(|our| self.hteam_id == Some(our) || self.vteam_id == Some(our)) => EventType::Practice,
+            _ => EventType::Other,
+        }
+    }
+
+    /// True when the event is flagged as cancelled or postponed.
+    pub fn is_called_off(&self) -> bool {
+        let flagged = match &self.is_canceled {
+            Some(serde_json::Value::Bool(b)) => *b,
+            Some(serde_json::Value::Number(n)) => n.as_i64().is_some_and(|n| n != 0),
+            Some(serde_json::Value::String(s)) => s.eq_ignore_ascii_case("true") || s == "1",
+            _ => false,
+        };
+        let status = self.status.as_ref().and_then(|s| s.as_str()).unwrap_or("");
+        flagged || ["canceled", "cancelled", "postponed"].iter().any(|s| status.eq_ignore_ascii_case(s))
+    }
+
+    /// Final (home, visitor) score when both sides have one.
+    pub fn score(&self) -> Option<(i64, i64)> {
+        fn value(v: &Option<serde_json::Value>) -> Option<i64> {
+            match v.as_ref()? {
+                serde_json::Value::Number(n) => n.as_i64(),
+                serde_json::Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            }
+        }
+        Some((value(&self.home_score)?, value(&self.visiting_score)?))
+    }
+}
+
+/// JSON:API collection document returned by the `events` endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventsDocument {
+    #[serde(default)]
+    pub data: Vec<EventResource>,
+    #[serde(default)]
+    pub included: Vec<crate::model::team::Included>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventResource {
+    pub id: String,
+    pub attributes: EventAttributes,
 }
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FacilityAttributes {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub address1: Option<String>,
+    #[serde(default)]
+    pub address2: Option<String>,
+    #[serde(default)]
+    pub city: Option<String>,
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub zip: Option<String>,
+}
+
+/// A building (e.g. "Kraken Community Iceplex") holding one or more rinks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Facility {
+    pub id: i64,
+    pub name: Option<String>,
+    /// Street address on one line, e.g. "10601 5th Ave NE, Seattle, WA 98125".
+    pub address: Option<String>,
+}
+
+impl FacilityAttributes {
+    /// The address parts DaySmart has, joined on one line; None without a street.
+    pub fn street_address(&self) -> Option<String> {
+        let present = |part: &Option<String>| part.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+        let street = [present(&self.address1), present(&self.address2)].into_iter().flatten().collect::<Vec<_>>().join(" ");
+        if street.is_empty() {
+            return None;
+        }
+        let region = [present(&self.state), present(&self.zip)].into_iter().flatten().collect::<Vec<_>>().join(" ");
+        Some([Some(street), present(&self.city), Some(region).filter(|r| !r.is_empty())].into_iter().flatten().collect::<Vec<_>>().join(", "))
+    }
+}
@@ -3,18 +3,73 @@ use chrono::{DateTime, Utc};
 #[derive(Clone, Debug)]
 pub struct GameCore {
     pub dt: DateTime<Utc>,
+    /// Timestamp `dt` was parsed from, as DaySmart sent it.
+    pub raw_start: String,
     pub h_id: Option<i64>,
     pub v_id: Option<i64>,
     pub res_id: Option<i64>,
+    /// Resource area (sheet of the rink), when DaySmart names one.
+    pub area_id: Option<i64>,
+    pub is_playoff: bool,
+    /// Final (home, visitor) score once the game has been played.
+    pub score: Option<(i64, i64)>,
+    /// Booked end of the ice slot, when DaySmart gives one.
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl GameCore {
+    /// Number of optional details (teams, rink) that are known.
+    pub fn completeness(&self) -> usize {
+        [self.h_id, self.v_id, self.res_id].iter().filter(|v| v.is_some()).count()
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct GameInfo {
+    // DaySmart event id of the game
+    pub id: i64,
     pub dt: DateTime<Utc>,
     pub h_id: Option<i64>,
     pub v_id: Option<i64>,
     pub res_id: Option<i64>,
+    /// Resource area (sheet of the rink), when DaySmart names one.
+    pub area_id: Option<i64>,
     // Locker room resource IDs (resolved to names at formatting time to avoid cloning)
     pub home_locker_res_id: Option<i64>,
     pub away_locker_res_id: Option<i64>,
-}
\ No newline at end of file
+    pub is_playoff: bool,
+    /// A practice rather than a game: no opponent, jerseys or score.
+    pub is_practice: bool,
+    pub score: Option<(i64, i64)>,
+    /// Booked end of the ice slot, when DaySmart gives one.
+    pub end: Option<DateTime<Utc>>,
+}
+
+/// Length assumed for games without a usable end time.
+pub const DEFAULT_GAME_MINUTES: i64 = 60;
+
+/// Longest slot taken at face value; anything longer is more likely a data entry error.
+const MAX_GAME_MINUTES: i64 = 4 * 60;
+
+impl GameInfo {
+    /// How long the ice is booked: from the DaySmart end time (e.g. 75 or 90-minute slots), or
+    /// [`DEFAULT_GAME_MINUTES`] when there is none or it doesn't make sense.
+    pub fn duration(&self) -> chrono::Duration {
+        let booked = self.end.map(|end| end - self.dt).filter(|d| *d > chrono::Duration::zero() && *d <= chrono::Duration::minutes(MAX_GAME_MINUTES));
+        booked.unwrap_or(chrono::Duration::minutes(DEFAULT_GAME_MINUTES))
+    }
+
+    /// Scheduled end: start plus [`GameInfo::duration`].
+    pub fn ends(&self) -> DateTime<Utc> {
+        self.dt + self.duration()
+    }
+}
+/// Whether a game id is still on the schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    Listed,
+    /// Still in the document but flagged as cancelled or postponed.
+    Cancelled,
+    /// No longer in the document at all.
+    Missing,
+}
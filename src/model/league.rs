@@ -0,0 +1,59 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeagueAttributes {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub season_id: Option<i64>,
+    #[serde(default)]
+    pub start_date: Option<String>,
+    #[serde(default)]
+    pub end_date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SeasonAttributes {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub start_date: Option<String>,
+    #[serde(default)]
+    pub end_date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProgramTypeAttributes {
+    pub name: Option<String>,
+}
+
+/// The league our team plays in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct League {
+    pub id: i64,
+    pub name: String,
+    pub season_id: Option<i64>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// The season the team is registered for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Season {
+    pub id: i64,
+    pub name: String,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// Kind of program (team league, camp, ...) the team belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramType {
+    pub id: i64,
+    pub name: String,
+}
+
+/// Date part of a DaySmart date/datetime attribute such as "2025-09-21T00:00:00".
+pub fn parse_date(value: Option<&str>) -> Option<NaiveDate> {
+    let value = value?;
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
@@ -3,3 +3,6 @@ pub mod event;
 pub mod team;
 pub mod resource;
 pub mod locker_room;
+pub mod league;
+pub mod facility;
+pub mod comment;
@@ -3,4 +3,12 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ResourceAttributes {
     pub name: Option<String>,
+    /// The facility (building) the resource is in.
+    #[serde(default)]
+    pub facility_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceAreaAttributes {
+    pub name: Option<String>,
 }
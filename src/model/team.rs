@@ -37,7 +37,7 @@ pub enum Included {
     #[serde(rename = "events")]
     Event {
         id: String,
-        attributes: crate::model::event::EventAttributes,
+        attributes: Box<crate::model::event::EventAttributes>,
         #[serde(default)]
         relationships: Option<serde_json::Value>,
     },
@@ -55,6 +55,27 @@ pub enum Included {
         #[serde(default)]
         relationships: Option<serde_json::Value>,
     },
+    #[serde(rename = "resource-areas")]
+    ResourceArea {
+        id: String,
+        attributes: crate::model::resource::ResourceAreaAttributes,
+        #[serde(default)]
+        relationships: Option<serde_json::Value>,
+    },
+    #[serde(rename = "comments")]
+    Comment {
+        id: String,
+        attributes: crate::model::comment::CommentAttributes,
+        #[serde(default)]
+        relationships: Option<serde_json::Value>,
+    },
+    #[serde(rename = "facilities")]
+    Facility {
+        id: String,
+        attributes: crate::model::facility::FacilityAttributes,
+        #[serde(default)]
+        relationships: Option<serde_json::Value>,
+    },
     #[serde(rename = "locker-rooms")]
     LockerRoom {
         id: String,
@@ -62,6 +83,27 @@ pub enum Included {
         #[serde(default)]
         relationships: Option<serde_json::Value>,
     },
+    #[serde(rename = "leagues")]
+    League {
+        id: String,
+        attributes: crate::model::league::LeagueAttributes,
+        #[serde(default)]
+        relationships: Option<serde_json::Value>,
+    },
+    #[serde(rename = "seasons")]
+    Season {
+        id: String,
+        attributes: crate::model::league::SeasonAttributes,
+        #[serde(default)]
+        relationships: Option<serde_json::Value>,
+    },
+    #[serde(rename = "program-types")]
+    ProgramType {
+        id: String,
+        attributes: crate::model::league::ProgramTypeAttributes,
+        #[serde(default)]
+        relationships: Option<serde_json::Value>,
+    },
     #[serde(other)]
     Other,
 }
@@ -0,0 +1,48 @@
+use crate::clock::IdGenerator;
+
+/// Minimal multipart/form-data body builder, so uploads don't need extra HTTP crate features.
+pub(crate) struct MultipartBody {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl MultipartBody {
+    /// Start a body whose boundary is built from two ids of `ids`.
+    pub(crate) fn new(ids: &dyn IdGenerator) -> Self {
+        Self { boundary: format!("---------------------------{:x}{:x}", ids.next_id(), ids.next_id()), body: Vec::new() }
+    }
+
+    /// Add a plain form field.
+    pub(crate) fn text(&mut self, name: &str, content_type: Option<&str>, value: &str) -> &mut Self {
+        self.body.extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+        self.body.extend_from_slice(format!("Content-Disposition: form-data; name=\"{}\"\r\n", name).as_bytes());
+        if let Some(content_type) = content_type {
+            self.body.extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+        }
+        self.body.extend_from_slice(b"\r\n");
+        self.body.extend_from_slice(value.as_bytes());
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    /// Add a file field.
+    pub(crate) fn file(&mut self, name: &str, filename: &str, bytes: &[u8]) -> &mut Self {
+        self.body.extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+        self.body.extend_from_slice(
+            format!("Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n", name, escape_header_value(filename)).as_bytes(),
+        );
+        self.body.extend_from_slice(b"Content-Type: application/octet-stream\r\n\r\n");
+        self.body.extend_from_slice(bytes);
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    /// Close the body and return it with the matching Content-Type header value.
+    pub(crate) fn finish(mut self) -> (String, Vec<u8>) {
+        self.body.extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        (format!("multipart/form-data; boundary={}", self.boundary), self.body)
+    }
+}
+
+// Escape double quotes in header values if any
+fn escape_header_value(s: &str) -> String { s.replace('"', "'") }
@@ -0,0 +1,98 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::destination::Publisher;
+use crate::state::StateStore;
+use crate::summary::Outcome;
+
+/// Low-key "still no games" notices during breaks, so silence isn't mistaken for a dead bot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoGamesNoticeConfig {
+    /// Longest gap between notices, in days. Gaps double from daily up to this.
+    #[serde(default = "default_max_interval_days")]
+    pub max_interval_days: i64,
+}
+
+fn default_max_interval_days() -> i64 { 7 }
+
+impl Default for NoGamesNoticeConfig {
+    fn default() -> Self {
+        Self { max_interval_days: default_max_interval_days() }
+    }
+}
+
+/// Notices sent since games last disappeared from the window.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct NoGamesState {
+    pub last_notified: Option<NaiveDate>,
+    pub notices_sent: u32,
+}
+
+impl NoGamesState {
+    /// Days to wait after the latest notice: 1, 2, 4, ... capped at `max_interval_days`.
+    pub fn interval_days(&self, max_interval_days: i64) -> i64 {
+        let doubling = 1i64 << self.notices_sent.saturating_sub(1).min(30);
+        doubling.min(max_interval_days.max(1))
+    }
+
+    /// True when a notice should go out on local date `today`.
+    pub fn is_due(&self, today: NaiveDate, max_interval_days: i64) -> bool {
+        match self.last_notified {
+            Some(last) => (today - last).num_days() >= self.interval_days(max_interval_days),
+            None => true,
+        }
+    }
+
+    pub fn record(&mut self, today: NaiveDate) {
+        self.last_notified = Some(today);
+        self.notices_sent += 1;
+    }
+}
+
+fn state_key(team_id: &str) -> String {
+    format!("no_games/{}", team_id)
+}
+
+/// Text of a notice, saying when the next one will come.
+pub fn notice_message(days_ahead: i64, next_notice_in_days: i64) -> String {
+    let next = if next_notice_in_days == 1 { "tomorrow".to_string() } else { format!("in {} days", next_notice_in_days) };
+    format!(":zzz: Still no games scheduled in the next {} days. Checking daily; next update {}.", days_ahead, next)
+}
+
+/// Post a notice if one is due and remember it. Returns the outcome and a short summary.
+pub async fn notify(
+    publisher: &Publisher,
+    store: &StateStore,
+    team_id: &str,
+    config: &NoGamesNoticeConfig,
+    today: NaiveDate,
+    days_ahead: i64,
+) -> (Outcome, String) {
+    let key = state_key(team_id);
+    let mut state: NoGamesState = match store.get(&key).await {
+        Ok(state) => state.unwrap_or_default(),
+        Err(e) => {
+            error!(error = %e, "Failed to load no-games state");
+            return (Outcome::Failed, format!("DaySmart: no upcoming games; notice skipped: {}", e));
+        }
+    };
+    if !state.is_due(today, config.max_interval_days) {
+        return (Outcome::Skipped, "DaySmart: no upcoming games (skipped)".to_string());
+    }
+    state.record(today);
+    let message = notice_message(days_ahead, state.interval_days(config.max_interval_days));
+    if let Err(e) = publisher.post(&message).await {
+        return (Outcome::Failed, format!("DaySmart no-games notice failed: {}", e));
+    }
+    if let Err(e) = store.put(&key, &state).await {
+        error!(error = %e, "Failed to save no-games state");
+    }
+    info!(notices_sent = state.notices_sent, "Posted no-games notice");
+    (Outcome::Done, "DaySmart: no upcoming games (notice posted)".to_string())
+}
+
+/// Forget previous notices once games are back, so the next break starts daily again.
+pub async fn reset(store: &StateStore, team_id: &str) -> Result<(), String> {
+    store.delete(&state_key(team_id)).await
+}
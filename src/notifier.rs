@@ -0,0 +1,210 @@
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use chrono_tz::Tz;
+
+use crate::discord::{Discord, Mentions, PostOptions, PostedMessage};
+use crate::error::Error;
+use crate::http;
+use crate::render::RenderProfile;
+use crate::slack::Slack;
+
+/// A rich card: Discord shows it as an embed, other sinks as text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Embed {
+    pub title: String,
+    pub description: String,
+    /// Link on the title.
+    pub url: Option<String>,
+    /// Sidebar color, 0xRRGGBB.
+    pub color: Option<u32>,
+}
+
+impl Embed {
+    /// The embed as a message, for sinks without embeds.
+    pub fn as_text(&self) -> String {
+        let mut text = format!("**{}**", self.title);
+        if !self.description.is_empty() {
+            text.push('\n');
+            text.push_str(&self.description);
+        }
+        if let Some(url) = &self.url {
+            text.push('\n');
+            text.push_str(url);
+        }
+        text
+    }
+}
+
+pub type NotifyFuture<'a> = Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'a>>;
+/// A post that may come back with the message it created.
+pub type MessageFuture<'a> = Pin<Box<dyn Future<Output = Result<Option<PostedMessage>, Error>> + Send + 'a>>;
+
+/// Somewhere posts go. Workflows post through a `destination::Publisher`, which fans out to
+/// notifiers; a new kind of destination implements this trait and is added to the handler's
+/// `notifiers`, without touching workflow code.
+pub trait Notifier: Debug + Send + Sync {
+    /// Short name used in delivery reports, e.g. "discord".
+    fn kind(&self) -> &'static str;
+
+    /// Where posts go, e.g. the webhook URL. Posts to the same host are paced together.
+    fn endpoint(&self) -> &str;
+
+    /// How messages written for Discord are rendered for this sink.
+    fn profile(&self) -> RenderProfile {
+        RenderProfile::Discord
+    }
+
+    /// The same notifier posting to `endpoint`, for per-workflow webhooks.
+    fn retarget(&self, endpoint: &str) -> Arc<dyn Notifier>;
+
+    fn post_text<'a>(&'a self, content: &'a str) -> NotifyFuture<'a>;
+
+    fn post_file<'a>(&'a self, content: &'a str, filename: &'a str, bytes: &'a [u8]) -> NotifyFuture<'a>;
+
+    /// Post a rich card; sinks without them get the card as rendered text, with timestamps
+    /// written out in `timezone` like [`destination::Publisher`](crate::destination::Publisher)
+    /// posts.
+    fn post_embed<'a>(&'a self, embed: &'a Embed, timezone: Tz) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let text = embed.as_text();
+            self.post_text(&self.profile().render(&text, timezone)).await
+        })
+    }
+
+    /// Post the message, with the attachment if given, and return it on sinks that can edit it
+    /// later with [`Notifier::edit_message`].
+    fn post_message<'a>(&'a self, content: &'a str, attachment: Option<(&'a str, &'a [u8])>) -> MessageFuture<'a> {
+        Box::pin(async move {
+            match attachment {
+                None => self.post_text(content).await?,
+                Some((filename, bytes)) => self.post_file(content, filename, bytes).await?,
+            }
+            Ok(None)
+        })
+    }
+
+    /// Replace message `message_id`, posted by [`Notifier::post_message`]. None for sinks
+    /// that can't edit their posts.
+    fn edit_message<'a>(&'a self, _message_id: &'a str, _content: &'a str, _attachment: Option<(&'a str, &'a [u8])>) -> Option<NotifyFuture<'a>> {
+        None
+    }
+
+    /// The same notifier pinging `mentions` with each post, for sinks that can ping anyone.
+    fn mentioning(&self, _mentions: &Mentions) -> Option<Arc<dyn Notifier>> {
+        None
+    }
+
+    /// The same notifier posting with `options`, for sinks that have them.
+    fn with_post_options(&self, _options: &PostOptions) -> Option<Arc<dyn Notifier>> {
+        None
+    }
+
+    /// The same notifier opening a thread named `name` with each post, for sinks with threads.
+    fn in_thread(&self, _name: &str) -> Option<Arc<dyn Notifier>> {
+        None
+    }
+
+    /// The same notifier posting into the existing thread `thread_id`, for sinks with threads.
+    fn in_existing_thread(&self, _thread_id: &str) -> Option<Arc<dyn Notifier>> {
+        None
+    }
+
+    /// "kind redacted-endpoint", as reported for each delivery.
+    fn label(&self) -> String {
+        format!("{} {}", self.kind(), http::redact_url(self.endpoint()))
+    }
+
+    fn host(&self) -> String {
+        reqwest::Url::parse(self.endpoint()).ok().and_then(|u| u.host_str().map(str::to_string)).unwrap_or_default()
+    }
+}
+
+impl Notifier for Discord {
+    fn kind(&self) -> &'static str {
+        "discord"
+    }
+
+    fn endpoint(&self) -> &str {
+        self.hook_url()
+    }
+
+    fn retarget(&self, endpoint: &str) -> Arc<dyn Notifier> {
+        Arc::new(self.clone().with_hook_url(endpoint.to_string()))
+    }
+
+    fn post_text<'a>(&'a self, content: &'a str) -> NotifyFuture<'a> {
+        Box::pin(async move { Ok(self.post(content).await.map(drop)?) })
+    }
+
+    fn post_file<'a>(&'a self, content: &'a str, filename: &'a str, bytes: &'a [u8]) -> NotifyFuture<'a> {
+        Box::pin(async move { Ok(self.post_with_attachment(content, filename, bytes).await.map(drop)?) })
+    }
+
+    fn post_embed<'a>(&'a self, embed: &'a Embed, _timezone: Tz) -> NotifyFuture<'a> {
+        Box::pin(async move { Ok(Discord::post_embed(self, embed).await?) })
+    }
+
+    fn post_message<'a>(&'a self, content: &'a str, attachment: Option<(&'a str, &'a [u8])>) -> MessageFuture<'a> {
+        Box::pin(async move {
+            match attachment {
+                None => Ok(self.post(content).await?),
+                Some((filename, bytes)) => Ok(self.post_with_attachment(content, filename, bytes).await?),
+            }
+        })
+    }
+
+    fn edit_message<'a>(&'a self, message_id: &'a str, content: &'a str, attachment: Option<(&'a str, &'a [u8])>) -> Option<NotifyFuture<'a>> {
+        Some(Box::pin(async move {
+            match attachment {
+                None => Ok(self.edit(message_id, content).await?),
+                Some((filename, bytes)) => Ok(self.edit_with_attachment(message_id, content, filename, bytes).await?),
+            }
+        }))
+    }
+
+    fn mentioning(&self, mentions: &Mentions) -> Option<Arc<dyn Notifier>> {
+        Some(Arc::new(self.clone().with_mentions(mentions.clone())))
+    }
+
+    fn with_post_options(&self, options: &PostOptions) -> Option<Arc<dyn Notifier>> {
+        Some(Arc::new(self.clone().with_options(options.clone())))
+    }
+
+    fn in_thread(&self, name: &str) -> Option<Arc<dyn Notifier>> {
+        Some(Arc::new(self.clone().with_thread_name(name)))
+    }
+
+    fn in_existing_thread(&self, thread_id: &str) -> Option<Arc<dyn Notifier>> {
+        Some(Arc::new(self.clone().with_thread_id(thread_id)))
+    }
+}
+
+/// Slack would show Discord's emoji shortcodes and markup literally, so it gets plain text.
+impl Notifier for Slack {
+    fn kind(&self) -> &'static str {
+        "slack"
+    }
+
+    fn endpoint(&self) -> &str {
+        self.hook_url()
+    }
+
+    fn profile(&self) -> RenderProfile {
+        RenderProfile::PlainText
+    }
+
+    fn retarget(&self, endpoint: &str) -> Arc<dyn Notifier> {
+        Arc::new(self.clone().with_hook_url(endpoint.to_string()))
+    }
+
+    fn post_text<'a>(&'a self, content: &'a str) -> NotifyFuture<'a> {
+        Box::pin(async move { self.post(content).await })
+    }
+
+    fn post_file<'a>(&'a self, content: &'a str, filename: &'a str, bytes: &'a [u8]) -> NotifyFuture<'a> {
+        Box::pin(async move { self.post_with_attachment(content, filename, bytes).await })
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+/// S3 location of a CSV exported from BenchApp (opponents or roster) whose team names should be
+/// used in place of DaySmart's, so imports line up with the opponent records BenchApp already has.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchappNamesConfig {
+    pub bucket: String,
+    pub key: String,
+}
+
+/// Header names recognised as the team name column, in order of preference.
+const NAME_COLUMNS: [&str; 5] = ["opponent", "opponent name", "team", "team name", "name"];
+
+/// Team names as BenchApp spells them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BenchappNames {
+    names: Vec<String>,
+}
+
+/// Lowercase words with punctuation dropped, so "St. Louis  Blues" and "st louis blues" compare equal.
+fn words(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Split one CSV line, honouring double quotes and "" escapes.
+fn split_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+impl BenchappNames {
+    pub fn new(names: Vec<String>) -> Self {
+        Self { names }
+    }
+
+    /// Parse a BenchApp export. The name is read from the first column headed Opponent, Team or
+    /// Name (any case); without such a header, from the first column. Blank names are skipped.
+    pub fn from_csv(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+        let header = lines.next().ok_or_else(|| "BenchApp names CSV is empty".to_string())?;
+        let header: Vec<String> = split_line(header.trim_start_matches('\u{feff}'))
+            .iter()
+            .map(|h| h.trim().to_lowercase())
+            .collect();
+        let column = NAME_COLUMNS.iter().find_map(|wanted| header.iter().position(|h| h == wanted)).unwrap_or(0);
+        let names = lines
+            .filter_map(|line| split_line(line).get(column).map(|name| name.trim().to_string()))
+            .filter(|name| !name.is_empty())
+            .collect();
+        Ok(Self { names })
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// BenchApp's spelling of a DaySmart team name. Names that are equal ignoring case and
+    /// punctuation match first; otherwise a single BenchApp name whose words all appear in the
+    /// DaySmart name (or the reverse), e.g. "Kraken" for "Seattle Kraken". None when nothing or
+    /// more than one name fits.
+    pub fn resolve(&self, daysmart_name: &str) -> Option<&str> {
+        let wanted = words(daysmart_name);
+        if wanted.is_empty() {
+            return None;
+        }
+        if let Some(exact) = self.names.iter().find(|name| words(name) == wanted) {
+            return Some(exact);
+        }
+        let contains = |outer: &[String], inner: &[String]| inner.iter().all(|w| outer.contains(w));
+        let mut partial = self.names.iter().filter(|name| {
+            let candidate = words(name);
+            !candidate.is_empty() && (contains(&wanted, &candidate) || contains(&candidate, &wanted))
+        });
+        match (partial.next(), partial.next()) {
+            (Some(only), None) => Some(only),
+            _ => None,
+        }
+    }
+
+    /// `name` as BenchApp spells it, or unchanged when there is no match.
+    pub fn rename<'a>(&'a self, name: &'a str) -> &'a str {
+        self.resolve(name).unwrap_or(name)
+    }
+}
+
+/// Load the names CSV from S3.
+pub async fn load(config: &BenchappNamesConfig) -> Result<BenchappNames, String> {
+    let client = crate::aws::s3_client().await;
+    let out = client
+        .get_object()
+        .bucket(&config.bucket)
+        .key(&config.key)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to load BenchApp names from s3://{}/{}: {}", config.bucket, config.key, e))?;
+    let bytes = out
+        .body
+        .collect()
+        .await
+        .map_err(|e| format!("Failed to read BenchApp names: {}", e))?
+        .into_bytes();
+    let text = String::from_utf8(bytes.to_vec()).map_err(|e| format!("BenchApp names CSV is not UTF-8: {}", e))?;
+    BenchappNames::from_csv(&text)
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::daysmart::DaySmart;
+
+/// One upcoming game, as listed in `Response::preview` for dashboards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct GamePreview {
+    pub game_id: i64,
+    /// Start, RFC 3339 in UTC.
+    pub start: String,
+    /// Start, RFC 3339 in the request timezone.
+    pub local_start: String,
+    pub opponent: String,
+    /// Whether we are the home team.
+    pub home: bool,
+    pub rink: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locker_room: Option<String>,
+    pub jersey: String,
+    pub playoff: bool,
+}
+
+/// JSON Schema of one game, generated from the Rust type, for consumers that take games out of
+/// the Response on their own.
+pub fn game_schema() -> schemars::Schema {
+    schemars::schema_for!(GamePreview)
+}
+
+/// Our games in the next `horizon_days` days, earliest first.
+pub fn games_ahead(day_smart: &DaySmart, horizon_days: i64, now: DateTime<Utc>) -> Vec<GamePreview> {
+    day_smart
+        .upcoming_games(horizon_days, now)
+        .iter()
+        .map(|game| {
+            let (opponent, jersey) = day_smart.matchup(game);
+            GamePreview {
+                game_id: game.id,
+                start: game.dt.to_rfc3339(),
+                local_start: game.dt.with_timezone(&day_smart.timezone()).to_rfc3339(),
+                opponent: opponent.to_string(),
+                home: day_smart.team_id().is_some() && day_smart.team_id() == game.h_id,
+                rink: day_smart.rink_name(game).to_string(),
+                locker_room: day_smart.locker_room(game).name().map(str::to_string),
+                jersey: jersey.to_string(),
+                playoff: game.is_playoff,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,119 @@
+use std::borrow::Cow;
+
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// How a message is rendered for a sink. Messages are written for Discord (emoji shortcodes,
+/// `**bold**`, code blocks); sinks that would show those literally get plain text instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderProfile {
+    Discord,
+    PlainText,
+}
+
+impl RenderProfile {
+    /// Render `content` for the sink; plain text writes Discord timestamps out in `timezone`.
+    pub fn render(self, content: &str, timezone: Tz) -> Cow<'_, str> {
+        match self {
+            RenderProfile::Discord => Cow::Borrowed(content),
+            RenderProfile::PlainText => Cow::Owned(plain_text(content, timezone)),
+        }
+    }
+}
+
+/// Paired markup stripped from plain text.
+const MARKERS: [&str; 4] = ["**", "__", "~~", "||"];
+
+/// Strip emoji (shortcodes like `:hockey:` and Unicode emoji) and Discord markup, keeping the
+/// text and line layout. Discord timestamps (`<t:1758512700:F>`) are written out in `timezone`.
+/// Code fences are dropped but their contents kept, so tables still line up; lines that held
+/// nothing but emoji are dropped.
+pub fn plain_text(content: &str, timezone: Tz) -> String {
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .filter_map(|line| {
+            let stripped = plain_line(line, timezone);
+            (!stripped.trim().is_empty() || line.trim().is_empty()).then_some(stripped)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn plain_line(line: &str, timezone: Tz) -> String {
+    let body = line.trim_start();
+    let indent = &line[..line.len() - body.len()];
+    let mut rest = body.strip_prefix("> ").unwrap_or(body);
+    let mut out = String::from(indent);
+    // Spaces next to something removed would otherwise pile up or lead the line
+    let mut removed = false;
+    while let Some(c) = rest.chars().next() {
+        if let Some((len, text)) = timestamp(rest, timezone) {
+            out.push_str(&text);
+            rest = &rest[len..];
+            removed = false;
+            continue;
+        }
+        let at_word_start = out.len() == indent.len() || out.ends_with(char::is_whitespace);
+        let skip = match (c, MARKERS.iter().find(|m| rest.starts_with(**m))) {
+            (_, Some(marker)) => Some(marker.len()),
+            (':', None) if at_word_start => shortcode_len(rest),
+            ('`', None) => Some(1),
+            (c, None) if is_emoji(c) => Some(c.len_utf8()),
+            _ => None,
+        };
+        match skip {
+            Some(len) => {
+                removed = true;
+                rest = &rest[len..];
+            }
+            None => {
+                rest = &rest[c.len_utf8()..];
+                if c == ' ' && removed && at_word_start {
+                    continue;
+                }
+                removed &= c == ' ';
+                out.push(c);
+            }
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// Length of the emoji shortcode (e.g. `:calendar_spiral:`) `text` starts with, if any.
+fn shortcode_len(text: &str) -> Option<usize> {
+    let name = &text[1..];
+    let end = name.find(':')?;
+    let name = &name[..end];
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '+' | '-'));
+    valid.then_some(end + 2)
+}
+
+/// Length of the Discord timestamp (`<t:unix>` or `<t:unix:style>`) `text` starts with, and the
+/// time it shows, written as local time in `timezone`. A countdown (style R) can't tick in plain
+/// text, so it shows the time.
+fn timestamp(text: &str, timezone: Tz) -> Option<(usize, String)> {
+    let end = text.find('>')?;
+    let mut parts = text.strip_prefix("<t:")?[..end - 3].split(':');
+    let seconds: i64 = parts.next()?.parse().ok()?;
+    let format = match parts.next().unwrap_or("f") {
+        "t" => "%-I:%M %p %Z",
+        "T" => "%-I:%M:%S %p %Z",
+        "d" => "%m/%d/%Y",
+        "D" => "%B %-d, %Y",
+        "F" => "%A, %B %-d, %Y %-I:%M %p %Z",
+        "f" | "R" => "%B %-d, %Y %-I:%M %p %Z",
+        _ => return None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    let time = chrono::DateTime::from_timestamp(seconds, 0)?;
+    Some((end + 1, time.with_timezone(&timezone).format(format).to_string()))
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(u32::from(c), 0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x231A..=0x23FF | 0x2B50 | 0x2B55 | 0xFE0F | 0x200D)
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::state::StateStore;
+
+/// Where a player lines up; goalies and defense are counted separately from other skaters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Position {
+    Skater,
+    Defense,
+    Goalie,
+}
+
+/// A registered player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Player {
+    /// Display name, as it was when the player registered.
+    pub name: String,
+    pub position: Position,
+}
+
+/// Players of a team, by Discord user id.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Roster {
+    pub players: BTreeMap<String, Player>,
+}
+
+/// A player's answer for one game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rsvp {
+    Yes,
+    No,
+}
+
+/// Answers for one game, by Discord user id.
+pub type Rsvps = BTreeMap<String, Rsvp>;
+
+/// Turnout for a game: who said they are coming, by position, and how many registered players
+/// have not answered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Attendance {
+    pub skaters: usize,
+    pub defense: usize,
+    pub goalies: usize,
+    pub unanswered: usize,
+}
+
+impl Attendance {
+    /// Players confirmed as coming.
+    pub fn total(&self) -> usize {
+        self.skaters + self.defense + self.goalies
+    }
+
+    /// "Confirmed: 8 skaters, 2 D, 1 G (3 haven't answered)".
+    pub fn line(&self) -> String {
+        let skaters = if self.skaters == 1 { "skater" } else { "skaters" };
+        let mut line = format!("Confirmed: {} {}, {} D, {} G", self.skaters, skaters, self.defense, self.goalies);
+        if self.unanswered > 0 {
+            line.push_str(&format!(" ({} {} answered)", self.unanswered, if self.unanswered == 1 { "hasn't" } else { "haven't" }));
+        }
+        line
+    }
+}
+
+/// How long answers for a game are kept, counted from the latest answer. Long enough for games
+/// announced well ahead; the answers stop mattering once the game is played.
+pub const RSVP_TTL_DAYS: i64 = 120;
+
+fn roster_key(team_id: &str) -> String {
+    format!("roster/{}", team_id)
+}
+
+fn rsvps_key(team_id: &str, game_id: &str) -> String {
+    format!("rsvp/{}/{}", team_id, game_id)
+}
+
+/// Load the registered players for a team.
+pub async fn roster(store: &StateStore, team_id: &str) -> Result<Roster, String> {
+    Ok(store.get(&roster_key(team_id)).await?.unwrap_or_default())
+}
+
+/// Register Discord user `user_id`, or change their name or position.
+/// Returns true if they were not registered before.
+pub async fn register(store: &StateStore, team_id: &str, user_id: &str, player: Player) -> Result<bool, String> {
+    let mut roster = roster(store, team_id).await?;
+    let added = roster.players.insert(user_id.to_string(), player).is_none();
+    store.put(&roster_key(team_id), &roster).await?;
+    Ok(added)
+}
+
+/// Remove a player. Returns true if they were registered.
+pub async fn unregister(store: &StateStore, team_id: &str, user_id: &str) -> Result<bool, String> {
+    let mut roster = roster(store, team_id).await?;
+    let removed = roster.players.remove(user_id).is_some();
+    if removed {
+        store.put(&roster_key(team_id), &roster).await?;
+    }
+    Ok(removed)
+}
+
+/// Load the answers given so far for a game.
+pub async fn rsvps(store: &StateStore, team_id: &str, game_id: &str) -> Result<Rsvps, String> {
+    Ok(store.get(&rsvps_key(team_id, game_id)).await?.unwrap_or_default())
+}
+
+/// Record Discord user `user_id`'s answer for a game, replacing any earlier one.
+pub async fn respond(
+    store: &StateStore,
+    team_id: &str,
+    game_id: &str,
+    user_id: &str,
+    rsvp: Rsvp,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let mut answers = rsvps(store, team_id, game_id).await?;
+    answers.insert(user_id.to_string(), rsvp);
+    store.put_expiring(&rsvps_key(team_id, game_id), &answers, now + chrono::Duration::days(RSVP_TTL_DAYS)).await
+}
+
+/// Who said yes, by position, and how many registered players haven't answered. Only answers
+/// count: a player who hasn't answered is neither in nor out. A yes from someone not on the
+/// roster counts as a skater.
+pub fn attendance(roster: &Roster, rsvps: &Rsvps) -> Attendance {
+    let unanswered = roster.players.keys().filter(|user_id| !rsvps.contains_key(*user_id)).count();
+    rsvps
+        .iter()
+        .filter(|(_, rsvp)| **rsvp == Rsvp::Yes)
+        .map(|(user_id, _)| roster.players.get(user_id).map_or(Position::Skater, |player| player.position))
+        .fold(Attendance { unanswered, ..Default::default() }, |mut counts, position| {
+            match position {
+                Position::Skater => counts.skaters += 1,
+                Position::Defense => counts.defense += 1,
+                Position::Goalie => counts.goalies += 1,
+            }
+            counts
+        })
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::state::StateStore;
+
+/// A round-robin duty rotation over a configured list of players.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotationConfig {
+    pub players: Vec<String>,
+    /// State key suffix; defaults to the duty's own name so rotations never share a pointer.
+    #[serde(default)]
+    pub key: Option<String>,
+}
+
+/// Persisted rotation pointer plus the assignment already made for each game,
+/// so a game keeps its assignee across runs.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RotationState {
+    pub next_index: usize,
+    pub assignments: BTreeMap<String, String>,
+}
+
+impl RotationState {
+    /// Return the player assigned to `game_id`, assigning the next player in turn if the
+    /// game has not been assigned yet. Returns None when there are no players.
+    pub fn assign(&mut self, game_id: &str, players: &[String]) -> Option<String> {
+        if let Some(existing) = self.assignments.get(game_id) {
+            return Some(existing.clone());
+        }
+        if players.is_empty() {
+            return None;
+        }
+        let player = players[self.next_index % players.len()].clone();
+        self.next_index = (self.next_index + 1) % players.len();
+        self.assignments.insert(game_id.to_string(), player.clone());
+        Some(player)
+    }
+
+    /// Exchange the assignees of two already-assigned games.
+    pub fn swap(&mut self, game_a: &str, game_b: &str) -> Result<(), String> {
+        let a = self.assignments.get(game_a).cloned().ok_or_else(|| format!("Game {} has no assignee", game_a))?;
+        let b = self.assignments.get(game_b).cloned().ok_or_else(|| format!("Game {} has no assignee", game_b))?;
+        self.assignments.insert(game_a.to_string(), b);
+        self.assignments.insert(game_b.to_string(), a);
+        Ok(())
+    }
+}
+
+fn state_key(team_id: &str, key: &str) -> String {
+    format!("rotation/{}/{}", team_id, key)
+}
+
+/// Look up (or make) the assignment for a game and persist the rotation when it changes.
+/// `default_key` names the duty and is used when the config has no explicit key.
+pub async fn assignee(
+    store: &StateStore,
+    team_id: &str,
+    default_key: &str,
+    config: &RotationConfig,
+    game_id: &str,
+) -> Result<Option<String>, String> {
+    let key = state_key(team_id, config.key.as_deref().unwrap_or(default_key));
+    let mut state: RotationState = store.get(&key).await?.unwrap_or_default();
+    let already_assigned = state.assignments.contains_key(game_id);
+    let player = state.assign(game_id, &config.players);
+    if !already_assigned && player.is_some() {
+        store.put(&key, &state).await?;
+        info!(rotation = %key, game_id, "Assigned rotation duty");
+    }
+    Ok(player)
+}
+
+/// Swap the assignees of two games in a persisted rotation.
+pub async fn swap(store: &StateStore, team_id: &str, key: &str, game_a: &str, game_b: &str) -> Result<(), String> {
+    let key = state_key(team_id, key);
+    let mut state: RotationState = store.get(&key).await?.unwrap_or_default();
+    state.swap(game_a, game_b)?;
+    store.put(&key, &state).await
+}
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::dedup::{self, PostedRecord};
+use crate::discord::PostedMessage;
+use crate::discord_bot::{DiscordBot, Reactor};
+use crate::roster::{self, Position, Roster};
+use crate::state::StateStore;
+
+/// Reaction on the game reminder meaning "I'm coming".
+pub const YES_EMOJI: &str = "\u{2705}";
+/// Reaction on the game reminder meaning "I can't make it".
+pub const NO_EMOJI: &str = "\u{274c}";
+
+/// Attendance from reactions on the game reminder (rsvp_reactions workflow): how many players
+/// are needed before the summary stops warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RsvpReactionsConfig {
+    /// Fewest skaters, defense included.
+    #[serde(default = "default_min_skaters")]
+    pub min_skaters: usize,
+    #[serde(default = "default_min_goalies")]
+    pub min_goalies: usize,
+}
+
+fn default_min_skaters() -> usize { 10 }
+
+fn default_min_goalies() -> usize { 1 }
+
+impl Default for RsvpReactionsConfig {
+    fn default() -> Self {
+        Self { min_skaters: default_min_skaters(), min_goalies: default_min_goalies() }
+    }
+}
+
+/// Players who answered by reaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReactionCount {
+    pub skaters: usize,
+    pub goalies: usize,
+    pub out: usize,
+}
+
+/// Count the reactions. A player's position comes from their roster entry (by Discord user
+/// id); anyone else counts as a skater. Someone who reacted with both counts as out, since the
+/// ❌ is usually the later change of plans.
+pub fn count(yes: &[Reactor], no: &[Reactor], roster: &Roster) -> ReactionCount {
+    let position = |reactor: &Reactor| roster.players.get(&reactor.user_id).map_or(Position::Skater, |player| player.position);
+    let coming = yes.iter().filter(|reactor| !no.iter().any(|out| out.user_id == reactor.user_id));
+    coming.fold(ReactionCount { out: no.len(), ..Default::default() }, |mut count, reactor| {
+        match position(reactor) {
+            Position::Goalie => count.goalies += 1,
+            Position::Skater | Position::Defense => count.skaters += 1,
+        }
+        count
+    })
+}
+
+/// Count the reactions on the first reminder posted for `game_id`, or None while no reminder
+/// with a known channel has been posted for it.
+pub async fn reaction_count(bot: &DiscordBot, store: &StateStore, team_id: &str, game_id: i64) -> Result<Option<ReactionCount>, String> {
+    let reminder = dedup::posted(store, team_id, &game_id.to_string()).await?.and_then(|record| record.first_message());
+    let Some((message_id, channel_id)) = reminder.and_then(|message| message.channel_id.map(|channel| (message.id, channel))) else {
+        return Ok(None);
+    };
+    let reactors = |emoji| bot.reactors(&channel_id, &message_id, emoji);
+    let (yes, no) = futures::try_join!(reactors(YES_EMOJI), reactors(NO_EMOJI))?;
+    let roster = roster::roster(store, team_id).await?;
+    Ok(Some(count(&yes, &no, &roster)))
+}
+
+/// Put ✅ and ❌ on a newly posted reminder, so players answer with one tap. Does nothing when
+/// the post didn't say which channel it landed in.
+pub async fn add_reactions(bot: &DiscordBot, message: &PostedMessage) -> Result<(), String> {
+    let Some(channel_id) = &message.channel_id else {
+        return Ok(());
+    };
+    // One after the other, so ✅ comes first
+    bot.add_reaction(channel_id, &message.id, YES_EMOJI).await?;
+    bot.add_reaction(channel_id, &message.id, NO_EMOJI).await
+}
+
+fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+impl ReactionCount {
+    /// "9 skaters, 1 goalie".
+    pub fn line(&self) -> String {
+        format!("{}, {}", plural(self.skaters, "skater", "skaters"), plural(self.goalies, "goalie", "goalies"))
+    }
+}
+
+/// The attendance message for `game` (e.g. "Sun Sep 28 vs Blackbirds"), warning when either
+/// position is short of the configured minimum.
+pub fn summary(count: &ReactionCount, game: &str, config: &RsvpReactionsConfig) -> String {
+    let mut lines = vec![format!(":white_check_mark: {} confirmed for {}", count.line(), game)];
+    if count.out > 0 {
+        lines.push(format!(":x: {} out", count.out));
+    }
+    if count.skaters < config.min_skaters {
+        lines.push(format!(":warning: Short of skaters: {} of {} needed", count.skaters, config.min_skaters));
+    }
+    if count.goalies < config.min_goalies {
+        lines.push(format!(":warning: Short of goalies: {} of {} needed", count.goalies, config.min_goalies));
+    }
+    lines.push(format!("React {} or {} on the reminder to answer.", YES_EMOJI, NO_EMOJI));
+    lines.join("\n")
+}
+
+/// How long a summary record is kept; by then the game is long over.
+const SUMMARY_TTL_DAYS: i64 = 30;
+
+fn state_key(team_id: &str, game_id: i64) -> String {
+    format!("rsvp_reactions/{}/{}", team_id, game_id)
+}
+
+/// The attendance summary last posted for a game.
+pub async fn last_summary(store: &StateStore, team_id: &str, game_id: i64) -> Result<Option<PostedRecord>, String> {
+    store.get(&state_key(team_id, game_id)).await
+}
+
+/// Remember the summary posted for a game, and its message to edit next time.
+pub async fn record_summary(
+    store: &StateStore,
+    team_id: &str,
+    game_id: i64,
+    content: &str,
+    message: Option<&PostedMessage>,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let record = PostedRecord {
+        content_hash: dedup::content_hash(content),
+        posted_at: now,
+        message_id: message.map(|m| m.id.clone()),
+        channel_id: message.and_then(|m| m.channel_id.clone()),
+        offset_hours: None,
+        first_message: None,
+    };
+    store.put_expiring(&state_key(team_id, game_id), &record, now + chrono::Duration::days(SUMMARY_TTL_DAYS)).await
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Cron-like calendar gate for a workflow, evaluated against the invocation time in the
+/// request's timezone. Empty lists match anything; every non-empty list must match.
+///
+/// e.g. `{ "days_of_month": [1] }` runs on the 1st of each month,
+/// `{ "weekdays": ["mon"] }` runs on Mondays only.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Schedule {
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>,
+    /// Days of the month, 1-31.
+    #[serde(default)]
+    pub days_of_month: Vec<u32>,
+    /// Months, 1-12.
+    #[serde(default)]
+    pub months: Vec<u32>,
+}
+
+impl Schedule {
+    /// True when the schedule allows running at `now` in `timezone`.
+    pub fn is_due(&self, now: DateTime<Utc>, timezone: chrono_tz::Tz) -> bool {
+        let local = now.with_timezone(&timezone);
+        let matches = |allowed: &[u32], value: u32| allowed.is_empty() || allowed.contains(&value);
+        (self.weekdays.is_empty() || self.weekdays.contains(&local.weekday()))
+            && matches(&self.days_of_month, local.day())
+            && matches(&self.months, local.month())
+    }
+
+    /// Reject values that can never match.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(day) = self.days_of_month.iter().find(|d| !(1..=31).contains(*d)) {
+            return Err(format!("Invalid day of month in schedule: {}", day));
+        }
+        if let Some(month) = self.months.iter().find(|m| !(1..=12).contains(*m)) {
+            return Err(format!("Invalid month in schedule: {}", month));
+        }
+        Ok(())
+    }
+}
+
+/// Local hours during which nothing is posted to the team, e.g. `{ "start": "22:00", "end": "08:00" }`.
+/// An end at or before the start means the quiet hours run past midnight.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| format!("Invalid quiet_hours time {:?}; expected \"HH:MM\"", value))
+}
+
+impl QuietHours {
+    pub fn validate(&self) -> Result<(), String> {
+        parse_time(&self.start)?;
+        parse_time(&self.end)?;
+        Ok(())
+    }
+
+    /// True when `now` falls within the quiet hours in `timezone`. Unparseable times are never quiet.
+    pub fn contains(&self, now: DateTime<Utc>, timezone: chrono_tz::Tz) -> bool {
+        let (Ok(start), Ok(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        let time = now.with_timezone(&timezone).time();
+        if start < end { time >= start && time < end } else { time >= start || time < end }
+    }
+}
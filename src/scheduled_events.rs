@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::daysmart::{DaySmart, MAX_LOOKAHEAD_DAYS};
+use crate::discord_bot::{DiscordBot, ScheduledEvent};
+use crate::model::game::{GameInfo, GameStatus};
+use crate::state::StateStore;
+
+/// Discord Scheduled Events for upcoming games, so they show in the server's events list with
+/// Discord's own "Interested" RSVPs. Kept in sync with the bot token by the scheduled_events
+/// workflow; state keeps each game to one event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledEventsConfig {
+    /// Server the events are created in. The bot needs the Manage Events permission there.
+    pub guild_id: String,
+    /// How many days ahead events are created.
+    #[serde(default = "default_days_ahead")]
+    pub days_ahead: i64,
+}
+
+fn default_days_ahead() -> i64 { 14 }
+
+impl ScheduledEventsConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.guild_id.is_empty() || !self.guild_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("scheduled_events.guild_id must be a Discord server id such as \"123456789012345678\" (got {:?})", self.guild_id));
+        }
+        if !(1..=MAX_LOOKAHEAD_DAYS).contains(&self.days_ahead) {
+            return Err(format!("scheduled_events.days_ahead must be between 1 and {} (got {})", MAX_LOOKAHEAD_DAYS, self.days_ahead));
+        }
+        Ok(())
+    }
+}
+
+/// The event created for a game, with the name and start it was last given.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub event_id: String,
+    pub created_at: DateTime<Utc>,
+    pub name: String,
+    pub start: DateTime<Utc>,
+}
+
+/// Each game's event, by DaySmart game id.
+pub type EventRecords = BTreeMap<i64, EventRecord>;
+
+/// Days after a game's start its record is kept; by then the game is no longer upcoming.
+const RECORD_TTL_DAYS: i64 = 7;
+
+fn state_key(team_id: &str) -> String {
+    format!("scheduled_events/{}", team_id)
+}
+
+/// The team's events, as left by the last sync.
+pub async fn records(store: &StateStore, team_id: &str) -> Result<EventRecords, String> {
+    Ok(store.get(&state_key(team_id)).await?.unwrap_or_default())
+}
+
+pub async fn save_records(store: &StateStore, team_id: &str, records: &EventRecords) -> Result<(), String> {
+    store.put(&state_key(team_id), records).await
+}
+
+/// The event for a game: named like its thread, at the rink and its street address, for the
+/// booked ice time.
+pub fn event_for(day_smart: &DaySmart, game: &GameInfo) -> ScheduledEvent {
+    let location = match day_smart.address(game) {
+        Some(address) => format!("{}, {}", day_smart.arena(game), address),
+        None => day_smart.arena(game),
+    };
+    let mut description = Vec::new();
+    if !game.is_practice {
+        let (home, visitor) = day_smart.home_and_visitor(game);
+        let (_, jersey) = day_smart.matchup(game);
+        description.push(format!("{} vs {}", home, visitor));
+        description.push(format!("{} jerseys", jersey));
+    }
+    if let Some(locker_room) = day_smart.locker_room(game).name() {
+        description.push(format!("Locker Room: {}", locker_room));
+    }
+    ScheduledEvent { name: day_smart.thread_name(game), description: description.join("\n"), location, start: game.dt, end: game.ends() }
+}
+
+/// What a sync did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+}
+
+impl std::fmt::Display for SyncSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} created, {} updated, {} deleted, {} unchanged", self.created, self.updated, self.deleted, self.unchanged)?;
+        if self.failed > 0 {
+            write!(f, ", {} failed", self.failed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bring the server's events in line with DaySmart: create one for every game in the next
+/// `days_ahead` days that has none, move or rename the event of a game whose start or name
+/// changed, and delete the events of upcoming games that were cancelled or dropped from the
+/// schedule. `records` is updated to match; records of games long past are dropped.
+pub async fn sync(
+    bot: &DiscordBot,
+    records: &mut EventRecords,
+    config: &ScheduledEventsConfig,
+    day_smart: &DaySmart,
+    now: DateTime<Utc>,
+) -> SyncSummary {
+    let mut summary = SyncSummary::default();
+    let mut games = day_smart.upcoming_games(config.days_ahead, now);
+    // A game moved past the window still has its event, which should show the new time
+    games.extend(
+        records
+            .keys()
+            .filter(|id| !games.iter().any(|game| game.id == **id))
+            .filter_map(|id| day_smart.upcoming_game(*id, now))
+            .collect::<Vec<_>>(),
+    );
+    for game in games {
+        let event = event_for(day_smart, &game);
+        match records.get_mut(&game.id) {
+            Some(record) if record.name == event.name && record.start == event.start => summary.unchanged += 1,
+            Some(record) => match bot.update_scheduled_event(&config.guild_id, &record.event_id, &event).await {
+                Ok(()) => {
+                    info!(game_id = game.id, event_id = %record.event_id, "Updated Discord scheduled event");
+                    record.name = event.name;
+                    record.start = event.start;
+                    summary.updated += 1;
+                }
+                Err(e) => {
+                    error!(error = %e, game_id = game.id, "Failed to update Discord scheduled event");
+                    summary.failed += 1;
+                }
+            },
+            None => match bot.create_scheduled_event(&config.guild_id, &event).await {
+                Ok(event_id) => {
+                    info!(game_id = game.id, event_id, "Created Discord scheduled event");
+                    records.insert(game.id, EventRecord { event_id, created_at: now, name: event.name, start: event.start });
+                    summary.created += 1;
+                }
+                Err(e) => {
+                    error!(error = %e, game_id = game.id, "Failed to create Discord scheduled event");
+                    summary.failed += 1;
+                }
+            },
+        }
+    }
+    // Games already played are no longer in the document, so only upcoming ones are checked
+    let gone: Vec<(i64, String)> = records
+        .iter()
+        .filter(|(id, record)| record.start > now && day_smart.game_status(**id) != GameStatus::Listed)
+        .map(|(id, record)| (*id, record.event_id.clone()))
+        .collect();
+    for (game_id, event_id) in gone {
+        match bot.delete_scheduled_event(&config.guild_id, &event_id).await {
+            Ok(()) => {
+                info!(game_id, event_id, "Deleted Discord scheduled event of a game no longer on the schedule");
+                records.remove(&game_id);
+                summary.deleted += 1;
+            }
+            Err(e) => {
+                error!(error = %e, game_id, "Failed to delete Discord scheduled event");
+                summary.failed += 1;
+            }
+        }
+    }
+    records.retain(|_, record| record.start + Duration::days(RECORD_TTL_DAYS) > now);
+    summary
+}
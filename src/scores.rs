@@ -0,0 +1,29 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::state::StateStore;
+
+/// Days a posted recap is remembered; the scores workflow only recaps the previous day's games.
+const POSTED_TTL_DAYS: i64 = 30;
+
+/// When each game's recap was posted, by DaySmart game id.
+pub type PostedRecaps = BTreeMap<i64, DateTime<Utc>>;
+
+fn state_key(team_id: &str) -> String {
+    format!("scores_posted/{}", team_id)
+}
+
+/// Games whose recap has already been posted.
+pub async fn posted(store: &StateStore, team_id: &str) -> Result<PostedRecaps, String> {
+    Ok(store.get(&state_key(team_id)).await?.unwrap_or_default())
+}
+
+/// Remember that the recaps of `game_ids` were posted at `now`, forgetting recaps older than
+/// 30 days.
+pub async fn record_posted(store: &StateStore, team_id: &str, game_ids: &[i64], now: DateTime<Utc>) -> Result<(), String> {
+    let mut posted = posted(store, team_id).await?;
+    posted.extend(game_ids.iter().map(|id| (*id, now)));
+    posted.retain(|_, posted_at| *posted_at + Duration::days(POSTED_TTL_DAYS) > now);
+    store.put(&state_key(team_id), &posted).await
+}
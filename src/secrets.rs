@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+/// Port the AWS Parameters and Secrets Lambda Extension listens on unless configured otherwise.
+const DEFAULT_EXTENSION_PORT: u16 = 2773;
+
+/// How long a resolved value is reused by warm invocations before it is looked up again, so a
+/// rotated secret is picked up without a cold start.
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// A value kept in Secrets Manager or SSM Parameter Store instead of the invoke payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretRef {
+    /// Secret ARN (or name). The secret string is either the value itself or a JSON object
+    /// with a `discord_hook_url` key.
+    SecretsManager(String),
+    /// Parameter name, e.g. `/hockey/discord_hook_url`; SecureString parameters are decrypted.
+    Parameter(String),
+}
+
+impl SecretRef {
+    fn cache_key(&self) -> String {
+        match self {
+            SecretRef::SecretsManager(id) => format!("secretsmanager:{}", id),
+            SecretRef::Parameter(name) => format!("ssm:{}", name),
+        }
+    }
+}
+
+/// A webhook URL or token: the value itself, or where it is kept, e.g.
+/// `{ "parameter": "/hockey/bot_token" }`. References are resolved once per invocation, before
+/// any workflow runs.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Secret {
+    Value(String),
+    Stored(SecretRef),
+}
+
+impl Secret {
+    /// The value; empty for a reference not yet resolved.
+    pub fn value(&self) -> &str {
+        match self {
+            Secret::Value(value) => value,
+            Secret::Stored(_) => "",
+        }
+    }
+
+    /// Whether neither a value nor a reference is given.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Secret::Value(value) if value.trim().is_empty())
+    }
+
+    /// Replace a reference with the value behind it.
+    pub async fn resolve(&mut self, client: &SecretsClient) -> Result<(), String> {
+        if let Secret::Stored(secret) = self {
+            *self = Secret::Value(client.resolve(secret).await?);
+        }
+        Ok(())
+    }
+}
+
+impl Default for Secret {
+    fn default() -> Self {
+        Secret::Value(String::new())
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret::Value(value.to_string())
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret::Value(value)
+    }
+}
+
+// Values never reach logs; references are only where to look
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Secret::Value(_) => f.write_str("Value(REDACTED)"),
+            Secret::Stored(secret) => f.debug_tuple("Stored").field(secret).finish(),
+        }
+    }
+}
+
+// Resolved values by cache key, kept across warm invocations.
+static CACHE: OnceLock<Mutex<HashMap<String, (Instant, String)>>> = OnceLock::new();
+
+fn cache() -> &'static Mutex<HashMap<String, (Instant, String)>> {
+    CACHE.get_or_init(Default::default)
+}
+
+/// Reads secrets and parameters through the Parameters and Secrets Lambda Extension, so no
+/// Secrets Manager or SSM SDK is needed. The function's role needs `secretsmanager:GetSecretValue`
+/// / `ssm:GetParameter` (and `kms:Decrypt` for customer-managed keys).
+#[derive(Debug, Clone)]
+pub struct SecretsClient {
+    base_url: String,
+    token: String,
+    client: reqwest::Client,
+}
+
+impl SecretsClient {
+    /// Client for the extension in this Lambda environment: its port from
+    /// `PARAMETERS_SECRETS_EXTENSION_HTTP_PORT`, authenticated with `AWS_SESSION_TOKEN`.
+    pub fn from_env() -> Self {
+        let port = std::env::var("PARAMETERS_SECRETS_EXTENSION_HTTP_PORT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(DEFAULT_EXTENSION_PORT);
+        Self::new(format!("http://localhost:{}", port), std::env::var("AWS_SESSION_TOKEN").unwrap_or_default())
+    }
+
+    pub fn new(base_url: String, token: String) -> Self {
+        Self { base_url, token, client: crate::http::shared_client() }
+    }
+
+    /// The value behind `secret`, from the cache while it is fresh.
+    pub async fn resolve(&self, secret: &SecretRef) -> Result<String, String> {
+        let key = secret.cache_key();
+        if let Some((fetched, value)) = cache().lock().expect("secrets cache lock").get(&key)
+            && fetched.elapsed() < CACHE_TTL
+        {
+            return Ok(value.clone());
+        }
+        let value = self.fetch(secret).await?;
+        info!(secret = %key, "Resolved secret");
+        cache().lock().expect("secrets cache lock").insert(key, (Instant::now(), value.clone()));
+        Ok(value)
+    }
+
+    async fn fetch(&self, secret: &SecretRef) -> Result<String, String> {
+        let (path, query) = match secret {
+            SecretRef::SecretsManager(id) => ("secretsmanager/get", vec![("secretId", id.as_str())]),
+            SecretRef::Parameter(name) => ("systemsmanager/parameters/get", vec![("name", name.as_str()), ("withDecryption", "true")]),
+        };
+        // Responses carry the secret, so unlike other outbound calls nothing here is captured
+        let response = self
+            .client
+            .get(format!("{}/{}", self.base_url, path))
+            .query(&query)
+            .header("X-Aws-Parameters-Secrets-Token", &self.token)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach the secrets extension: {}", e))?;
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.map_err(|e| format!("Invalid secrets extension response: {}", e))?;
+        if !status.is_success() {
+            error!(secret = %secret.cache_key(), status = status.as_u16(), "Secret lookup failed");
+            return Err(format!("Failed to resolve {}: HTTP {}", secret.cache_key(), status.as_u16()));
+        }
+        let value = match secret {
+            SecretRef::SecretsManager(_) => body.get("SecretString").and_then(|v| v.as_str()).map(secret_value),
+            SecretRef::Parameter(_) => body.pointer("/Parameter/Value").and_then(|v| v.as_str()).map(|v| v.trim().to_string()),
+        };
+        value.filter(|v| !v.is_empty()).ok_or_else(|| format!("{} has no value", secret.cache_key()))
+    }
+}
+
+/// The webhook in a secret string: the string itself, or its `discord_hook_url` when it holds a
+/// JSON object (the key/value form the Secrets Manager console creates).
+fn secret_value(secret_string: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(secret_string)
+        .ok()
+        .and_then(|json| json.get("discord_hook_url")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| secret_string.trim().to_string())
+}
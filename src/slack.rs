@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::clock::{IdGenerator, RandomIds};
+use crate::error::Error;
+use crate::http::Capture;
+use crate::multipart::MultipartBody;
+use crate::secrets::Secret;
+
+const FILES_UPLOAD_URL: &str = "https://slack.com/api/files.upload";
+
+/// Slack destination settings. Plain messages go through the incoming webhook; attachments need
+/// a bot token with `files:write` and the channel to upload into.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SlackConfig {
+    pub webhook_url: Secret,
+    /// Webhook used instead of `webhook_url` when the request runs in test mode.
+    #[serde(default)]
+    pub test_webhook_url: Option<Secret>,
+    #[serde(default)]
+    pub bot_token: Option<Secret>,
+    /// Channel id for file uploads.
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Channel id for file uploads in test mode.
+    #[serde(default)]
+    pub test_channel: Option<String>,
+}
+
+// The bot token never reaches logs
+impl std::fmt::Debug for SlackConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SlackConfig")
+            .field("webhook_url", &crate::http::redact_url(self.webhook_url.value()))
+            .field("test_webhook_url", &self.test_webhook_url.as_ref().map(|url| crate::http::redact_url(url.value())))
+            .field("bot_token", &self.bot_token.as_ref().map(|_| "REDACTED"))
+            .field("channel", &self.channel)
+            .field("test_channel", &self.test_channel)
+            .finish()
+    }
+}
+
+/// Simple Slack client mirroring `Discord`: an incoming webhook plus optional file uploads.
+#[derive(Clone)]
+pub struct Slack {
+    hook_url: String,
+    // (bot token, channel id) when attachments can be uploaded
+    upload: Option<(String, String)>,
+    ids: Arc<dyn IdGenerator>,
+    client: reqwest::Client,
+}
+
+impl std::fmt::Debug for Slack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Slack")
+            .field("hook_url", &crate::http::redact_url(&self.hook_url))
+            .field("upload_channel", &self.upload_channel())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Slack {
+    /// Create a new Slack client with the provided incoming webhook URL.
+    pub fn new(hook_url: String) -> Self {
+        Self { hook_url, upload: None, ids: Arc::new(RandomIds), client: crate::http::shared_client() }
+    }
+
+    /// The webhook this client posts to.
+    pub fn hook_url(&self) -> &str {
+        &self.hook_url
+    }
+
+    /// Channel attachments are uploaded into, when uploads are enabled.
+    pub fn upload_channel(&self) -> Option<&str> {
+        self.upload.as_ref().map(|(_, channel)| channel.as_str())
+    }
+
+    /// Enable attachment uploads to `channel` using a bot token.
+    pub fn with_upload(mut self, bot_token: String, channel: String) -> Self {
+        self.upload = Some((bot_token, channel));
+        self
+    }
+
+    /// The same client posting to `hook_url`.
+    pub fn with_hook_url(mut self, hook_url: String) -> Self {
+        self.hook_url = hook_url;
+        self
+    }
+
+    /// Take multipart boundaries from `ids` instead of random ones.
+    pub fn with_ids(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Build a client from config, choosing the test webhook/channel in test mode when present.
+    pub fn from_config(config: &SlackConfig, test_mode: bool) -> Self {
+        let pick = |prod: &Option<String>, test: &Option<String>| if test_mode { test.clone().or_else(|| prod.clone()) } else { prod.clone() };
+        let value = |secret: &Option<Secret>| secret.as_ref().map(|secret| secret.value().to_string());
+        let hook_url = pick(&value(&Some(config.webhook_url.clone())), &value(&config.test_webhook_url)).unwrap_or_default();
+        let slack = Self::new(hook_url);
+        match (value(&config.bot_token), pick(&config.channel, &config.test_channel)) {
+            (Some(token), Some(channel)) => slack.with_upload(token, channel),
+            _ => slack,
+        }
+    }
+
+    /// Post a simple text message to the webhook URL.
+    pub async fn post(&self, content: &str) -> Result<(), Error> {
+        let payload = serde_json::json!({ "text": content });
+        let capture = Capture::start("POST", &self.hook_url);
+        let result = self
+            .client
+            .post(&self.hook_url)
+            .json(&payload)
+            .send()
+            .await
+            .inspect(|resp| capture.finish(resp.status().as_u16(), None))
+            .inspect_err(|e| capture.failed(e))
+            .and_then(|resp| resp.error_for_status());
+        match result {
+            Ok(resp) => {
+                info!(status = resp.status().as_u16(), "Posted message to Slack webhook");
+                Ok(())
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to post to Slack webhook");
+                Err(classify(format!("Failed to post to Slack webhook: {}", e), &e))
+            }
+        }
+    }
+
+    /// Post a message with a single file attachment via files.upload, using the message as the
+    /// file's initial comment. Without upload credentials only the message is posted.
+    /// See: https://api.slack.com/methods/files.upload
+    pub async fn post_with_attachment(&self, content: &str, filename: &str, bytes: &[u8]) -> Result<(), Error> {
+        let Some((token, channel)) = &self.upload else {
+            info!(filename, "No Slack bot token/channel configured; posting message without attachment");
+            return self.post(content).await;
+        };
+
+        let mut multipart = MultipartBody::new(self.ids.as_ref());
+        multipart
+            .text("channels", None, channel)
+            .text("initial_comment", None, content)
+            .text("filename", None, filename)
+            .file("file", filename, bytes);
+        let (content_type, body) = multipart.finish();
+
+        let capture = Capture::start("POST", FILES_UPLOAD_URL);
+        let response: serde_json::Value = self
+            .client
+            .post(FILES_UPLOAD_URL)
+            .bearer_auth(token)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body)
+            .send()
+            .await
+            .inspect(|resp| capture.finish(resp.status().as_u16(), None))
+            .inspect_err(|e| capture.failed(e))
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| {
+                error!(error = %e, "Failed to upload attachment to Slack");
+                classify(format!("Failed to upload attachment to Slack: {}", e), &e)
+            })?
+            .json()
+            .await
+            .map_err(|e| Error::Parse(format!("Failed to read Slack upload response: {}", e)))?;
+        // Slack reports API errors with HTTP 200 and ok=false
+        if response.get("ok").and_then(|v| v.as_bool()) == Some(true) {
+            info!("Posted message with attachment to Slack");
+            Ok(())
+        } else {
+            let reason = response.get("error").and_then(|v| v.as_str()).unwrap_or("unknown error");
+            error!(error = %reason, "Slack rejected attachment upload");
+            // e.g. invalid_auth or channel_not_found, which retrying won't fix
+            Err(Error::Config(format!("Slack rejected attachment upload: {}", reason)))
+        }
+    }
+}
+
+/// A rejected request (4xx other than rate limiting) means the webhook or token is wrong, not
+/// that Slack is down.
+fn classify(message: String, e: &reqwest::Error) -> Error {
+    match e.status() {
+        Some(status) if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS => Error::Config(message),
+        _ => Error::Network(message),
+    }
+}
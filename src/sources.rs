@@ -0,0 +1,149 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::error::Error;
+use crate::daysmart::{DaySmart, View};
+use crate::display::Placeholders;
+use crate::facility::FacilityHoursConfig;
+use crate::http::HttpPolicy;
+use crate::http_cache::HttpCache;
+use crate::jersey::JerseyConfig;
+use crate::ical::ingest::QuarantinedEvent;
+use crate::ical::Ical;
+
+/// Timing and outcome of a single upstream fetch, reported back in the Response.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FetchReport {
+    pub source: String,
+    pub duration_ms: u64,
+    pub ok: bool,
+    /// iCal events left out because they were malformed; the rest of the feed was used.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quarantined: Vec<QuarantinedEvent>,
+}
+
+type SharedFetch<T> = Shared<BoxFuture<'static, T>>;
+
+/// Upstream data shared by every workflow in one invocation.
+/// A source that no selected workflow needs is left as None and never fetched.
+#[derive(Clone, Default)]
+pub struct Sources {
+    daysmart: Option<SharedFetch<Result<Arc<DaySmart>, Error>>>,
+    ical: Option<SharedFetch<Arc<Ical>>>,
+    started: Option<Instant>,
+    fetches: Arc<Mutex<Vec<FetchReport>>>,
+}
+
+impl Sources {
+    /// Prepare the requested sources. Each is fetched exactly once, on first use, and workflows
+    /// await only the sources they read, so a slow source holds up only its own workflows.
+    /// `daysmart` carries (team_id, company) when the DaySmart document is needed.
+    /// `timezone` overrides the display timezone of both sources; `policy` sets their timeouts and retries.
+    /// `placeholders` is the text DaySmart output shows for missing names; `jerseys` picks our jersey color;
+    /// `view` is how the request wants games shown.
+    /// `facility_hours` holds back DaySmart games listed while their rink is closed.
+    /// With `cache`, both are fetched conditionally and not parsed again when unchanged.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fetch(
+        daysmart: Option<(String, String)>,
+        ical_url: Option<String>,
+        timezone: Option<chrono_tz::Tz>,
+        placeholders: Placeholders,
+        jerseys: JerseyConfig,
+        view: View,
+        policy: HttpPolicy,
+        facility_hours: Option<FacilityHoursConfig>,
+        cache: Option<HttpCache>,
+    ) -> Sources {
+        let fetches: Arc<Mutex<Vec<FetchReport>>> = Arc::default();
+        let started = Instant::now();
+
+        let daysmart = daysmart.map(|(team_id, company)| {
+            let fetches = Arc::clone(&fetches);
+            let cache = cache.clone();
+            async move {
+                let started = Instant::now();
+                let fetched = match &cache {
+                    Some(cache) => DaySmart::for_team_cached(&team_id, &company, &policy, cache).await,
+                    None => DaySmart::for_team(&team_id, &company, &policy).await,
+                };
+                let result = fetched
+                    .map(|ds| ds.with_placeholders(placeholders).with_jerseys(jerseys).with_view(view))
+                    .map(|ds| match timezone { Some(tz) => ds.with_timezone(tz), None => ds })
+                    .map(|ds| Arc::new(match &facility_hours { Some(hours) => ds.with_facility_hours(hours), None => ds }));
+                if let Err(e) = &result {
+                    error!(error = %e, "DaySmart fetch failed");
+                }
+                record(&fetches, "daysmart", started, result.is_ok(), Vec::new());
+                result
+            }
+            .boxed()
+            .shared()
+        });
+        let ical = ical_url.map(|url| {
+            let fetches = Arc::clone(&fetches);
+            async move {
+                let started = Instant::now();
+                let generator = match &cache {
+                    Some(cache) => Ical::from_url_cached(&url, &policy, cache).await,
+                    None => Ical::from_url(&url, &policy).await,
+                };
+                let generator = match timezone { Some(tz) => generator.with_timezone(tz), None => generator };
+                record(&fetches, "ical", started, generator.calendar.is_some(), generator.quarantined.clone());
+                Arc::new(generator)
+            }
+            .boxed()
+            .shared()
+        });
+
+        Sources { daysmart, ical, started: Some(started), fetches }
+    }
+
+    /// The parsed DaySmart document, fetching it if no workflow has yet.
+    pub async fn daysmart(&self) -> Option<Result<Arc<DaySmart>, Error>> {
+        match &self.daysmart {
+            Some(fetch) => Some(fetch.clone().await),
+            None => None,
+        }
+    }
+
+    /// The DaySmart document if its fetch has already finished; never waits.
+    pub fn daysmart_if_ready(&self) -> Option<Result<Arc<DaySmart>, Error>> {
+        self.daysmart.as_ref()?.peek().cloned()
+    }
+
+    /// The iCal feed, fetching it if no workflow has yet.
+    pub async fn ical(&self) -> Option<Arc<Ical>> {
+        match &self.ical {
+            Some(fetch) => Some(fetch.clone().await),
+            None => None,
+        }
+    }
+
+    /// Reports for finished fetches, plus a failed entry for each source still in flight.
+    pub fn fetches(&self) -> Vec<FetchReport> {
+        let mut reports = self.fetches.lock().map(|f| f.clone()).unwrap_or_default();
+        let elapsed = self.started.map(|s| s.elapsed().as_millis() as u64).unwrap_or(0);
+        let pending = [
+            ("daysmart", self.daysmart.as_ref().is_some_and(|f| f.peek().is_none())),
+            ("ical", self.ical.as_ref().is_some_and(|f| f.peek().is_none())),
+        ];
+        for (source, _) in pending.iter().filter(|(_, pending)| *pending) {
+            reports.push(FetchReport { source: source.to_string(), duration_ms: elapsed, ok: false, quarantined: Vec::new() });
+        }
+        reports
+    }
+}
+
+fn record(fetches: &Mutex<Vec<FetchReport>>, source: &str, started: Instant, ok: bool, quarantined: Vec<QuarantinedEvent>) {
+    let duration_ms = started.elapsed().as_millis() as u64;
+    info!(source, duration_ms, ok, quarantined = quarantined.len(), "Fetched source");
+    if let Ok(mut fetches) = fetches.lock() {
+        fetches.push(FetchReport { source: source.to_string(), duration_ms, ok, quarantined });
+    }
+}
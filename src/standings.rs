@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use tracing::{info, instrument};
+
+use crate::daysmart::DaySmart;
+use crate::error::Error;
+use crate::http::HttpPolicy;
+
+/// A team's record, ranked by points then wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub team_id: Option<i64>,
+    pub team_name: String,
+    pub games_played: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub ties: i64,
+    pub points: i64,
+}
+
+/// The standings of a league document's teams from its scored regular-season games, best team
+/// first: two points per win and one per tie. Playoff games don't count.
+pub fn from_league(league: &DaySmart) -> Vec<Standing> {
+    let mut records: HashMap<i64, (i64, i64, i64)> = HashMap::new();
+    for game in league.all_games().into_iter().filter(|game| !game.is_playoff) {
+        let (Some((home_score, visitor_score)), Some(home), Some(visitor)) = (game.score, game.h_id, game.v_id) else {
+            continue;
+        };
+        for (team, ours, theirs) in [(home, home_score, visitor_score), (visitor, visitor_score, home_score)] {
+            let (wins, losses, ties) = records.entry(team).or_default();
+            match ours.cmp(&theirs) {
+                std::cmp::Ordering::Greater => *wins += 1,
+                std::cmp::Ordering::Less => *losses += 1,
+                std::cmp::Ordering::Equal => *ties += 1,
+            }
+        }
+    }
+    let mut rows: Vec<Standing> = league
+        .league_teams()
+        .into_iter()
+        .map(|(id, name)| {
+            let (wins, losses, ties) = records.get(&id).copied().unwrap_or_default();
+            Standing { team_id: Some(id), team_name: name.as_str().to_string(), games_played: wins + losses + ties, wins, losses, ties, points: wins * 2 + ties }
+        })
+        .collect();
+    rows.sort_by(|a, b| b.points.cmp(&a.points).then(b.wins.cmp(&a.wins)).then(a.team_name.cmp(&b.team_name)));
+    rows
+}
+
+/// Fetch a DaySmart league's games and work out its standings. DaySmart's JSON:API has no
+/// standings resource, so they are counted from the scores.
+#[instrument(level = "info", skip(policy))]
+pub async fn fetch(league_id: i64, company: &str, policy: &HttpPolicy) -> Result<Vec<Standing>, Error> {
+    let rows = from_league(&DaySmart::for_league(league_id, company, policy).await?);
+    info!(teams = rows.len(), "Worked out league standings");
+    Ok(rows)
+}
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Discord message with the standings as a fixed-width table, our team marked with an arrow
+/// and summarized on the last line.
+pub fn format_table(league_name: &str, rows: &[Standing], our_team_id: Option<i64>) -> String {
+    let name_width = rows.iter().map(|r| r.team_name.chars().count()).max().unwrap_or(4).max(4);
+    let mut out = format!(":bar_chart: Standings: {}\n```\n", league_name);
+    out.push_str(&format!("   # {:<width$}  GP   W   L   T  PTS\n", "Team", width = name_width));
+    for (i, row) in rows.iter().enumerate() {
+        let marker = if row.team_id.is_some() && row.team_id == our_team_id { ">" } else { " " };
+        out.push_str(&format!(
+            "{} {:>2} {:<width$} {:>3} {:>3} {:>3} {:>3} {:>4}\n",
+            marker,
+            i + 1,
+            row.team_name,
+            row.games_played,
+            row.wins,
+            row.losses,
+            row.ties,
+            row.points,
+            width = name_width
+        ));
+    }
+    out.push_str("```");
+    if let Some((i, row)) = rows.iter().enumerate().find(|(_, r)| r.team_id.is_some() && r.team_id == our_team_id) {
+        out.push_str(&format!(
+            "\n{}: {} of {}, {}-{}-{} ({} pts)",
+            row.team_name,
+            ordinal(i + 1),
+            rows.len(),
+            row.wins,
+            row.losses,
+            row.ties,
+            row.points
+        ));
+    }
+    out
+}
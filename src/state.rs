@@ -0,0 +1,275 @@
+use std::path::PathBuf;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+/// Where state that must survive between invocations is kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum StateConfig {
+    /// One JSON object per key under `s3://bucket/prefix/`.
+    S3 {
+        bucket: String,
+        #[serde(default = "default_prefix")]
+        prefix: String,
+    },
+    /// One JSON file per key under `dir`. Only useful for local runs and tests:
+    /// Lambda's /tmp does not survive a cold start.
+    File { dir: String },
+    /// One item per key in a DynamoDB table with a string partition key named `key`;
+    /// the JSON document is kept in the `value` attribute.
+    Dynamodb { table: String },
+}
+
+fn default_prefix() -> String { "state".to_string() }
+
+const KEY_ATTRIBUTE: &str = "key";
+const VALUE_ATTRIBUTE: &str = "value";
+/// Epoch seconds after which DynamoDB may delete an expiring item; enable TTL on this attribute.
+pub const EXPIRES_AT_ATTRIBUTE: &str = "expires_at";
+/// Object tag on expiring S3 records, for a lifecycle rule to expire them.
+pub const EXPIRING_TAG: &str = "expiring=true";
+
+/// How an expiring document is stored: the document plus when it may be deleted.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expiring<T> {
+    expires_at: DateTime<Utc>,
+    value: T,
+}
+
+/// Parse a stored document, unwrapping an expiring one. Expiry is left to the backends (TTL,
+/// lifecycle rule, [`StateStore::remove_expired`]) rather than checked here, so a run with a
+/// simulated clock reads the same records as a real one.
+fn decode<T: DeserializeOwned>(key: &str, bytes: &[u8]) -> Result<Option<T>, String> {
+    let parse_error = |e: serde_json::Error| format!("Failed to parse state '{}': {}", key, e);
+    match serde_json::from_slice::<Expiring<serde_json::Value>>(bytes) {
+        Ok(record) => serde_json::from_value(record.value).map(Some).map_err(parse_error),
+        Err(_) => serde_json::from_slice(bytes).map(Some).map_err(parse_error),
+    }
+}
+
+/// Small key/value store for JSON documents, shared by features that need memory across runs.
+/// Keys are slash-separated paths such as `dm_subscribers/11007`.
+#[derive(Debug, Clone)]
+pub enum StateStore {
+    S3 { client: aws_sdk_s3::Client, bucket: String, prefix: String },
+    File { dir: PathBuf },
+    Dynamodb { client: aws_sdk_dynamodb::Client, table: String },
+    /// Reads from the wrapped store; writes and deletes are dropped (dry runs).
+    ReadOnly(Box<StateStore>),
+}
+
+impl StateStore {
+    pub async fn from_config(config: &StateConfig) -> Self {
+        match config {
+            StateConfig::S3 { bucket, prefix } => StateStore::S3 {
+                client: crate::aws::s3_client().await,
+                bucket: bucket.clone(),
+                prefix: prefix.trim_end_matches('/').to_string(),
+            },
+            StateConfig::File { dir } => StateStore::File { dir: PathBuf::from(dir) },
+            StateConfig::Dynamodb { table } => StateStore::Dynamodb {
+                client: crate::aws::dynamodb_client().await,
+                table: table.clone(),
+            },
+        }
+    }
+
+    /// This store, with writes and deletes dropped.
+    pub fn read_only(self) -> Self {
+        match self {
+            StateStore::ReadOnly(_) => self,
+            store => StateStore::ReadOnly(Box::new(store)),
+        }
+    }
+
+    fn file_path(dir: &std::path::Path, key: &str) -> PathBuf {
+        let mut path = dir.to_path_buf();
+        // Only keep normal path segments so keys cannot escape the state directory
+        for segment in key.split('/').filter(|s| !s.is_empty() && *s != "." && *s != "..") {
+            path.push(segment);
+        }
+        path.set_extension("json");
+        path
+    }
+
+    /// Load the document stored under `key`, or None if nothing has been stored yet.
+    #[instrument(level = "info", skip(self))]
+    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, String> {
+        let bytes = match self {
+            StateStore::ReadOnly(inner) => return Box::pin(inner.get(key)).await,
+            StateStore::S3 { client, bucket, prefix } => {
+                match client.get_object().bucket(bucket).key(format!("{}/{}.json", prefix, key)).send().await {
+                    Ok(out) => out
+                        .body
+                        .collect()
+                        .await
+                        .map_err(|e| format!("Failed to read state '{}': {}", key, e))?
+                        .into_bytes()
+                        .to_vec(),
+                    Err(e) if e.as_service_error().map(|se| se.is_no_such_key()).unwrap_or(false) => return Ok(None),
+                    Err(e) => return Err(format!("Failed to load state '{}': {}", key, e)),
+                }
+            }
+            StateStore::File { dir } => match tokio::fs::read(Self::file_path(dir, key)).await {
+                Ok(bytes) => bytes,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(format!("Failed to load state '{}': {}", key, e)),
+            },
+            StateStore::Dynamodb { client, table } => {
+                let out = client
+                    .get_item()
+                    .table_name(table)
+                    .key(KEY_ATTRIBUTE, AttributeValue::S(key.to_string()))
+                    .consistent_read(true)
+                    .send()
+                    .await
+                    .map_err(|e| format!("Failed to load state '{}': {}", key, e))?;
+                match out.item.as_ref().and_then(|item| item.get(VALUE_ATTRIBUTE)) {
+                    Some(AttributeValue::S(json)) => json.clone().into_bytes(),
+                    Some(_) => return Err(format!("State '{}' has a non-string value attribute", key)),
+                    None => return Ok(None),
+                }
+            }
+        };
+        decode(key, &bytes)
+    }
+
+    /// Store `value` under `key`, replacing any previous document.
+    #[instrument(level = "info", skip(self, value))]
+    pub async fn put<T: Serialize>(&self, key: &str, value: &T) -> Result<(), String> {
+        let body = serde_json::to_vec(value).map_err(|e| format!("Failed to serialize state '{}': {}", key, e))?;
+        self.put_body(key, body, None).await
+    }
+
+    /// Store `value` under `key` until `expires_at`, after which the backend may delete it. Use it
+    /// for per-game records that would otherwise pile up across seasons. DynamoDB deletes the item
+    /// once TTL is enabled on `expires_at`; S3 objects are tagged for a lifecycle rule; local
+    /// files are removed by [`StateStore::remove_expired`].
+    #[instrument(level = "info", skip(self, value))]
+    pub async fn put_expiring<T: Serialize>(&self, key: &str, value: &T, expires_at: DateTime<Utc>) -> Result<(), String> {
+        let body = serde_json::to_vec(&Expiring { expires_at, value })
+            .map_err(|e| format!("Failed to serialize state '{}': {}", key, e))?;
+        self.put_body(key, body, Some(expires_at)).await
+    }
+
+    async fn put_body(&self, key: &str, body: Vec<u8>, expires_at: Option<DateTime<Utc>>) -> Result<(), String> {
+        match self {
+            StateStore::ReadOnly(_) => {
+                info!(key, "Read-only state; not saving");
+                Ok(())
+            }
+            StateStore::S3 { client, bucket, prefix } => client
+                .put_object()
+                .bucket(bucket)
+                .key(format!("{}/{}.json", prefix, key))
+                .content_type("application/json")
+                .set_tagging(expires_at.map(|_| EXPIRING_TAG.to_string()))
+                .body(body.into())
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Failed to save state '{}': {}", key, e)),
+            StateStore::File { dir } => {
+                let path = Self::file_path(dir, key);
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent)
+                        .await
+                        .map_err(|e| format!("Failed to create state directory: {}", e))?;
+                }
+                tokio::fs::write(&path, body)
+                    .await
+                    .map_err(|e| format!("Failed to save state '{}': {}", key, e))
+            }
+            StateStore::Dynamodb { client, table } => {
+                let json = String::from_utf8(body).map_err(|e| format!("Failed to serialize state '{}': {}", key, e))?;
+                let mut item = client
+                    .put_item()
+                    .table_name(table)
+                    .item(KEY_ATTRIBUTE, AttributeValue::S(key.to_string()))
+                    .item(VALUE_ATTRIBUTE, AttributeValue::S(json));
+                if let Some(expires_at) = expires_at {
+                    item = item.item(EXPIRES_AT_ATTRIBUTE, AttributeValue::N(expires_at.timestamp().to_string()));
+                }
+                item.send()
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| format!("Failed to save state '{}': {}", key, e))
+            }
+        }
+    }
+
+    /// Remove the document under `key`; removing a missing key is not an error.
+    #[instrument(level = "info", skip(self))]
+    pub async fn delete(&self, key: &str) -> Result<(), String> {
+        match self {
+            StateStore::ReadOnly(_) => {
+                info!(key, "Read-only state; not deleting");
+                Ok(())
+            }
+            StateStore::S3 { client, bucket, prefix } => client
+                .delete_object()
+                .bucket(bucket)
+                .key(format!("{}/{}.json", prefix, key))
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Failed to delete state '{}': {}", key, e)),
+            StateStore::File { dir } => match tokio::fs::remove_file(Self::file_path(dir, key)).await {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(format!("Failed to delete state '{}': {}", key, e)),
+            },
+            StateStore::Dynamodb { client, table } => client
+                .delete_item()
+                .table_name(table)
+                .key(KEY_ATTRIBUTE, AttributeValue::S(key.to_string()))
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Failed to delete state '{}': {}", key, e)),
+        }
+    }
+
+    /// Delete expired records from the local-file backend and return how many were removed.
+    /// Returns None for S3 and DynamoDB, which expire records themselves (lifecycle rule, TTL),
+    /// and for read-only stores.
+    #[instrument(level = "info", skip(self))]
+    pub async fn remove_expired(&self, now: DateTime<Utc>) -> Result<Option<usize>, String> {
+        let StateStore::File { dir } = self else {
+            return Ok(None);
+        };
+        let mut removed = 0;
+        let mut pending = vec![dir.clone()];
+        while let Some(current) = pending.pop() {
+            let mut entries = match tokio::fs::read_dir(&current).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(format!("Failed to list state directory: {}", e)),
+            };
+            while let Some(entry) = entries.next_entry().await.map_err(|e| format!("Failed to list state directory: {}", e))? {
+                let path = entry.path();
+                if entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false) {
+                    pending.push(path);
+                    continue;
+                }
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    continue;
+                }
+                let Ok(bytes) = tokio::fs::read(&path).await else { continue };
+                let expired = serde_json::from_slice::<Expiring<serde_json::Value>>(&bytes).is_ok_and(|r| r.expires_at <= now);
+                if expired {
+                    tokio::fs::remove_file(&path)
+                        .await
+                        .map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+                    removed += 1;
+                }
+            }
+        }
+        Ok(Some(removed))
+    }
+}
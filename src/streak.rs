@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// How one of our games ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    Win,
+    Loss,
+    Tie,
+}
+
+/// Consecutive identical results up to and including the most recent game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Streak {
+    pub result: GameResult,
+    pub length: usize,
+}
+
+/// When to show the streak line (enabled by setting `Request::streak`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreakConfig {
+    /// Shortest streak worth mentioning.
+    #[serde(default = "default_min_length")]
+    pub min_length: usize,
+    /// Leave losing streaks out, for morale.
+    #[serde(default)]
+    pub hide_losing: bool,
+}
+
+fn default_min_length() -> usize { 2 }
+
+impl Default for StreakConfig {
+    fn default() -> Self {
+        Self { min_length: default_min_length(), hide_losing: false }
+    }
+}
+
+/// Current streak from results in the order they were played.
+pub fn current_streak(results: &[GameResult]) -> Option<Streak> {
+    let last = *results.last()?;
+    let length = results.iter().rev().take_while(|r| **r == last).count();
+    Some(Streak { result: last, length })
+}
+
+/// e.g. ":fire: Won 3 straight", or None when the streak shouldn't be shown.
+pub fn streak_line(streak: Option<Streak>, config: &StreakConfig) -> Option<String> {
+    let streak = streak.filter(|s| s.length >= config.min_length.max(1))?;
+    let (emoji, verb) = match streak.result {
+        GameResult::Win => (":fire:", "Won"),
+        GameResult::Loss if config.hide_losing => return None,
+        GameResult::Loss => (":chart_with_downwards_trend:", "Lost"),
+        GameResult::Tie => (":handshake:", "Tied"),
+    };
+    Some(match streak.length {
+        1 => format!("{} {} the last game", emoji, verb),
+        n => format!("{} {} {} straight", emoji, verb, n),
+    })
+}
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::destination::{DeliveryReport, PostedFile};
+use crate::error::ErrorInfo;
+use crate::explain::GameExplanation;
+use crate::workflow::Workflow;
+use crate::sources::FetchReport;
+
+/// How a workflow run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Done,
+    Skipped,
+    Failed,
+}
+
+impl Outcome {
+    fn emoji(self) -> &'static str {
+        match self {
+            Outcome::Done => ":white_check_mark:",
+            Outcome::Skipped => ":fast_forward:",
+            Outcome::Failed => ":x:",
+        }
+    }
+}
+
+/// Result of one workflow in an invocation.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowReport {
+    pub workflow: Workflow,
+    pub outcome: Outcome,
+    pub detail: String,
+    /// What the explain workflow found out about its game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<GameExplanation>,
+    /// The panic message, when the workflow panicked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panic: Option<String>,
+    /// The error behind a failure, machine-readable, when the workflow knows it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorInfo>,
+    /// How long the run took, from start to report; 0 for a workflow that never started.
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Text of every message the workflow posted, as generated, in the order they were posted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<String>,
+    /// Files attached to those messages, e.g. the BenchApp CSV.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<PostedFile>,
+}
+
+impl WorkflowReport {
+    pub fn new(workflow: Workflow, outcome: Outcome, detail: impl Into<String>) -> Self {
+        Self { workflow, outcome, detail: detail.into(), explanation: None, panic: None, error: None, duration_ms: 0, messages: Vec::new(), attachments: Vec::new() }
+    }
+
+    pub fn with_explanation(mut self, explanation: GameExplanation) -> Self {
+        self.explanation = Some(explanation);
+        self
+    }
+
+    pub fn with_error(mut self, error: ErrorInfo) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    /// Failed report for a workflow that panicked with `message`.
+    pub fn panicked(workflow: Workflow, message: String) -> Self {
+        let mut report = Self::new(workflow, Outcome::Failed, format!("{:?}: panicked: {}", workflow, message));
+        report.panic = Some(message);
+        report
+    }
+}
+
+/// Inputs for the captains' run summary.
+pub struct RunSummary<'a> {
+    pub team_id: &'a str,
+    pub now: DateTime<Utc>,
+    pub timezone: chrono_tz::Tz,
+    pub test_mode: bool,
+    /// DaySmart games within `days_ahead`, when the DaySmart source was fetched.
+    pub games_found: Option<usize>,
+    pub days_ahead: i64,
+    pub reports: &'a [WorkflowReport],
+    pub fetches: &'a [FetchReport],
+    /// Schedule problems for the captains to fix, e.g. games listed outside facility hours.
+    pub warnings: &'a [String],
+}
+
+/// Concise report of what broke in a run, for the error webhook: failed workflows (including
+/// panics), failed source fetches and failed posts. None when nothing did.
+pub fn format_error_report(team_id: &str, reports: &[WorkflowReport], fetches: &[FetchReport], deliveries: &[DeliveryReport]) -> Option<String> {
+    let mut lines = Vec::new();
+    for report in reports.iter().filter(|r| r.outcome == Outcome::Failed) {
+        lines.push(format!("{}: {}", report.workflow.name(), report.detail));
+    }
+    for fetch in fetches.iter().filter(|f| !f.ok) {
+        lines.push(format!("{} fetch failed after {} ms", fetch.source, fetch.duration_ms));
+    }
+    for delivery in deliveries {
+        if let Some(error) = &delivery.error {
+            lines.push(format!("post to {} failed: {}", delivery.destination, error));
+        }
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    let mut out = format!(":rotating_light: Team {}: {} problem{} in the last run", team_id, lines.len(), if lines.len() == 1 { "" } else { "s" });
+    for line in lines {
+        out.push_str(&format!("\n• {}", line));
+    }
+    Some(out)
+}
+
+/// Short per-run report for the captains channel: one line per workflow plus fetch timings.
+pub fn format_run_summary(summary: &RunSummary) -> String {
+    let local = summary.now.with_timezone(&summary.timezone);
+    let mut out = format!(":bar_chart: Run summary for team {} ({})", summary.team_id, local.format("%a %b %e, %-I:%M %p %Z"));
+    if summary.test_mode {
+        out.push_str(" [test mode]");
+    }
+    if let Some(count) = summary.games_found {
+        out.push_str(&format!("\nGames found: {} in the next {} days", count, summary.days_ahead));
+    }
+    if summary.reports.is_empty() {
+        out.push_str("\nNo workflows executed");
+    }
+    for report in summary.reports {
+        out.push_str(&format!("\n{} {}: {}", report.outcome.emoji(), report.workflow.name(), report.detail));
+    }
+    if !summary.fetches.is_empty() {
+        let fetches: Vec<String> = summary
+            .fetches
+            .iter()
+            .map(|f| {
+                let quarantined = match f.quarantined.len() {
+                    0 => String::new(),
+                    n => format!(", {} malformed events skipped", n),
+                };
+                format!("{} {} ms{}{}", f.source, f.duration_ms, if f.ok { "" } else { " (failed)" }, quarantined)
+            })
+            .collect();
+        out.push_str(&format!("\nFetches: {}", fetches.join(", ")));
+    }
+    for warning in summary.warnings {
+        out.push_str(&format!("\n:warning: {}", warning));
+    }
+    out
+}
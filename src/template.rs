@@ -0,0 +1,62 @@
+use handlebars::Handlebars;
+use serde::Serialize;
+
+/// Wording of the DaySmart reminder unless the request supplies its own `message_template`.
+pub const DEFAULT_REMINDER_TEMPLATE: &str = "{{header}}\n{{date}}\n{{time}} at {{arena}}{{#if arrive_by}}\n:alarm_clock: Arrive by {{arrive_by}} ({{arrive_minutes_early}} min before puck drop){{/if}}{{#if address}}\n:round_pushpin: {{address}} <{{maps_url}}>{{/if}}\n{{home}} vs {{visitor}}{{#unless opponent_decided}}\n:grey_question: Opponent not decided yet{{/unless}}{{#if locker_room}}\nLocker Room: {{locker_room}}{{/if}}{{#each notes}}\n:memo: {{this}}{{/each}}{{#if venue_note}}\n:information_source: {{venue_note}}{{/if}}\n:shirt: {{jersey}} Jerseys";
+
+/// Built-in wording for a practice reminder.
+pub const DEFAULT_PRACTICE_TEMPLATE: &str = "{{header}}\n{{date}}\n{{time}} at {{arena}}{{#if arrive_by}}\n:alarm_clock: Arrive by {{arrive_by}} ({{arrive_minutes_early}} min before puck drop){{/if}}{{#if address}}\n:round_pushpin: {{address}} <{{maps_url}}>{{/if}}{{#if locker_room}}\nLocker Room: {{locker_room}}{{/if}}{{#each notes}}\n:memo: {{this}}{{/each}}{{#if venue_note}}\n:information_source: {{venue_note}}{{/if}}";
+
+/// Variables available to reminder templates, e.g. `{{home}}` or `{{#if playoff}}...{{/if}}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReminderVars {
+    /// ":hockey: Kraken Hockey League Game :goal:", or the playoff banner.
+    pub header: String,
+    /// e.g. "Sun Sep 21, 2025", or with Discord timestamps "<t:1758512700:D> (<t:1758512700:R>)",
+    /// which Discord shows as the date in the reader's timezone and a countdown.
+    pub date: String,
+    /// e.g. "8:45 PM", or with Discord timestamps "<t:1758512700:t>"
+    pub time: String,
+    /// When to be at the rink with `arrive_minutes_early`, written like `time`.
+    pub arrive_by: Option<String>,
+    pub arrive_minutes_early: Option<u32>,
+    /// Start as Unix seconds, for custom `<t:...>` markup.
+    pub timestamp: i64,
+    pub arena: String,
+    /// Street address of the arena, when DaySmart has one.
+    pub address: Option<String>,
+    /// Google Maps search link for `address`.
+    pub maps_url: Option<String>,
+    pub home: String,
+    pub visitor: String,
+    pub opponent: String,
+    /// False while the opponent is missing or a bracket placeholder ("Winner of Game 3").
+    pub opponent_decided: bool,
+    /// Our jersey color, "Light" or "Dark" unless jerseys are configured
+    pub jersey: String,
+    pub locker_room: Option<String>,
+    pub playoff: bool,
+    /// True for a practice, which has no opponent or jerseys.
+    pub practice: bool,
+    /// League notes for the game from DaySmart event comments, e.g. "Wear alternate jerseys".
+    pub notes: Vec<String>,
+    /// The team's note for the venue from `venue_notes`, e.g. "Park in the north lot".
+    pub venue_note: Option<String>,
+}
+
+fn registry() -> Handlebars<'static> {
+    let mut handlebars = Handlebars::new();
+    // Discord markdown, not HTML
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars
+}
+
+/// Check that a template parses, so a broken template is rejected with the request.
+pub fn validate(template: &str) -> Result<(), String> {
+    handlebars::Template::compile(template).map(|_| ()).map_err(|e| format!("Invalid message_template: {}", e))
+}
+
+/// Render a reminder template.
+pub fn render(template: &str, vars: &ReminderVars) -> Result<String, String> {
+    registry().render_template(template, vars).map_err(|e| format!("Failed to render message_template: {}", e))
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::daysmart::MAX_LOOKAHEAD_DAYS;
+use crate::model::game::GameInfo;
+
+/// When several games fall within a few days, the reminder switches to a tournament itinerary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TournamentConfig {
+    /// Minimum number of games that makes a tournament.
+    #[serde(default = "default_min_games")]
+    pub min_games: usize,
+    /// Length of the window, in local calendar days, starting on the day of the next game.
+    #[serde(default = "default_span_days")]
+    pub span_days: i64,
+    /// Hotel details shown under the itinerary.
+    #[serde(default)]
+    pub hotel: Option<String>,
+    /// Notes per rink name (parking, entrances, ...), shown for the rinks on the itinerary.
+    #[serde(default)]
+    pub rink_notes: BTreeMap<String, String>,
+}
+
+fn default_min_games() -> usize { 3 }
+
+fn default_span_days() -> i64 { 3 }
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        Self { min_games: default_min_games(), span_days: default_span_days(), hotel: None, rink_notes: BTreeMap::new() }
+    }
+}
+
+impl TournamentConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_LOOKAHEAD_DAYS).contains(&self.span_days) {
+            return Err(format!("tournament.span_days must be between 1 and {} (got {})", MAX_LOOKAHEAD_DAYS, self.span_days));
+        }
+        Ok(())
+    }
+}
+
+/// The games making up a tournament starting with the earliest of `games`, or None when fewer
+/// than `min_games` fall within `span_days` local calendar days of it.
+pub fn detect(games: &[GameInfo], config: &TournamentConfig, timezone: chrono_tz::Tz) -> Option<Vec<GameInfo>> {
+    let mut sorted = games.to_vec();
+    sorted.sort_by_key(|g| g.dt);
+    let first_day = sorted.first()?.dt.with_timezone(&timezone).date_naive();
+    let weekend: Vec<GameInfo> = sorted
+        .into_iter()
+        .take_while(|g| (g.dt.with_timezone(&timezone).date_naive() - first_day).num_days() < config.span_days)
+        .collect();
+    (weekend.len() >= config.min_games.max(2)).then_some(weekend)
+}
+
+/// Human-readable break between two games, e.g. "2h 30m".
+pub fn format_gap(gap: chrono::Duration) -> String {
+    let minutes = gap.num_minutes().max(0);
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use crate::dedup::content_hash;
+use crate::discord::Discord;
+use crate::secrets::{SecretRef, SecretsClient};
+use crate::state::StateStore;
+use crate::summary::Outcome;
+
+/// Webhook to move production posts to (webhook_rotate workflow). It is given, like
+/// `discord_hook_secret_arn` / `discord_hook_parameter`, as a secret or parameter holding the
+/// URL, so state only ever holds the reference.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookRotationConfig {
+    #[serde(default)]
+    pub new_hook_secret_arn: Option<String>,
+    #[serde(default)]
+    pub new_hook_parameter: Option<String>,
+}
+
+impl WebhookRotationConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match (&self.new_hook_secret_arn, &self.new_hook_parameter) {
+            (Some(_), None) | (None, Some(_)) => Ok(()),
+            _ => Err("webhook_rotation needs exactly one of new_hook_secret_arn and new_hook_parameter".to_string()),
+        }
+    }
+
+    /// Where the new webhook is kept.
+    pub fn new_hook(&self) -> Option<SecretRef> {
+        match (&self.new_hook_secret_arn, &self.new_hook_parameter) {
+            (Some(arn), _) => Some(SecretRef::SecretsManager(arn.clone())),
+            (None, Some(name)) => Some(SecretRef::Parameter(name.clone())),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Production Discord webhook set by a rotation; takes precedence over `discord_hook_url` as
+/// long as that is still the webhook it replaced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredWebhook {
+    /// Where the new webhook's URL is kept; the URL itself is never stored.
+    pub new_hook: SecretRef,
+    /// Fingerprint of the `discord_hook_url` in use when the webhook was rotated.
+    pub replaces: String,
+    pub rotated_at: DateTime<Utc>,
+}
+
+fn state_key(team_id: &str) -> String {
+    format!("webhook/{}", team_id)
+}
+
+/// The webhook stored by the last rotation, if any.
+pub async fn stored(store: &StateStore, team_id: &str) -> Result<Option<StoredWebhook>, String> {
+    store.get(&state_key(team_id)).await
+}
+
+/// Production webhook to post to: the rotated one when there is one, else `configured`. A
+/// rotation stops counting once `configured` is changed, since that is the newer decision.
+/// State and secret errors are logged and fall back to `configured`.
+pub async fn effective_hook_url(store: &StateStore, secrets: &SecretsClient, team_id: &str, configured: &str) -> String {
+    match stored(store, team_id).await {
+        Ok(Some(rotated)) if rotated.replaces != content_hash(configured) => {
+            info!(rotated_at = %rotated.rotated_at, "discord_hook_url changed since the webhook was rotated; using it");
+            configured.to_string()
+        }
+        Ok(Some(rotated)) => match secrets.resolve(&rotated.new_hook).await {
+            Ok(url) => {
+                info!(rotated_at = %rotated.rotated_at, "Using rotated Discord webhook");
+                url
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to resolve rotated webhook; using discord_hook_url");
+                configured.to_string()
+            }
+        },
+        Ok(None) => configured.to_string(),
+        Err(e) => {
+            error!(error = %e, "Failed to load rotated webhook; using discord_hook_url");
+            configured.to_string()
+        }
+    }
+}
+
+/// Move production posts from `old` to `new` (the webhook behind `new_hook`): verify `new` with a
+/// test post, store the reference, then tell the old channel. Nothing is stored if the new
+/// webhook can't be posted to. The old webhook may already be revoked, so failing to reach it
+/// doesn't undo the cutover.
+pub async fn rotate(store: &StateStore, team_id: &str, old: &Discord, new: &Discord, new_hook: &SecretRef, now: DateTime<Utc>) -> (Outcome, String) {
+    if old.hook_url() == new.hook_url() {
+        return (Outcome::Skipped, "Webhook rotation: new webhook is already in use (skipped)".to_string());
+    }
+    if let Err(e) = new.post(":white_check_mark: Webhook check: hockey reminders will be posted here from now on.").await {
+        error!(error = %e, "New webhook failed verification");
+        return (Outcome::Failed, format!("Webhook rotation failed: new webhook did not accept a post: {}", e));
+    }
+    let rotated = StoredWebhook { new_hook: new_hook.clone(), replaces: content_hash(old.hook_url()), rotated_at: now };
+    if let Err(e) = store.put(&state_key(team_id), &rotated).await {
+        error!(error = %e, "Failed to store rotated webhook");
+        return (Outcome::Failed, format!("Webhook rotation failed: {}", e));
+    }
+    info!("Rotated Discord webhook");
+    match old.post(":arrows_counterclockwise: Hockey reminders have moved to a new webhook; this one is no longer used.").await {
+        Ok(_) => (Outcome::Done, "Webhook rotated; new webhook verified, old channel notified".to_string()),
+        Err(e) => {
+            warn!(error = %e, "Could not notify the old webhook");
+            (Outcome::Done, format!("Webhook rotated; new webhook verified, old channel not reachable: {}", e))
+        }
+    }
+}
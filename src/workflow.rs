@@ -0,0 +1,100 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The workflows a request can select, by the name the payload uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Workflow {
+    Ical,
+    Daysmart,
+    /// Publish the team calendar to the tokenized feed location
+    Feed,
+    /// Move the calendar feed to a new token and announce the new subscription URL
+    FeedRotate,
+    /// Announce newly posted drop-in/pickup sessions at a facility or program
+    DropIn,
+    /// Post final scores of the previous day's games
+    Scores,
+    /// Create and update the DaySmart games directly in BenchApp
+    BenchappApi,
+    /// Publish the DaySmart calendar to a fixed S3 location
+    IcsPublish,
+    /// Post the league standings table (give it a weekly schedule)
+    Standings,
+    /// Self-test: render a reminder from the bundled sample and post it to `canary_hook_url`
+    Canary,
+    /// Move production posts to the webhook behind `webhook_rotation` after verifying it
+    WebhookRotate,
+    /// Delete expired records from the local-file state backend
+    StateCleanup,
+    /// Post the week's games, schedule changes since the last digest and how far the ICS feed runs
+    Digest,
+    /// Debugging: report everything derived about the DaySmart event `explain_game_id`; posts nothing
+    Explain,
+    /// Zip a calendar per team in the league plus a combined league calendar (league admins)
+    LeagueBundle,
+    /// Count ✅/❌ reactions on the latest game reminder and post the attendance
+    RsvpReactions,
+    /// Call for a spare goalie in the spares channel while none has confirmed for the next game
+    GoalieEscalation,
+    /// Create, move and delete Discord scheduled events so upcoming games show in the server's events
+    ScheduledEvents,
+}
+
+impl Workflow {
+    /// Name as written in the request payload.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Workflow::Ical => "ical",
+            Workflow::Daysmart => "daysmart",
+            Workflow::Feed => "feed",
+            Workflow::FeedRotate => "feed_rotate",
+            Workflow::DropIn => "drop_in",
+            Workflow::Scores => "scores",
+            Workflow::BenchappApi => "benchapp_api",
+            Workflow::IcsPublish => "ics_publish",
+            Workflow::Standings => "standings",
+            Workflow::Canary => "canary",
+            Workflow::WebhookRotate => "webhook_rotate",
+            Workflow::StateCleanup => "state_cleanup",
+            Workflow::Digest => "digest",
+            Workflow::Explain => "explain",
+            Workflow::LeagueBundle => "league_bundle",
+            Workflow::RsvpReactions => "rsvp_reactions",
+            Workflow::GoalieEscalation => "goalie_escalation",
+            Workflow::ScheduledEvents => "scheduled_events",
+        }
+    }
+
+    /// Whether the workflow changes things outside the publisher (S3, BenchApp, its own webhooks
+    /// or state), so a dry run can't preview it.
+    pub fn acts_outside_publisher(&self) -> bool {
+        matches!(
+            self,
+            Workflow::Feed
+                | Workflow::FeedRotate
+                | Workflow::IcsPublish
+                | Workflow::BenchappApi
+                | Workflow::Canary
+                | Workflow::GoalieEscalation
+                | Workflow::ScheduledEvents
+                | Workflow::WebhookRotate
+                | Workflow::StateCleanup
+        )
+    }
+
+    /// Whether the workflow posts where the team (or the spares channel) sees it, so quiet hours
+    /// hold it back.
+    pub fn posts_to_team(&self) -> bool {
+        !matches!(
+            self,
+            Workflow::Feed
+                | Workflow::IcsPublish
+                | Workflow::BenchappApi
+                | Workflow::ScheduledEvents
+                | Workflow::Canary
+                | Workflow::StateCleanup
+                | Workflow::Explain
+        )
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::benchapp_api::{self, BenchappClient, BenchappGame};
+use crate::daysmart::DaySmart;
+use crate::destination::Publisher;
+use crate::handler::{self, Mode};
+use crate::opponents::BenchappNames;
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// Creates, updates and deletes the team's BenchApp games to match DaySmart.
+pub struct BenchappApi {
+    pub client: BenchappClient,
+}
+
+impl Workflow for BenchappApi {
+    type Input = (Arc<DaySmart>, BenchappNames);
+    type Output = Vec<BenchappGame>;
+
+    const KIND: workflow::Workflow = workflow::Workflow::BenchappApi;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        let Some(config) = &ctx.request.benchapp else {
+            info!("No benchapp config provided; skipping benchapp_api workflow");
+            return Err(Halt::new(Outcome::Skipped, "BenchApp: not configured (skipped)".to_string()));
+        };
+        match BenchappClient::from_config(config, matches!(ctx.request.mode, Mode::Test), &ctx.http_policy) {
+            Ok(Some(client)) => Ok(BenchappApi { client }),
+            Ok(None) => {
+                info!("No BenchApp test team configured; skipping benchapp_api workflow in test mode");
+                Err(Halt::new(Outcome::Skipped, "BenchApp: no test team (skipped)".to_string()))
+            }
+            Err(e) => Err(Halt::new(Outcome::Failed, format!("BenchApp sync failed: {}", e))),
+        }
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async move {
+            let day_smart = ctx.daysmart().await?;
+            Ok((day_smart, handler::benchapp_names(ctx.request.benchapp_names.as_ref()).await))
+        })
+    }
+
+    fn render(&self, ctx: &Context, (day_smart, names): Self::Input) -> Result<Self::Output, Halt> {
+        let games = day_smart.upcoming_games(i64::from(ctx.request.benchapp_months_ahead) * 31, ctx.now);
+        Ok(benchapp_api::games_from_daysmart_with(&day_smart, &games, &names))
+    }
+
+    // Without state games are only matched by date and opponent, and nothing is deleted
+    fn deliver<'a>(&'a self, ctx: &'a Context, _publisher: &'a Publisher, wanted: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            let team_id = self.client.team_id();
+            let mut links = match &ctx.state {
+                Some(store) => benchapp_api::links(store, team_id).await.map_err(|e| Halt::new(Outcome::Failed, format!("BenchApp sync failed: {}", e)))?,
+                None => Default::default(),
+            };
+            let today = ctx.now.with_timezone(&ctx.local_tz).date_naive();
+            let result = self.client.sync(&wanted, &mut links, today).await;
+            if let Some(store) = &ctx.state
+                && let Err(e) = benchapp_api::save_links(store, team_id, &links).await
+            {
+                error!(error = %e, "Failed to save BenchApp game links");
+            }
+            match result {
+                Ok(s) => Ok(format!("BenchApp: {} created, {} updated, {} deleted, {} unchanged", s.created, s.updated, s.deleted, s.unchanged)),
+                Err(e) => Err(Halt::new(Outcome::Failed, format!("BenchApp sync failed: {}", e))),
+            }
+        })
+    }
+}
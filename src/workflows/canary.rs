@@ -0,0 +1,61 @@
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::canary;
+use crate::destination::Publisher;
+use crate::discord::Discord;
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// A reminder rendered from the bundled sample document, posted to its own webhook.
+pub struct Canary {
+    pub hook_url: String,
+}
+
+/// The canary message and its CSV attachment.
+pub struct CanaryPost {
+    pub message: String,
+    pub csv: String,
+}
+
+impl Workflow for Canary {
+    type Input = ();
+    type Output = CanaryPost;
+
+    const KIND: workflow::Workflow = workflow::Workflow::Canary;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        match &ctx.request.canary_hook_url {
+            Some(hook_url) => Ok(Canary { hook_url: hook_url.value().to_string() }),
+            None => {
+                info!("No canary_hook_url provided; skipping canary workflow");
+                Err(Halt::new(Outcome::Skipped, "Canary: not configured (skipped)".to_string()))
+            }
+        }
+    }
+
+    fn fetch<'a>(&'a self, _ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn render(&self, ctx: &Context, _input: Self::Input) -> Result<Self::Output, Halt> {
+        let (message, csv) = canary::render(ctx.local_tz).map_err(|e| {
+            error!(error = %e, "Failed to render canary");
+            Halt::new(Outcome::Failed, format!("Canary render failed: {}", e))
+        })?;
+        Ok(CanaryPost { message, csv })
+    }
+
+    // Posts straight to the canary webhook, not through the team's destinations
+    fn deliver<'a>(&'a self, ctx: &'a Context, _publisher: &'a Publisher, post: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            match Discord::new(self.hook_url.clone()).with_ids(ctx.ids.clone()).post_with_attachment(&post.message, "games.csv", post.csv.as_bytes()).await {
+                Ok(_) => Ok("Canary posted".to_string()),
+                Err(e) => {
+                    error!(error = %e, "Failed to post canary");
+                    Err(Halt::failed(format!("Canary post failed: {}", e), e.into()))
+                }
+            }
+        })
+    }
+}
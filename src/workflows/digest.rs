@@ -0,0 +1,75 @@
+use chrono::NaiveDate;
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::changes;
+use crate::destination::Publisher;
+use crate::digest::{self, DigestChanges};
+use crate::email::DigestEntry;
+use crate::workflow;
+
+/// The week's games, schedule changes since the last digest and how far the ICS feed runs.
+pub struct Digest {
+    pub team_id: String,
+    pub months_ahead: u32,
+}
+
+/// Everything the digest reports.
+pub struct Week {
+    pub entries: Vec<DigestEntry>,
+    /// None without state, or when the previous snapshot couldn't be loaded.
+    pub changes: Option<DigestChanges>,
+    pub schedule_until: Option<NaiveDate>,
+    pub locker_room_placeholder: String,
+}
+
+/// The digest message and how many games it lists.
+pub struct DigestMessage {
+    pub message: String,
+    pub games: usize,
+}
+
+impl Workflow for Digest {
+    type Input = Week;
+    type Output = DigestMessage;
+
+    const KIND: workflow::Workflow = workflow::Workflow::Digest;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        Ok(Digest { team_id: ctx.request.team_id.clone(), months_ahead: ctx.request.benchapp_months_ahead })
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async move {
+            let day_smart = ctx.daysmart().await?;
+            let entries = digest::week_entries(&day_smart, ctx.now);
+            // Without state there is no earlier snapshot to compare with
+            let changes = match &ctx.state {
+                Some(store) => {
+                    let games = day_smart.upcoming_games(i64::from(self.months_ahead) * 31, ctx.now);
+                    let snapshot = changes::snapshot(&day_smart, &games);
+                    digest::changes_since_last(store, &self.team_id, snapshot, ctx.now, |id| day_smart.game_status(id))
+                        .await
+                        .inspect_err(|e| error!(error = %e, "Failed to load digest snapshot"))
+                        .ok()
+                }
+                None => None,
+            };
+            let schedule_until = ctx.sources.ical().await.and_then(|ical| ical.last_event_start(ctx.feed_cutoff())).map(|dt| dt.date());
+            Ok(Week { entries, changes, schedule_until, locker_room_placeholder: day_smart.placeholders().locker_room.clone() })
+        })
+    }
+
+    fn render(&self, ctx: &Context, week: Self::Input) -> Result<Self::Output, Halt> {
+        let message = digest::format_digest(&week.entries, week.changes.as_ref(), week.schedule_until, ctx.local_tz, &week.locker_room_placeholder);
+        info!(games = week.entries.len(), "Prepared weekly digest");
+        Ok(DigestMessage { message, games: week.entries.len() })
+    }
+
+    fn deliver<'a>(&'a self, _ctx: &'a Context, publisher: &'a Publisher, digest: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            super::post(publisher, &digest.message, "Digest").await?;
+            Ok(format!("Digest posted ({} games)", digest.games))
+        })
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::BTreeSet;
+
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::destination::Publisher;
+use crate::dropin::{self, DropInConfig, DropInSlot};
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// Announces drop-in sessions matching the configured filters that weren't announced before.
+pub struct DropIn {
+    pub config: DropInConfig,
+    pub company: String,
+}
+
+/// Sessions currently matching, and those announced on earlier runs.
+pub struct Sessions {
+    pub matching: Vec<DropInSlot>,
+    pub seen: BTreeSet<String>,
+}
+
+/// The announcement, and every session to remember as seen once it is posted.
+pub struct Announcement {
+    pub message: String,
+    pub count: usize,
+    pub now_seen: BTreeSet<String>,
+}
+
+impl DropIn {
+    fn seen_key(&self) -> String {
+        self.config.seen_key(&self.company)
+    }
+}
+
+impl Workflow for DropIn {
+    type Input = Sessions;
+    type Output = Announcement;
+
+    const KIND: workflow::Workflow = workflow::Workflow::DropIn;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        match &ctx.request.dropin {
+            Some(config) => Ok(DropIn { config: config.clone(), company: ctx.request.company.clone() }),
+            None => {
+                info!("No dropin config provided; skipping drop_in workflow");
+                Err(Halt::new(Outcome::Skipped, "Drop-in: not configured (skipped)".to_string()))
+            }
+        }
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async move {
+            let slots = dropin::fetch_slots(&self.config, &self.company, ctx.now, &ctx.http_policy).await.map_err(|e| {
+                error!(error = %e, "Failed to fetch drop-in sessions");
+                Halt::new(Outcome::Failed, format!("Drop-in fetch failed: {}", e))
+            })?;
+            let matching = slots.into_iter().filter(|s| dropin::matches(s, &self.config, ctx.now, ctx.local_tz)).collect();
+            // Without state every matching session is announced on each run
+            let seen = match &ctx.state {
+                Some(store) => store
+                    .get(&self.seen_key())
+                    .await
+                    .map_err(|e| Halt::new(Outcome::Failed, format!("Drop-in state load failed: {}", e)))?
+                    .unwrap_or_default(),
+                None => BTreeSet::new(),
+            };
+            Ok(Sessions { matching, seen })
+        })
+    }
+
+    fn render(&self, ctx: &Context, sessions: Self::Input) -> Result<Self::Output, Halt> {
+        let new_slots = dropin::unseen(&sessions.matching, &sessions.seen);
+        if new_slots.is_empty() {
+            return Err(Halt::new(Outcome::Skipped, "Drop-in: no new sessions (skipped)".to_string()));
+        }
+        Ok(Announcement {
+            message: dropin::format_announcement(&new_slots, ctx.local_tz),
+            count: new_slots.len(),
+            // Sessions that fall out of the window are forgotten
+            now_seen: sessions.matching.iter().map(|s| s.id.clone()).collect(),
+        })
+    }
+
+    fn deliver<'a>(&'a self, ctx: &'a Context, publisher: &'a Publisher, announcement: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            publisher
+                .post(&announcement.message)
+                .await
+                .map_err(|e| Halt::failed(format!("Drop-in post failed: {}", e), e))?;
+            if let Some(store) = &ctx.state
+                && let Err(e) = store.put(&self.seen_key(), &announcement.now_seen).await
+            {
+                error!(error = %e, "Failed to save drop-in state");
+            }
+            Ok(format!("Drop-in: {} new sessions announced", announcement.count))
+        })
+    }
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use tracing::info;
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::daysmart::DaySmart;
+use crate::destination::Publisher;
+use crate::explain::GameExplanation;
+use crate::summary::{Outcome, WorkflowReport};
+use crate::workflow;
+
+/// How DaySmart's document yields one game's reminder, returned in the report; nothing is posted.
+pub struct Explain {
+    pub game_id: i64,
+}
+
+impl Workflow for Explain {
+    type Input = Arc<DaySmart>;
+    type Output = GameExplanation;
+
+    const KIND: workflow::Workflow = workflow::Workflow::Explain;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        match ctx.request.explain_game_id {
+            Some(game_id) => Ok(Explain { game_id }),
+            None => {
+                info!("No explain_game_id; skipping explain workflow");
+                Err(Halt::new(Outcome::Skipped, "Explain: no explain_game_id (skipped)".to_string()))
+            }
+        }
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(ctx.daysmart())
+    }
+
+    fn render(&self, ctx: &Context, day_smart: Self::Input) -> Result<Self::Output, Halt> {
+        let explanation = day_smart.explain(self.game_id, ctx.request.message_template.as_deref());
+        info!(game_id = self.game_id, status = %explanation.status, "Explained DaySmart game");
+        Ok(explanation)
+    }
+
+    fn deliver<'a>(&'a self, _ctx: &'a Context, _publisher: &'a Publisher, explanation: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move { Ok(explanation.summary()) })
+    }
+
+    // The explanation itself goes in the report; a game that isn't ours counts as skipped
+    fn finish<'a>(&'a self, _ctx: &'a Context, _publisher: &'a Publisher, explanation: Self::Output) -> StepFuture<'a, WorkflowReport> {
+        Box::pin(async move {
+            let outcome = if explanation.game.is_some() { Outcome::Done } else { Outcome::Skipped };
+            Ok(WorkflowReport::new(Self::KIND, outcome, explanation.summary()).with_explanation(explanation))
+        })
+    }
+}
@@ -0,0 +1,102 @@
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::destination::Publisher;
+use crate::feed::{FeedConfig, FeedHost};
+use crate::ical;
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// Republishes the team calendar at its tokenized feed location.
+pub struct FeedPublish {
+    pub config: FeedConfig,
+}
+
+/// Moves the calendar feed to a new token and announces the new subscription URL.
+pub struct FeedRotate {
+    pub config: FeedConfig,
+}
+
+fn feed_config(ctx: &Context) -> Result<FeedConfig, Halt> {
+    ctx.request.feed.clone().ok_or_else(|| {
+        info!("Feed workflow needs feed config; skipping");
+        Halt::new(Outcome::Skipped, "Feed: not configured (skipped)".to_string())
+    })
+}
+
+/// The calendar to host: the iCal feed republished when there is one, otherwise built from DaySmart.
+async fn calendar(ctx: &Context) -> Result<String, Halt> {
+    if ctx.request.ical_url.is_none() {
+        let day_smart = ctx.daysmart().await?;
+        return Ok(ical::writer::write_calendar(&day_smart, &day_smart.all_games()));
+    }
+    let generator = ctx.sources.ical().await.ok_or_else(|| Halt::new(Outcome::Skipped, "Feed: no iCal feed (skipped)".to_string()))?;
+    let calendar = generator.calendar.as_ref().ok_or_else(|| Halt::new(Outcome::Skipped, "Feed: no ICS available".to_string()))?;
+    Ok(calendar.to_string())
+}
+
+impl Workflow for FeedPublish {
+    type Input = String;
+    type Output = String;
+
+    const KIND: workflow::Workflow = workflow::Workflow::Feed;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        Ok(FeedPublish { config: feed_config(ctx)? })
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(calendar(ctx))
+    }
+
+    fn render(&self, _ctx: &Context, ics: Self::Input) -> Result<Self::Output, Halt> {
+        Ok(ics)
+    }
+
+    fn deliver<'a>(&'a self, ctx: &'a Context, _publisher: &'a Publisher, ics: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            let host = FeedHost::new(self.config.clone(), &ctx.request.team_id).await;
+            match host.publish(&ics, ctx.now).await {
+                Ok(_) => Ok("Calendar feed published".to_string()),
+                Err(e) => {
+                    error!(error = %e, "Failed to publish calendar feed");
+                    Err(Halt::new(Outcome::Failed, format!("Feed publish failed: {}", e)))
+                }
+            }
+        })
+    }
+}
+
+impl Workflow for FeedRotate {
+    type Input = String;
+    type Output = String;
+
+    const KIND: workflow::Workflow = workflow::Workflow::FeedRotate;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        Ok(FeedRotate { config: feed_config(ctx)? })
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(calendar(ctx))
+    }
+
+    fn render(&self, _ctx: &Context, ics: Self::Input) -> Result<Self::Output, Halt> {
+        Ok(ics)
+    }
+
+    fn deliver<'a>(&'a self, ctx: &'a Context, publisher: &'a Publisher, ics: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            let host = FeedHost::new(self.config.clone(), &ctx.request.team_id).await;
+            let url = host.rotate(&ics, ctx.now).await.map_err(|e| {
+                error!(error = %e, "Failed to rotate calendar feed");
+                Halt::new(Outcome::Failed, format!("Feed rotation failed: {}", e))
+            })?;
+            let message = format!(":calendar: The team calendar feed has moved. Re-subscribe with: {}", url);
+            match publisher.post(&message).await {
+                Ok(()) => Ok("Calendar feed rotated and announced".to_string()),
+                Err(e) => Err(Halt::failed(format!("Calendar feed rotated; announcement failed: {}", e), e)),
+            }
+        })
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::destination::Publisher;
+use crate::discord::Discord;
+use crate::discord_bot::DiscordBot;
+use crate::goalie_escalation::{self, Calls, GoalieEscalationConfig};
+use crate::rsvp_reactions;
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// Calls for a spare goalie in the spares channel while nobody has confirmed in goal for the
+/// next game, more urgently with each run.
+pub struct GoalieEscalation {
+    pub config: GoalieEscalationConfig,
+}
+
+/// The next game still short of a goalie.
+pub struct Shortage {
+    pub game_id: i64,
+    pub game_start: DateTime<Utc>,
+    pub day: String,
+    pub game: String,
+    pub calls_made: Calls,
+}
+
+/// The call to post, and the calls for the game once it is posted.
+pub struct Call {
+    pub game_id: i64,
+    pub game_start: DateTime<Utc>,
+    pub content: String,
+    pub calls: Calls,
+}
+
+impl Workflow for GoalieEscalation {
+    type Input = Shortage;
+    type Output = Call;
+
+    const KIND: workflow::Workflow = workflow::Workflow::GoalieEscalation;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        match &ctx.request.goalie_escalation {
+            Some(config) if config.goalie_confirmed => Err(Halt::new(Outcome::Skipped, "Goalie escalation: goalie confirmed (skipped)".to_string())),
+            Some(config) => Ok(GoalieEscalation { config: config.clone() }),
+            None => {
+                info!("No goalie_escalation config provided; skipping goalie_escalation workflow");
+                Err(Halt::new(Outcome::Skipped, "Goalie escalation: not configured (skipped)".to_string()))
+            }
+        }
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async move {
+            let day_smart = ctx.daysmart().await?;
+            let window = chrono::Duration::hours(self.config.hours_before);
+            let Some(game) = day_smart.next_game((self.config.hours_before + 23) / 24, ctx.now).filter(|game| game.dt - ctx.now <= window) else {
+                return Err(Halt::new(
+                    Outcome::Skipped,
+                    format!("Goalie escalation: no game in the next {} hours (skipped)", self.config.hours_before),
+                ));
+            };
+            let team_id = &ctx.request.team_id;
+            let failed = |e: String| {
+                error!(error = %e, "Failed to check goalie confirmation");
+                Halt::new(Outcome::Failed, format!("Goalie escalation fetch failed: {}", e))
+            };
+            let confirmed = || Err(Halt::new(Outcome::Skipped, "Goalie escalation: goalie confirmed (skipped)".to_string()));
+            // Without state there are no answers to read and no calls remembered, only the config flag
+            let calls_made = match &ctx.state {
+                Some(store) => {
+                    if goalie_escalation::goalie_answered(store, team_id, game.id).await.map_err(failed)? {
+                        return confirmed();
+                    }
+                    if let Some(token) = &ctx.request.bot_token {
+                        let bot = DiscordBot::new(token.value().to_string());
+                        let count = rsvp_reactions::reaction_count(&bot, store, team_id, game.id).await.map_err(failed)?;
+                        if count.is_some_and(|count| count.goalies > 0) {
+                            return confirmed();
+                        }
+                    }
+                    goalie_escalation::calls_made(store, team_id, game.id).await.map_err(failed)?
+                }
+                None => Calls::default(),
+            };
+            if let Some(reason) = calls_made.not_due(&self.config, ctx.now) {
+                info!(reason = %reason, "No goalie call due");
+                return Err(Halt::new(Outcome::Skipped, format!("Goalie escalation: {} (skipped)", reason)));
+            }
+            let local = game.dt.with_timezone(&day_smart.timezone());
+            Ok(Shortage {
+                game_id: game.id,
+                game_start: game.dt,
+                day: local.format("%A").to_string(),
+                game: format!("{}, {}", day_smart.thread_name(&game), local.format("%-I:%M %p")),
+                calls_made,
+            })
+        })
+    }
+
+    fn render(&self, ctx: &Context, shortage: Self::Input) -> Result<Self::Output, Halt> {
+        Ok(Call {
+            game_id: shortage.game_id,
+            game_start: shortage.game_start,
+            content: goalie_escalation::message(shortage.calls_made.count, &shortage.day, &shortage.game),
+            calls: shortage.calls_made.and_one_more(ctx.now),
+        })
+    }
+
+    // Posts straight to the spares channel, not through the team's destinations
+    fn deliver<'a>(&'a self, ctx: &'a Context, _publisher: &'a Publisher, call: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            let spares = Discord::new(self.config.spares_hook_url.value().to_string()).with_ids(ctx.ids.clone());
+            if let Err(e) = spares.post_with_options(&call.content, &goalie_escalation::post_options()).await {
+                error!(error = %e, "Failed to post goalie call");
+                return Err(Halt::failed(format!("Goalie escalation post failed: {}", e), e.into()));
+            }
+            if let Some(store) = &ctx.state
+                && let Err(e) = goalie_escalation::record_calls(store, &ctx.request.team_id, call.game_id, call.calls, call.game_start).await
+            {
+                error!(error = %e, "Failed to save goalie escalation state");
+            }
+            Ok(format!("Goalie escalation: call {} of {} posted", call.calls.count, self.config.max_calls))
+        })
+    }
+}
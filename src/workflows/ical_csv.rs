@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::destination::Publisher;
+use crate::fees::{self, FeeSplitConfig};
+use crate::ical::Ical;
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// The BenchApp import CSV built from the iCal feed, posted as an attachment.
+pub struct IcalCsv {
+    pub fee_split: Option<FeeSplitConfig>,
+}
+
+/// The feed, and on a game day how many skaters confirmed for it.
+pub struct Feed {
+    pub ical: Arc<Ical>,
+    pub confirmed: Option<usize>,
+}
+
+/// The message and the CSV it carries.
+pub struct CsvPost {
+    pub content: String,
+    pub csv: String,
+}
+
+impl Workflow for IcalCsv {
+    type Input = Feed;
+    type Output = CsvPost;
+
+    const KIND: workflow::Workflow = workflow::Workflow::Ical;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        if ctx.request.ical_url.is_none() {
+            info!("No ical_url provided; skipping ical workflow");
+            return Err(Halt::new(Outcome::Skipped, "BenchApp: no ical_url (skipped)".to_string()));
+        }
+        Ok(IcalCsv { fee_split: ctx.request.fee_split.clone() })
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async move {
+            let ical = ctx.sources.ical().await.ok_or_else(|| Halt::new(Outcome::Skipped, "BenchApp: no iCal feed (skipped)".to_string()))?;
+            // On game day, each skater's share of the ice cost goes by the answers given for that day
+            let today = ctx.now.with_timezone(&ctx.local_tz).date_naive();
+            let game_day = ical.next_event_start(ctx.feed_cutoff()).map(|start| start.date()).filter(|day| *day == today);
+            let confirmed = match (&self.fee_split, &ctx.state, game_day) {
+                (Some(_), Some(store), Some(day)) => fees::confirmed(store, &ctx.request.team_id, day)
+                    .await
+                    .inspect_err(|e| error!(error = %e, "Failed to count confirmed skaters"))
+                    .ok(),
+                _ => None,
+            };
+            Ok(Feed { ical, confirmed })
+        })
+    }
+
+    fn render(&self, ctx: &Context, Feed { ical: generator, confirmed }: Self::Input) -> Result<Self::Output, Halt> {
+        let cutoff = ctx.feed_cutoff();
+        let csv = generator.to_bench_app_csv(cutoff).map_err(|e| {
+            error!(error = %e, "Failed to generate BenchApp CSV");
+            Halt::failed(format!("BenchApp CSV generation failed: {}", e), e)
+        })?;
+        // If the CSV contains only the header (no data rows), skip posting to Discord
+        if !csv.lines().skip(1).any(|l| !l.trim().is_empty()) {
+            info!("No upcoming BenchApp events after cutoff; skipping Discord post");
+            return Err(Halt::new(Outcome::Skipped, "BenchApp: no upcoming games (skipped)".to_string()));
+        }
+        let mut content = generator.discord_message(cutoff).unwrap_or_else(|_| "BenchApp import schedule attached.".to_string());
+        // On game day, add each skater's share of the ice cost
+        if let (Some(config), Some(confirmed)) = (&self.fee_split, confirmed)
+            && let Some(line) = fees::fee_line(config.ice_cost, confirmed)
+        {
+            content.push_str("\n:moneybag: ");
+            content.push_str(&line);
+        }
+        Ok(CsvPost { content, csv })
+    }
+
+    fn deliver<'a>(&'a self, _ctx: &'a Context, publisher: &'a Publisher, post: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            super::post_file(publisher, &post.content, "benchapp_schedule.csv", post.csv.as_bytes(), "BenchApp").await?;
+            Ok("BenchApp CSV posted".to_string())
+        })
+    }
+}
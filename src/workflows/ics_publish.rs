@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::daysmart::DaySmart;
+use crate::destination::Publisher;
+use crate::feed::{self, IcsPublishConfig};
+use crate::ical;
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// The team calendar as a static ICS file on S3.
+pub struct IcsPublish {
+    pub config: IcsPublishConfig,
+}
+
+impl Workflow for IcsPublish {
+    type Input = Arc<DaySmart>;
+    type Output = String;
+
+    const KIND: workflow::Workflow = workflow::Workflow::IcsPublish;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        match &ctx.request.ics_publish {
+            Some(config) => Ok(IcsPublish { config: config.clone() }),
+            None => {
+                info!("No ics_publish config provided; skipping ics_publish workflow");
+                Err(Halt::new(Outcome::Skipped, "ICS publish: not configured (skipped)".to_string()))
+            }
+        }
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(ctx.daysmart())
+    }
+
+    fn render(&self, _ctx: &Context, day_smart: Self::Input) -> Result<Self::Output, Halt> {
+        Ok(ical::writer::write_calendar(&day_smart, &day_smart.all_games()))
+    }
+
+    fn deliver<'a>(&'a self, _ctx: &'a Context, _publisher: &'a Publisher, ics: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            match feed::publish_ics(&self.config, &ics).await {
+                Ok(url) => Ok(format!("Calendar published: {}", url)),
+                Err(e) => {
+                    error!(error = %e, "Failed to publish team calendar");
+                    Err(Halt::new(Outcome::Failed, format!("ICS publish failed: {}", e)))
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,96 @@
+use tracing::{error, info};
+
+use super::{post_file, Context, Halt, StepFuture, Workflow};
+use crate::daysmart::DaySmart;
+use crate::destination::Publisher;
+use crate::league_bundle::{self, LeagueBundleConfig, LEAGUE_CALENDAR};
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// Every team's calendar in the league, plus a combined one, zipped for a league admin.
+pub struct LeagueBundle {
+    pub config: LeagueBundleConfig,
+}
+
+pub struct Bundle {
+    pub league: String,
+    pub teams: usize,
+    pub zip: Vec<u8>,
+}
+
+impl Workflow for LeagueBundle {
+    type Input = DaySmart;
+    type Output = Bundle;
+
+    const KIND: workflow::Workflow = workflow::Workflow::LeagueBundle;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        match &ctx.request.league_bundle {
+            Some(config) if config.post || config.bucket.is_some() => Ok(LeagueBundle { config: config.clone() }),
+            Some(_) => Err(Halt::new(Outcome::Skipped, "League bundle: neither post nor bucket set (skipped)".to_string())),
+            None => {
+                info!("No league_bundle config provided; skipping league_bundle workflow");
+                Err(Halt::new(Outcome::Skipped, "League bundle: not configured (skipped)".to_string()))
+            }
+        }
+    }
+
+    // One league-wide fetch; the team document is only read when it has to name the league
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async move {
+            let league_id = match self.config.league_id {
+                Some(id) => id,
+                None => match ctx.daysmart().await?.league() {
+                    Some(league) => league.id,
+                    None => return Err(Halt::new(Outcome::Skipped, "League bundle: team document names no league (skipped)".to_string())),
+                },
+            };
+            let league = DaySmart::for_league(league_id, &ctx.request.company, &ctx.http_policy).await.map_err(|e| {
+                error!(error = %e, league_id, "League fetch failed");
+                Halt::failed(format!("League fetch failed: {}", e), e)
+            })?;
+            let league = league.with_placeholders(ctx.request.placeholders.clone().unwrap_or_default());
+            Ok(match ctx.timezone {
+                Some(tz) => league.with_timezone(tz),
+                None => league,
+            })
+        })
+    }
+
+    fn render(&self, _ctx: &Context, league: Self::Input) -> Result<Self::Output, Halt> {
+        let files = league_bundle::calendars(&league);
+        if files.len() < 2 {
+            return Err(Halt::new(Outcome::Skipped, "League bundle: no games in the league document (skipped)".to_string()));
+        }
+        let zip = league_bundle::zip_files(&files).map_err(|e| {
+            error!(error = %e, "Failed to zip league calendars");
+            Halt::new(Outcome::Failed, e)
+        })?;
+        let name = league.league().map(|l| l.name.clone()).filter(|n| !n.is_empty()).unwrap_or_else(|| "League".to_string());
+        Ok(Bundle { league: name, teams: files.len() - 1, zip })
+    }
+
+    fn deliver<'a>(&'a self, _ctx: &'a Context, publisher: &'a Publisher, bundle: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            let mut done = vec![format!("{} team calendars", bundle.teams)];
+            // A dry run records the post but leaves S3 alone
+            if let Some(bucket) = self.config.bucket.as_deref().filter(|_| !publisher.dry_run) {
+                let key = self.config.key.as_deref().unwrap_or(&self.config.filename);
+                let location = league_bundle::upload(bucket, key, bundle.zip.clone()).await.map_err(|e| {
+                    error!(error = %e, "Failed to upload league calendars");
+                    Halt::new(Outcome::Failed, format!("League bundle upload failed: {}", e))
+                })?;
+                done.push(format!("uploaded to {}", location));
+            }
+            if self.config.post {
+                let message = format!(
+                    ":calendar: {} calendars: one per team ({} teams) plus {} with every game.",
+                    bundle.league, bundle.teams, LEAGUE_CALENDAR
+                );
+                post_file(publisher, &message, &self.config.filename, &bundle.zip, "League bundle").await?;
+                done.push("posted".to_string());
+            }
+            Ok(format!("League bundle: {}", done.join(", ")))
+        })
+    }
+}
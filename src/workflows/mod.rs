@@ -0,0 +1,285 @@
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::future::{join_all, BoxFuture, FutureExt};
+use tracing::{error, info, warn};
+
+use crate::clock::IdGenerator;
+use crate::daysmart::DaySmart;
+use crate::destination::Publisher;
+use crate::error::Error;
+use crate::handler::Request;
+use crate::http::HttpPolicy;
+use crate::sources::Sources;
+use crate::state::StateStore;
+use crate::summary::{Outcome, WorkflowReport};
+use crate::workflow;
+
+pub mod benchapp_api;
+pub mod canary;
+pub mod digest;
+pub mod drop_in;
+pub mod explain;
+pub mod feed;
+pub mod goalie_escalation;
+pub mod ical_csv;
+pub mod ics_publish;
+pub mod league_bundle;
+pub mod reminder;
+pub mod rsvp_reactions;
+pub mod scheduled_events;
+pub mod scores;
+pub mod standings;
+pub mod state_cleanup;
+pub mod webhook_rotate;
+
+pub use benchapp_api::BenchappApi;
+pub use canary::Canary;
+pub use digest::Digest;
+pub use drop_in::DropIn;
+pub use explain::Explain;
+pub use feed::{FeedPublish, FeedRotate};
+pub use goalie_escalation::GoalieEscalation;
+pub use ical_csv::IcalCsv;
+pub use ics_publish::IcsPublish;
+pub use league_bundle::LeagueBundle;
+pub use reminder::Reminder;
+pub use rsvp_reactions::RsvpReactions;
+pub use scheduled_events::ScheduledEvents;
+pub use scores::Scores;
+pub use standings::Standings;
+pub use state_cleanup::StateCleanup;
+pub use webhook_rotate::WebhookRotate;
+
+/// Why a workflow stopped early: the outcome and detail to report, e.g.
+/// `Halt::new(Outcome::Skipped, "Scores: no final scores from yesterday (skipped)")`, and for a
+/// failure the typed error behind it, when there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Halt {
+    pub outcome: Outcome,
+    pub detail: String,
+    pub error: Option<Error>,
+}
+
+impl Halt {
+    pub fn new(outcome: Outcome, detail: impl Into<String>) -> Self {
+        Halt { outcome, detail: detail.into(), error: None }
+    }
+
+    /// A failure caused by `error`, reported as `detail`.
+    pub fn failed(detail: impl Into<String>, error: Error) -> Self {
+        Halt { outcome: Outcome::Failed, detail: detail.into(), error: Some(error) }
+    }
+
+    /// The report for `kind` stopping here.
+    pub fn report(self, kind: workflow::Workflow) -> WorkflowReport {
+        let report = WorkflowReport::new(kind, self.outcome, self.detail);
+        match self.error {
+            Some(error) => report.with_error(error.info()),
+            None => report,
+        }
+    }
+}
+
+pub type StepFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Halt>> + Send + 'a>>;
+
+/// What every workflow in one invocation shares.
+pub struct Context {
+    pub request: Arc<Request>,
+    pub sources: Sources,
+    pub state: Option<StateStore>,
+    /// The invocation's single clock reading.
+    pub now: DateTime<Utc>,
+    /// The request timezone, if one was given.
+    pub timezone: Option<chrono_tz::Tz>,
+    /// The request timezone, or the default one.
+    pub local_tz: chrono_tz::Tz,
+    pub http_policy: HttpPolicy,
+    /// Id generator for webhook clients the workflow creates itself.
+    pub ids: Arc<dyn IdGenerator>,
+    /// The Discord webhook posts go to: the test webhook in test mode, otherwise the production
+    /// one (as rotated by webhook_rotate).
+    pub hook_url: String,
+    /// When unfinished workflows are reported as timed out (`deadline_ms`).
+    pub deadline: Option<tokio::time::Instant>,
+}
+
+impl Context {
+    /// Local "now" for comparing with feed times: local when a timezone is configured, otherwise
+    /// used as-is.
+    pub fn feed_cutoff(&self) -> chrono::NaiveDateTime {
+        match self.timezone {
+            Some(tz) => self.now.with_timezone(&tz).naive_local(),
+            None => self.now.naive_utc(),
+        }
+    }
+
+    /// The DaySmart document, or the result to report when it is unavailable.
+    pub async fn daysmart(&self) -> Result<Arc<DaySmart>, Halt> {
+        daysmart_source(&self.sources).await
+    }
+}
+
+/// The DaySmart document for a workflow, or the result to report when it is unavailable.
+pub async fn daysmart_source(sources: &Sources) -> Result<Arc<DaySmart>, Halt> {
+    match sources.daysmart().await {
+        Some(Ok(ds)) => Ok(ds),
+        Some(Err(e)) => {
+            let msg = format!("DaySmart init error: {}", e);
+            error!(error = %msg, "DaySmart init failed");
+            Err(Halt::failed(msg, e))
+        }
+        None => Err(Halt::new(Outcome::Skipped, "DaySmart: not fetched (skipped)".to_string())),
+    }
+}
+
+/// One workflow, as four steps the engine runs in order. Each step can stop the run with a
+/// [`Halt`]; `render` is synchronous and free of I/O so it can be tested on canned input.
+pub trait Workflow: Sized + Send + Sync + 'static {
+    /// What `fetch` gathers for `render`.
+    type Input: Send;
+    /// What `render` produces for `deliver`.
+    type Output: Send;
+
+    const KIND: workflow::Workflow;
+
+    /// Pick the workflow's settings out of the request, before anything is fetched.
+    fn prepare(ctx: &Context) -> Result<Self, Halt>;
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input>;
+
+    fn render(&self, ctx: &Context, input: Self::Input) -> Result<Self::Output, Halt>;
+
+    /// Send or store the output; the detail reported for a successful run.
+    fn deliver<'a>(&'a self, ctx: &'a Context, publisher: &'a Publisher, output: Self::Output) -> StepFuture<'a, String>;
+
+    /// Deliver and report the run; override to put more than the detail in the report.
+    fn finish<'a>(&'a self, ctx: &'a Context, publisher: &'a Publisher, output: Self::Output) -> StepFuture<'a, WorkflowReport> {
+        Box::pin(async move {
+            let detail = self.deliver(ctx, publisher, output).await?;
+            Ok(WorkflowReport::new(Self::KIND, Outcome::Done, detail))
+        })
+    }
+}
+
+/// Prepare `W` and, unless it halts there, return the rest of its run as a future.
+pub fn launch<W: Workflow>(ctx: Arc<Context>, publisher: Publisher) -> Result<BoxFuture<'static, WorkflowReport>, Halt> {
+    let workflow = W::prepare(&ctx).inspect_err(|halt| info!(workflow = ?W::KIND, detail = %halt.detail, "Workflow not started"))?;
+    Ok(async move {
+        let steps = async {
+            let input = workflow.fetch(&ctx).await?;
+            let output = workflow.render(&ctx, input)?;
+            workflow.finish(&ctx, &publisher, output).await
+        };
+        steps.await.unwrap_or_else(|halt| halt.report(W::KIND))
+    }
+    .boxed())
+}
+
+/// Prepare `kind` and, unless it halts there, return the rest of its run as a future.
+pub fn start(kind: workflow::Workflow, ctx: &Arc<Context>, publisher: Publisher) -> Result<BoxFuture<'static, WorkflowReport>, Halt> {
+    let ctx = ctx.clone();
+    match kind {
+        workflow::Workflow::Daysmart => launch::<Reminder>(ctx, publisher),
+        workflow::Workflow::Ical => launch::<IcalCsv>(ctx, publisher),
+        workflow::Workflow::Feed => launch::<FeedPublish>(ctx, publisher),
+        workflow::Workflow::FeedRotate => launch::<FeedRotate>(ctx, publisher),
+        workflow::Workflow::BenchappApi => launch::<BenchappApi>(ctx, publisher),
+        workflow::Workflow::WebhookRotate => launch::<WebhookRotate>(ctx, publisher),
+        workflow::Workflow::Explain => launch::<Explain>(ctx, publisher),
+        workflow::Workflow::Scores => launch::<Scores>(ctx, publisher),
+        workflow::Workflow::Standings => launch::<Standings>(ctx, publisher),
+        workflow::Workflow::Digest => launch::<Digest>(ctx, publisher),
+        workflow::Workflow::StateCleanup => launch::<StateCleanup>(ctx, publisher),
+        workflow::Workflow::Canary => launch::<Canary>(ctx, publisher),
+        workflow::Workflow::IcsPublish => launch::<IcsPublish>(ctx, publisher),
+        workflow::Workflow::DropIn => launch::<DropIn>(ctx, publisher),
+        workflow::Workflow::LeagueBundle => launch::<LeagueBundle>(ctx, publisher),
+        workflow::Workflow::RsvpReactions => launch::<RsvpReactions>(ctx, publisher),
+        workflow::Workflow::GoalieEscalation => launch::<GoalieEscalation>(ctx, publisher),
+        workflow::Workflow::ScheduledEvents => launch::<ScheduledEvents>(ctx, publisher),
+    }
+}
+
+/// Run every planned workflow concurrently on this task, each with its own publisher. Workflows
+/// that halt while preparing are reported first, then the others in order as they finish, reach
+/// their own `timeout_ms` or `ctx.deadline` passes; one that panics is reported as failed instead
+/// of failing the invocation.
+pub async fn run_all(ctx: &Arc<Context>, planned: Vec<(workflow::Workflow, Publisher)>) -> Vec<WorkflowReport> {
+    let started = tokio::time::Instant::now();
+    let mut reports = Vec::new();
+    let mut runs = Vec::new();
+    for (kind, publisher) in planned {
+        // The earlier of the workflow's own limit and the invocation's deadline
+        let deadline = ctx.request.workflow_timeout(kind).map(|timeout| started + timeout).into_iter().chain(ctx.deadline).min();
+        match start(kind, ctx, publisher) {
+            Ok(run) => runs.push(supervise(kind, run, deadline)),
+            Err(halt) => reports.push(halt.report(kind)),
+        }
+    }
+    reports.extend(join_all(runs).await);
+    reports
+}
+
+/// Turn a workflow run into its report even when it panics or outlives `deadline`.
+pub fn supervise(kind: workflow::Workflow, run: BoxFuture<'static, WorkflowReport>, deadline: Option<tokio::time::Instant>) -> BoxFuture<'static, WorkflowReport> {
+    async move {
+        let started = tokio::time::Instant::now();
+        let run = AssertUnwindSafe(run).catch_unwind();
+        let result = match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, run).await,
+            None => Ok(run.await),
+        };
+        let mut report = match result {
+            Ok(Ok(report)) => report,
+            Ok(Err(payload)) => {
+                let message = panic_message(payload.as_ref());
+                error!(workflow = ?kind, panic = %message, "Workflow panicked");
+                WorkflowReport::panicked(kind, message)
+            }
+            Err(_) => {
+                warn!(workflow = ?kind, "Workflow did not finish before the deadline");
+                WorkflowReport::new(kind, Outcome::Failed, format!("{:?}: timed out", kind))
+            }
+        };
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        report
+    }
+    .boxed()
+}
+
+/// The text a panic was raised with, e.g. by `panic!("...")`, `unwrap` or `expect`.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// A step result for an outcome reported as a tuple: the detail when done, otherwise a halt.
+pub(crate) fn settle((outcome, detail): (Outcome, String)) -> Result<String, Halt> {
+    match outcome {
+        Outcome::Done => Ok(detail),
+        _ => Err(Halt::new(outcome, detail)),
+    }
+}
+
+/// Post `message`, reporting a failure as "`what` post failed: ...".
+pub(crate) async fn post(publisher: &Publisher, message: &str, what: &str) -> Result<(), Halt> {
+    publisher.post(message).await.map_err(|e| {
+        error!(error = %e, what, "Failed to post message");
+        Halt::failed(format!("{} post failed: {}", what, e), e)
+    })
+}
+
+/// Post `message` with a file attached, reporting a failure like [`post`].
+pub(crate) async fn post_file(publisher: &Publisher, message: &str, filename: &str, bytes: &[u8], what: &str) -> Result<(), Halt> {
+    publisher.post_with_attachment(message, filename, bytes).await.map_err(|e| {
+        error!(error = %e, what, "Failed to post message");
+        Halt::failed(format!("{} post failed: {}", what, e), e)
+    })
+}
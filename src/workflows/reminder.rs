@@ -0,0 +1,316 @@
+use std::sync::Arc;
+
+use tracing::{error, info, warn};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::changes;
+use crate::daysmart::{self, DaySmart};
+use crate::dedup::{self, PendingAnnouncement};
+use crate::destination::Publisher;
+use crate::discord_bot::DiscordBot;
+use crate::dm;
+use crate::enrich::GameContext;
+use crate::feed::FeedHost;
+use crate::handler;
+use crate::links;
+use crate::model::game::GameInfo;
+use crate::no_games;
+use crate::opponents::BenchappNames;
+use crate::rsvp_reactions;
+use crate::streak;
+use crate::summary::Outcome;
+use crate::tournament;
+use crate::workflow;
+
+/// The game reminder from DaySmart, with its BenchApp CSV (or tournament calendar), change alerts,
+/// "still no games" notices and game-day DMs.
+pub struct Reminder;
+
+/// The DaySmart document, with the reminder that failed to post on an earlier run and the
+/// BenchApp spelling of team names.
+pub struct Schedule {
+    pub day_smart: Arc<DaySmart>,
+    pub pending: Option<PendingAnnouncement>,
+    pub names: BenchappNames,
+}
+
+/// The reminder before its add-on lines: one part per game (the tournament itinerary is a single
+/// part, under the next game), its attachment and the thread it opens.
+pub struct Draft {
+    pub day_smart: Arc<DaySmart>,
+    pub pending: Option<PendingAnnouncement>,
+    pub next_game: Option<GameInfo>,
+    /// Whether the next game is the pending announcement, posted late.
+    pub delayed: bool,
+    /// Whether the reminder is about the next game alone, not a digest or tournament itinerary.
+    pub single_game: bool,
+    pub parts: Vec<(GameInfo, String)>,
+    pub filename: &'static str,
+    pub attachment: String,
+    /// Thread a game's first reminder opens, with game_threads.
+    pub thread_name: Option<String>,
+}
+
+impl Workflow for Reminder {
+    type Input = Schedule;
+    type Output = Draft;
+
+    const KIND: workflow::Workflow = workflow::Workflow::Daysmart;
+
+    fn prepare(_ctx: &Context) -> Result<Self, Halt> {
+        Ok(Reminder)
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async move {
+            let day_smart = ctx.daysmart().await?;
+            let pending = match &ctx.state {
+                Some(store) => dedup::pending(store, &ctx.request.team_id).await.unwrap_or_else(|e| {
+                    error!(error = %e, "Failed to check for a pending announcement");
+                    None
+                }),
+                None => None,
+            };
+            let names = handler::benchapp_names(ctx.request.benchapp_names.as_ref()).await;
+            Ok(Schedule { day_smart, pending, names })
+        })
+    }
+
+    fn render(&self, ctx: &Context, Schedule { day_smart, pending, names }: Self::Input) -> Result<Self::Output, Halt> {
+        let request = &ctx.request;
+        let (now, local_tz, days_ahead) = (ctx.now, ctx.local_tz, request.days_ahead);
+        let tournament_config = request.tournament.clone().unwrap_or_default();
+        // A reminder that failed to post goes out now even if the game is past the usual window
+        let next_game = day_smart
+            .next_game(days_ahead, now)
+            .or_else(|| pending.as_ref().and_then(|p| day_smart.upcoming_game(p.game_id, now)));
+        let delayed = pending.as_ref().zip(next_game.as_ref()).is_some_and(|(p, game)| p.game_id == game.id);
+        // Several games within a few days get one itinerary and a combined calendar instead
+        let tournament = next_game.as_ref().and_then(|_| {
+            let games: Vec<GameInfo> = day_smart.upcoming_games(days_ahead + tournament_config.span_days, now).into_iter().filter(|g| !g.is_practice).collect();
+            tournament::detect(&games, &tournament_config, local_tz)
+        });
+        let parts = match (&tournament, &next_game) {
+            (Some(games), Some(game)) => vec![(game.clone(), day_smart.format_itinerary(games, &tournament_config))],
+            _ => {
+                let games = if request.announce_all { day_smart.upcoming_games(days_ahead, now) } else { next_game.iter().cloned().collect() };
+                games
+                    .into_iter()
+                    .map(|game| {
+                        let message = match &request.message_template {
+                            Some(template) => day_smart.format_game_message_with(template, &game).unwrap_or_else(|e| {
+                                // Better the default wording than no reminder
+                                error!(error = %e, "Custom reminder template failed; using the default");
+                                day_smart.format_game_message(&game)
+                            }),
+                            None => day_smart.format_game_message(&game),
+                        };
+                        (game, message)
+                    })
+                    .collect()
+            }
+        };
+        let (filename, attachment) = match &tournament {
+            Some(games) => ("tournament.ics", day_smart.to_ics(games)),
+            None => ("games.csv", day_smart.to_benchapp_csv_with(now, request.benchapp_months_ahead, &names)),
+        };
+        let single_game = next_game.is_some() && !request.announce_all && tournament.is_none();
+        let thread_name = next_game.as_ref().filter(|_| request.game_threads && single_game).map(|game| day_smart.thread_name(game));
+        Ok(Draft { day_smart, pending, next_game, delayed, single_game, parts, filename, attachment, thread_name })
+    }
+
+    // Change alerts, enrichers and dedup all read or write state around the post
+    fn deliver<'a>(&'a self, ctx: &'a Context, publisher: &'a Publisher, draft: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            // With state, alert on time/rink/locker room changes and cancellations of games seen on an earlier run
+            let change_summary = match &ctx.state {
+                Some(store) => {
+                    let day_smart = &draft.day_smart;
+                    // Practices are left out of change alerts
+                    let mut games = day_smart.upcoming_games(i64::from(ctx.request.benchapp_months_ahead) * 31, ctx.now);
+                    games.retain(|g| !g.is_practice);
+                    let snapshot = changes::snapshot(day_smart, &games);
+                    changes::notify_changes(
+                        publisher,
+                        store,
+                        &ctx.request.team_id,
+                        snapshot,
+                        ctx.now,
+                        |id| day_smart.game_status(id),
+                        ctx.local_tz,
+                        &day_smart.placeholders().locker_room,
+                    )
+                    .await
+                }
+                None => None,
+            };
+            let (outcome, detail) = remind(ctx, publisher, draft).await;
+            super::settle(match change_summary {
+                Some(changes) => (outcome, format!("{} ({})", detail, changes)),
+                None => (outcome, detail),
+            })
+        })
+    }
+}
+
+async fn remind(ctx: &Context, publisher: &Publisher, draft: Draft) -> (Outcome, String) {
+    let request = &ctx.request;
+    let (team_id, now, local_tz, days_ahead) = (&request.team_id, ctx.now, ctx.local_tz, request.days_ahead);
+    let state_store = &ctx.state;
+    let dry_run = request.dry_run;
+    let Draft { day_smart, pending, next_game, delayed, single_game, parts, filename, attachment, thread_name } = draft;
+    // Game-day DMs and RSVP reactions need a bot token, and state to know subscribers and the posted message
+    let dm_context = match (&request.bot_token, state_store) {
+        (Some(token), Some(store)) if !dry_run => Some((DiscordBot::new(token.value().to_string()), store)),
+        _ => None,
+    };
+    let no_games_context = request.no_games_notice.as_ref().zip(state_store.as_ref());
+    let pipeline = handler::enrichment_pipeline(request, state_store);
+
+    if let (Some(store), Some(stale)) = (state_store, &pending)
+        && day_smart.upcoming_game(stale.game_id, now).is_none()
+    {
+        // Started, cancelled or dropped since; nothing left to announce
+        info!(game_id = stale.game_id, "Dropping pending announcement for a game no longer upcoming");
+        if let Err(e) = dedup::clear_pending(store, team_id).await {
+            error!(error = %e, "Failed to clear pending announcement");
+        }
+    }
+    let streak = streak::current_streak(&day_smart.results());
+    // Each game gets its own add-on lines, so e.g. every game in a digest shows its scorekeeper
+    let mut messages = Vec::with_capacity(parts.len());
+    for (game, mut message) in parts {
+        let context = GameContext { game, team_id: team_id.clone(), now, timezone: local_tz, streak };
+        pipeline.apply(&mut message, &context).await;
+        messages.push(message);
+    }
+    let Some(message) = daysmart::games_digest(messages) else {
+        info!(days_ahead, now = %now, "No upcoming games; skipping game reminder");
+        return match no_games_context {
+            Some((config, store)) => {
+                let today = now.with_timezone(&local_tz).date_naive();
+                no_games::notify(publisher, store, team_id, config, today, days_ahead).await
+            }
+            None => (Outcome::Skipped, "DaySmart: no upcoming games (skipped)".to_string()),
+        };
+    };
+    if let Some((_, store)) = no_games_context
+        && let Err(e) = no_games::reset(store, team_id).await
+    {
+        error!(error = %e, "Failed to reset no-games state");
+    }
+
+    info!(message = %message, "Prepared DaySmart message");
+    // With state, a reminder identical to the one already posted for this game is not
+    // re-posted, and a changed one replaces the earlier message; a dry run shows it regardless
+    let dedup_target = state_store.as_ref().zip(next_game.as_ref().map(|g| g.id.to_string())).filter(|_| !dry_run);
+    // With reminder offsets a game is reminded about once per offset `now` has passed; a
+    // delayed announcement goes out regardless
+    let offset = next_game.as_ref().filter(|_| !request.reminder_offsets_hours.is_empty()).map(|game| dedup::crossed_offset(&request.reminder_offsets_hours, game.dt, now));
+    let not_due = !delayed && !dry_run && matches!(offset, Some(None));
+    let offset = offset.flatten();
+    let previous = match &dedup_target {
+        Some((store, game_id)) => dedup::posted(store, team_id, game_id).await.unwrap_or_else(|e| {
+            error!(error = %e, "Failed to check posted reminders");
+            None
+        }),
+        None => None,
+    };
+    let first_message = previous.as_ref().and_then(|record| record.first_message());
+    // A reminder for an earlier offset is left alone, so the new one notifies as a fresh post
+    let previous = previous.filter(|record| offset.is_none() || record.offset_hours == offset);
+    let duplicate = previous.as_ref().is_some_and(|record| record.matches(&message));
+    let previous_message = previous.and_then(|record| record.message());
+    // The note is left out of the dedup hash so the next on-time run sees the same reminder
+    let content = if delayed { format!("{}\n{}", message, dedup::DELAYED_NOTE) } else { message.clone() };
+    let pending_store = state_store.as_ref().filter(|_| pending.is_some() && !dry_run);
+    let posted = if not_due {
+        info!("No reminder offset passed since the last reminder; skipping");
+        (Outcome::Skipped, "DaySmart: no reminder offset reached (skipped)".to_string())
+    } else if duplicate {
+        info!("Reminder unchanged since it was last posted; skipping");
+        if let Some(store) = pending_store
+            && let Err(e) = dedup::clear_pending(store, team_id).await
+        {
+            error!(error = %e, "Failed to clear pending announcement");
+        }
+        (Outcome::Skipped, "DaySmart: reminder already posted (skipped)".to_string())
+    } else {
+        let mut publisher = publisher.clone().mentioning(&request.mentions());
+        // Later offsets follow the game's first reminder into the thread it opened
+        if let Some(name) = &thread_name {
+            publisher = match first_message.as_ref().and_then(|message| message.channel_id.as_deref()) {
+                Some(thread_id) => publisher.in_existing_thread(thread_id),
+                None => publisher.in_thread(name),
+            };
+        }
+        let delivery = publisher.post_or_edit_with_attachment(previous_message.as_ref(), &content, filename, attachment.as_bytes()).await;
+        // The post counts as made once the main webhook has it; other destinations that failed
+        // are reported, not re-announced
+        let others_failed = delivery.others_result().err();
+        if let Some(e) = &others_failed {
+            warn!(error = %e, "Failed to post DaySmart message to other destinations");
+        }
+        match delivery.lead {
+            Err(post_error) => {
+                error!(error = %post_error, "Failed to post DaySmart message");
+                // Remembered so the next run re-announces it
+                if let (Some((store, _)), Some(game)) = (&dedup_target, &next_game)
+                    && let Err(e) = dedup::record_failed(store, team_id, game.id, &post_error.to_string(), now, game.dt).await
+                {
+                    error!(error = %e, "Failed to record failed reminder");
+                }
+                (Outcome::Failed, format!("DaySmart post failed: {}", post_error))
+            }
+            Ok(posted_message) => {
+                if let (Some((store, game_id)), Some(game)) = (&dedup_target, &next_game)
+                    && let Err(e) = dedup::record_posted(store, team_id, game_id, &message, posted_message.as_ref(), offset, now, game.dt).await
+                {
+                    error!(error = %e, "Failed to record posted reminder");
+                }
+                if let Some(store) = pending_store
+                    && let Err(e) = dedup::clear_pending(store, team_id).await
+                {
+                    error!(error = %e, "Failed to clear pending announcement");
+                }
+                let edited = previous_message.is_some() && posted_message == previous_message;
+                // With rsvp_reactions set up, the game's first reminder gets the reactions to count
+                if first_message.is_none()
+                    && request.rsvp_reactions.is_some()
+                    && single_game
+                    && let (Some((bot, _)), Some(message)) = (&dm_context, &posted_message)
+                    && let Err(e) = rsvp_reactions::add_reactions(bot, message).await
+                {
+                    warn!(error = %e, "Failed to add RSVP reactions to the reminder");
+                }
+                let detail = match (edited, delayed) {
+                    (true, _) => "DaySmart message updated",
+                    (false, true) => "DaySmart message posted (delayed announcement)",
+                    (false, false) => "DaySmart message posted",
+                };
+                match others_failed {
+                    Some(e) => (Outcome::Failed, format!("{}; other destinations failed: {}", detail, e)),
+                    None => (Outcome::Done, detail.to_string()),
+                }
+            }
+        }
+    };
+
+    match (dm_context, next_game) {
+        (Some((bot, store)), Some(game)) if !game.is_practice && dm::is_game_day(game.dt, now, local_tz) => {
+            // The hosted calendar feed doubles as the full schedule link when no page is configured
+            let links = request.links.clone().unwrap_or_default();
+            let hosted_schedule = match request.feed.clone().filter(|_| links.schedule_url.is_none()) {
+                Some(config) => FeedHost::new(config, team_id).await.current_url().await.unwrap_or_else(|e| {
+                    error!(error = %e, "Failed to look up hosted calendar feed");
+                    None
+                }),
+                None => None,
+            };
+            let buttons = links::reminder_buttons(&links, day_smart.rink_name(&game).name(), hosted_schedule);
+            let dms = dm::send_game_day_dms(&bot, store, team_id, game.id, game.dt, &message, &buttons).await;
+            (posted.0, format!("{} ({})", posted.1, dms))
+        }
+        _ => posted,
+    }
+}
@@ -0,0 +1,105 @@
+use tracing::{error, info, warn};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::dedup::PostedRecord;
+use crate::destination::Publisher;
+use crate::discord::PostedMessage;
+use crate::discord_bot::DiscordBot;
+use crate::rsvp_reactions::{self, RsvpReactionsConfig};
+use crate::state::StateStore;
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// Counts ✅/❌ reactions on the latest game reminder and posts (or updates) the attendance.
+pub struct RsvpReactions {
+    pub bot: DiscordBot,
+    pub store: StateStore,
+    pub config: RsvpReactionsConfig,
+}
+
+/// The reactions on the reminder for the next game, and the summary already posted for it.
+pub struct Reactions {
+    pub game_id: i64,
+    pub game_name: String,
+    pub count: rsvp_reactions::ReactionCount,
+    pub previous: Option<PostedRecord>,
+}
+
+/// The summary to post, and the message it replaces.
+pub struct Attendance {
+    pub game_id: i64,
+    pub content: String,
+    pub count: rsvp_reactions::ReactionCount,
+    pub previous: Option<PostedMessage>,
+}
+
+impl Workflow for RsvpReactions {
+    type Input = Reactions;
+    type Output = Attendance;
+
+    const KIND: workflow::Workflow = workflow::Workflow::RsvpReactions;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        match (&ctx.request.bot_token, &ctx.state) {
+            (Some(token), Some(store)) => Ok(RsvpReactions {
+                bot: DiscordBot::new(token.value().to_string()),
+                store: store.clone(),
+                config: ctx.request.rsvp_reactions.unwrap_or_default(),
+            }),
+            _ => {
+                info!("No bot_token or state configured; skipping rsvp_reactions workflow");
+                Err(Halt::new(Outcome::Skipped, "RSVP reactions: needs bot_token and state (skipped)".to_string()))
+            }
+        }
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async move {
+            let day_smart = ctx.daysmart().await?;
+            let team_id = &ctx.request.team_id;
+            let Some(game) = day_smart.next_game(ctx.request.days_ahead, ctx.now) else {
+                return Err(Halt::new(Outcome::Skipped, "RSVP reactions: no upcoming game (skipped)".to_string()));
+            };
+            let failed = |e: String| {
+                error!(error = %e, "Failed to read reminder reactions");
+                Halt::new(Outcome::Failed, format!("RSVP reactions fetch failed: {}", e))
+            };
+            // Reactions are read off the reminder, so there is nothing to count until it is posted
+            // with its channel known
+            let Some(count) = rsvp_reactions::reaction_count(&self.bot, &self.store, team_id, game.id).await.map_err(failed)? else {
+                return Err(Halt::new(Outcome::Skipped, "RSVP reactions: no reminder posted for the next game (skipped)".to_string()));
+            };
+            let previous = rsvp_reactions::last_summary(&self.store, team_id, game.id).await.map_err(failed)?;
+            Ok(Reactions { game_id: game.id, game_name: day_smart.thread_name(&game), count, previous })
+        })
+    }
+
+    fn render(&self, _ctx: &Context, reactions: Self::Input) -> Result<Self::Output, Halt> {
+        let content = rsvp_reactions::summary(&reactions.count, &reactions.game_name, &self.config);
+        if reactions.previous.as_ref().is_some_and(|record| record.matches(&content)) {
+            return Err(Halt::new(Outcome::Skipped, "RSVP reactions: attendance unchanged (skipped)".to_string()));
+        }
+        Ok(Attendance {
+            game_id: reactions.game_id,
+            content,
+            count: reactions.count,
+            previous: reactions.previous.and_then(|record| record.message()),
+        })
+    }
+
+    fn deliver<'a>(&'a self, ctx: &'a Context, publisher: &'a Publisher, attendance: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            let delivery = publisher.post_or_edit(attendance.previous.as_ref(), &attendance.content).await;
+            if let Err(e) = delivery.others_result() {
+                warn!(error = %e, "Failed to post RSVP reactions to other destinations");
+            }
+            let message = delivery.lead.map_err(|e| Halt::failed(format!("RSVP reactions post failed: {}", e), e))?;
+            if !ctx.request.dry_run
+                && let Err(e) = rsvp_reactions::record_summary(&self.store, &ctx.request.team_id, attendance.game_id, &attendance.content, message.as_ref(), ctx.now).await
+            {
+                error!(error = %e, "Failed to save RSVP reactions state");
+            }
+            Ok(format!("RSVP reactions: {} confirmed", attendance.count.line()))
+        })
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::daysmart::DaySmart;
+use crate::destination::Publisher;
+use crate::discord_bot::DiscordBot;
+use crate::scheduled_events::{self, ScheduledEventsConfig};
+use crate::state::StateStore;
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// Creates, moves and deletes the server's Discord scheduled events to match DaySmart.
+pub struct ScheduledEvents {
+    pub bot: DiscordBot,
+    pub store: StateStore,
+    pub config: ScheduledEventsConfig,
+}
+
+impl Workflow for ScheduledEvents {
+    type Input = Arc<DaySmart>;
+    type Output = Arc<DaySmart>;
+
+    const KIND: workflow::Workflow = workflow::Workflow::ScheduledEvents;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        let Some(config) = &ctx.request.scheduled_events else {
+            info!("No scheduled_events config provided; skipping scheduled_events workflow");
+            return Err(Halt::new(Outcome::Skipped, "Scheduled events: not configured (skipped)".to_string()));
+        };
+        match (&ctx.request.bot_token, &ctx.state) {
+            (Some(token), Some(store)) => Ok(ScheduledEvents { bot: DiscordBot::new(token.value().to_string()), store: store.clone(), config: config.clone() }),
+            _ => {
+                info!("No bot_token or state configured; skipping scheduled_events workflow");
+                Err(Halt::new(Outcome::Skipped, "Scheduled events: needs bot_token and state (skipped)".to_string()))
+            }
+        }
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(ctx.daysmart())
+    }
+
+    fn render(&self, _ctx: &Context, day_smart: Self::Input) -> Result<Self::Output, Halt> {
+        Ok(day_smart)
+    }
+
+    fn deliver<'a>(&'a self, ctx: &'a Context, _publisher: &'a Publisher, day_smart: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            let team_id = &ctx.request.team_id;
+            let mut records = scheduled_events::records(&self.store, team_id)
+                .await
+                .map_err(|e| Halt::new(Outcome::Failed, format!("Scheduled events sync failed: {}", e)))?;
+            let summary = scheduled_events::sync(&self.bot, &mut records, &self.config, &day_smart, ctx.now).await;
+            if let Err(e) = scheduled_events::save_records(&self.store, team_id, &records).await {
+                error!(error = %e, "Failed to save scheduled events state");
+            }
+            if summary.failed > 0 {
+                return Err(Halt::new(Outcome::Failed, format!("Scheduled events: {}", summary)));
+            }
+            Ok(format!("Scheduled events: {}", summary))
+        })
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::daysmart::DaySmart;
+use crate::destination::Publisher;
+use crate::scores::{self, PostedRecaps};
+use crate::streak::{self, StreakConfig};
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// Recaps of games played on the previous local day.
+pub struct Scores {
+    pub streak: Option<StreakConfig>,
+}
+
+/// The team document, and the games whose recap is already posted.
+pub struct Played {
+    pub day_smart: Arc<DaySmart>,
+    pub posted: PostedRecaps,
+}
+
+/// The recap message and the games it covers.
+pub struct Recap {
+    pub message: String,
+    pub game_ids: Vec<i64>,
+}
+
+impl Workflow for Scores {
+    type Input = Played;
+    type Output = Recap;
+
+    const KIND: workflow::Workflow = workflow::Workflow::Scores;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        Ok(Scores { streak: ctx.request.streak })
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async move {
+            let day_smart = ctx.daysmart().await?;
+            // Without state every run recaps yesterday's games
+            let posted = match &ctx.state {
+                Some(store) => scores::posted(store, &ctx.request.team_id).await.map_err(|e| {
+                    error!(error = %e, "Failed to load posted score recaps");
+                    Halt::new(Outcome::Failed, format!("Scores: failed to check posted recaps: {}", e))
+                })?,
+                None => PostedRecaps::new(),
+            };
+            Ok(Played { day_smart, posted })
+        })
+    }
+
+    fn render(&self, ctx: &Context, Played { day_smart, posted }: Self::Input) -> Result<Self::Output, Halt> {
+        // Recaps go out the morning after, for games played on the previous local day
+        let yesterday = ctx.now.with_timezone(&ctx.local_tz).date_naive() - chrono::Duration::days(1);
+        let (game_ids, recaps): (Vec<i64>, Vec<String>) = day_smart
+            .completed_games_on(yesterday)
+            .iter()
+            .filter_map(|game| Some((game.id, day_smart.format_recap(game)?)))
+            .unzip();
+        if recaps.is_empty() {
+            info!(%yesterday, "No completed games with scores; skipping recap");
+            return Err(Halt::new(Outcome::Skipped, "Scores: no final scores from yesterday (skipped)".to_string()));
+        }
+        if game_ids.iter().all(|id| posted.contains_key(id)) {
+            info!(%yesterday, "Score recaps already posted; skipping");
+            return Err(Halt::new(Outcome::Skipped, "Scores: recap already posted (skipped)".to_string()));
+        }
+        let mut message = recaps.join("\n");
+        if let Some(line) = self.streak.and_then(|config| streak::streak_line(streak::current_streak(&day_smart.results()), &config)) {
+            message.push('\n');
+            message.push_str(&line);
+        }
+        Ok(Recap { message, game_ids })
+    }
+
+    fn deliver<'a>(&'a self, ctx: &'a Context, publisher: &'a Publisher, recap: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            super::post(publisher, &recap.message, "Scores").await?;
+            if let Some(store) = ctx.state.as_ref().filter(|_| !ctx.request.dry_run)
+                && let Err(e) = scores::record_posted(store, &ctx.request.team_id, &recap.game_ids, ctx.now).await
+            {
+                error!(error = %e, "Failed to record posted score recaps");
+            }
+            Ok(format!("Scores: {} recap(s) posted", recap.game_ids.len()))
+        })
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use tracing::error;
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::daysmart::DaySmart;
+use crate::destination::Publisher;
+use crate::standings::{self, Standing};
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// The league standings table.
+pub struct Standings {
+    pub company: String,
+}
+
+/// The team document and its league's table.
+pub struct Table {
+    pub day_smart: Arc<DaySmart>,
+    pub league_name: String,
+    pub rows: Vec<Standing>,
+}
+
+impl Workflow for Standings {
+    type Input = Table;
+    type Output = String;
+
+    const KIND: workflow::Workflow = workflow::Workflow::Standings;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        Ok(Standings { company: ctx.request.company.clone() })
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async move {
+            let day_smart = ctx.daysmart().await?;
+            let Some(league) = day_smart.league() else {
+                return Err(Halt::new(Outcome::Skipped, "Standings: team document has no league (skipped)".to_string()));
+            };
+            let league_name = league.name.clone();
+            match standings::fetch(league.id, &self.company, &ctx.http_policy).await {
+                Ok(rows) if rows.iter().all(|row| row.games_played == 0) => Err(Halt::new(Outcome::Skipped, "Standings: no scored games yet (skipped)".to_string())),
+                Ok(rows) => Ok(Table { day_smart, league_name, rows }),
+                Err(e) => {
+                    error!(error = %e, "Failed to fetch standings");
+                    Err(Halt::failed(format!("Standings fetch failed: {}", e), e))
+                }
+            }
+        })
+    }
+
+    fn render(&self, _ctx: &Context, table: Self::Input) -> Result<Self::Output, Halt> {
+        Ok(standings::format_table(&table.league_name, &table.rows, table.day_smart.team_id()))
+    }
+
+    fn deliver<'a>(&'a self, _ctx: &'a Context, publisher: &'a Publisher, message: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            super::post(publisher, &message, "Standings").await?;
+            Ok("Standings posted".to_string())
+        })
+    }
+}
@@ -0,0 +1,50 @@
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::destination::Publisher;
+use crate::state::StateStore;
+use crate::summary::Outcome;
+use crate::workflow;
+
+/// Removes expired per-game records from backends that don't expire them themselves.
+pub struct StateCleanup {
+    pub store: StateStore,
+}
+
+impl Workflow for StateCleanup {
+    type Input = ();
+    type Output = ();
+
+    const KIND: workflow::Workflow = workflow::Workflow::StateCleanup;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        match &ctx.state {
+            Some(store) => Ok(StateCleanup { store: store.clone() }),
+            None => {
+                info!("No state configured; skipping state_cleanup workflow");
+                Err(Halt::new(Outcome::Skipped, "State cleanup: no state configured (skipped)".to_string()))
+            }
+        }
+    }
+
+    fn fetch<'a>(&'a self, _ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn render(&self, _ctx: &Context, _input: Self::Input) -> Result<Self::Output, Halt> {
+        Ok(())
+    }
+
+    fn deliver<'a>(&'a self, ctx: &'a Context, _publisher: &'a Publisher, _output: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            match self.store.remove_expired(ctx.now).await {
+                Ok(Some(removed)) => Ok(format!("State cleanup: {} expired records removed", removed)),
+                Ok(None) => Err(Halt::new(Outcome::Skipped, "State cleanup: backend expires records itself (skipped)".to_string())),
+                Err(e) => {
+                    error!(error = %e, "State cleanup failed");
+                    Err(Halt::new(Outcome::Failed, format!("State cleanup failed: {}", e)))
+                }
+            }
+        })
+    }
+}
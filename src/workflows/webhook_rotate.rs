@@ -0,0 +1,58 @@
+use tracing::{error, info};
+
+use super::{Context, Halt, StepFuture, Workflow};
+use crate::destination::Publisher;
+use crate::discord::Discord;
+use crate::handler::Mode;
+use crate::secrets::{SecretRef, SecretsClient};
+use crate::state::StateStore;
+use crate::summary::Outcome;
+use crate::webhook_rotation::{self, WebhookRotationConfig};
+use crate::workflow;
+
+/// Moves production posts to a new webhook once it accepts a post, and tells the old channel.
+pub struct WebhookRotate {
+    pub new_hook: SecretRef,
+    pub store: StateStore,
+}
+
+impl Workflow for WebhookRotate {
+    type Input = ();
+    type Output = ();
+
+    const KIND: workflow::Workflow = workflow::Workflow::WebhookRotate;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        let (Some(new_hook), Some(store)) = (ctx.request.webhook_rotation.as_ref().and_then(WebhookRotationConfig::new_hook), ctx.state.clone()) else {
+            info!("Webhook rotation needs webhook_rotation and state; skipping webhook_rotate workflow");
+            return Err(Halt::new(Outcome::Skipped, "Webhook rotation: not configured (skipped)".to_string()));
+        };
+        // Rotation changes where production posts go; test runs must not touch it
+        if matches!(ctx.request.mode, Mode::Test) {
+            info!("Skipping webhook_rotate workflow in test mode");
+            return Err(Halt::new(Outcome::Skipped, "Webhook rotation: production only (skipped)".to_string()));
+        }
+        Ok(WebhookRotate { new_hook, store })
+    }
+
+    fn fetch<'a>(&'a self, _ctx: &'a Context) -> StepFuture<'a, Self::Input> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn render(&self, _ctx: &Context, _input: Self::Input) -> Result<Self::Output, Halt> {
+        Ok(())
+    }
+
+    // Posts to both webhooks directly; the publisher's destinations don't change mid-run
+    fn deliver<'a>(&'a self, ctx: &'a Context, _publisher: &'a Publisher, _output: Self::Output) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            let new_hook_url = SecretsClient::from_env().resolve(&self.new_hook).await.map_err(|e| {
+                error!(error = %e, "Failed to resolve the new webhook");
+                Halt::new(Outcome::Failed, format!("Webhook rotation failed: {}", e))
+            })?;
+            let old = Discord::new(ctx.hook_url.clone()).with_ids(ctx.ids.clone());
+            let new = Discord::new(new_hook_url).with_ids(ctx.ids.clone());
+            super::settle(webhook_rotation::rotate(&self.store, &ctx.request.team_id, &old, &new, &self.new_hook, ctx.now).await)
+        })
+    }
+}
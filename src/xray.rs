@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::clock::{IdGenerator, RandomIds};
+
+/// Environment variable that turns trace export on; "xray" is the only exporter.
+pub const EXPORTER_VAR: &str = "TRACE_EXPORTER";
+
+/// Where the X-Ray daemon listens unless `AWS_XRAY_DAEMON_ADDRESS` says otherwise.
+const DEFAULT_DAEMON_ADDRESS: &str = "127.0.0.1:2000";
+
+/// Lambda's trace header for the current invocation, set when active tracing is on.
+const TRACE_HEADER_VAR: &str = "_X_AMZN_TRACE_ID";
+
+/// An `X-Amzn-Trace-Id` header, e.g. "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceHeader {
+    pub root: String,
+    /// The Lambda function segment new subsegments hang off.
+    pub parent: Option<String>,
+    pub sampled: bool,
+}
+
+impl TraceHeader {
+    pub fn parse(header: &str) -> Option<Self> {
+        let mut root = None;
+        let mut parent = None;
+        let mut sampled = false;
+        for part in header.split(';') {
+            match part.trim().split_once('=') {
+                Some(("Root", value)) => root = Some(value.to_string()),
+                Some(("Parent", value)) => parent = Some(value.to_string()),
+                Some(("Sampled", value)) => sampled = value == "1",
+                _ => {}
+            }
+        }
+        Some(TraceHeader { root: root?, parent, sampled })
+    }
+}
+
+/// Sends every closed span in a sampled trace to the X-Ray daemon as a subsegment, nested as the
+/// spans were, so the DaySmart and iCal fetches and Discord posts show up in the invocation's
+/// trace with their durations. A span is marked failed when an ERROR event is logged inside it;
+/// a recorded `status` field becomes the HTTP status. Nothing is sent when the daemon is not
+/// listening.
+pub struct XrayLayer {
+    socket: Option<UdpSocket>,
+    daemon: String,
+    ids: Arc<dyn IdGenerator>,
+    /// A fixed header instead of Lambda's per-invocation one.
+    header: Option<String>,
+}
+
+impl XrayLayer {
+    pub fn new(daemon: impl Into<String>) -> Self {
+        Self { socket: UdpSocket::bind("0.0.0.0:0").ok(), daemon: daemon.into(), ids: Arc::new(RandomIds), header: None }
+    }
+
+    /// The layer `TRACE_EXPORTER=xray` asks for, sending to the daemon Lambda runs when active
+    /// tracing is enabled.
+    pub fn from_env() -> Option<Self> {
+        if std::env::var(EXPORTER_VAR).ok()?.trim() != "xray" {
+            return None;
+        }
+        let address = std::env::var("AWS_XRAY_DAEMON_ADDRESS").unwrap_or_else(|_| DEFAULT_DAEMON_ADDRESS.to_string());
+        Some(Self::new(daemon_udp_address(&address)))
+    }
+
+    /// Override where subsegment ids come from.
+    pub fn with_ids(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Trace every span under `header` instead of reading `_X_AMZN_TRACE_ID`.
+    pub fn with_trace_header(mut self, header: impl Into<String>) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+
+    fn current_header(&self) -> Option<TraceHeader> {
+        match &self.header {
+            Some(header) => TraceHeader::parse(header),
+            None => TraceHeader::parse(&std::env::var(TRACE_HEADER_VAR).ok()?),
+        }
+    }
+
+    fn send(&self, document: &Value) {
+        if let Some(socket) = &self.socket {
+            let packet = format!("{{\"format\": \"json\", \"version\": 1}}\n{}", document);
+            // Tracing must never fail the run; a missing daemon just loses the trace
+            let _ = socket.send_to(packet.as_bytes(), &self.daemon);
+        }
+    }
+}
+
+/// The UDP address in `AWS_XRAY_DAEMON_ADDRESS`, which is either "host:port" or
+/// "tcp:host:port udp:host:port".
+fn daemon_udp_address(value: &str) -> String {
+    value
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix("udp:"))
+        .unwrap_or(value.trim())
+        .to_string()
+}
+
+/// What the layer keeps for an open span.
+struct SpanData {
+    id: String,
+    header: Option<TraceHeader>,
+    start: f64,
+    fields: BTreeMap<String, Value>,
+    error: Option<String>,
+}
+
+/// Collects span and event fields as JSON.
+#[derive(Default)]
+struct Fields(BTreeMap<String, Value>);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), json!(format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+}
+
+fn epoch_seconds() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or_default()
+}
+
+/// The subsegment document for a span that ran from `start` to `end` (seconds since the epoch).
+/// A failure is an error for a 4xx status (and a throttle for 429), otherwise a fault.
+pub fn subsegment(name: &str, id: &str, trace: &TraceHeader, parent_id: Option<&str>, (start, end): (f64, f64), fields: &BTreeMap<String, Value>, error: Option<&str>) -> Value {
+    let mut doc = json!({
+        "name": name,
+        "id": id,
+        "trace_id": trace.root,
+        "start_time": start,
+        "end_time": end,
+        "type": "subsegment",
+    });
+    if let Some(parent_id) = parent_id {
+        doc["parent_id"] = json!(parent_id);
+    }
+    let status = fields.get("status").and_then(Value::as_u64);
+    if let Some(status) = status {
+        doc["http"] = json!({ "response": { "status": status } });
+    }
+    let metadata: BTreeMap<&String, &Value> = fields.iter().filter(|(key, _)| *key != "status").collect();
+    if !metadata.is_empty() {
+        doc["metadata"] = json!({ "fields": metadata });
+    }
+    if let Some(message) = error {
+        match status {
+            Some(429) => {
+                doc["error"] = json!(true);
+                doc["throttle"] = json!(true);
+            }
+            Some(400..=499) => doc["error"] = json!(true),
+            _ => doc["fault"] = json!(true),
+        }
+        doc["cause"] = json!({ "exceptions": [{ "message": message }] });
+    }
+    doc
+}
+
+impl<S> Layer<S> for XrayLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        // Children stay in the trace their parent started in
+        let header = match span.parent() {
+            Some(parent) => parent.extensions().get::<SpanData>().and_then(|data| data.header.clone()),
+            None => self.current_header(),
+        };
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        let data = SpanData { id: format!("{:016x}", self.ids.next_id()), header, start: epoch_seconds(), fields: fields.0, error: None };
+        span.extensions_mut().insert(data);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id)
+            && let Some(data) = span.extensions_mut().get_mut::<SpanData>()
+        {
+            let mut fields = Fields(std::mem::take(&mut data.fields));
+            values.record(&mut fields);
+            data.fields = fields.0;
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if *event.metadata().level() != Level::ERROR {
+            return;
+        }
+        let Some(span) = ctx.event_span(event) else { return };
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let message = fields.0.get("error").or(fields.0.get("message")).map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()));
+        if let Some(data) = span.extensions_mut().get_mut::<SpanData>() {
+            data.error = data.error.take().or(message);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(data) = span.extensions_mut().remove::<SpanData>() else { return };
+        let Some(header) = data.header.as_ref().filter(|header| header.sampled) else { return };
+        let parent_id = match span.parent() {
+            Some(parent) => parent.extensions().get::<SpanData>().map(|parent| parent.id.clone()),
+            None => header.parent.clone(),
+        };
+        let document = subsegment(span.name(), &data.id, header, parent_id.as_deref(), (data.start, epoch_seconds()), &data.fields, data.error.as_deref());
+        self.send(&document);
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::api::{self, Format};
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::handler::Request;
+use hockey_reminder_lambda_rust::interactions::HttpRequest;
+use hockey_reminder_lambda_rust::opponents::BenchappNames;
+
+#[test]
+fn accept_header_picks_the_format() {
+    assert_eq!(Format::negotiate(None), Some(Format::Json));
+    assert_eq!(Format::negotiate(Some("*/*")), Some(Format::Json));
+    assert_eq!(Format::negotiate(Some("text/csv")), Some(Format::Csv));
+    assert_eq!(Format::negotiate(Some("text/html, text/csv;q=0.9, application/json;q=0.5")), Some(Format::Csv));
+    assert_eq!(Format::negotiate(Some("text/csv;q=0.2, application/json")), Some(Format::Json));
+    assert_eq!(Format::negotiate(Some("text/csv;q=0, image/png")), None);
+}
+
+#[test]
+fn http_method_comes_from_either_event_version() {
+    let v1: HttpRequest = serde_json::from_value(serde_json::json!({ "httpMethod": "get" })).unwrap();
+    assert_eq!(v1.method(), "GET");
+    let v2: HttpRequest = serde_json::from_value(serde_json::json!({ "requestContext": { "http": { "method": "POST" } } })).unwrap();
+    assert_eq!(v2.method(), "POST");
+}
+
+#[test]
+fn renders_games_as_json_or_csv() {
+    let ds = DaySmart::from_json(&std::fs::read_to_string("tests/sample_response.json").unwrap()).unwrap();
+    let request: Request = serde_json::from_value(serde_json::json!({
+        "mode": "test",
+        "team_id": "11007",
+        "company": "kraken",
+        "discord_hook_url": "https://discord.com/api/webhooks/1/x",
+        "preview_horizon_days": 14
+    }))
+    .unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 15, 0, 0).unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&api::render(Format::Json, &ds, &request, &BenchappNames::default(), now)).unwrap();
+    let games = json["games"].as_array().unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0]["game_id"], 312149);
+
+    let csv = api::render(Format::Csv, &ds, &request, &BenchappNames::default(), now);
+    assert!(csv.starts_with("Type,Game Type,"), "csv was: {}", csv);
+    assert_eq!(csv.lines().count(), 3);
+}
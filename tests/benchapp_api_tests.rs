@@ -0,0 +1,124 @@
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use hockey_reminder_lambda_rust::benchapp_api::{self, games_from_daysmart, plan, BenchappClient, BenchappConfig, BenchappGame, Links, SyncAction};
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+
+fn game(id: Option<&str>, day: u32, opponent: &str, time: &str) -> BenchappGame {
+    BenchappGame {
+        id: id.map(str::to_string),
+        date: NaiveDate::from_ymd_opt(2025, 9, day).unwrap(),
+        time: time.to_string(),
+        opponent: opponent.to_string(),
+        home: true,
+        location: "Starbucks Rink 1".to_string(),
+        notes: "Light Jerseys".to_string(),
+        game_type: "REGULAR".to_string(),
+        daysmart_id: None,
+    }
+}
+
+/// A game made from DaySmart event `daysmart_id`.
+fn wanted(daysmart_id: i64, day: u32, opponent: &str, time: &str) -> BenchappGame {
+    BenchappGame { daysmart_id: Some(daysmart_id), ..game(None, day, opponent, time) }
+}
+
+fn day(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 9, day).unwrap()
+}
+
+#[test]
+fn plan_creates_missing_games_and_updates_changed_ones() {
+    let existing = vec![game(Some("a"), 14, "Blackbirds", "21:45"), game(Some("b"), 21, "Yetis", "20:00"), game(Some("c"), 28, "Hand Added", "19:00")];
+    let wanted = vec![
+        game(None, 14, "blackbirds ", "21:45"),
+        game(None, 21, "Yetis", "22:15"),
+        game(None, 30, "Sharks", "18:00"),
+    ];
+    let plan = plan(&existing, &wanted, &Links::new(), day(1));
+    let (actions, unchanged) = (plan.actions, plan.unchanged);
+    // Matching is by date and opponent, ignoring case and surrounding whitespace
+    assert_eq!(unchanged, 0, "opponent spelling differs, so the first game is updated: {:?}", actions);
+    assert_eq!(actions.len(), 3);
+    assert!(matches!(&actions[0], SyncAction::Update { id, .. } if id == "a"));
+    assert!(matches!(&actions[1], SyncAction::Update { id, game } if id == "b" && game.time == "22:15"));
+    assert!(matches!(&actions[2], SyncAction::Create(game) if game.opponent == "Sharks"));
+}
+
+#[test]
+fn plan_leaves_identical_games_alone() {
+    let existing = vec![game(Some("a"), 14, "Blackbirds", "21:45")];
+    let plan = plan(&existing, &[game(None, 14, "Blackbirds", "21:45")], &Links::new(), day(1));
+    assert!(plan.actions.is_empty());
+    assert_eq!(plan.unchanged, 1);
+}
+
+#[test]
+fn linked_games_follow_their_daysmart_event_and_are_deleted_with_it() {
+    let existing = vec![
+        game(Some("a"), 14, "Blackbirds", "21:45"),
+        game(Some("b"), 21, "Yetis", "20:00"),
+        game(Some("c"), 28, "Sharks", "19:00"),
+        game(Some("d"), 7, "Played", "19:00"),
+        game(Some("e"), 30, "Hand Added", "19:00"),
+    ];
+    let links: Links = [("1", "a"), ("2", "b"), ("3", "c"), ("4", "d")].into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    // Event 1 moved to another day and opponent; event 3 was cancelled; event 4 is in the past
+    let wanted = vec![wanted(1, 15, "Kraken", "21:45"), wanted(2, 21, "Yetis", "20:00"), wanted(5, 30, "Hand Added", "19:00")];
+    let plan = plan(&existing, &wanted, &links, day(10));
+    assert_eq!(
+        plan.actions,
+        vec![SyncAction::Update { id: "a".to_string(), game: wanted[0].clone() }, SyncAction::Delete { id: "c".to_string() }]
+    );
+    assert_eq!(plan.unchanged, 2);
+    // The hand-added game matching a new event by date and opponent is linked to it
+    let linked: Vec<(&str, &str)> = plan.links.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    assert_eq!(linked, [("1", "a"), ("2", "b"), ("5", "e")]);
+}
+
+#[tokio::test]
+async fn sync_links_created_games_and_deletes_cancelled_ones() {
+    let listed = r#"[{"id":"c","date":"2025-09-28","time":"19:00","opponent":"Sharks","home":true}]"#;
+    let (url, received) = common::capture(
+        "/api",
+        vec![common::json_response(listed), common::json_response(r#"{"id":"n1","date":"2025-09-21","time":"20:00","opponent":"Yetis","home":true}"#), common::NO_CONTENT.to_string()],
+    );
+    let config: BenchappConfig = serde_json::from_value(serde_json::json!({ "base_url": url, "team_id": "98765", "api_token": "t" })).unwrap();
+    let client = BenchappClient::from_config(&config, false, &Default::default()).unwrap().unwrap();
+    let mut links: Links = [("3".to_string(), "c".to_string())].into_iter().collect();
+
+    let summary = client.sync(&[wanted(2, 21, "Yetis", "20:00")], &mut links, day(10)).await.unwrap();
+    assert_eq!((summary.created, summary.deleted), (1, 1));
+    let requests: Vec<String> = received.iter().take(3).map(|request| request.lines().next().unwrap().to_string()).collect();
+    assert_eq!(requests[0], "GET /api/teams/98765/games HTTP/1.1");
+    assert_eq!(requests[1], "POST /api/teams/98765/games HTTP/1.1");
+    assert_eq!(requests[2], "DELETE /api/teams/98765/games/c HTTP/1.1");
+    assert_eq!(links.into_iter().collect::<Vec<_>>(), [("2".to_string(), "n1".to_string())]);
+
+    let store = common::temp_store("benchapp-links");
+    let saved: Links = [("2".to_string(), "n1".to_string())].into_iter().collect();
+    benchapp_api::save_links(&store, "98765", &saved).await.unwrap();
+    assert_eq!(benchapp_api::links(&store, "98765").await.unwrap(), saved);
+}
+
+#[test]
+fn config_needs_an_explicit_endpoint() {
+    let missing = serde_json::from_value::<BenchappConfig>(serde_json::json!({ "team_id": "98765", "api_token": "t" }));
+    assert!(missing.is_err(), "there is no default base_url");
+    let config: BenchappConfig = serde_json::from_value(serde_json::json!({ "base_url": "ftp://example.com", "team_id": "98765", "api_token": "t" })).unwrap();
+    assert!(config.validate().unwrap_err().contains("base_url"));
+}
+
+#[test]
+fn daysmart_games_convert_to_local_benchapp_games() {
+    let json = std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json");
+    let ds = DaySmart::from_json(&json).expect("from_json failed");
+    let games = ds.upcoming_games(60, Utc.with_ymd_and_hms(2025, 9, 1, 0, 0, 0).unwrap());
+    let converted = games_from_daysmart(&ds, &games);
+    assert_eq!(converted.len(), games.len());
+    let first = &converted[0];
+    assert_eq!(first.id, None);
+    assert_eq!(first.location, "Starbucks Rink 1");
+    assert!(first.notes.ends_with("Jerseys") || first.notes.contains("; Locker Room: "), "notes were: {}", first.notes);
+    assert_eq!(first.game_type, "REGULAR");
+}
@@ -0,0 +1,10 @@
+use hockey_reminder_lambda_rust::canary;
+
+#[test]
+fn canary_renders_a_reminder_and_csv_from_the_bundled_sample() {
+    let (message, csv) = canary::render(chrono_tz::America::Los_Angeles).expect("bundled sample should render");
+    assert!(message.starts_with(":bird: Canary:"), "message was: {}", message);
+    assert!(message.contains(":hockey: Kraken Hockey League Game"), "message was: {}", message);
+    assert!(message.contains("Starbucks Rink 1"), "message was: {}", message);
+    assert!(csv.lines().count() > 1, "csv should have game rows: {}", csv);
+}
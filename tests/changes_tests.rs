@@ -0,0 +1,121 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::changes::{self, GameSnapshot, ScheduleSnapshot};
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::destination::Publisher;
+use hockey_reminder_lambda_rust::model::game::GameStatus;
+
+fn game(hour: u32, rink: &str, locker_room: Option<&str>) -> GameSnapshot {
+    GameSnapshot {
+        start: Utc.with_ymd_and_hms(2025, 9, 22, hour, 45, 0).unwrap(),
+        rink: rink.to_string(),
+        locker_room: locker_room.map(str::to_string),
+        opponent: "Seal Team Sticks".to_string(),
+        opponent_tbd: false,
+    }
+}
+
+#[test]
+fn snapshot_resolves_rink_and_locker_room() {
+    let json = std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json");
+    let ds = DaySmart::from_json(&json).expect("from_json failed");
+    let games = ds.upcoming_games(3, Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap());
+    let snapshot = changes::snapshot(&ds, &games);
+    assert_eq!(snapshot.get(&312149), Some(&game(3, "Starbucks Rink 1", Some("LR11"))));
+}
+
+#[test]
+fn diff_reports_changed_games_only() {
+    let previous: ScheduleSnapshot = [(1, game(3, "Rink 1", Some("LR1"))), (2, game(3, "Rink 2", None))].into_iter().collect();
+    let current: ScheduleSnapshot =
+        [(1, game(4, "Rink 1", Some("LR3"))), (2, game(3, "Rink 2", None)), (3, game(5, "Rink 3", None))].into_iter().collect();
+    let changes = changes::diff(&previous, &current);
+    assert_eq!(changes.len(), 1, "unchanged and newly added games are not changes");
+    assert_eq!(changes[0].game_id, 1);
+
+    let alert = changes::format_alert(&changes, chrono_tz::America::Los_Angeles, "none");
+    assert_eq!(
+        alert,
+        ":warning: **Schedule change**\n:hockey: Game on Sun Sep 21, 8:45 PM:\n    Time: Sun Sep 21, 8:45 PM → **Sun Sep 21, 9:45 PM**\n    Locker room: LR1 → **LR3**"
+    );
+}
+
+#[test]
+fn decided_opponents_get_a_follow_up_not_a_schedule_change() {
+    let undecided = GameSnapshot { opponent: "Winner of Game 3".to_string(), opponent_tbd: true, ..game(3, "Rink 1", None) };
+    let previous: ScheduleSnapshot = [(1, undecided.clone()), (2, undecided.clone())].into_iter().collect();
+    let current: ScheduleSnapshot = [(1, game(3, "Rink 1", None)), (2, undecided)].into_iter().collect();
+
+    assert!(changes::diff(&previous, &current).is_empty());
+    let decided = changes::opponents_decided(&previous, &current);
+    assert_eq!(decided.len(), 1);
+    assert_eq!(decided[0].game_id, 1);
+    assert_eq!(
+        changes::format_opponents_decided(&decided, chrono_tz::America::Los_Angeles),
+        ":crossed_swords: **Opponent decided**\n:hockey: Sun Sep 21, 8:45 PM at Rink 1: vs **Seal Team Sticks** (was Winner of Game 3)"
+    );
+
+    // Snapshots stored before opponents were tracked don't trigger follow-ups
+    let old: GameSnapshot = serde_json::from_value(serde_json::json!({ "start": "2025-09-22T03:45:00Z", "rink": "Rink 1" })).unwrap();
+    let previous: ScheduleSnapshot = [(1, old)].into_iter().collect();
+    assert!(changes::opponents_decided(&previous, &current).is_empty());
+    assert!(changes::diff(&previous, &current).is_empty());
+}
+
+#[tokio::test]
+async fn first_run_records_and_later_runs_alert() {
+    let store = common::temp_store("changes");
+    let tz = chrono_tz::America::Los_Angeles;
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+    let original: ScheduleSnapshot = [(1, game(3, "Rink 1", None))].into_iter().collect();
+    let moved: ScheduleSnapshot = [(1, game(3, "Rink 2", None))].into_iter().collect();
+
+    assert_eq!(changes::notify_changes(&Publisher::default(), &store, "11007", original.clone(), now, |_| GameStatus::Listed, tz, "none").await, None);
+    assert_eq!(changes::notify_changes(&Publisher::default(), &store, "11007", original, now, |_| GameStatus::Listed, tz, "none").await, None);
+    assert_eq!(
+        changes::notify_changes(&Publisher::default(), &store, "11007", moved.clone(), now, |_| GameStatus::Listed, tz, "none").await,
+        Some("schedule change alert posted".to_string())
+    );
+    assert_eq!(changes::notify_changes(&Publisher::default(), &store, "11007", moved, now, |_| GameStatus::Listed, tz, "none").await, None);
+}
+
+#[test]
+fn cancelled_and_removed_games_are_reported() {
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+    let previous: ScheduleSnapshot =
+        [(1, game(3, "Rink 1", None)), (2, game(4, "Rink 2", None)), (3, game(5, "Rink 3", None))].into_iter().collect();
+    let current: ScheduleSnapshot = [(1, game(3, "Rink 1", None))].into_iter().collect();
+    let status = |id: i64| match id {
+        2 => GameStatus::Cancelled,
+        _ => GameStatus::Missing,
+    };
+    let cancelled = changes::cancellations(&previous, &current, now, status);
+    assert_eq!(cancelled.iter().map(|c| (c.game_id, c.status)).collect::<Vec<_>>(), vec![(2, GameStatus::Cancelled), (3, GameStatus::Missing)]);
+    assert_eq!(
+        changes::format_cancellations(&cancelled, chrono_tz::America::Los_Angeles),
+        ":no_entry: **Game cancelled**\n:hockey: Sun Sep 21, 9:45 PM at Rink 2: cancelled\n:hockey: Sun Sep 21, 10:45 PM at Rink 3: removed from the schedule"
+    );
+
+    // Past games and games that moved outside the window are not cancellations
+    let later = Utc.with_ymd_and_hms(2025, 9, 23, 0, 0, 0).unwrap();
+    assert!(changes::cancellations(&previous, &current, later, status).is_empty());
+    assert!(changes::cancellations(&previous, &current, now, |_| GameStatus::Listed).is_empty());
+}
+
+#[test]
+fn cancelled_flag_keeps_game_out_of_the_schedule() {
+    let mut json_obj: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string("tests/sample_response.json").unwrap()).unwrap();
+    for item in json_obj["included"].as_array_mut().unwrap() {
+        if item["type"] == "events" && item["id"] == "312149" {
+            item["attributes"]["is_canceled"] = serde_json::json!(1);
+        }
+    }
+    let ds = DaySmart::from_json(&serde_json::to_string(&json_obj).unwrap()).expect("from_json failed");
+    assert_eq!(ds.game_status(312149), GameStatus::Cancelled);
+    assert_eq!(ds.game_status(314460), GameStatus::Listed);
+    assert_eq!(ds.game_status(1), GameStatus::Missing);
+    assert!(ds.upcoming_games(3, Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap()).is_empty());
+}
@@ -0,0 +1,125 @@
+//! Helpers shared by the integration tests: a throwaway state store and a local HTTP server
+//! answering with canned responses.
+// Each test binary compiles this module and uses only some of it
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+
+use hockey_reminder_lambda_rust::state::StateStore;
+
+pub const NO_CONTENT: &str = "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n";
+
+/// A file state store in a fresh temp directory, which is deleted when this is dropped.
+pub struct TempStore {
+    store: StateStore,
+    dir: PathBuf,
+}
+
+impl TempStore {
+    /// The directory, for configs that name it.
+    pub fn dir(&self) -> &str {
+        self.dir.to_str().expect("temp dir is UTF-8")
+    }
+}
+
+impl Deref for TempStore {
+    type Target = StateStore;
+
+    fn deref(&self) -> &StateStore {
+        &self.store
+    }
+}
+
+impl Drop for TempStore {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// A new empty store; `name` only makes the directory easy to spot.
+pub fn temp_store(name: &str) -> TempStore {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!("hockey-{}-{}-{}", name, std::process::id(), NEXT.fetch_add(1, Ordering::SeqCst)));
+    let _ = std::fs::remove_dir_all(&dir);
+    TempStore { store: StateStore::File { dir: dir.clone() }, dir }
+}
+
+/// `body` as a 200 JSON response.
+pub fn json_response(body: &str) -> String {
+    format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)
+}
+
+/// Serve `path` on a local port, answering one request per response, in order. Returns the URL
+/// and the number of requests answered so far.
+pub fn serve(path: &str, responses: Vec<impl Into<String>>) -> (String, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let url = answer(path, responses, move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    (url, hits)
+}
+
+/// [`serve`], handing back everything the client sent (headers and body) for each request.
+pub fn capture(path: &str, responses: Vec<impl Into<String>>) -> (String, mpsc::Receiver<String>) {
+    let (tx, rx) = mpsc::channel();
+    let url = answer(path, responses, move |request| {
+        let _ = tx.send(request);
+    });
+    (url, rx)
+}
+
+fn answer(path: &str, responses: Vec<impl Into<String>>, on_request: impl Fn(String) + Send + 'static) -> String {
+    let responses: Vec<String> = responses.into_iter().map(Into::into).collect();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}{}", listener.local_addr().unwrap(), path);
+    std::thread::spawn(move || {
+        for (stream, response) in listener.incoming().zip(responses) {
+            let mut stream = stream.unwrap();
+            // Recorded before answering, so the client never sees a response before its request counts
+            on_request(read_request(&mut stream));
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    url
+}
+
+/// URL of a local server that accepts connections and never answers.
+pub fn silent_server() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/schedule.ics", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let idle: Vec<_> = listener.incoming().take(4).collect();
+        std::thread::sleep(std::time::Duration::from_secs(5));
+        drop(idle);
+    });
+    url
+}
+
+/// Read until the headers and the Content-Length worth of body are in.
+fn read_request(stream: &mut std::net::TcpStream) -> String {
+    let mut received = Vec::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = stream.read(&mut buf).unwrap_or(0);
+        received.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&received);
+        if n == 0 {
+            break;
+        }
+        if let Some(end) = text.find("\r\n\r\n") {
+            let length = text[..end]
+                .lines()
+                .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap_or(0)))
+                .unwrap_or(0);
+            if received.len() >= end + 4 + length {
+                break;
+            }
+        }
+    }
+    String::from_utf8_lossy(&received).into_owned()
+}
@@ -0,0 +1,53 @@
+use hockey_reminder_lambda_rust::config_doc::{self, ConfigLocation};
+use hockey_reminder_lambda_rust::handler::Request;
+use serde_json::json;
+
+#[test]
+fn locations_parse() {
+    assert_eq!(
+        ConfigLocation::parse("s3://hockey-config/teams/123.json").unwrap(),
+        ConfigLocation::S3 { bucket: "hockey-config".to_string(), key: "teams/123.json".to_string() }
+    );
+    assert_eq!(ConfigLocation::parse("ssm:/hockey/request").unwrap(), ConfigLocation::Parameter("/hockey/request".to_string()));
+    assert_eq!(ConfigLocation::parse("file:///tmp/request.json").unwrap(), ConfigLocation::File("/tmp/request.json".into()));
+    for bad in ["s3://bucket-only", "s3:///key", "ssm:", "https://example.test/request.json"] {
+        assert!(ConfigLocation::parse(bad).is_err(), "{} should be rejected", bad);
+    }
+}
+
+#[test]
+fn scheduled_events_are_recognized() {
+    let event = json!({
+        "version": "0",
+        "id": "53dc4d37-cffa-4f76-80c9-8b7d4a4d2eaa",
+        "detail-type": "Scheduled Event",
+        "source": "aws.events",
+        "account": "123456789012",
+        "time": "2025-09-20T15:00:00Z",
+        "region": "us-east-1",
+        "resources": ["arn:aws:events:us-east-1:123456789012:rule/hockey-daily"],
+        "detail": {}
+    });
+    assert!(config_doc::is_eventbridge_event(&event));
+    assert!(!config_doc::is_eventbridge_event(&json!({ "mode": "test", "team_id": "123" })));
+    assert!(!config_doc::is_eventbridge_event(&json!({})));
+}
+
+#[tokio::test]
+async fn stored_request_is_loaded_and_payload_fields_override_it() {
+    let path = std::env::temp_dir().join(format!("hockey-config-{}.json", std::process::id()));
+    let document = json!({ "mode": "production", "discord_hook_url": "prod", "team_id": "123", "company": "acme", "workflows": ["daysmart"] });
+    std::fs::write(&path, document.to_string()).unwrap();
+    let location = ConfigLocation::parse(&format!("file://{}", path.display())).unwrap();
+
+    let loaded = location.load().await.unwrap();
+    let merged = config_doc::overlay(loaded.clone(), json!({ "mode": "test" })).unwrap();
+    let req: Request = serde_json::from_value(merged).unwrap();
+    assert!(matches!(req.mode, hockey_reminder_lambda_rust::handler::Mode::Test));
+    assert_eq!(req.team_id, "123");
+
+    // A scheduled event adds nothing
+    assert_eq!(config_doc::overlay(loaded, serde_json::Value::Null).unwrap(), document);
+    assert!(config_doc::overlay(json!([]), serde_json::Value::Null).is_err());
+    std::fs::remove_file(path).unwrap();
+}
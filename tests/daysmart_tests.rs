@@ -1,6 +1,6 @@
-use chrono::{TimeZone, Utc};
+use chrono::{TimeZone, Timelike, Utc};
 
-use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::daysmart::{DaySmart, View};
 
 fn load_sample() -> String {
     std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json")
@@ -55,7 +55,7 @@ fn daysmart_generates_benchapp_like_csv_next_4_months() {
 
     // Act: choose a fixed date early in September, to include 9/21 and 9/28 games within ~4 months
     let now = Utc.with_ymd_and_hms(2025, 9, 1, 0, 0, 0).unwrap();
-    let csv = ds.to_benchapp_csv(now);
+    let csv = ds.to_benchapp_csv(now, 4);
 
     // Assert: header present
     assert!(csv.starts_with("Type,Game Type,Title (Optional),Away,Home,Date,Time,Duration,Location (Optional),Address (Optional),Notes (Optional)\n"));
@@ -70,6 +70,16 @@ fn daysmart_generates_benchapp_like_csv_next_4_months() {
     assert!(csv.contains("Locker Room:"), "expected locker room note in CSV when known. csv was: {}", csv);
 }
 
+#[test]
+fn benchapp_csv_respects_months_ahead() {
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed");
+
+    // One month from 8/25 reaches the 9/21 game but not the 9/28 one
+    let now = Utc.with_ymd_and_hms(2025, 8, 25, 0, 0, 0).unwrap();
+    assert_eq!(ds.to_benchapp_csv(now, 1).lines().count(), 2);
+    assert_eq!(ds.to_benchapp_csv(now, 2).lines().count(), 3);
+}
+
 #[test]
 fn filters_out_games_not_belonging_to_our_team() {
     // Arrange
@@ -104,3 +114,447 @@ fn filters_out_games_not_belonging_to_our_team() {
     // There are no other games on 9/25 in the sample, so it should be None
     assert!(msg_opt.is_none(), "Expected no games for our team on this date, but got: {:?}", msg_opt);
 }
+
+#[test]
+fn formats_times_in_configured_timezone() {
+    let json = load_sample();
+    let ds = DaySmart::from_json(&json).expect("from_json failed").with_timezone(chrono_tz::America::New_York);
+
+    // The 2025-09-21 game is 8:45 PM Pacific, i.e. 11:45 PM Eastern
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+    let msg = ds.get_next_game_message(3, now).expect("expected a game within window");
+    assert!(msg.contains("11:45 PM"), "message was: {}", msg);
+    assert!(msg.contains("Sun Sep 21, 2025"), "message was: {}", msg);
+}
+
+#[test]
+fn parses_every_observed_start_gmt_variant() {
+    use hockey_reminder_lambda_rust::daysmart::parse_timestamp;
+
+    let expected = Utc.with_ymd_and_hms(2025, 9, 22, 3, 45, 0).unwrap();
+    for raw in [
+        "2025-09-22T03:45:00Z",
+        "2025-09-22T03:45:00+00:00",
+        "2025-09-21T20:45:00-07:00",
+        "2025-09-22T03:45:00.000Z",
+        "2025-09-22T03:45:00.000000+00:00",
+        "2025-09-22 03:45:00",
+        "2025-09-22 03:45:00.000",
+        "2025-09-22 03:45:00+00:00",
+        "2025-09-22 03:45:00.123+0000",
+        "2025-09-22T03:45:00",
+        "2025-09-22T03:45:00.5",
+        "2025-09-22T03:45",
+        " 2025-09-22  03:45:00 ",
+    ] {
+        let parsed = parse_timestamp(raw).unwrap_or_else(|| panic!("failed to parse {:?}", raw));
+        assert_eq!(parsed.with_nanosecond(0).unwrap(), expected, "input {:?}", raw);
+    }
+    assert_eq!(
+        parse_timestamp("2025-09-22 03:45:00.123").unwrap().timestamp_subsec_millis(),
+        123,
+        "fractional seconds are kept"
+    );
+    assert!(parse_timestamp("Sep 22 2025").is_none());
+    assert!(parse_timestamp("").is_none());
+}
+
+/// Sample document with a stripped-down copy of game 312149 (no rink) added to `included`,
+/// either before or after the original event.
+fn sample_with_duplicate_game(before_original: bool) -> String {
+    let mut json_obj: serde_json::Value = serde_json::from_str(&load_sample()).unwrap();
+    let included = json_obj.get_mut("included").and_then(|v| v.as_array_mut()).unwrap();
+    let mut duplicate = included
+        .iter()
+        .find(|item| item["type"] == "events" && item["id"] == "312149")
+        .cloned()
+        .expect("sample has event 312149");
+    duplicate["attributes"].as_object_mut().unwrap().remove("resource_id");
+    if before_original {
+        included.insert(0, duplicate);
+    } else {
+        included.push(duplicate);
+    }
+    serde_json::to_string(&json_obj).unwrap()
+}
+
+#[test]
+fn duplicate_game_events_keep_the_more_complete_copy() {
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+    for before_original in [false, true] {
+        let ds = DaySmart::from_json(&sample_with_duplicate_game(before_original)).expect("from_json failed");
+        let games = ds.upcoming_games(3, now);
+        assert_eq!(games.len(), 1, "duplicates collapse into one game");
+        assert_eq!(ds.rink_name(&games[0]), "Starbucks Rink 1", "before_original = {}", before_original);
+    }
+}
+
+#[test]
+fn exposes_league_season_and_program_type() {
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed");
+
+    let league = ds.league().expect("sample includes the league");
+    assert_eq!(league.id, 2407);
+    assert_eq!(league.name, "Kraken Hockey League - Fall/Winter 2025-26");
+    assert_eq!(league.season_id, Some(613));
+    assert_eq!(league.start_date, chrono::NaiveDate::from_ymd_opt(2025, 9, 21));
+
+    let season = ds.season().expect("sample includes the season");
+    assert_eq!(season.id, 613);
+    assert_eq!(season.start_date, chrono::NaiveDate::from_ymd_opt(2025, 9, 21));
+    assert_eq!(season.end_date, None);
+
+    assert_eq!(ds.program_type().map(|p| p.name.as_str()), Some("Team"));
+}
+
+#[test]
+fn playoff_games_are_flagged_formatted_and_exported() {
+    let mut json_obj: serde_json::Value = serde_json::from_str(&load_sample()).unwrap();
+    for item in json_obj["included"].as_array_mut().unwrap() {
+        // One playoff game marked by its sub_type, the other only listed under the league's playoffEvents
+        if item["type"] == "events" && item["id"] == "312149" {
+            item["attributes"]["sub_type"] = serde_json::json!("playoff");
+        }
+        if item["type"] == "leagues" {
+            item["relationships"]["playoffEvents"] = serde_json::json!({ "data": [{ "id": "314460", "type": "events" }] });
+        }
+    }
+    let ds = DaySmart::from_json(&serde_json::to_string(&json_obj).unwrap()).expect("from_json failed");
+
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+    let games = ds.upcoming_games(10, now);
+    assert!(games.iter().all(|g| g.is_playoff), "both games should be playoff games");
+
+    let msg = ds.get_next_game_message(3, now).unwrap();
+    assert!(msg.starts_with(":trophy: PLAYOFF GAME"), "message was: {}", msg);
+
+    let csv = ds.to_benchapp_csv(now, 4);
+    assert_eq!(csv.matches("\"PLAYOFF\"").count(), 2, "csv was: {}", csv);
+    assert!(!csv.contains("\"REGULAR\""));
+
+    // The unmodified sample has regular-season games only
+    let regular = DaySmart::from_json(&load_sample()).unwrap();
+    assert!(regular.get_next_game_message(3, now).unwrap().starts_with(":hockey: Kraken Hockey League Game"));
+    assert!(!regular.to_benchapp_csv(now, 4).contains("\"PLAYOFF\""));
+}
+
+#[test]
+fn unlinked_locker_room_events_match_the_overlapping_game_at_the_same_facility() {
+    let sample_with = |edit: &dyn Fn(&mut serde_json::Value)| {
+        let mut json_obj: serde_json::Value = serde_json::from_str(&load_sample()).unwrap();
+        for item in json_obj["included"].as_array_mut().unwrap() {
+            if item["type"] == "events" && item["id"] == "313466" {
+                edit(item);
+            }
+        }
+        DaySmart::from_json(&serde_json::to_string(&json_obj).unwrap()).expect("from_json failed")
+    };
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+
+    // The locker room event lost its parent_event_id but is booked at the game's time and facility
+    let unlinked = sample_with(&|item| item["attributes"]["parent_event_id"] = serde_json::Value::Null);
+    let msg = unlinked.get_next_game_message(3, now).unwrap();
+    assert!(msg.contains("Locker Room: LR11"), "message was: {}", msg);
+
+    // Booked a day later, it belongs to no game
+    let elsewhere = sample_with(&|item| {
+        item["attributes"]["parent_event_id"] = serde_json::Value::Null;
+        item["attributes"]["start_gmt"] = serde_json::json!("2025-09-23T03:45:00");
+        item["attributes"]["end_gmt"] = serde_json::json!("2025-09-23T05:00:00");
+    });
+    let msg = elsewhere.get_next_game_message(3, now).unwrap();
+    assert!(!msg.contains("LR11"), "message was: {}", msg);
+}
+
+#[test]
+fn facility_address_is_shown_with_a_maps_link_and_exported() {
+    let mut json_obj: serde_json::Value = serde_json::from_str(&load_sample()).unwrap();
+    for item in json_obj["included"].as_array_mut().unwrap() {
+        if item["type"] == "facilities" && item["id"] == "1" {
+            item["attributes"]["address1"] = serde_json::json!("10601 5th Ave NE");
+            item["attributes"]["city"] = serde_json::json!("Seattle");
+            item["attributes"]["state"] = serde_json::json!("WA");
+            item["attributes"]["zip"] = serde_json::json!("98125");
+        }
+    }
+    let ds = DaySmart::from_json(&serde_json::to_string(&json_obj).unwrap()).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+
+    let msg = ds.get_next_game_message(3, now).unwrap();
+    assert!(
+        msg.contains("Starbucks Rink 1\n:round_pushpin: 10601 5th Ave NE, Seattle, WA 98125 <https://www.google.com/maps/search/?api=1&query=10601%205th%20Ave%20NE%2C%20Seattle%2C%20WA%2098125>\n"),
+        "message was: {}",
+        msg
+    );
+
+    let csv = ds.to_benchapp_csv(now, 4);
+    assert!(csv.contains("\"Starbucks Rink 1\",\"10601 5th Ave NE, Seattle, WA 98125\""), "csv was: {}", csv);
+
+    // Olympic View Arena's facility isn't included, and the sample's facility has no street address
+    assert!(csv.contains("\"Olympic View Arena\",\"\""), "csv was: {}", csv);
+    let plain = DaySmart::from_json(&load_sample()).unwrap();
+    assert!(!plain.get_next_game_message(3, now).unwrap().contains(":round_pushpin:"));
+}
+
+#[test]
+fn resource_area_is_appended_to_the_rink() {
+    let mut json_obj: serde_json::Value = serde_json::from_str(&load_sample()).unwrap();
+    let included = json_obj["included"].as_array_mut().unwrap();
+    for item in included.iter_mut() {
+        if item["type"] == "events" && item["id"] == "312149" {
+            item["attributes"]["resource_area_id"] = serde_json::json!(5);
+        }
+    }
+    included.push(serde_json::json!({ "type": "resource-areas", "id": "5", "attributes": { "name": "North Rink" } }));
+    let ds = DaySmart::from_json(&serde_json::to_string(&json_obj).unwrap()).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+
+    let msg = ds.get_next_game_message(3, now).unwrap();
+    assert!(msg.contains("at Starbucks Rink 1 – North Rink\n"), "message was: {}", msg);
+
+    // The second game books the whole rink (resource_area_id 0)
+    let csv = ds.to_benchapp_csv(now, 4);
+    assert!(csv.contains("\"Starbucks Rink 1 – North Rink\""), "csv was: {}", csv);
+    assert!(csv.contains("\"Olympic View Arena\""), "csv was: {}", csv);
+}
+
+#[test]
+fn event_comments_are_added_as_league_notes() {
+    let mut json_obj: serde_json::Value = serde_json::from_str(&load_sample()).unwrap();
+    let included = json_obj["included"].as_array_mut().unwrap();
+    for item in included.iter_mut() {
+        if item["type"] == "events" && item["id"] == "312149" {
+            item["relationships"]["comments"] = serde_json::json!({ "data": [{ "id": "902", "type": "comments" }, { "id": "901", "type": "comments" }] });
+        }
+    }
+    included.push(serde_json::json!({ "type": "comments", "id": "901", "attributes": { "comment": "Arrive 30 min early" } }));
+    included.push(serde_json::json!({ "type": "comments", "id": "902", "attributes": { "comment": "Wear alternate jerseys" } }));
+    // Linked only by its event_id; blank comments are dropped
+    included.push(serde_json::json!({ "type": "comments", "id": "903", "attributes": { "comment": "Team photos", "event_id": 314460 } }));
+    included.push(serde_json::json!({ "type": "comments", "id": "904", "attributes": { "comment": "  ", "event_id": 314460 } }));
+    let ds = DaySmart::from_json(&serde_json::to_string(&json_obj).unwrap()).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+
+    let msg = ds.get_next_game_message(3, now).unwrap();
+    assert!(msg.contains("Locker Room: LR11\n:memo: Wear alternate jerseys\n:memo: Arrive 30 min early\n:shirt: Light Jerseys"), "message was: {}", msg);
+
+    let csv = ds.to_benchapp_csv(now, 4);
+    assert!(csv.contains("\"Light Jerseys; Locker Room: LR11; Wear alternate jerseys; Arrive 30 min early\""), "csv was: {}", csv);
+    assert!(csv.contains("Jerseys; Team photos\""), "csv was: {}", csv);
+}
+
+#[test]
+fn practices_are_announced_and_exported_when_included() {
+    let mut json_obj: serde_json::Value = serde_json::from_str(&load_sample()).unwrap();
+    let included = json_obj["included"].as_array_mut().unwrap();
+    let game = included.iter().find(|item| item["type"] == "events" && item["id"] == "312149").unwrap().clone();
+    // Practice ice for our team the night before the game, with a locker room booked for it
+    let mut practice = game.clone();
+    practice["id"] = serde_json::json!("500001");
+    practice["attributes"]["event_type_id"] = serde_json::json!("P");
+    practice["attributes"]["vteam_id"] = serde_json::Value::Null;
+    practice["attributes"]["start_gmt"] = serde_json::json!("2025-09-21T03:00:00");
+    practice["attributes"]["start"] = serde_json::json!("2025-09-20T20:00:00");
+    practice["attributes"]["end_gmt"] = serde_json::json!("2025-09-21T04:00:00");
+    practice["attributes"]["end"] = serde_json::json!("2025-09-20T21:00:00");
+    let mut locker = included.iter().find(|item| item["type"] == "events" && item["id"] == "313466").unwrap().clone();
+    locker["id"] = serde_json::json!("500002");
+    locker["attributes"]["parent_event_id"] = serde_json::json!(500001);
+    // Another team's practice is not ours
+    let mut theirs = practice.clone();
+    theirs["id"] = serde_json::json!("500003");
+    theirs["attributes"]["hteam_id"] = serde_json::json!(10997);
+    included.extend([practice, locker, theirs]);
+    let ds = DaySmart::from_json(&serde_json::to_string(&json_obj).unwrap()).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+
+    // Left out unless asked for
+    assert!(ds.get_next_game_message(3, now).unwrap().starts_with(":hockey: Kraken Hockey League Game"));
+    assert!(!ds.to_benchapp_csv(now, 4).contains("PRACTICE"));
+
+    let ds = ds.with_view(View { include_practices: true, ..Default::default() });
+    let msg = ds.get_next_game_message(3, now).unwrap();
+    assert_eq!(msg, ":hockey: Team Practice\nSat Sep 20, 2025\n8:00 PM at Starbucks Rink 1\nLocker Room: LR11");
+    assert_eq!(ds.thread_name(&ds.next_game(3, now).unwrap()), "Sat Sep 20 practice");
+    assert_eq!(ds.upcoming_games(3, now).len(), 2);
+
+    let csv = ds.to_benchapp_csv(now, 4);
+    assert!(csv.contains("\"PRACTICE\",\"\",\"\",\"\",\"\",\"20/9/2025\",\"08:00 PM\",\"1:00\",\"Starbucks Rink 1\",\"\",\"Locker Room: LR11\""), "csv was: {}", csv);
+    assert_eq!(csv.matches("\"PRACTICE\"").count(), 1, "csv was: {}", csv);
+    assert_eq!(csv.matches("\"GAME\"").count(), 2, "csv was: {}", csv);
+}
+
+#[test]
+fn game_threads_are_named_after_the_local_date_and_opponent() {
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 27, 0, 0, 0).unwrap();
+    let game = ds.next_game(7, now).unwrap();
+    assert_eq!(ds.thread_name(&game), "Sun Sep 28 vs Blackbirds");
+}
+
+#[test]
+fn discord_timestamps_replace_the_local_date_and_time() {
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed").with_view(View { discord_timestamps: true, ..Default::default() });
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+
+    let msg = ds.get_next_game_message(3, now).unwrap();
+    assert!(msg.contains("\n<t:1758512700:D> (<t:1758512700:R>)\n<t:1758512700:t> at Starbucks Rink 1\n"), "message was: {}", msg);
+    assert!(!msg.contains("8:45 PM"), "message was: {}", msg);
+
+    // Custom templates can use the raw start
+    let game = ds.next_game(3, now).unwrap();
+    assert_eq!(ds.format_game_message_with("Game <t:{{timestamp}}:F>", &game).unwrap(), "Game <t:1758512700:F>");
+}
+
+#[test]
+fn arrive_by_line_follows_the_start_time() {
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+    let view = View { arrive_minutes_early: Some(30), ..Default::default() };
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed").with_view(view.clone());
+    let msg = ds.get_next_game_message(3, now).unwrap();
+    assert!(msg.contains("\n8:45 PM at Starbucks Rink 1\n:alarm_clock: Arrive by 8:15 PM (30 min before puck drop)\n"), "message was: {}", msg);
+
+    let ds = ds.with_view(View { discord_timestamps: true, ..view });
+    let msg = ds.get_next_game_message(3, now).unwrap();
+    assert!(msg.contains(":alarm_clock: Arrive by <t:1758510900:t> (30 min before puck drop)"), "message was: {}", msg);
+
+    let plain = DaySmart::from_json(&load_sample()).expect("from_json failed").get_next_game_message(3, now).unwrap();
+    assert!(!plain.contains("Arrive by"), "message was: {}", plain);
+}
+
+#[test]
+fn venue_notes_are_added_to_reminders_and_csv_notes() {
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+    let notes = std::collections::HashMap::from([("starbucks rink 1 ".to_string(), "Park in the north lot; rink door code 4521".to_string())]);
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed").with_view(View { venue_notes: notes, ..Default::default() });
+
+    let msg = ds.get_next_game_message(3, now).unwrap();
+    assert!(msg.contains("\n:information_source: Park in the north lot; rink door code 4521\n:shirt:"), "message was: {}", msg);
+    let csv = ds.to_benchapp_csv(now, 4);
+    assert!(csv.lines().any(|line| line.contains("Starbucks Rink 1") && line.ends_with("; Park in the north lot; rink door code 4521\"")), "csv was: {}", csv);
+    // Other rinks have no note
+    assert!(csv.lines().skip(1).any(|line| !line.contains("door code")), "csv was: {}", csv);
+}
+
+#[test]
+fn completed_games_get_a_score_recap() {
+    let mut json_obj: serde_json::Value = serde_json::from_str(&load_sample()).unwrap();
+    for item in json_obj["included"].as_array_mut().unwrap() {
+        if item["type"] == "events" && item["id"] == "312149" {
+            item["attributes"]["home_score"] = serde_json::json!(5);
+            item["attributes"]["visiting_score"] = serde_json::json!("3");
+        }
+    }
+    let ds = DaySmart::from_json(&serde_json::to_string(&json_obj).unwrap()).expect("from_json failed");
+
+    // 312149 starts 2025-09-22T03:45Z, i.e. Sunday evening Sep 21 in Los Angeles
+    let played = ds.completed_games_on(chrono::NaiveDate::from_ymd_opt(2025, 9, 21).unwrap());
+    assert_eq!(played.len(), 1);
+    assert_eq!(played[0].score, Some((5, 3)));
+    assert_eq!(ds.format_recap(&played[0]).unwrap(), ":hockey: Final: Yacht Flippers 5 – 3 Seal Team Sticks");
+    // We are the home team, so 5 – 3 is a win; unscored games don't count
+    assert_eq!(ds.results(), vec![hockey_reminder_lambda_rust::streak::GameResult::Win]);
+
+    assert!(ds.completed_games_on(chrono::NaiveDate::from_ymd_opt(2025, 9, 22).unwrap()).is_empty());
+    // Games without a score are not recapped
+    assert!(ds.completed_games_on(chrono::NaiveDate::from_ymd_opt(2025, 9, 28).unwrap()).is_empty());
+}
+
+#[test]
+fn ics_export_has_one_event_per_game_with_jersey_and_locker_room() {
+    let json = std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json");
+    let ds = DaySmart::from_json(&json).expect("from_json failed");
+    let games = ds.all_games();
+    assert!(!games.is_empty());
+    let ics = hockey_reminder_lambda_rust::ical::writer::write_calendar(&ds, &games);
+    assert!(ics.starts_with("BEGIN:VCALENDAR"), "ics was: {}", ics);
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), games.len());
+    for game in &games {
+        assert!(ics.contains(&format!("UID:daysmart-{}", game.id)), "ics was: {}", ics);
+    }
+    assert!(ics.contains("DTSTART:") && ics.contains("DTEND:"), "ics was: {}", ics);
+    assert!(ics.contains("LOCATION:Starbucks Rink 1"), "ics was: {}", ics);
+    assert!(ics.contains("Jerseys"), "ics was: {}", ics);
+    assert!(ics.contains("Locker Room:"), "ics was: {}", ics);
+}
+
+#[test]
+fn explain_reports_what_was_derived_for_a_game() {
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed");
+    let explanation = ds.explain(312149, None);
+    assert_eq!(explanation.status, "listed");
+    let game = explanation.game.expect("listed game has details");
+    assert_eq!(game.start_utc, "2025-09-22T03:45:00+00:00");
+    assert_eq!(game.start_local, "2025-09-21T20:45:00-07:00");
+    assert_eq!(game.timezone, "America/Los_Angeles");
+    assert!(game.home);
+    assert_eq!(game.home_team.shown, "Yacht Flippers");
+    assert_eq!(game.opponent, "Seal Team Sticks");
+    assert_eq!(game.jersey, "Light");
+    assert_eq!(game.home_locker_room.name.as_deref(), Some("LR11"));
+    assert!(game.includes.iter().any(|i| i.ends_with("(locker room assignment)")), "includes were: {:?}", game.includes);
+    assert!(game.reminder.contains("Locker Room: LR11"), "reminder was: {}", game.reminder);
+
+    let unknown = ds.explain(1, None);
+    assert_eq!(unknown.status, "missing");
+    assert!(unknown.game.is_none());
+}
+
+#[test]
+fn placeholder_opponents_are_worded_as_undecided() {
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+    let msg = ds.get_next_game_message(3, now).unwrap();
+    assert!(!msg.contains("Opponent not decided"), "message was: {}", msg);
+
+    // A regular season game is against a team, whatever it is called
+    let renamed = load_sample().replace("Seal Team Sticks", "Winner of Game 3");
+    let msg = DaySmart::from_json(&renamed).expect("from_json failed").get_next_game_message(3, now).unwrap();
+    assert!(!msg.contains("Opponent not decided"), "message was: {}", msg);
+
+    let mut json_obj: serde_json::Value = serde_json::from_str(&renamed).unwrap();
+    for item in json_obj["included"].as_array_mut().unwrap() {
+        if item["type"] == "events" && item["id"] == "312149" {
+            item["attributes"]["sub_type"] = serde_json::json!("playoff");
+        }
+    }
+    let bracket = DaySmart::from_json(&serde_json::to_string(&json_obj).unwrap()).expect("from_json failed");
+    let msg = bracket.get_next_game_message(3, now).unwrap();
+    assert!(msg.contains("Yacht Flippers vs Winner of Game 3\n:grey_question: Opponent not decided yet"), "message was: {}", msg);
+}
+
+#[test]
+fn durations_come_from_daysmart_end_times() {
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed");
+    let games = ds.all_games();
+    // Both sample games are booked 75-minute slots
+    assert!(games.iter().all(|g| g.duration() == chrono::Duration::minutes(75)), "games: {:?}", games);
+
+    let csv = ds.to_benchapp_csv(Utc.with_ymd_and_hms(2025, 9, 1, 0, 0, 0).unwrap(), 4);
+    assert!(csv.contains("\"1:15\""), "csv was: {}", csv);
+    assert!(!csv.contains("\"1:00\""), "csv was: {}", csv);
+    let ics = hockey_reminder_lambda_rust::ical::writer::write_calendar(&ds, &games);
+    assert!(ics.contains("DTEND:20250922T050000Z"), "ics was: {}", ics);
+
+    // No end, or one that makes no sense, falls back to an hour
+    let mut game = games[0].clone();
+    game.end = None;
+    assert_eq!(game.duration(), chrono::Duration::minutes(60));
+    game.end = Some(game.dt - chrono::Duration::minutes(30));
+    assert_eq!(game.duration(), chrono::Duration::minutes(60));
+}
+
+#[test]
+fn benchapp_csv_leaves_missing_names_empty() {
+    let mut json_obj: serde_json::Value = serde_json::from_str(&load_sample()).unwrap();
+    // Keep only our own team, and drop every rink
+    json_obj["included"].as_array_mut().unwrap().retain(|item| item["type"] != "teams" && item["type"] != "resources");
+    let ds = DaySmart::from_json(&serde_json::to_string(&json_obj).unwrap()).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+
+    assert!(ds.get_next_game_message(3, now).unwrap().contains("Unknown Arena"));
+    let csv = ds.to_benchapp_csv(now, 4);
+    let row = csv.lines().nth(1).unwrap();
+    assert_eq!(row, "\"GAME\",\"REGULAR\",\"\",\"\",\"Yacht Flippers\",\"21/9/2025\",\"08:45 PM\",\"1:15\",\"\",\"\",\"Light Jerseys\"");
+    assert!(!csv.contains("Unknown Arena") && !csv.contains("\"Visitor\""), "csv was: {}", csv);
+}
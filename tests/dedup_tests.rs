@@ -0,0 +1,82 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::dedup;
+use hockey_reminder_lambda_rust::discord::PostedMessage;
+
+#[test]
+fn content_hash_is_stable() {
+    // FNV-1a test vectors
+    assert_eq!(dedup::content_hash(""), "cbf29ce484222325");
+    assert_eq!(dedup::content_hash("a"), "af63dc4c8601ec8c");
+    assert_ne!(dedup::content_hash("Game at 7:45 PM"), dedup::content_hash("Game at 8:45 PM"));
+}
+
+#[test]
+fn crossed_offset_is_the_latest_passed_before_the_game() {
+    let start = Utc.with_ymd_and_hms(2025, 9, 28, 19, 0, 0).unwrap();
+    let offsets = [72, 24, 2];
+    let hours_before = |hours| start - chrono::Duration::hours(hours);
+    assert_eq!(dedup::crossed_offset(&offsets, start, hours_before(80)), None);
+    assert_eq!(dedup::crossed_offset(&offsets, start, hours_before(72)), Some(72));
+    assert_eq!(dedup::crossed_offset(&offsets, start, hours_before(10)), Some(24));
+    assert_eq!(dedup::crossed_offset(&offsets, start, hours_before(1)), Some(2));
+    assert_eq!(dedup::crossed_offset(&offsets, start, start), None, "no reminders once the game started");
+}
+
+#[tokio::test]
+async fn identical_reminder_is_recognized_until_it_changes() {
+    let store = common::temp_store("dedup");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 15, 0, 0).unwrap();
+    let game_start = Utc.with_ymd_and_hms(2025, 9, 22, 3, 45, 0).unwrap();
+    assert!(dedup::posted(&store, "11007", "312149").await.unwrap().is_none());
+
+    dedup::record_posted(&store, "11007", "312149", "Game Sunday", None, None, now, game_start).await.unwrap();
+    let record = dedup::posted(&store, "11007", "312149").await.unwrap().expect("the post is recorded");
+    assert!(record.matches("Game Sunday"));
+    assert!(!record.matches("Game Sunday, new rink"));
+    assert!(dedup::posted(&store, "11007", "314460").await.unwrap().is_none(), "records are per game");
+
+    // The message id is kept so a changed reminder can edit it
+    dedup::record_posted(&store, "11007", "312149", "Game Sunday, new rink", Some(&PostedMessage { id: "9001".to_string(), channel_id: Some("77".to_string()) }), None, now, game_start).await.unwrap();
+    let record = dedup::posted(&store, "11007", "312149").await.unwrap().expect("posted record");
+    assert_eq!(record.message().map(|m| m.id), Some("9001".to_string()));
+    assert_eq!(record.channel_id.as_deref(), Some("77"));
+    assert!(record.matches("Game Sunday, new rink"));
+}
+
+#[tokio::test]
+async fn posted_records_last_until_after_a_game_months_away() {
+    let store = common::temp_store("dedup");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 15, 0, 0).unwrap();
+    let game_start = now + chrono::Duration::days(60);
+    dedup::record_posted(&store, "11007", "312149", "Game in November", None, None, now, game_start).await.unwrap();
+
+    // Still there when the game is a day away, so the reminder isn't posted again
+    store.remove_expired(game_start - chrono::Duration::days(1)).await.unwrap();
+    assert!(dedup::posted(&store, "11007", "312149").await.unwrap().is_some());
+
+    store.remove_expired(game_start + chrono::Duration::days(dedup::POSTED_RECORD_TTL_DAYS)).await.unwrap();
+    assert!(dedup::posted(&store, "11007", "312149").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn failed_reminder_stays_pending_until_cleared() {
+    let store = common::temp_store("dedup");
+    let failed_at = Utc.with_ymd_and_hms(2025, 9, 20, 15, 0, 0).unwrap();
+    let game_start = Utc.with_ymd_and_hms(2025, 9, 21, 2, 0, 0).unwrap();
+    assert!(dedup::pending(&store, "11007").await.unwrap().is_none());
+
+    dedup::record_failed(&store, "11007", 312149, "HTTP 500", failed_at, game_start).await.unwrap();
+    let pending = dedup::pending(&store, "11007").await.unwrap().expect("pending announcement");
+    assert_eq!(pending.game_id, 312149);
+    assert_eq!(pending.error, "HTTP 500");
+    // Retries off schedule are throttled
+    assert!(!pending.retry_due(failed_at + chrono::Duration::minutes(10)));
+    assert!(pending.retry_due(failed_at + chrono::Duration::minutes(dedup::PENDING_RETRY_INTERVAL_MINUTES)));
+    assert!(dedup::pending(&store, "11008").await.unwrap().is_none(), "pending announcements are per team");
+
+    dedup::clear_pending(&store, "11007").await.unwrap();
+    assert!(dedup::pending(&store, "11007").await.unwrap().is_none());
+}
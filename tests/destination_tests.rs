@@ -0,0 +1,147 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use common::NO_CONTENT;
+use hockey_reminder_lambda_rust::destination::{DeliveryLog, Destinations, FanOutConfig, Publisher};
+use hockey_reminder_lambda_rust::discord::Discord;
+use hockey_reminder_lambda_rust::error::ErrorCode;
+use hockey_reminder_lambda_rust::handler::Workflow;
+use hockey_reminder_lambda_rust::notifier::{Embed, Notifier, NotifyFuture};
+use hockey_reminder_lambda_rust::render::RenderProfile;
+
+/// Answer a single request with a canned response.
+fn serve_once(response: &'static str) -> String {
+    common::serve("/webhook", vec![response]).0
+}
+
+const BAD_REQUEST: &str = "HTTP/1.1 400 Bad Request\r\nContent-Length: 3\r\nConnection: close\r\n\r\nbad";
+
+#[test]
+fn destinations_default_to_no_extra_hooks() {
+    let destinations: Destinations = serde_json::from_str("{}").unwrap();
+    assert!(destinations.discord && destinations.discord_hooks.is_empty() && destinations.fan_out.is_none());
+    let fan_out: FanOutConfig = serde_json::from_str(r#"{ "batch_size": 10 }"#).unwrap();
+    assert_eq!(fan_out, FanOutConfig { batch_size: 10, batch_delay_ms: 1_000 });
+}
+
+#[tokio::test]
+async fn fan_out_batches_posts_per_host_and_reports_each_destination() {
+    let hooks = vec![serve_once(NO_CONTENT), serve_once(BAD_REQUEST), serve_once(NO_CONTENT)];
+    let publisher = Publisher::new(None)
+        .with_notifiers(hooks.into_iter().map(|hook| Arc::new(Discord::new(hook)) as Arc<dyn Notifier>))
+        .with_fan_out(FanOutConfig { batch_size: 2, batch_delay_ms: 150 });
+
+    let started = Instant::now();
+    let reports = publisher.deliver("hello", None).await;
+    // All three hooks share a host, so the third waits for the second batch
+    assert!(started.elapsed() >= Duration::from_millis(150), "elapsed {:?}", started.elapsed());
+
+    assert_eq!(reports.len(), 3);
+    assert!(reports.iter().all(|r| r.destination.starts_with("discord http://127.0.0.1:")));
+    assert!(reports[0].error.is_none());
+    assert!(reports[1].error.as_deref().is_some_and(|e| e.contains("400")), "reports were: {:?}", reports);
+    let info = reports[1].error_info.as_ref().unwrap();
+    assert_eq!((info.code, info.status), (ErrorCode::Discord, Some(400)));
+    assert!(reports[2].error.is_none());
+}
+
+#[tokio::test]
+async fn posts_are_logged_with_their_workflow_and_each_delivery() {
+    let log = DeliveryLog::default();
+    let publisher = Publisher::new(Some(Arc::new(Discord::new(serve_once(NO_CONTENT)))))
+        .with_notifiers([Arc::new(Discord::new(serve_once(BAD_REQUEST))) as Arc<dyn Notifier>])
+        .with_log(log.clone());
+
+    assert!(publisher.clone().for_workflow(Workflow::Daysmart).post("hello").await.is_err());
+
+    let posts = log.lock().unwrap().clone();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0].workflow, Some(Workflow::Daysmart));
+    assert_eq!(posts[0].content, "hello");
+    let logged = &posts[0].deliveries;
+    assert_eq!(logged.len(), 2);
+    assert!(logged.iter().all(|r| r.workflow == Some(Workflow::Daysmart)));
+    assert!(logged[0].error.is_none(), "logged: {:?}", logged);
+    assert!(logged[1].error.as_deref().is_some_and(|e| e.contains("400")), "logged: {:?}", logged);
+    let json = serde_json::to_value(&logged[0]).unwrap();
+    assert_eq!(json["workflow"], "daysmart");
+    assert!(json.get("error").is_none());
+}
+
+#[tokio::test]
+async fn dry_run_records_posts_without_sending() {
+    let log = DeliveryLog::default();
+    // Nothing listens on port 9, so a real post would fail
+    let publisher = Publisher::new(Some(Arc::new(Discord::new("http://127.0.0.1:9/webhook".to_string()))))
+        .with_log(log.clone())
+        .dry_run()
+        .for_workflow(Workflow::Daysmart);
+
+    publisher.post_with_attachment("hello", "games.csv", b"Date,Time\n").await.expect("dry runs don't post");
+
+    let posts = log.lock().unwrap().clone();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0].content, "hello");
+    let attachment = posts[0].attachment.as_ref().unwrap();
+    assert_eq!((attachment.filename.as_str(), attachment.content.as_str()), ("games.csv", "Date,Time\n"));
+    assert!(posts[0].deliveries.is_empty());
+}
+
+/// A destination defined outside the crate, keeping what it was sent.
+#[derive(Debug, Default)]
+struct Recorder {
+    endpoint: String,
+    sent: Arc<Mutex<Vec<String>>>,
+}
+
+impl Notifier for Recorder {
+    fn kind(&self) -> &'static str {
+        "recorder"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn profile(&self) -> RenderProfile {
+        RenderProfile::PlainText
+    }
+
+    fn retarget(&self, endpoint: &str) -> Arc<dyn Notifier> {
+        Arc::new(Recorder { endpoint: endpoint.to_string(), sent: self.sent.clone() })
+    }
+
+    fn post_text<'a>(&'a self, content: &'a str) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            self.sent.lock().unwrap().push(format!("{}: {}", self.endpoint, content));
+            Ok(())
+        })
+    }
+
+    fn post_file<'a>(&'a self, content: &'a str, filename: &'a str, _bytes: &'a [u8]) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            self.sent.lock().unwrap().push(format!("{}: {} [{}]", self.endpoint, content, filename));
+            Ok(())
+        })
+    }
+}
+
+#[tokio::test]
+async fn new_notifiers_plug_into_the_publisher() {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::new(Recorder { endpoint: "https://example.test/main".to_string(), sent: sent.clone() });
+    let publisher = Publisher::new(Some(recorder.clone())).with_discord_hook("https://example.test/workflow");
+
+    let reports = publisher.post_with_attachment(":hockey: **Game day**", "games.csv", b"").await;
+    assert!(reports.is_ok());
+    let embed = Embed { title: "Final".to_string(), description: "4-2 W at <t:1758512700:t>".to_string(), ..Default::default() };
+    recorder.post_embed(&embed, chrono_tz::America::Los_Angeles).await.unwrap();
+
+    let sent = sent.lock().unwrap().clone();
+    // Rendered for the notifier's profile, and sent to the workflow's own endpoint
+    assert_eq!(sent[0], "https://example.test/workflow: Game day [games.csv]");
+    // Sinks without embeds get the card as text, with times in the given timezone
+    assert_eq!(sent[1], "https://example.test/main: Final\n4-2 W at 8:45 PM PDT");
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::changes;
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::digest;
+use hockey_reminder_lambda_rust::model::game::GameStatus;
+
+fn sample() -> DaySmart {
+    let json = std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json");
+    DaySmart::from_json(&json).expect("from_json failed")
+}
+
+#[test]
+fn digest_lists_the_week_and_how_far_the_schedule_runs() {
+    let ds = sample();
+    // Sunday 9/14, 9 PM local: the 9/21 game is within the week, 9/28 is not
+    let now = Utc.with_ymd_and_hms(2025, 9, 15, 4, 0, 0).unwrap();
+    let entries = digest::week_entries(&ds, now);
+    assert_eq!(entries.len(), 1);
+
+    let until = NaiveDate::from_ymd_opt(2025, 12, 14);
+    let message = digest::format_digest(&entries, None, until, chrono_tz::America::Los_Angeles, "none");
+    assert!(message.starts_with(":calendar_spiral: **Week ahead**\n:hockey: Sun Sep 21, 8:45 PM at Starbucks Rink 1: vs "), "message was: {}", message);
+    assert!(message.contains("(Light Jerseys), Locker Room: LR11"), "message was: {}", message);
+    assert!(message.ends_with("\n\n:date: Games scheduled until Sun Dec 14, 2025"), "message was: {}", message);
+
+    let empty = digest::format_digest(&[], None, None, chrono_tz::America::Los_Angeles, "none");
+    assert_eq!(empty, ":calendar_spiral: **Week ahead**\nNo games this week.");
+}
+
+#[tokio::test]
+async fn digest_reports_changes_since_the_previous_digest() {
+    let store = common::temp_store("digest");
+    let ds = sample();
+    let now = Utc.with_ymd_and_hms(2025, 9, 15, 4, 0, 0).unwrap();
+    let snapshot = changes::snapshot(&ds, &ds.upcoming_games(60, now));
+    let status = |_| GameStatus::Cancelled;
+
+    let first = digest::changes_since_last(&store, "11007", snapshot.clone(), now, status).await.unwrap();
+    assert!(first.changed.is_empty() && first.cancelled.is_empty(), "nothing to compare with yet");
+
+    // A week later one game moved rinks and another is gone
+    let mut current = snapshot.clone();
+    let (&moved, _) = current.iter().next().unwrap();
+    current.get_mut(&moved).unwrap().rink = "Olympic View Arena".to_string();
+    let (&dropped, _) = current.iter().nth(1).unwrap();
+    current.remove(&dropped);
+    let found = digest::changes_since_last(&store, "11007", current, now, status).await.unwrap();
+    assert_eq!(found.changed.len(), 1);
+    assert_eq!(found.cancelled.iter().map(|c| c.game_id).collect::<Vec<_>>(), vec![dropped]);
+
+    let message = digest::format_digest(&[], Some(&found), None, chrono_tz::America::Los_Angeles, "none");
+    assert!(message.contains(":warning: **Schedule change**"), "message was: {}", message);
+    assert!(message.contains(":no_entry: **Game cancelled**"), "message was: {}", message);
+}
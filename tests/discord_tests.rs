@@ -1,3 +1,6 @@
+mod common;
+
+use common::NO_CONTENT;
 use hockey_reminder_lambda_rust::discord::Discord;
 
 #[test]
@@ -13,3 +16,265 @@ fn discord_new_clones_url() {
     // Avoid network: don't call post/post_with_attachment here
     let _ = url; // silence unused
 }
+
+#[test]
+fn zip_attachment_preserves_filename_and_content() {
+    use hockey_reminder_lambda_rust::discord::zip_attachment;
+    use std::io::Read;
+
+    let csv = "Type,Game Type\n".repeat(1000);
+    let (name, zipped) = zip_attachment("games.csv", csv.as_bytes()).expect("zip failed");
+    assert_eq!(name, "games.csv.zip");
+    assert!(zipped.len() < csv.len(), "repetitive CSV should compress");
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zipped)).expect("valid archive");
+    assert_eq!(archive.len(), 1);
+    let mut file = archive.by_name("games.csv").expect("original filename inside archive");
+    let mut out = String::new();
+    file.read_to_string(&mut out).unwrap();
+    assert_eq!(out, csv);
+}
+
+const TOO_MANY: &str = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0.01\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 4\r\nConnection: close\r\n\r\nbusy";
+const BAD_REQUEST: &str = "HTTP/1.1 400 Bad Request\r\nContent-Length: 3\r\nConnection: close\r\n\r\nbad";
+
+fn fast_policy() -> hockey_reminder_lambda_rust::discord::RetryPolicy {
+    hockey_reminder_lambda_rust::discord::RetryPolicy {
+        max_attempts: 3,
+        base_delay: std::time::Duration::from_millis(5),
+        max_delay: std::time::Duration::from_secs(1),
+    }
+}
+
+#[tokio::test]
+async fn post_retries_rate_limits_and_server_errors() {
+    let (url, hits) = common::serve("/webhook", vec![TOO_MANY, UNAVAILABLE, NO_CONTENT]);
+    let discord = Discord::new(url).with_retry_policy(fast_policy());
+    discord.post("hello").await.expect("third attempt succeeds");
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn post_surfaces_typed_error_after_exhausting_attempts() {
+    use hockey_reminder_lambda_rust::discord::DiscordError;
+
+    let (url, hits) = common::serve("/webhook", vec![UNAVAILABLE, UNAVAILABLE, UNAVAILABLE]);
+    let err = Discord::new(url).with_retry_policy(fast_policy()).post("hello").await.unwrap_err();
+    assert_eq!(err, DiscordError::Status { status: 503, body: "busy".to_string(), attempts: 3 });
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+    // Client errors are not retried
+    let (url, hits) = common::serve("/webhook", vec![BAD_REQUEST, NO_CONTENT]);
+    let err = Discord::new(url).with_retry_policy(fast_policy()).post_with_attachment("hi", "a.csv", b"x").await.unwrap_err();
+    assert!(matches!(err, DiscordError::Status { status: 400, attempts: 1, .. }), "{:?}", err);
+    assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[test]
+fn backoff_is_capped_and_retry_after_parses_fractions() {
+    use hockey_reminder_lambda_rust::discord::{parse_retry_after, RetryPolicy};
+    use std::time::Duration;
+
+    let policy = RetryPolicy::default();
+    for retry in 1..10 {
+        let delay = policy.backoff(retry);
+        let exp = (policy.base_delay * 2u32.pow(retry - 1)).min(policy.max_delay);
+        assert!(delay >= exp / 2 && delay <= exp, "retry {}: {:?}", retry, delay);
+    }
+    assert_eq!(parse_retry_after("1.5"), Some(Duration::from_millis(1500)));
+    assert_eq!(parse_retry_after("soon"), None);
+}
+
+/// Answer one request with 204 and hand back everything the client sent.
+fn capture_one() -> (String, std::sync::mpsc::Receiver<String>) {
+    capture(1)
+}
+
+/// Answer `requests` requests with 204 and hand back everything the client sent, in order.
+fn capture(requests: usize) -> (String, std::sync::mpsc::Receiver<String>) {
+    capture_replying(vec![NO_CONTENT; requests])
+}
+
+/// Answer one request per response, in order, and hand back everything the client sent.
+fn capture_replying(responses: Vec<&'static str>) -> (String, std::sync::mpsc::Receiver<String>) {
+    common::capture("/webhook", responses)
+}
+
+#[tokio::test]
+async fn attachment_uploads_are_identical_with_sequential_ids() {
+    use hockey_reminder_lambda_rust::clock::SequentialIds;
+
+    let mut bodies = Vec::new();
+    for _ in 0..2 {
+        let (url, received) = capture_one();
+        let discord = Discord::new(url).with_ids(std::sync::Arc::new(SequentialIds::starting_at(1)));
+        discord.post_with_attachment("Game day", "games.csv", b"Type,Game Type\n").await.unwrap();
+        let request = received.recv().unwrap();
+        bodies.push(request.split_once("\r\n\r\n").unwrap().1.to_string());
+    }
+    assert!(bodies[0].starts_with("-----------------------------12\r\n"), "body was: {}", bodies[0]);
+    assert_eq!(bodies[0], bodies[1]);
+}
+
+#[tokio::test]
+async fn mentions_are_prepended_and_only_they_ping() {
+    use hockey_reminder_lambda_rust::discord::Mentions;
+
+    let mentions = Mentions { role_id: Some("111".to_string()), user_ids: vec!["222".to_string(), "333".to_string()] };
+    let (url, received) = capture_one();
+    Discord::new(url).with_mentions(mentions.clone()).post("Game day @everyone").await.unwrap();
+    let request = received.recv().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+    assert_eq!(payload["content"], "<@&111> <@222> <@333>\nGame day @everyone");
+    assert_eq!(payload["allowed_mentions"], serde_json::json!({ "parse": [], "roles": ["111"], "users": ["222", "333"] }));
+
+    // Attachments carry them in payload_json
+    let (url, received) = capture_one();
+    Discord::new(url).with_mentions(mentions).post_with_attachment("Game day", "games.csv", b"Type\n").await.unwrap();
+    let request = received.recv().unwrap();
+    assert!(request.contains(r#""allowed_mentions":{"parse":[],"roles":["111"],"users":["222","333"]}"#), "request was: {}", request);
+    assert!(request.contains(r#""content":"<@&111> <@222> <@333>\nGame day""#), "request was: {}", request);
+
+    // Without mentions the payload is unchanged
+    let (url, received) = capture_one();
+    Discord::new(url).post("Game day").await.unwrap();
+    let request = received.recv().unwrap();
+    assert!(request.ends_with(r#"{"content":"Game day"}"#), "request was: {}", request);
+}
+
+#[tokio::test]
+async fn post_options_brand_messages_and_embeds() {
+    use hockey_reminder_lambda_rust::discord::{AllowedMentions, PostOptions};
+    use hockey_reminder_lambda_rust::notifier::Embed;
+
+    let options: PostOptions = serde_json::from_value(serde_json::json!({
+        "username": "Scorekeeper",
+        "avatar_url": "https://example.com/whistle.png",
+        "allowed_mentions": { "parse": ["roles"] },
+        "suppress_embeds": true
+    }))
+    .unwrap();
+    assert_eq!(options.allowed_mentions, Some(AllowedMentions { parse: vec!["roles".to_string()], ..Default::default() }));
+
+    let (url, received) = capture_one();
+    Discord::new(url).with_options(options.clone()).post("Final: 4-2").await.unwrap();
+    let request = received.recv().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+    assert_eq!(
+        payload,
+        serde_json::json!({
+            "content": "Final: 4-2",
+            "username": "Scorekeeper",
+            "avatar_url": "https://example.com/whistle.png",
+            "allowed_mentions": { "parse": ["roles"], "roles": [], "users": [] },
+            "flags": 4
+        })
+    );
+
+    // A single post can use its own options instead of the client's
+    let (url, received) = capture_one();
+    Discord::new(url).with_options(options.clone()).post_with_options("Hi", &PostOptions::default()).await.unwrap();
+    assert!(received.recv().unwrap().ends_with(r#"{"content":"Hi"}"#));
+
+    let (url, received) = capture_one();
+    Discord::new(url).with_options(options).post_embed(&Embed { title: "Final".to_string(), ..Default::default() }).await.unwrap();
+    let request = received.recv().unwrap();
+    assert!(request.contains(r#""username":"Scorekeeper""#) && request.contains(r#""flags":4"#), "request was: {}", request);
+}
+
+#[test]
+fn long_content_splits_between_lines_and_keeps_code_blocks_closed() {
+    use hockey_reminder_lambda_rust::discord::split_content;
+
+    assert_eq!(split_content("short\nmessage", 40), vec!["short\nmessage"]);
+
+    let parts = split_content("aaaaaaaaaa\nbbbbbbbbbb\ncccccccccc\ndddddddddd", 30);
+    assert_eq!(parts, vec!["aaaaaaaaaa\nbbbbbbbbbb", "cccccccccc\ndddddddddd"]);
+
+    // A line longer than a whole part is cut
+    let parts = split_content(&"x".repeat(50), 30);
+    assert_eq!(parts, vec!["x".repeat(22), "x".repeat(22), "x".repeat(6)]);
+
+    let table = "Standings\n```text\nTeam A 5 1\nTeam B 4 2\nTeam C 3 3\nTeam D 2 4\n```\nDone";
+    let parts = split_content(table, 40);
+    assert!(parts.iter().all(|p| p.chars().count() <= 40), "parts were: {:?}", parts);
+    assert!(parts.iter().all(|p| p.matches("```").count() % 2 == 0), "parts were: {:?}", parts);
+    assert_eq!(parts[1].lines().next(), Some("```text"), "parts were: {:?}", parts);
+    let rejoined: Vec<&str> = parts.iter().flat_map(|p| p.lines()).filter(|l| !l.starts_with("```")).collect();
+    assert_eq!(rejoined, vec!["Standings", "Team A 5 1", "Team B 4 2", "Team C 3 3", "Team D 2 4", "Done"]);
+}
+
+#[tokio::test]
+async fn over_long_messages_go_out_once_with_the_whole_text_attached() {
+    let line = "y".repeat(99);
+    let content = vec![line.as_str(); 30].join("\n");
+    let (url, received) = capture(1);
+    Discord::new(url).post_with_attachment(&content, "games.csv", b"Type\n").await.unwrap();
+    let request = received.recv().unwrap();
+    assert!(request.contains("multipart/form-data"));
+    let payload_json = request.split("name=\"payload_json\"").nth(1).unwrap().split("\r\n\r\n").nth(1).unwrap().split("\r\n").next().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(payload_json).unwrap();
+    let text = payload["content"].as_str().unwrap();
+    assert!(text.chars().count() <= 2000, "{} chars", text.chars().count());
+    assert!(text.starts_with(&line) && text.ends_with("Full message in message.txt"), "content was: {}", text);
+    assert_eq!(payload["attachments"], serde_json::json!([{ "id": 0, "filename": "games.csv" }, { "id": 1, "filename": "message.txt" }]));
+    assert!(request.contains("name=\"files[1]\"; filename=\"message.txt\""));
+    assert!(request.contains(&content), "the attachment carries the whole text");
+}
+
+#[test]
+fn fitting_content_is_kept_whole() {
+    use hockey_reminder_lambda_rust::discord::fit_content;
+
+    assert_eq!(fit_content("Game Sunday", 2000), ("Game Sunday".to_string(), None));
+    let content = vec!["z"; 1500].join("\n");
+    let (text, whole) = fit_content(&content, 2000);
+    assert!(text.chars().count() <= 2000);
+    assert_eq!(whole.as_deref(), Some(content.as_str()));
+}
+
+const POSTED: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 26\r\nConnection: close\r\n\r\n{\"id\":\"9001\",\"content\":\"\"}";
+
+#[tokio::test]
+async fn posts_wait_for_the_message_id_and_edits_replace_it() {
+    let (url, received) = capture_replying(vec![POSTED, POSTED, NO_CONTENT, NO_CONTENT]);
+    let discord = Discord::new(format!("{}?thread_id=77", url));
+    let id = discord.post_with_attachment("Game Sunday", "games.csv", b"Type\n").await.unwrap();
+    assert_eq!(id.map(|message| message.id).as_deref(), Some("9001"));
+    assert!(received.recv().unwrap().starts_with("POST /webhook?thread_id=77&wait=true HTTP/1.1"));
+
+    // An over-long message still goes out as one, so it can be edited later
+    let long = discord.post(&"z\n".repeat(1500)).await.unwrap();
+    assert_eq!(long.map(|message| message.id).as_deref(), Some("9001"));
+    assert!(received.recv().unwrap().starts_with("POST /webhook?thread_id=77&wait=true "));
+
+    discord.edit("9001", "Game Sunday, new rink").await.unwrap();
+    let request = received.recv().unwrap();
+    assert!(request.starts_with("PATCH /webhook/messages/9001?thread_id=77 HTTP/1.1"), "request was: {}", request);
+    let payload: serde_json::Value = serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+    assert_eq!(payload["content"], "Game Sunday, new rink");
+
+    discord.edit("9001", &"z".repeat(2001)).await.unwrap();
+    let request = received.recv().unwrap();
+    assert!(request.starts_with("PATCH /webhook/messages/9001?thread_id=77 HTTP/1.1"), "request was: {}", request);
+    assert!(request.contains("filename=\"message.txt\""), "request was: {}", request);
+}
+
+const OPENED_THREAD: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 31\r\nConnection: close\r\n\r\n{\"id\":\"555\",\"channel_id\":\"555\"}";
+
+#[tokio::test]
+async fn thread_name_opens_a_thread_that_edits_follow() {
+    let (url, received) = capture_replying(vec![OPENED_THREAD, NO_CONTENT]);
+    let discord = Discord::new(url).with_thread_name("Sun Sep 28 vs Blackbirds");
+    discord.post("Game Sunday").await.unwrap();
+    let body = |request: &str| serde_json::from_str::<serde_json::Value>(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+
+    let first = received.recv().unwrap();
+    assert!(first.starts_with("POST /webhook?wait=true HTTP/1.1"), "request was: {}", first);
+    assert_eq!(body(&first)["thread_name"], "Sun Sep 28 vs Blackbirds");
+
+    discord.edit("555", "Game moved").await.unwrap();
+    let edit = received.recv().unwrap();
+    assert!(edit.starts_with("PATCH /webhook/messages/555?thread_id=555 HTTP/1.1"), "request was: {}", edit);
+}
@@ -0,0 +1,41 @@
+use hockey_reminder_lambda_rust::display::{is_bracket_placeholder, LockerRoom, Placeholders, RinkName, TeamName};
+
+#[test]
+fn names_fall_back_to_their_placeholder() {
+    assert_eq!(TeamName::new(Some("Blackbirds"), "TBD").to_string(), "Blackbirds");
+    assert_eq!(TeamName::new(None, "TBD").to_string(), "TBD");
+    assert_eq!(TeamName::new(None, "TBD").name(), None);
+    assert_eq!(RinkName::new(None, "Unknown Arena"), "Unknown Arena");
+}
+
+#[test]
+fn locker_room_line_is_left_out_when_unassigned() {
+    assert_eq!(LockerRoom::new(Some("3"), "none").line().as_deref(), Some("Locker Room: 3"));
+    assert_eq!(LockerRoom::new(None, "none").line(), None);
+    assert_eq!(LockerRoom::new(None, "none").to_string(), "none");
+}
+
+#[test]
+fn placeholders_fill_in_defaults_for_omitted_fields() {
+    let placeholders: Placeholders = serde_json::from_str(r#"{ "rink": "TBA" }"#).unwrap();
+    assert_eq!(placeholders.rink, "TBA");
+    assert_eq!(placeholders.opponent, "TBD");
+    assert_eq!(placeholders, Placeholders { rink: "TBA".to_string(), ..Placeholders::default() });
+}
+
+#[test]
+fn bracket_placeholders_are_not_teams() {
+    for name in ["Winner of Game 3", "Loser G2", "TBD", "tba", "#2 Seed", "Seed 4", "BYE", ""] {
+        assert!(is_bracket_placeholder(name), "{:?} should be a placeholder", name);
+    }
+    for name in ["Seal Team Sticks", "Winners Circle", "Tbdogs", "Seedlings"] {
+        assert!(!is_bracket_placeholder(name), "{:?} should be a team", name);
+    }
+    assert!(!TeamName::new(None, "TBD").is_decided(true));
+    assert!(!TeamName::new(Some("Winner of Game 3"), "TBD").is_decided(true));
+    assert!(TeamName::new(Some("Seal Team Sticks"), "TBD").is_decided(true));
+    // Outside the playoffs a team is never a bracket slot, whatever it is called
+    assert!(TeamName::new(Some("Bye Bye Birdies"), "TBD").is_decided(false));
+    assert!(TeamName::new(Some("Loser's Club"), "TBD").is_decided(false));
+    assert!(!TeamName::new(None, "TBD").is_decided(false));
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::discord_bot::DiscordBot;
+use hockey_reminder_lambda_rust::dm;
+
+#[tokio::test]
+async fn subscribe_and_unsubscribe_are_idempotent() {
+    let store = common::temp_store("dm");
+
+    assert!(dm::subscribe(&store, "11007", "42").await.unwrap());
+    assert!(!dm::subscribe(&store, "11007", "42").await.unwrap(), "second subscribe is a no-op");
+    assert!(dm::subscribe(&store, "11007", "43").await.unwrap());
+    assert_eq!(dm::subscribers(&store, "11007").await.unwrap().user_ids.len(), 2);
+
+    assert!(dm::unsubscribe(&store, "11007", "42").await.unwrap());
+    assert!(!dm::unsubscribe(&store, "11007", "42").await.unwrap());
+    let subs = dm::subscribers(&store, "11007").await.unwrap();
+    assert_eq!(subs.user_ids.into_iter().collect::<Vec<_>>(), vec!["43".to_string()]);
+}
+
+#[test]
+fn game_day_uses_local_calendar_date() {
+    // 2025-09-22T03:45Z is 8:45 PM on Sunday 9/21 in Seattle
+    let game = Utc.with_ymd_and_hms(2025, 9, 22, 3, 45, 0).unwrap();
+    let sunday_morning = Utc.with_ymd_and_hms(2025, 9, 21, 15, 0, 0).unwrap();
+    let saturday_evening = Utc.with_ymd_and_hms(2025, 9, 21, 2, 0, 0).unwrap();
+    assert!(dm::is_game_day(game, sunday_morning, chrono_tz::America::Los_Angeles));
+    assert!(!dm::is_game_day(game, saturday_evening, chrono_tz::America::Los_Angeles));
+}
+
+#[tokio::test]
+async fn game_day_dms_go_out_once_per_game() {
+    let store = common::temp_store("dm-sent");
+    dm::subscribe(&store, "11007", "42").await.unwrap();
+    dm::subscribe(&store, "11007", "43").await.unwrap();
+    let channel = common::json_response(r#"{"id":"900"}"#);
+    let message = common::json_response(r#"{"id":"901"}"#);
+    let (url, rx) = common::capture("/api/v10", vec![channel.clone(), message.clone(), channel, message]);
+    let bot = DiscordBot::new("token".to_string()).with_api_base(&url);
+    let game = Utc.with_ymd_and_hms(2025, 9, 22, 3, 45, 0).unwrap();
+
+    // Two scheduled runs on game day
+    let first = dm::send_game_day_dms(&bot, &store, "11007", 5001, game, "Game tonight", &[]).await;
+    let second = dm::send_game_day_dms(&bot, &store, "11007", 5001, game, "Game tonight", &[]).await;
+    assert_eq!(first, "2 DMs sent");
+    assert_eq!(second, "game-day DMs already sent");
+
+    let recipients: Vec<String> = rx
+        .try_iter()
+        .filter_map(|request| request.split("\"recipient_id\":\"").nth(1).map(|rest| rest[..2].to_string()))
+        .collect();
+    assert_eq!(recipients, ["42", "43"], "one DM per subscriber");
+}
@@ -0,0 +1,80 @@
+use std::collections::BTreeSet;
+
+use chrono::{TimeZone, Utc, Weekday};
+
+use hockey_reminder_lambda_rust::dropin::{self, DropInConfig};
+
+const EVENTS: &str = r#"{
+  "data": [
+    { "type": "events", "id": "901", "attributes": { "start_gmt": "2025-09-24T02:00:00Z", "resource_id": 5, "desc": "Adult Drop-In" } },
+    { "type": "events", "id": "900", "attributes": { "start_gmt": "2025-09-22T18:00:00Z", "resource_id": 5, "desc": " " } },
+    { "type": "events", "id": "902", "attributes": { "resource_id": 5 } }
+  ],
+  "included": [
+    { "type": "resources", "id": "5", "attributes": { "name": "Rink B" } }
+  ]
+}"#;
+
+fn config(weekdays: Vec<Weekday>, earliest_start: Option<&str>) -> DropInConfig {
+    DropInConfig { filters: Default::default(), weekdays, earliest_start: earliest_start.map(str::to_string), days_ahead: 14 }
+}
+
+#[test]
+fn parses_slots_sorted_with_rink_names() {
+    let slots = dropin::parse_slots(EVENTS).expect("parse events");
+    // The event without a start time is dropped
+    assert_eq!(slots.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), vec!["900", "901"]);
+    assert_eq!(slots[1].rink.as_deref(), Some("Rink B"));
+    assert_eq!(slots[1].name.as_deref(), Some("Adult Drop-In"));
+    assert_eq!(slots[0].name, None, "blank descriptions are ignored");
+}
+
+#[test]
+fn matches_local_weekday_and_earliest_start() {
+    let slots = dropin::parse_slots(EVENTS).unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 9, 21, 12, 0, 0).unwrap();
+    let tz = chrono_tz::America::Los_Angeles;
+    // 900 is Monday 11:00 AM local, 901 is Tuesday 7:00 PM local
+    let evenings = config(vec![], Some("17:00"));
+    assert!(!dropin::matches(&slots[0], &evenings, now, tz));
+    assert!(dropin::matches(&slots[1], &evenings, now, tz));
+
+    let mondays = config(vec![Weekday::Mon], None);
+    assert!(dropin::matches(&slots[0], &mondays, now, tz));
+    assert!(!dropin::matches(&slots[1], &mondays, now, tz));
+
+    let later = Utc.with_ymd_and_hms(2025, 9, 23, 0, 0, 0).unwrap();
+    assert!(!dropin::matches(&slots[0], &config(vec![], None), later, tz), "past sessions never match");
+}
+
+#[test]
+fn only_unseen_slots_are_announced() {
+    let slots = dropin::parse_slots(EVENTS).unwrap();
+    let seen: BTreeSet<String> = ["900".to_string()].into_iter().collect();
+    let new_slots = dropin::unseen(&slots, &seen);
+    assert_eq!(new_slots.len(), 1);
+    let message = dropin::format_announcement(&new_slots, chrono_tz::America::Los_Angeles);
+    assert!(message.contains("Tue Sep 23, 7:00 PM at Rink B (Adult Drop-In)"), "{}", message);
+}
+
+#[test]
+fn filters_are_encoded_and_part_of_the_seen_key() {
+    let mut config = config(vec![], None);
+    assert_eq!(config.seen_key("acme"), "dropin_seen/acme");
+
+    config.filters.insert("resource.facility_id".to_string(), "1&company=other".to_string());
+    let now = Utc.with_ymd_and_hms(2025, 9, 21, 12, 0, 0).unwrap();
+    let url = dropin::events_url(&config, "acme", now);
+    assert!(url.contains("&filter[resource.facility_id]=1%26company%3Dother&company=acme"), "{}", url);
+
+    let key = config.seen_key("acme");
+    assert!(key.starts_with("dropin_seen/acme/") && !key["dropin_seen/acme/".len()..].contains('/'), "{}", key);
+    config.filters.insert("resource.facility_id".to_string(), "2".to_string());
+    assert_ne!(config.seen_key("acme"), key, "another facility keeps its own seen set");
+}
+
+#[test]
+fn earliest_start_must_parse() {
+    assert!(config(vec![], Some("17:00")).validate().is_ok());
+    assert!(config(vec![], Some("5pm")).validate().is_err());
+}
@@ -0,0 +1,41 @@
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::email::{render_weekly_digest, DigestEntry};
+
+#[test]
+fn digest_renders_table_and_plain_text_fallback() {
+    let json = std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json");
+    let ds = DaySmart::from_json(&json).expect("from_json failed");
+    let games = ds.upcoming_games(7, Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap());
+    let entries = ds.digest_entries(&games);
+    assert_eq!(entries.len(), 1);
+
+    let email = render_weekly_digest("Yacht Flippers", &entries, chrono_tz::America::Los_Angeles);
+    assert_eq!(email.subject, "Yacht Flippers: 1 game this week");
+    assert!(email.html.contains("<td>Sun Sep 21</td><td>8:45 PM</td><td>Seal Team Sticks</td><td>Light</td><td>Starbucks Rink 1</td><td>LR11</td>"), "html was: {}", email.html);
+    assert!(email.html.contains("https://calendar.google.com/calendar/render?action=TEMPLATE&amp;text=vs%20Seal%20Team%20Sticks&amp;dates=20250922T034500Z/20250922T050000Z"), "the booked 75-minute slot, html was: {}", email.html);
+    assert!(email.text.contains("Sun Sep 21 8:45 PM vs Seal Team Sticks (Light jerseys) at Starbucks Rink 1, locker room LR11"), "text was: {}", email.text);
+    assert!(!email.text.contains(":hockey:") && !email.text.contains("**"), "plain text must not carry Discord markup");
+}
+
+#[test]
+fn digest_escapes_html() {
+    let entry = DigestEntry {
+        start: Utc.with_ymd_and_hms(2025, 9, 22, 3, 45, 0).unwrap(),
+        end: Utc.with_ymd_and_hms(2025, 9, 22, 5, 15, 0).unwrap(),
+        opponent: "Pucks & <Sticks>".to_string(),
+        jersey: "Dark".to_string(),
+        rink: "Rink \"A\"".to_string(),
+        locker_room: None,
+    };
+    let email = render_weekly_digest("Team", &[entry], chrono_tz::America::Los_Angeles);
+    assert!(email.html.contains("<td>Pucks &amp; &lt;Sticks&gt;</td>"));
+    assert!(email.html.contains("<td>Rink &quot;A&quot;</td>"));
+    assert!(email.html.contains("dates=20250922T034500Z/20250922T051500Z"), "html was: {}", email.html);
+    assert!(email.text.contains("vs Pucks & <Sticks> (Dark jerseys) at Rink \"A\"\n"));
+
+    let empty = render_weekly_digest("Team", &[], chrono_tz::America::Los_Angeles);
+    assert_eq!(empty.subject, "Team: no games this week");
+    assert!(empty.text.contains("No games are scheduled this week."));
+}
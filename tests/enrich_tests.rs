@@ -0,0 +1,69 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::enrich::{DutyEnricher, EnrichFuture, Enricher, GameContext, NotesEnricher, Pipeline};
+use hockey_reminder_lambda_rust::model::game::GameInfo;
+use hockey_reminder_lambda_rust::rotation::RotationConfig;
+
+struct Broken;
+
+impl Enricher for Broken {
+    fn name(&self) -> &'static str {
+        "broken"
+    }
+
+    fn enrich<'a>(&'a self, _ctx: &'a GameContext) -> EnrichFuture<'a> {
+        Box::pin(async { Err("weather service down".to_string()) })
+    }
+}
+
+fn context() -> GameContext {
+    GameContext {
+        game: GameInfo {
+            id: 312149,
+            dt: Utc.with_ymd_and_hms(2025, 9, 22, 3, 45, 0).unwrap(),
+            h_id: None,
+            v_id: None,
+            res_id: None,
+            area_id: None,
+            home_locker_res_id: None,
+            away_locker_res_id: None,
+            is_playoff: false,
+            is_practice: false,
+            score: None,
+            end: None,
+        },
+        team_id: "11007".to_string(),
+        now: Utc.with_ymd_and_hms(2025, 9, 21, 15, 0, 0).unwrap(),
+        timezone: chrono_tz::America::Los_Angeles,
+        streak: None,
+    }
+}
+
+#[tokio::test]
+async fn pipeline_appends_lines_in_order_and_skips_failures() {
+    let store = common::temp_store("enrich");
+    let pipeline = Pipeline::new(vec![
+        Box::new(NotesEnricher { note: "Bring a white jersey too".to_string() }),
+        Box::new(Broken),
+        Box::new(DutyEnricher {
+            key: "scorekeeper",
+            prefix: ":clipboard: Scorekeeper: ",
+            config: RotationConfig { players: vec!["Lee".to_string(), "Kim".to_string()], key: None },
+            store: store.clone(),
+        }),
+    ]);
+    assert_eq!(pipeline.names(), vec!["notes", "broken", "scorekeeper"]);
+
+    let mut message = "Game on".to_string();
+    pipeline.apply(&mut message, &context()).await;
+    assert_eq!(message, "Game on\n:pushpin: Bring a white jersey too\n:clipboard: Scorekeeper: Lee");
+}
+
+#[tokio::test]
+async fn empty_pipeline_leaves_message_untouched() {
+    let mut message = "Game on".to_string();
+    Pipeline::default().apply(&mut message, &context()).await;
+    assert_eq!(message, "Game on");
+}
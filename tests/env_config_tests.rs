@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use hockey_reminder_lambda_rust::env_config::ENV_FIELDS;
+use hockey_reminder_lambda_rust::handler::{Mode, Request, Workflow};
+use serde_json::json;
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+fn runs_on_environment_variables_alone() {
+    let vars = env(&[
+        ("MODE", "production"),
+        ("DISCORD_HOOK_URL", "https://discord.test/prod"),
+        ("TEAM_ID", "123"),
+        ("COMPANY", "acme"),
+        ("ICAL_URL", "https://example.test/team.ics"),
+        ("WORKFLOWS", r#"["ical"]"#),
+        ("DAYS_AHEAD", "3"),
+        ("DRY_RUN", "true"),
+    ]);
+    let req = Request::from_payload_with_env(serde_json::Value::Null, &vars).unwrap();
+    assert!(matches!(req.mode, Mode::Production));
+    // Plain-text fields stay strings even when they look like numbers
+    assert_eq!(req.team_id, "123");
+    assert_eq!(req.ical_url.as_deref(), Some("https://example.test/team.ics"));
+    assert_eq!(req.selected_workflows(), vec![Workflow::Ical]);
+    assert_eq!(req.days_ahead, 3);
+    assert!(req.dry_run);
+    assert!(req.validate().is_ok());
+
+    let empty = Request::from_payload_with_env(json!({}), &vars).unwrap();
+    assert_eq!(empty.company, "acme");
+}
+
+#[test]
+fn payload_fields_win_over_the_environment() {
+    let vars = env(&[("MODE", "production"), ("DISCORD_HOOK_URL", "https://discord.test/prod"), ("TEAM_ID", "123"), ("COMPANY", "acme")]);
+    let req = Request::from_payload_with_env(json!({ "mode": "test", "team_id": "456", "company": null }), &vars).unwrap();
+    assert!(matches!(req.mode, Mode::Test));
+    assert_eq!(req.team_id, "456");
+    // null counts as left out
+    assert_eq!(req.company, "acme");
+}
+
+#[test]
+fn a_webhook_source_in_the_payload_replaces_the_environment_one() {
+    let vars = env(&[("MODE", "production"), ("DISCORD_HOOK_URL", "https://discord.test/prod"), ("TEAM_ID", "123"), ("COMPANY", "acme")]);
+    let req = Request::from_payload_with_env(json!({ "discord_hook_parameter": "/hockey/hook" }), &vars).unwrap();
+    assert_eq!(req.discord_hook_url.value(), "");
+    assert!(req.validate().is_ok());
+}
+
+#[test]
+fn bad_environment_values_are_reported() {
+    let invalid = Request::from_payload_with_env(json!({}), env(&[("WORKFLOWS", "ical,daysmart")])).unwrap_err();
+    assert!(invalid.contains("WORKFLOWS"), "{}", invalid);
+    let missing = Request::from_payload_with_env(json!({}), env(&[])).unwrap_err();
+    assert!(missing.contains("mode"), "{}", missing);
+    assert!(Request::from_payload_with_env(json!([1]), env(&[])).is_err());
+}
+
+#[test]
+fn every_request_field_has_an_environment_variable() {
+    let req: Request = serde_json::from_value(json!({ "mode": "test", "discord_hook_url": "prod", "team_id": "1", "company": "acme" })).unwrap();
+    let serialized = serde_json::to_value(req).unwrap();
+    for field in serialized.as_object().unwrap().keys() {
+        assert!(ENV_FIELDS.iter().any(|(name, _)| name == field), "{} has no environment variable", field);
+    }
+}
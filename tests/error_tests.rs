@@ -0,0 +1,30 @@
+use hockey_reminder_lambda_rust::discord::DiscordError;
+use hockey_reminder_lambda_rust::error::{Error, ErrorCode};
+use hockey_reminder_lambda_rust::ical::Ical;
+
+#[test]
+fn errors_serialize_with_their_code() {
+    let info = Error::Parse("Invalid iCal feed: no VCALENDAR".to_string()).info();
+    let json = serde_json::to_value(&info).unwrap();
+    assert_eq!(json, serde_json::json!({ "code": "parse", "message": "Invalid iCal feed: no VCALENDAR" }));
+    assert_eq!(Error::Network("timed out".to_string()).code(), ErrorCode::Network);
+    assert_eq!(Error::Config("bad template".to_string()).code(), ErrorCode::Config);
+}
+
+#[test]
+fn discord_errors_keep_their_status() {
+    let refused: Error = DiscordError::Status { status: 404, body: "Unknown Webhook".to_string(), attempts: 1 }.into();
+    let json = serde_json::to_value(refused.info()).unwrap();
+    assert_eq!(json["code"], "discord");
+    assert_eq!(json["status"], 404);
+    let limited: Error = DiscordError::RateLimited { retry_after: std::time::Duration::from_secs(2), attempts: 3 }.into();
+    assert_eq!(limited.info().status, Some(429));
+    let unreachable: Error = DiscordError::Transport { message: "connection refused".to_string(), attempts: 3 }.into();
+    assert_eq!(unreachable.info().status, None);
+}
+
+#[test]
+fn unparseable_ical_reports_a_parse_error() {
+    let ical = Ical::from_ics("not a calendar");
+    assert_eq!(ical.error.map(|e| e.code()), Some(ErrorCode::Parse));
+}
@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+
+use chrono::{NaiveTime, TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::facility::{FacilityHoursConfig, Hours};
+use hockey_reminder_lambda_rust::model::game::GameStatus;
+
+fn hours(open: &str, close: &str) -> Hours {
+    Hours { open: open.to_string(), close: close.to_string() }
+}
+
+fn at(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, 0).unwrap()
+}
+
+#[test]
+fn hours_allow_overnight_and_midnight_closes() {
+    let day = hours("06:00", "23:30");
+    assert!(day.allows(at(6, 0)));
+    assert!(!day.allows(at(23, 30)));
+    assert!(!day.allows(at(3, 0)));
+
+    let overnight = hours("05:30", "01:00");
+    assert!(overnight.allows(at(23, 45)));
+    assert!(overnight.allows(at(0, 30)));
+    assert!(!overnight.allows(at(3, 0)));
+
+    let midnight = hours("06:00", "24:00");
+    assert!(midnight.allows(at(23, 59)));
+    assert!(!midnight.allows(at(0, 15)));
+}
+
+#[test]
+fn config_rejects_malformed_times() {
+    let config = FacilityHoursConfig { default: Some(hours("6am", "23:00")), rinks: BTreeMap::new() };
+    assert!(config.validate().is_err());
+    assert!(FacilityHoursConfig { default: Some(hours("06:00", "24:00")), rinks: BTreeMap::new() }.validate().is_ok());
+}
+
+#[test]
+fn games_outside_rink_hours_are_held_back() {
+    let json = std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json");
+    // Game 312149 starts at 8:45 PM on Starbucks Rink 1; Olympic View Arena has no hours configured
+    let config = FacilityHoursConfig { default: None, rinks: BTreeMap::from([("Starbucks Rink 1".to_string(), hours("06:00", "20:00"))]) };
+    let ds = DaySmart::from_json(&json)
+        .expect("from_json failed")
+        .with_timezone(chrono_tz::America::Los_Angeles)
+        .with_facility_hours(&config);
+
+    let flagged = ds.out_of_hours_games();
+    assert_eq!(flagged.len(), 1);
+    assert_eq!(flagged[0].game_id, 312149);
+    assert_eq!(flagged[0].start, "Sun Sep 21 8:45 PM");
+    assert!(flagged[0].warning().contains("Starbucks Rink 1"), "warning was: {}", flagged[0].warning());
+
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+    assert!(ds.next_game(3, now).is_none(), "the held-back game must not be announced");
+    // Still scheduled, so no cancellation alert fires for it
+    assert_eq!(ds.game_status(312149), GameStatus::Listed);
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::destination::DeliveryReport;
+use hockey_reminder_lambda_rust::failure_budget::{self, FailureBudgetConfig, FailureLedger};
+use hockey_reminder_lambda_rust::handler::Workflow;
+use hockey_reminder_lambda_rust::summary::{Outcome, WorkflowReport};
+
+fn at(day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 10, day, 15, 0, 0).unwrap()
+}
+
+fn ok() -> Vec<WorkflowReport> {
+    vec![WorkflowReport::new(Workflow::Daysmart, Outcome::Done, "DaySmart: reminder posted")]
+}
+
+fn failed(detail: &str) -> Vec<WorkflowReport> {
+    vec![WorkflowReport::new(Workflow::Daysmart, Outcome::Failed, detail)]
+}
+
+fn delivery(error: Option<&str>) -> DeliveryReport {
+    DeliveryReport { workflow: None, destination: "discord https://discord.com/api/webhooks/1/REDACTED".to_string(), error: error.map(str::to_string), error_info: None }
+}
+
+#[test]
+fn failures_are_categorized_by_detail() {
+    let category = |detail: &str| failure_budget::category(&failed(detail)[0]);
+    assert_eq!(category("DaySmart post failed: HTTP 500"), "posting");
+    assert_eq!(category("Daysmart: timed out"), "timeouts");
+    assert_eq!(category("DaySmart init error: HTTP 503"), "fetches");
+    assert_eq!(category("Template error: unknown placeholder"), "daysmart");
+}
+
+#[test]
+fn posting_stopped_only_when_every_delivery_failed() {
+    assert!(!failure_budget::posting_stopped(&[]));
+    assert!(!failure_budget::posting_stopped(&[delivery(Some("HTTP 500")), delivery(None)]));
+    assert!(failure_budget::posting_stopped(&[delivery(Some("HTTP 500")), delivery(Some("HTTP 404"))]));
+}
+
+#[test]
+fn transient_failures_stay_quiet_until_consecutive() {
+    let config = FailureBudgetConfig::default();
+    let mut ledger = FailureLedger::default();
+    assert_eq!(ledger.record(&failed("DaySmart init error: HTTP 503"), &[], at(1), &config).alert, None);
+    assert_eq!(ledger.record(&ok(), &[], at(2), &config).alert, None);
+    assert_eq!(ledger.record(&failed("DaySmart init error: HTTP 503"), &[], at(3), &config).alert, None);
+    assert_eq!(ledger.record(&failed("DaySmart init error: HTTP 503"), &[], at(4), &config).alert, None);
+
+    let alert = ledger.record(&failed("DaySmart init error: HTTP 503"), &[], at(5), &config).alert.unwrap();
+    assert!(alert.starts_with(":rotating_light: 3 runs in a row have failed"));
+    assert!(alert.contains("daysmart: DaySmart init error: HTTP 503"));
+    // One alert per streak
+    assert_eq!(ledger.record(&failed("DaySmart init error: HTTP 503"), &[], at(6), &config).alert, None);
+}
+
+#[test]
+fn posting_stopped_alerts_immediately() {
+    let config = FailureBudgetConfig::default();
+    let mut ledger = FailureLedger::default();
+    let notices = ledger.record(&failed("DaySmart post failed: HTTP 404"), &[delivery(Some("HTTP 404"))], at(1), &config);
+    assert!(notices.alert.unwrap().starts_with(":rotating_light: No posts are getting through"));
+}
+
+#[test]
+fn digest_summarizes_the_week_and_starts_over() {
+    let config = FailureBudgetConfig::default();
+    let mut ledger = FailureLedger::default();
+    ledger.record(&failed("DaySmart post failed: HTTP 500"), &[], at(1), &config);
+    ledger.record(&ok(), &[], at(2), &config);
+    ledger.record(&failed("Daysmart: timed out"), &[], at(3), &config);
+    ledger.record(&failed("DaySmart post failed: HTTP 502"), &[delivery(None)], at(4), &config);
+    assert_eq!(ledger.record(&ok(), &[], at(7), &config).digest, None);
+
+    let digest = ledger.record(&ok(), &[], at(8), &config).digest.unwrap();
+    assert_eq!(digest, ":bookkeeping: Ops digest Oct 1 – Oct 8: 3 of 6 runs had failures\n• posting: 2\n• timeouts: 1");
+    assert_eq!(ledger.period_start, Some(at(8)));
+    assert_eq!((ledger.runs, ledger.failed_runs), (0, 0));
+    assert!(ledger.counts.is_empty());
+    assert_eq!(ledger.record(&ok(), &[], at(8) + Duration::days(6), &config).digest, None);
+}
+
+#[tokio::test]
+async fn track_keeps_the_ledger_in_state() {
+    let store = common::temp_store("failures");
+    let config = FailureBudgetConfig { consecutive_runs: 2, ..Default::default() };
+
+    let first = failure_budget::track(&store, "11007", &failed("DaySmart init error: HTTP 503"), &[], at(1), &config).await.unwrap();
+    assert_eq!(first.alert, None);
+    let second = failure_budget::track(&store, "11007", &failed("DaySmart init error: HTTP 503"), &[], at(2), &config).await.unwrap();
+    assert!(second.alert.unwrap().starts_with(":rotating_light: 2 runs in a row have failed"));
+    let ledger: FailureLedger = store.get("failures/11007").await.unwrap().unwrap();
+    assert_eq!(ledger.counts.get("fetches"), Some(&2));
+}
@@ -0,0 +1,33 @@
+use hockey_reminder_lambda_rust::feed::{feed_key, feed_url, generate_token, FeedConfig};
+
+#[test]
+fn tokens_are_long_hex_and_unique() {
+    let a = generate_token().expect("token");
+    let b = generate_token().expect("token");
+    assert_eq!(a.len(), 64);
+    assert!(a.chars().all(|c| c.is_ascii_hexdigit()), "token was: {}", a);
+    assert_ne!(a, b);
+}
+
+#[test]
+fn feed_url_uses_public_base_or_s3_host() {
+    let key = feed_key("feeds/", "abc123", "11007");
+    assert_eq!(key, "feeds/abc123/11007.ics");
+
+    let mut config: FeedConfig = serde_json::from_value(serde_json::json!({ "bucket": "team-cal" })).unwrap();
+    assert_eq!(config.prefix, "feeds");
+    assert_eq!(feed_url(&config, &key), "https://team-cal.s3.amazonaws.com/feeds/abc123/11007.ics");
+
+    config.public_base_url = Some("https://cal.example.com/".to_string());
+    assert_eq!(feed_url(&config, &key), "https://cal.example.com/feeds/abc123/11007.ics");
+}
+
+#[test]
+fn webcal_url_uses_public_base_or_s3_host() {
+    use hockey_reminder_lambda_rust::feed::{webcal_url, IcsPublishConfig};
+
+    let mut config: IcsPublishConfig = serde_json::from_str(r#"{ "bucket": "team-cal", "key": "/calendars/11007.ics" }"#).unwrap();
+    assert_eq!(webcal_url(&config), "webcal://team-cal.s3.amazonaws.com/calendars/11007.ics");
+    config.public_base_url = Some("https://cal.example.com/".to_string());
+    assert_eq!(webcal_url(&config), "webcal://cal.example.com/calendars/11007.ics");
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::fees::{self, fee_line, per_player_cost, FeeSplitConfig};
+use hockey_reminder_lambda_rust::handler::Request;
+use hockey_reminder_lambda_rust::roster::{self, Player, Position, Rsvp};
+
+#[test]
+fn splits_cost_rounding_up_to_the_cent() {
+    assert_eq!(per_player_cost(168.0, 12), Some(14.0));
+    assert_eq!(per_player_cost(100.0, 3), Some(33.34));
+    assert_eq!(per_player_cost(100.0, 0), None);
+}
+
+#[test]
+fn formats_fee_line() {
+    assert_eq!(fee_line(168.0, 12).as_deref(), Some("$14/skater at 12 confirmed"));
+    assert_eq!(fee_line(100.0, 3).as_deref(), Some("$33.34/skater at 3 confirmed"));
+    assert_eq!(fee_line(100.0, 0), None);
+}
+
+#[test]
+fn ice_cost_must_be_a_positive_amount() {
+    for ice_cost in [0.0, -168.0, f64::NAN, f64::INFINITY] {
+        assert!(FeeSplitConfig { ice_cost }.validate().is_err(), "ice_cost {} should be rejected", ice_cost);
+    }
+    assert!(FeeSplitConfig { ice_cost: 168.0 }.validate().is_ok());
+
+    let mut json = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme",
+        "fee_split": { "ice_cost": -168 }
+    });
+    let req: Request = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(req.validate().unwrap_err(), "fee_split.ice_cost must be a positive amount (got -168)");
+    json["fee_split"]["ice_cost"] = serde_json::json!(168);
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert!(req.validate().is_ok());
+}
+
+#[tokio::test]
+async fn skaters_who_answered_yes_for_the_day_share_the_cost() {
+    let store = common::temp_store("fee-split");
+    let now = Utc.with_ymd_and_hms(2025, 10, 1, 12, 0, 0).unwrap();
+    let day = NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+    let player = |name: &str, position| Player { name: name.to_string(), position };
+    roster::register(&store, "11007", "1", player("Sam", Position::Skater)).await.unwrap();
+    roster::register(&store, "11007", "2", player("Dee", Position::Defense)).await.unwrap();
+    roster::register(&store, "11007", "3", player("Gus", Position::Goalie)).await.unwrap();
+    roster::register(&store, "11007", "4", player("Nat", Position::Skater)).await.unwrap();
+    for (user_id, answer) in [("1", Rsvp::Yes), ("2", Rsvp::Yes), ("3", Rsvp::Yes), ("4", Rsvp::No), ("5", Rsvp::Yes)] {
+        roster::respond(&store, "11007", &fees::rsvp_key(day), user_id, answer, now).await.unwrap();
+    }
+    // Goalies play free and a no doesn't count; a yes from someone unregistered is a skater
+    assert_eq!(fees::confirmed(&store, "11007", day).await.unwrap(), 3);
+    assert_eq!(fees::confirmed(&store, "11007", day.succ_opt().unwrap()).await.unwrap(), 0);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::goalie_escalation::{self, Calls, GoalieEscalationConfig};
+use hockey_reminder_lambda_rust::roster::{self, Player, Position, Rsvp};
+
+#[test]
+fn calls_get_more_urgent() {
+    let game = "Sun Sep 28 vs Blackbirds, 7:15 PM";
+    assert_eq!(
+        goalie_escalation::message(0, "Sunday", game),
+        "@here Still need a goalie for Sunday (Sun Sep 28 vs Blackbirds, 7:15 PM). Reply here if you can play!"
+    );
+    assert!(goalie_escalation::message(1, "Sunday", game).starts_with("@here Still no goalie for Sunday"));
+    assert!(goalie_escalation::message(2, "Sunday", game).starts_with("@here :rotating_light: Last call"));
+    assert_eq!(goalie_escalation::message(5, "Sunday", game), goalie_escalation::message(2, "Sunday", game));
+}
+
+#[test]
+fn calls_may_ping_here() {
+    let options = goalie_escalation::post_options();
+    assert_eq!(options.allowed_mentions.unwrap().parse, vec!["everyone".to_string()]);
+}
+
+#[test]
+fn config_defaults_and_validation() {
+    let config: GoalieEscalationConfig = serde_json::from_str(r#"{ "spares_hook_url": "https://discord.com/api/webhooks/1/x" }"#).unwrap();
+    assert_eq!(config.hours_before, 48);
+    assert_eq!((config.min_hours_between_calls, config.max_calls), (6, 3));
+    assert!(!config.goalie_confirmed);
+    assert!(config.validate().is_ok());
+    assert!(GoalieEscalationConfig { hours_before: 0, ..config.clone() }.validate().is_err());
+    assert!(GoalieEscalationConfig { hours_before: i64::MAX, ..config.clone() }.validate().is_err());
+    assert!(GoalieEscalationConfig { min_hours_between_calls: 49, ..config.clone() }.validate().is_err());
+    assert!(GoalieEscalationConfig { max_calls: 0, ..config.clone() }.validate().is_err());
+    assert!(GoalieEscalationConfig { spares_hook_url: " ".into(), ..config }.validate().is_err());
+}
+
+#[tokio::test]
+async fn calls_are_counted_per_game() {
+    let store = common::temp_store("goalie");
+    let start = Utc.with_ymd_and_hms(2099, 9, 28, 2, 15, 0).unwrap();
+    let now = Utc.with_ymd_and_hms(2099, 9, 26, 20, 0, 0).unwrap();
+    assert_eq!(goalie_escalation::calls_made(&store, "4113", 312149).await.unwrap(), Calls::default());
+    let calls = Calls::default().and_one_more(now).and_one_more(now);
+    goalie_escalation::record_calls(&store, "4113", 312149, calls, start).await.unwrap();
+    assert_eq!(goalie_escalation::calls_made(&store, "4113", 312149).await.unwrap(), Calls { count: 2, last_at: Some(now) });
+    assert_eq!(goalie_escalation::calls_made(&store, "4113", 312150).await.unwrap(), Calls::default());
+}
+
+#[test]
+fn calls_are_spaced_out_and_capped() {
+    let config: GoalieEscalationConfig = serde_json::from_str(r#"{ "spares_hook_url": "https://discord.com/api/webhooks/1/x" }"#).unwrap();
+    let now = Utc.with_ymd_and_hms(2099, 9, 26, 20, 0, 0).unwrap();
+    assert_eq!(Calls::default().not_due(&config, now), None);
+    let one = Calls::default().and_one_more(now);
+    assert_eq!(one.not_due(&config, now + chrono::Duration::hours(5)).as_deref(), Some("last call less than 6 hours ago"));
+    assert_eq!(one.not_due(&config, now + chrono::Duration::hours(6)), None);
+    let three = one.and_one_more(now).and_one_more(now);
+    assert_eq!(three.not_due(&config, now + chrono::Duration::days(1)).as_deref(), Some("all 3 calls made"));
+}
+
+#[tokio::test]
+async fn a_rostered_goalie_saying_yes_stops_the_calls() {
+    let store = common::temp_store("goalie-rsvp");
+    let now = Utc.with_ymd_and_hms(2099, 9, 26, 20, 0, 0).unwrap();
+    roster::register(&store, "4113", "1", Player { name: "Jo".to_string(), position: Position::Goalie }).await.unwrap();
+    roster::register(&store, "4113", "2", Player { name: "Lee".to_string(), position: Position::Skater }).await.unwrap();
+    roster::respond(&store, "4113", "312149", "2", Rsvp::Yes, now).await.unwrap();
+    roster::respond(&store, "4113", "312149", "1", Rsvp::No, now).await.unwrap();
+    assert!(!goalie_escalation::goalie_answered(&store, "4113", 312149).await.unwrap());
+    roster::respond(&store, "4113", "312149", "1", Rsvp::Yes, now).await.unwrap();
+    assert!(goalie_escalation::goalie_answered(&store, "4113", 312149).await.unwrap());
+}
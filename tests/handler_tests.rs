@@ -1,4 +1,7 @@
-use hockey_reminder_lambda_rust::handler::{Mode, Workflow, Request};
+mod common;
+
+use hockey_reminder_lambda_rust::handler::{Mode, Workflow, WorkflowEntry, Request};
+use hockey_reminder_lambda_rust::secrets::{Secret, SecretRef};
 
 #[test]
 fn serde_mode_and_workflow_lowercase() {
@@ -38,3 +41,553 @@ fn request_deserializes_and_defaults_workflows() {
     assert!(names.contains(&"\"ical\"".to_string()));
     assert!(names.contains(&"\"daysmart\"".to_string()));
 }
+
+#[test]
+fn v1_request_defaults_schema_version_and_validates() {
+    let json = serde_json::json!({
+        "mode": "production",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme",
+        "workflows": ["ical"]
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert_eq!(req.schema_version, 1);
+    assert!(req.validate().is_ok());
+    assert!(matches!(req.selected_workflows().as_slice(), [Workflow::Ical]));
+}
+
+#[test]
+fn v2_request_accepts_configured_workflow_entries() {
+    let json = serde_json::json!({
+        "schema_version": 2,
+        "mode": "production",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme",
+        "workflows": ["daysmart", { "workflow": "ical", "enabled": false }]
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert!(req.validate().is_ok());
+    assert!(matches!(req.workflows[1], WorkflowEntry::Configured(_)));
+    // Disabled entries are dropped from the run list
+    assert!(matches!(req.selected_workflows().as_slice(), [Workflow::Daysmart]));
+}
+
+#[test]
+fn workflow_entries_may_carry_their_own_webhook() {
+    let json = serde_json::json!({
+        "schema_version": 2,
+        "mode": "production",
+        "discord_hook_url": "general",
+        "team_id": "123",
+        "company": "acme",
+        "workflows": [
+            "daysmart",
+            { "workflow": "ical", "enabled": false, "discord_hook_url": "disabled" },
+            { "workflow": "ical", "discord_hook_url": "managers" }
+        ]
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert!(req.validate().is_ok());
+    assert_eq!(req.workflow_hook_url(Workflow::Ical), Some("managers"));
+    // Workflows without their own webhook fall back to discord_hook_url
+    assert_eq!(req.workflow_hook_url(Workflow::Daysmart), None);
+    assert_eq!(req.workflow_hook_url(Workflow::Scores), None);
+}
+
+#[test]
+fn workflow_entries_may_brand_their_discord_posts() {
+    let json = serde_json::json!({
+        "schema_version": 2,
+        "mode": "production",
+        "discord_hook_url": "general",
+        "team_id": "123",
+        "company": "acme",
+        "workflows": [
+            "daysmart",
+            { "workflow": "scores", "discord_options": { "username": "Scorekeeper", "suppress_embeds": true } }
+        ]
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert!(req.validate().is_ok());
+    let options = req.workflow_discord_options(Workflow::Scores).unwrap();
+    assert_eq!(options.username.as_deref(), Some("Scorekeeper"));
+    assert!(options.suppress_embeds && options.avatar_url.is_none());
+    assert!(req.workflow_discord_options(Workflow::Daysmart).is_none());
+}
+
+#[test]
+fn validate_rejects_unknown_versions_and_v1_with_settings() {
+    let base = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme"
+    });
+
+    let mut future = base.clone();
+    future["schema_version"] = serde_json::json!(99);
+    let req: Request = serde_json::from_value(future).unwrap();
+    assert!(req.validate().is_err());
+
+    let mut v1_with_settings = base.clone();
+    v1_with_settings["workflows"] = serde_json::json!([{ "workflow": "daysmart" }]);
+    let req: Request = serde_json::from_value(v1_with_settings).unwrap();
+    assert!(req.validate().is_err());
+}
+
+#[test]
+fn request_parses_now_override() {
+    let json = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme",
+        "now_override": "2025-09-27T08:00:00-07:00"
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    let now = req.now_override.expect("now_override should parse");
+    assert_eq!(now.to_rfc3339(), "2025-09-27T15:00:00+00:00");
+}
+
+#[test]
+fn request_timezone_is_validated() {
+    let mut json = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme",
+        "timezone": "America/Denver"
+    });
+    let req: Request = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(req.timezone().unwrap(), Some(chrono_tz::America::Denver));
+
+    json["timezone"] = serde_json::json!("Mars/Olympus_Mons");
+    let bad: Request = serde_json::from_value(json).unwrap();
+    assert!(bad.validate().is_err());
+}
+
+#[test]
+fn webhook_may_come_from_a_secret_instead() {
+    let mut json = serde_json::json!({
+        "mode": "production",
+        "team_id": "123",
+        "company": "acme",
+        "discord_hook_secret_arn": "arn:aws:secretsmanager:us-east-1:1:secret:hook"
+    });
+    let req: Request = serde_json::from_value(json.clone()).unwrap();
+    assert!(req.validate().is_ok());
+    assert_eq!(req.discord_hook_secret(), Some(SecretRef::SecretsManager("arn:aws:secretsmanager:us-east-1:1:secret:hook".to_string())));
+
+    // One source only
+    json["discord_hook_parameter"] = serde_json::json!("/hockey/hook");
+    let both: Request = serde_json::from_value(json.clone()).unwrap();
+    assert!(both.validate().is_err());
+
+    json.as_object_mut().unwrap().remove("discord_hook_secret_arn");
+    let parameter: Request = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(parameter.discord_hook_secret(), Some(SecretRef::Parameter("/hockey/hook".to_string())));
+
+    json.as_object_mut().unwrap().remove("discord_hook_parameter");
+    let none: Request = serde_json::from_value(json).unwrap();
+    assert!(none.validate().is_err());
+}
+
+#[test]
+fn extra_discord_hooks_may_be_listed_at_the_top_level() {
+    let json = serde_json::json!({
+        "mode": "production",
+        "discord_hook_url": "prod",
+        "discord_hook_urls": ["https://discord.com/api/webhooks/1/spares", { "parameter": "/hockey/league_hook" }],
+        "team_id": "123",
+        "company": "acme"
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert!(req.validate().is_ok());
+    assert_eq!(req.discord_hook_urls.len(), 2);
+    assert_eq!(req.discord_hook_urls[0].value(), "https://discord.com/api/webhooks/1/spares");
+    assert!(matches!(&req.discord_hook_urls[1], Secret::Stored(SecretRef::Parameter(name)) if name == "/hockey/league_hook"));
+}
+
+#[test]
+fn request_lookahead_windows_default_and_validate() {
+    let mut json = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme"
+    });
+    let req: Request = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(req.days_ahead, 5);
+    assert!(!req.announce_all, "only the next game by default");
+    assert_eq!(req.benchapp_months_ahead, 4);
+
+    json["days_ahead"] = serde_json::json!(10);
+    json["benchapp_months_ahead"] = serde_json::json!(6);
+    let req: Request = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!((req.days_ahead, req.benchapp_months_ahead), (10, 6));
+    assert!(req.validate().is_ok());
+
+    json["days_ahead"] = serde_json::json!(0);
+    let bad: Request = serde_json::from_value(json.clone()).unwrap();
+    assert!(bad.validate().is_err());
+}
+
+#[test]
+fn lookahead_windows_are_capped() {
+    let json = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme"
+    });
+    let too_far = [
+        ("days_ahead", serde_json::json!(i64::MAX)),
+        ("days_ahead", serde_json::json!(366)),
+        ("preview_horizon_days", serde_json::json!(100_000)),
+        ("benchapp_months_ahead", serde_json::json!(13)),
+        ("tournament", serde_json::json!({ "span_days": 1000 })),
+        ("dropin", serde_json::json!({ "days_ahead": i64::MAX })),
+    ];
+    for (field, value) in too_far {
+        let mut json = json.clone();
+        json[field] = value;
+        let req: Request = serde_json::from_value(json).unwrap();
+        assert!(req.validate().is_err(), "{} is not capped", field);
+    }
+
+    let mut json = json;
+    json["days_ahead"] = serde_json::json!(365);
+    json["benchapp_months_ahead"] = serde_json::json!(12);
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert!(req.validate().is_ok());
+}
+
+#[test]
+fn reminder_offsets_need_state_and_fit_days_ahead() {
+    let mut json = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme",
+        "days_ahead": 3,
+        "reminder_offsets_hours": [72, 24, 2]
+    });
+    let stateless: Request = serde_json::from_value(json.clone()).unwrap();
+    assert!(stateless.validate().unwrap_err().contains("state"));
+
+    json["state"] = serde_json::json!({ "backend": "file", "dir": "/tmp/hockey-state" });
+    let req: Request = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(req.reminder_offsets_hours, vec![72, 24, 2]);
+    assert!(req.validate().is_ok());
+
+    json["reminder_offsets_hours"] = serde_json::json!([96]);
+    let beyond: Request = serde_json::from_value(json).unwrap();
+    assert!(beyond.validate().is_err(), "an offset past days_ahead never finds the game");
+}
+
+#[test]
+fn scheduled_workflows_only_run_on_matching_days() {
+    let json = serde_json::json!({
+        "schema_version": 2,
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme",
+        "workflows": [
+            "daysmart",
+            { "workflow": "ical", "schedule": { "days_of_month": [1] } },
+            { "workflow": "drop_in", "schedule": { "weekdays": ["mon"] } }
+        ]
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert!(req.validate().is_ok());
+    let tz = chrono_tz::America::Los_Angeles;
+
+    // Wed Oct 1 2025, 8 AM Pacific
+    let first = chrono::DateTime::parse_from_rfc3339("2025-10-01T15:00:00Z").unwrap().with_timezone(&chrono::Utc);
+    let (due, not_due) = req.due_workflows(first, tz);
+    assert!(matches!(due.as_slice(), [Workflow::Daysmart, Workflow::Ical]));
+    assert!(matches!(not_due.as_slice(), [Workflow::DropIn]));
+
+    // Monday Oct 6 at 01:00 UTC is still Sunday in Seattle
+    let sunday_night = chrono::DateTime::parse_from_rfc3339("2025-10-06T01:00:00Z").unwrap().with_timezone(&chrono::Utc);
+    let (due, _) = req.due_workflows(sunday_night, tz);
+    assert!(matches!(due.as_slice(), [Workflow::Daysmart]));
+}
+
+#[test]
+fn schedule_values_are_validated() {
+    let json = serde_json::json!({
+        "schema_version": 2,
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme",
+        "workflows": [{ "workflow": "ical", "schedule": { "months": [13] } }]
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert!(req.validate().is_err());
+}
+
+#[tokio::test]
+async fn deadline_reports_laggards_as_timed_out() {
+    use hockey_reminder_lambda_rust::handler::handler;
+    use hockey_reminder_lambda_rust::summary::Outcome;
+
+    let json = serde_json::json!({
+        "schema_version": 2,
+        "mode": "test",
+        "discord_hook_url": "http://127.0.0.1:9/unused",
+        "destinations": { "discord": false },
+        "team_id": "123",
+        "company": "acme",
+        "ical_url": common::silent_server(),
+        "workflows": ["ical", "drop_in"],
+        "http": { "read_timeout_ms": 10000, "retries": 0 },
+        "deadline_ms": 300
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    let started = std::time::Instant::now();
+    let response = handler(lambda_runtime::LambdaEvent::new(req, lambda_runtime::Context::default())).await.unwrap();
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(3), "the slow feed must not hold up the invocation");
+    let ical = response.workflows.iter().find(|r| matches!(r.workflow, Workflow::Ical)).unwrap();
+    assert_eq!(ical.outcome, Outcome::Failed);
+    assert_eq!(ical.detail, "Ical: timed out");
+    // Workflows that finished keep their own result
+    let dropin = response.workflows.iter().find(|r| matches!(r.workflow, Workflow::DropIn)).unwrap();
+    assert_eq!(dropin.outcome, Outcome::Skipped);
+    assert!(response.fetches.iter().any(|f| f.source == "ical" && !f.ok));
+}
+
+#[tokio::test]
+async fn workflow_timeout_stops_only_that_workflow() {
+    use hockey_reminder_lambda_rust::handler::handler;
+    use hockey_reminder_lambda_rust::summary::Outcome;
+
+    let json = serde_json::json!({
+        "schema_version": 2,
+        "mode": "test",
+        "discord_hook_url": "http://127.0.0.1:9/unused",
+        "destinations": { "discord": false },
+        "team_id": "123",
+        "company": "acme",
+        "ical_url": common::silent_server(),
+        "workflows": [{ "workflow": "ical", "timeout_ms": 300 }, "drop_in"],
+        "http": { "read_timeout_ms": 10000, "retries": 0 }
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert_eq!(req.workflow_timeout(Workflow::Ical), Some(std::time::Duration::from_millis(300)));
+    assert_eq!(req.workflow_timeout(Workflow::DropIn), None);
+    let started = std::time::Instant::now();
+    let response = handler(lambda_runtime::LambdaEvent::new(req, lambda_runtime::Context::default())).await.unwrap();
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(3), "the hung feed must not hold up the invocation");
+    let ical = response.workflows.iter().find(|r| matches!(r.workflow, Workflow::Ical)).unwrap();
+    assert_eq!((ical.outcome, ical.detail.as_str()), (Outcome::Failed, "Ical: timed out"));
+    let dropin = response.workflows.iter().find(|r| matches!(r.workflow, Workflow::DropIn)).unwrap();
+    assert_eq!(dropin.outcome, Outcome::Skipped);
+}
+
+#[test]
+fn validate_rejects_missing_or_malformed_company() {
+    let base = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme"
+    });
+    for company in ["", "acme&include=secrets", "two words"] {
+        let mut json = base.clone();
+        json["company"] = serde_json::json!(company);
+        let req: Request = serde_json::from_value(json).unwrap();
+        assert!(req.validate().is_err(), "company {:?} should be rejected", company);
+    }
+    let mut json = base.clone();
+    json["company"] = serde_json::json!("snoking_ice-2");
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert!(req.validate().is_ok());
+}
+
+#[test]
+fn validate_rejects_non_numeric_team_ids() {
+    let base = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "11007",
+        "company": "acme"
+    });
+    let req: Request = serde_json::from_value(base.clone()).unwrap();
+    assert!(req.validate().is_ok());
+    for team_id in ["", "11007?include=secrets", "../11007"] {
+        let mut json = base.clone();
+        json["team_id"] = serde_json::json!(team_id);
+        let req: Request = serde_json::from_value(json).unwrap();
+        assert!(req.validate().is_err(), "team_id {:?} should be rejected", team_id);
+    }
+}
+
+#[tokio::test]
+async fn entry_points_outside_the_handler_validate_the_request() {
+    use hockey_reminder_lambda_rust::handler::load_valid_request;
+
+    let mut json = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme"
+    });
+    assert!(load_valid_request(json.clone()).await.is_ok());
+    json["company"] = serde_json::json!("kraken&include=");
+    let err = load_valid_request(json).await.unwrap_err();
+    assert!(err.starts_with("company must be a DaySmart company slug"), "error was: {}", err);
+}
+
+#[test]
+fn mentions_must_be_discord_ids() {
+    let base = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme",
+        "mention_role_id": "111",
+        "mention_user_ids": ["222", "333"]
+    });
+    let req: Request = serde_json::from_value(base.clone()).unwrap();
+    assert!(req.validate().is_ok());
+    assert_eq!(req.mentions().markup(), "<@&111> <@222> <@333>");
+
+    for (field, value) in [("mention_role_id", serde_json::json!("@Skaters")), ("mention_user_ids", serde_json::json!(["222", ""]))] {
+        let mut json = base.clone();
+        json[field] = value;
+        let req: Request = serde_json::from_value(json).unwrap();
+        assert!(req.validate().is_err(), "{} should be rejected", field);
+    }
+}
+
+#[tokio::test]
+async fn dry_run_skips_workflows_it_cannot_preview() {
+    use hockey_reminder_lambda_rust::handler::handler;
+    use hockey_reminder_lambda_rust::summary::Outcome;
+
+    let json = serde_json::json!({
+        "mode": "production",
+        "discord_hook_url": "http://127.0.0.1:9/unused",
+        "admin_hook_url": "http://127.0.0.1:9/unused",
+        "team_id": "123",
+        "company": "acme",
+        "workflows": ["ics_publish", "webhook_rotate"],
+        "dry_run": true
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    let response = handler(lambda_runtime::LambdaEvent::new(req, lambda_runtime::Context::default())).await.unwrap();
+
+    assert!(response.workflows.iter().all(|r| r.outcome == Outcome::Skipped && r.detail.ends_with("dry run (skipped)")), "{:?}", response.workflows);
+    assert!(response.deliveries.is_empty());
+    assert!(response.message.starts_with("[dry run] "), "message was: {}", response.message);
+}
+
+#[tokio::test]
+async fn handler_reads_the_time_from_its_services() {
+    use hockey_reminder_lambda_rust::clock::Services;
+    use hockey_reminder_lambda_rust::handler::handler_with;
+
+    let json = serde_json::json!({
+        "schema_version": 2,
+        "mode": "test",
+        "discord_hook_url": "http://127.0.0.1:9/unused",
+        "team_id": "123",
+        "company": "acme",
+        "workflows": [{ "workflow": "state_cleanup", "schedule": { "weekdays": ["mon"] } }],
+        "dry_run": true
+    });
+    let run = |now: &str| {
+        let req: Request = serde_json::from_value(json.clone()).unwrap();
+        let services = Services::fixed(chrono::DateTime::parse_from_rfc3339(now).unwrap().with_timezone(&chrono::Utc));
+        async move { handler_with(lambda_runtime::LambdaEvent::new(req, lambda_runtime::Context::default()), &services).await.unwrap() }
+    };
+
+    // Wed Oct 1 2025 and Mon Oct 6 2025, Pacific
+    let wednesday = run("2025-10-01T15:00:00Z").await;
+    assert_eq!(wednesday.workflows[0].detail, "StateCleanup: not scheduled today (skipped)");
+    let monday = run("2025-10-06T15:00:00Z").await;
+    assert_ne!(monday.workflows[0].detail, "StateCleanup: not scheduled today (skipped)");
+    // Same clock, same Response
+    let again = run("2025-10-06T15:00:00Z").await;
+    assert_eq!(serde_json::to_value(&monday).unwrap(), serde_json::to_value(&again).unwrap());
+}
+
+#[test]
+fn quiet_hours_wrap_past_midnight() {
+    use hockey_reminder_lambda_rust::schedule::QuietHours;
+
+    let quiet = QuietHours { start: "22:00".to_string(), end: "08:00".to_string() };
+    assert!(quiet.validate().is_ok());
+    let at = |utc: &str| chrono::DateTime::parse_from_rfc3339(utc).unwrap().with_timezone(&chrono::Utc);
+    // Pacific daylight time is UTC-7
+    assert!(quiet.contains(at("2025-10-01T10:00:00Z"), chrono_tz::America::Los_Angeles), "3 AM");
+    assert!(quiet.contains(at("2025-10-02T05:30:00Z"), chrono_tz::America::Los_Angeles), "10:30 PM");
+    assert!(!quiet.contains(at("2025-10-01T15:00:00Z"), chrono_tz::America::Los_Angeles), "8 AM");
+    assert!(QuietHours { start: "9pm".to_string(), end: "08:00".to_string() }.validate().is_err());
+}
+
+#[tokio::test]
+async fn quiet_hours_hold_back_posts_to_the_team() {
+    use hockey_reminder_lambda_rust::clock::Services;
+    use hockey_reminder_lambda_rust::handler::handler_with;
+    use hockey_reminder_lambda_rust::summary::Outcome;
+
+    let json = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "http://127.0.0.1:9/unused",
+        "test_discord_hook_url": "http://127.0.0.1:9/unused",
+        "team_id": "123",
+        "company": "acme",
+        "workflows": ["scores", "state_cleanup"],
+        "quiet_hours": { "start": "22:00", "end": "08:00" }
+    });
+    let req: Request = serde_json::from_value(json).unwrap();
+    // 3 AM Pacific
+    let services = Services::fixed(chrono::DateTime::parse_from_rfc3339("2025-10-01T10:00:00Z").unwrap().with_timezone(&chrono::Utc));
+    let response = handler_with(lambda_runtime::LambdaEvent::new(req, lambda_runtime::Context::default()), &services).await.unwrap();
+
+    let report = |workflow| response.workflows.iter().find(|r| r.workflow == workflow).unwrap();
+    let scores = report(Workflow::Scores);
+    assert_eq!((scores.outcome, scores.detail.as_str()), (Outcome::Skipped, "Scores: quiet hours until 08:00 (skipped)"));
+    // Cleaning up state pings nobody
+    assert!(!report(Workflow::StateCleanup).detail.contains("quiet hours"), "{:?}", response.workflows);
+    assert!(response.deliveries.is_empty());
+}
+
+#[tokio::test]
+async fn error_reports_wait_for_the_failure_budget_alert() {
+    use hockey_reminder_lambda_rust::handler::handler;
+    use std::sync::atomic::Ordering;
+
+    let store = common::temp_store("error-budget");
+    let (error_hook_url, reports) = common::serve("/errors", vec![common::NO_CONTENT; 2]);
+    let json = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "http://127.0.0.1:9/unused",
+        "destinations": { "discord": false },
+        "team_id": "123",
+        "company": "acme",
+        "ical_url": "http://127.0.0.1:9/schedule.ics",
+        "workflows": ["ical"],
+        "http": { "retries": 0 },
+        "state": { "backend": "file", "dir": store.dir() },
+        "error_hook_url": error_hook_url,
+        "failure_budget": { "consecutive_runs": 2 }
+    });
+    let run = || async {
+        let req: Request = serde_json::from_value(json.clone()).unwrap();
+        handler(lambda_runtime::LambdaEvent::new(req, lambda_runtime::Context::default())).await.unwrap()
+    };
+
+    run().await;
+    assert_eq!(reports.load(Ordering::SeqCst), 0, "one failed run stays within the budget");
+    run().await;
+    assert_eq!(reports.load(Ordering::SeqCst), 1, "the second failure in a row is reported");
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+use hockey_reminder_lambda_rust::http::{self, Conditional, HttpPolicy, Validators};
+use hockey_reminder_lambda_rust::http_cache::HttpCache;
+
+const BODY: &str = "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n";
+
+/// A feed at version "v1" that answers 304 to requests that already have it. Sends each
+/// request's If-None-Match header (empty when absent) down the channel.
+fn feed(requests: usize) -> (String, mpsc::Receiver<String>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/schedule.ics", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let mut buf = [0u8; 8192];
+            let len = stream.read(&mut buf).unwrap();
+            let request = String::from_utf8_lossy(&buf[..len]).to_lowercase();
+            let if_none_match = request.lines().find_map(|line| line.strip_prefix("if-none-match: ")).unwrap_or_default().to_string();
+            let response = if if_none_match == "\"v1\"" {
+                "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_string()
+            } else {
+                format!("HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", BODY.len(), BODY)
+            };
+            stream.write_all(response.as_bytes()).unwrap();
+            tx.send(if_none_match).unwrap();
+        }
+    });
+    (url, rx)
+}
+
+/// A cache in a fresh store, and the store's guard, which deletes it when dropped.
+fn cache() -> (HttpCache, common::TempStore) {
+    let store = common::temp_store("http-cache");
+    (HttpCache::new(store.clone()), store)
+}
+
+#[tokio::test]
+async fn conditional_get_sends_validators_and_reports_not_modified() {
+    let (url, requests) = feed(2);
+    let policy = HttpPolicy::default();
+    let Conditional::Modified { body, validators } = http::get_conditional(&url, &policy, None).await.unwrap() else {
+        panic!("first fetch should return the body");
+    };
+    assert_eq!((body.as_str(), validators.etag.as_deref()), (BODY, Some("\"v1\"")));
+    assert_eq!(http::get_conditional(&url, &policy, Some(&validators)).await.unwrap(), Conditional::NotModified);
+    assert_eq!(requests.try_iter().collect::<Vec<_>>(), ["", "\"v1\""]);
+    assert!(Validators::default().is_empty());
+}
+
+#[tokio::test]
+async fn unchanged_feed_is_not_parsed_again() {
+    let (url, requests) = feed(2);
+    let (cache, _store) = cache();
+    let parses = AtomicUsize::new(0);
+    let parse = |body: &str| {
+        parses.fetch_add(1, Ordering::SeqCst);
+        Ok::<_, String>(body.lines().count())
+    };
+    assert_eq!(cache.fetch(&url, &HttpPolicy::default(), parse).await.unwrap(), Ok(2));
+    assert_eq!(cache.fetch(&url, &HttpPolicy::default(), parse).await.unwrap(), Ok(2));
+    assert_eq!(parses.load(Ordering::SeqCst), 1);
+    assert_eq!(requests.try_iter().collect::<Vec<_>>(), ["", "\"v1\""]);
+}
+
+#[tokio::test]
+async fn stored_body_is_parsed_when_nothing_parsed_is_kept() {
+    let (url, _requests) = feed(2);
+    let (cache, _store) = cache();
+    let first = cache.fetch(&url, &HttpPolicy::default(), |body| Ok::<_, String>(body.to_string())).await.unwrap();
+    assert_eq!(first.as_deref(), Ok(BODY));
+    // A document of another type stands in for a cold start: the 304 is answered from the stored body
+    let length = cache.fetch(&url, &HttpPolicy::default(), |body| Ok::<_, String>(body.len())).await.unwrap();
+    assert_eq!(length, Ok(BODY.len()));
+}
@@ -0,0 +1,98 @@
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hockey_reminder_lambda_rust::http::{self, HttpPolicy};
+
+/// Serve one canned HTTP response per incoming connection and count the requests.
+/// A `None` response accepts the connection and never answers.
+fn serve(responses: Vec<Option<&'static str>>) -> (String, Arc<AtomicUsize>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/api", listener.local_addr().unwrap());
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    std::thread::spawn(move || {
+        let mut idle = Vec::new();
+        for (stream, response) in listener.incoming().zip(responses) {
+            let mut stream = stream.unwrap();
+            let mut buf = [0u8; 8192];
+            let _ = stream.read(&mut buf);
+            counter.fetch_add(1, Ordering::SeqCst);
+            match response {
+                Some(response) => stream.write_all(response.as_bytes()).unwrap(),
+                None => idle.push(stream),
+            }
+        }
+        std::thread::sleep(Duration::from_secs(2));
+    });
+    (url, hits)
+}
+
+const UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Length: 4\r\nConnection: close\r\n\r\nnope";
+const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}";
+
+fn policy() -> HttpPolicy {
+    HttpPolicy { connect_timeout_ms: 1_000, read_timeout_ms: 200, retries: 2, backoff_ms: 5 }
+}
+
+#[tokio::test]
+async fn retries_server_errors_and_timeouts() {
+    let (url, hits) = serve(vec![Some(UNAVAILABLE), None, Some(OK)]);
+    assert_eq!(http::get_text(&url, &policy()).await.unwrap(), "{}");
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_configured_retries_and_on_client_errors() {
+    let (url, hits) = serve(vec![Some(UNAVAILABLE), Some(UNAVAILABLE), Some(UNAVAILABLE), Some(OK)]);
+    assert!(http::get_text(&url, &policy()).await.unwrap_err().contains("503"));
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+    let (url, hits) = serve(vec![Some(NOT_FOUND), Some(OK)]);
+    assert_eq!(http::get_text(&url, &policy()).await.unwrap_err(), "HTTP 404: nope");
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn policy_fields_default_individually() {
+    let policy: HttpPolicy = serde_json::from_value(serde_json::json!({ "retries": 0 })).unwrap();
+    assert_eq!(policy, HttpPolicy { retries: 0, ..HttpPolicy::default() });
+    assert_eq!(HttpPolicy::default().backoff(3), Duration::from_millis(2_000));
+}
+
+#[test]
+fn redact_url_hides_webhook_tokens_and_secret_query_values() {
+    let discord = "https://discord.com/api/webhooks/123456789012345678/abcdefghijklmnopqrstuvwxyz0123456789";
+    assert_eq!(http::redact_url(discord), "https://discord.com/api/webhooks/123456789012345678/REDACTED");
+
+    let daysmart = "https://apps.daysmartrecreation.com/dash/jsonapi/api/v1/teams/1234?include=events&company=kraken";
+    assert_eq!(http::redact_url(daysmart), daysmart);
+
+    let signed = "https://user:pw@example.com/feed.ics?access_token=secret&view=all";
+    assert_eq!(http::redact_url(signed), "https://example.com/feed.ics?access_token=REDACTED&view=all");
+}
+
+#[tokio::test]
+async fn compressed_bodies_are_decoded_and_requests_identify_the_crate() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/feed.ics", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 8192];
+        let len = stream.read(&mut buf).unwrap();
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(b"BEGIN:VCALENDAR").unwrap();
+        let body = gzip.finish().unwrap();
+        let head = format!("HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+        stream.write_all(head.as_bytes()).unwrap();
+        stream.write_all(&body).unwrap();
+        String::from_utf8_lossy(&buf[..len]).to_lowercase()
+    });
+
+    assert_eq!(http::get_text(&url, &policy()).await.unwrap(), "BEGIN:VCALENDAR");
+    let request = server.join().unwrap();
+    assert!(request.contains("accept-encoding: gzip, deflate"), "request was: {}", request);
+    assert!(request.contains(&format!("user-agent: {}", http::USER_AGENT)), "request was: {}", request);
+}
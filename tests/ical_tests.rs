@@ -55,6 +55,16 @@ fn split_home_away_omits_non_team_prefix() {
     assert!(row.contains(",\"Yacht Flippers\","), "row was: {}", row);
 }
 
+#[test]
+fn quotes_in_fields_are_doubled_in_csv() {
+    let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:Home vs Away\nDTSTART:20250102T030000Z\nDTEND:20250102T040000Z\nDESCRIPTION:Bring the \"white\" jerseys\nEND:VEVENT\nEND:VCALENDAR\n";
+    let generator = Ical::from_ics(ics);
+    let cutoff = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let csv = generator.to_bench_app_csv(cutoff).expect("csv generation");
+    let row = csv.lines().nth(1).unwrap_or("");
+    assert!(row.ends_with(",\"Bring the \"\"white\"\" jerseys\""), "row was: {}", row);
+}
+
 #[test]
 fn discord_message_reports_latest_or_none() {
     let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:Home vs Away\nDTSTART:20250102T030000Z\nEND:VEVENT\nBEGIN:VEVENT\nSUMMARY:Another vs Team\nDTSTART:20250105T030000Z\nEND:VEVENT\nEND:VCALENDAR\n";
@@ -68,3 +78,46 @@ fn discord_message_reports_latest_or_none() {
     let msg2 = generator.discord_message(cutoff2).unwrap();
     assert!(msg2.contains("No upcoming games"), "msg was: {}", msg2);
 }
+
+#[test]
+fn next_event_start_picks_earliest_after_cutoff() {
+    let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:Home vs Away\nDTSTART:20250105T030000Z\nEND:VEVENT\nBEGIN:VEVENT\nSUMMARY:Another vs Team\nDTSTART:20250102T030000Z\nEND:VEVENT\nEND:VCALENDAR\n";
+    let generator = Ical::from_ics(ics);
+    let cutoff = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let next = generator.next_event_start(cutoff).expect("an event after cutoff");
+    assert_eq!(next.date(), NaiveDate::from_ymd_opt(2025, 1, 2).unwrap());
+
+    let late = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    assert!(generator.next_event_start(late).is_none());
+}
+
+#[test]
+fn utc_times_convert_to_configured_timezone() {
+    let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:Home vs Away\nDTSTART:20250102T030000Z\nDTEND:20250102T041500Z\nEND:VEVENT\nEND:VCALENDAR\n";
+    let generator = Ical::from_ics(ics).with_timezone(chrono_tz::America::Los_Angeles);
+    let cutoff = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let csv = generator.to_bench_app_csv(cutoff).expect("csv generation");
+    // 03:00Z on Jan 2 is 7:00 PM on Jan 1 in Pacific time; duration is unaffected
+    assert!(csv.contains("\"1/1/2025\",\"07:00 PM\",\"1:15\""), "csv was: {}", csv);
+}
+
+#[test]
+fn malformed_events_are_quarantined_and_the_rest_kept() {
+    let ics = "BEGIN:VCALENDAR\nVERSION:2.0\n\
+BEGIN:VEVENT\nUID:good-1\nSUMMARY:Home vs Away\nDTSTART:20250102T030000Z\nEND:VEVENT\n\
+BEGIN:VEVENT\nUID:bad-start\nSUMMARY:Home vs Away\nDTSTART:next tuesday\nEND:VEVENT\n\
+BEGIN:VEVENT\nUID:garbled\nthis line has no property name\nEND:VEVENT\n\
+BEGIN:VEVENT\nUID:good-2\nSUMMARY:Home vs Away\nDTSTART:20250109T030000Z\nEND:VEVENT\n\
+BEGIN:VEVENT\nUID:unterminated\nDTSTART:20250116T030000Z\n\
+END:VCALENDAR\n";
+    let generator = Ical::from_ics(ics);
+    let lines: Vec<(usize, Option<&str>)> = generator.quarantined.iter().map(|q| (q.line, q.uid.as_deref())).collect();
+    assert_eq!(lines, vec![(8, Some("bad-start")), (13, Some("garbled")), (22, Some("unterminated"))], "{:?}", generator.quarantined);
+    assert!(generator.quarantined[0].reason.contains("DTSTART"), "{:?}", generator.quarantined[0]);
+    assert_eq!(generator.quarantined[1].reason, "Line 15 is not a property: \"this line has no property name\"");
+
+    let cutoff = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let csv = generator.to_bench_app_csv(cutoff).expect("the remaining events still export");
+    assert_eq!(csv.lines().count(), 3, "csv was: {}", csv);
+    assert_eq!(generator.last_event_start(cutoff).map(|dt| dt.date()), NaiveDate::from_ymd_opt(2025, 1, 9));
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::dm;
+use hockey_reminder_lambda_rust::handler::Request;
+use hockey_reminder_lambda_rust::interactions::{self, Action, Command, Interaction};
+use hockey_reminder_lambda_rust::roster::{self, Position, Rsvp};
+use hockey_reminder_lambda_rust::rotation;
+
+fn key_pair() -> Ed25519KeyPair {
+    Ed25519KeyPair::from_seed_unchecked(&[7u8; 32]).unwrap()
+}
+
+#[test]
+fn signatures_are_checked_against_the_public_key() {
+    let keys = key_pair();
+    let public_key = hex::encode(keys.public_key().as_ref());
+    let body = r#"{"type":1}"#;
+    let signature = hex::encode(keys.sign(format!("1700000000{}", body).as_bytes()).as_ref());
+
+    assert!(interactions::verify_signature(&public_key, &signature, "1700000000", body));
+    // Replayed with another timestamp, or a tampered body
+    assert!(!interactions::verify_signature(&public_key, &signature, "1700000001", body));
+    assert!(!interactions::verify_signature(&public_key, &signature, "1700000000", r#"{"type":2}"#));
+    assert!(!interactions::verify_signature(&public_key, "not hex", "1700000000", body));
+}
+
+#[test]
+fn interactions_parse() {
+    assert_eq!(interactions::parse_interaction(r#"{"type":1}"#).unwrap(), Interaction::Ping);
+    assert_eq!(
+        interactions::parse_interaction(r#"{"type":2,"data":{"name":"nextgame"}}"#).unwrap(),
+        Interaction::Command(Command::NextGame)
+    );
+    assert_eq!(
+        interactions::parse_interaction(r#"{"type":2,"data":{"name":"schedule"}}"#).unwrap(),
+        Interaction::Command(Command::Schedule)
+    );
+    assert_eq!(
+        interactions::parse_interaction(r#"{"type":2,"data":{"name":"standings"}}"#).unwrap(),
+        Interaction::Unsupported("standings".to_string())
+    );
+    assert!(interactions::parse_interaction("not json").is_err());
+}
+
+#[test]
+fn commands_reply_with_formatted_games() {
+    let json = std::fs::read_to_string("tests/sample_response.json").unwrap();
+    let ds = DaySmart::from_json(&json).unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 15, 0, 0).unwrap();
+
+    let next = interactions::command_reply(Command::NextGame, &ds, None, now);
+    assert!(next.contains("Starbucks Rink 1"), "reply was: {}", next);
+
+    let schedule = interactions::command_reply(Command::Schedule, &ds, None, now);
+    assert!(schedule.contains("2 upcoming games"), "reply was: {}", schedule);
+
+    let later = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(interactions::command_reply(Command::NextGame, &ds, None, later), "No games in the next 60 days.");
+}
+
+fn now() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 9, 20, 15, 0, 0).unwrap()
+}
+
+/// The team's request with `extra` fields merged in.
+fn request(extra: serde_json::Value) -> Request {
+    let mut json = serde_json::json!({ "mode": "test", "discord_hook_url": "prod", "team_id": "11007", "company": "acme" });
+    json.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    serde_json::from_value(json).unwrap()
+}
+
+#[tokio::test]
+async fn remindme_subscribes_the_caller_to_game_day_messages() {
+    let remind = Action::RemindMe { user_id: "42".to_string(), off: false };
+    let stop = Action::RemindMe { user_id: "42".to_string(), off: true };
+    let guild = r#"{"type":2,"member":{"user":{"id":"42"}},"data":{"name":"remindme"}}"#;
+    assert_eq!(interactions::parse_interaction(guild).unwrap(), Interaction::Action(remind.clone()));
+    let direct = r#"{"type":2,"user":{"id":"42"},"data":{"name":"remindme","options":[{"name":"off","type":5,"value":true}]}}"#;
+    assert_eq!(interactions::parse_interaction(direct).unwrap(), Interaction::Action(stop.clone()));
+
+    let store = common::temp_store("remindme");
+    let request = request(serde_json::json!({}));
+    assert!(interactions::action_reply(&remind, &store, &request, None, now()).await.starts_with("You'll get a direct message"));
+    assert!(interactions::action_reply(&remind, &store, &request, None, now()).await.contains("already"));
+    assert!(dm::subscribers(&store, "11007").await.unwrap().user_ids.contains("42"));
+    assert_eq!(interactions::action_reply(&stop, &store, &request, None, now()).await, "No more game-day messages.");
+    assert!(dm::subscribers(&store, "11007").await.unwrap().user_ids.is_empty());
+}
+
+#[tokio::test]
+async fn swap_exchanges_rotation_duty_between_two_game_dates() {
+    let json = std::fs::read_to_string("tests/sample_response.json").unwrap();
+    let ds = DaySmart::from_json(&json).unwrap();
+    let store = common::temp_store("swap");
+    let request = request(serde_json::json!({ "scorekeeper": { "players": ["Lee", "Kim"] } }));
+    let body = r#"{"type":2,"member":{"user":{"id":"42"}},"data":{"name":"swap","options":[
+        {"name":"duty","type":3,"value":"scorekeeper"},{"name":"first","type":3,"value":"2025-09-21"},{"name":"second","type":3,"value":"2025-09-28"}]}}"#;
+    let Interaction::Action(swap) = interactions::parse_interaction(body).unwrap() else {
+        panic!("swap should parse as an action");
+    };
+    assert!(swap.needs_schedule());
+
+    let config = request.scorekeeper.clone().unwrap();
+    let games: Vec<String> = ["2025-09-21", "2025-09-28"]
+        .iter()
+        .map(|date| ds.games_on(date.parse().unwrap())[0].id.to_string())
+        .collect();
+    assert_eq!(rotation::assignee(&store, "11007", "scorekeeper", &config, &games[0]).await.unwrap().as_deref(), Some("Lee"));
+
+    assert_eq!(interactions::action_reply(&swap, &store, &request, Some(&ds), now()).await, "Swapped scorekeeper duty between Sun Sep 21 and Sun Sep 28.");
+    assert_eq!(rotation::assignee(&store, "11007", "scorekeeper", &config, &games[0]).await.unwrap().as_deref(), Some("Kim"));
+    assert_eq!(rotation::assignee(&store, "11007", "scorekeeper", &config, &games[1]).await.unwrap().as_deref(), Some("Lee"));
+
+    let no_game = Action::Swap { duty: "scorekeeper".to_string(), first: "2025-09-29".to_string(), second: "2025-09-28".to_string() };
+    assert_eq!(interactions::action_reply(&no_game, &store, &request, Some(&ds), now()).await, "There is no game on Mon Sep 29.");
+    let unconfigured = Action::Swap { duty: "beverages".to_string(), first: "2025-09-21".to_string(), second: "2025-09-28".to_string() };
+    assert_eq!(interactions::action_reply(&unconfigured, &store, &request, Some(&ds), now()).await, "No beverages rotation is configured.");
+}
+
+#[tokio::test]
+async fn roster_registers_the_caller_by_user_id() {
+    let body = r#"{"type":2,"member":{"nick":"Jo","user":{"id":"42","username":"jo99","global_name":"Joanna"}},"data":{"name":"roster","options":[
+        {"name":"position","type":3,"value":"goalie"}]}}"#;
+    let join = Action::Roster { user_id: "42".to_string(), name: "Jo".to_string(), position: "goalie".to_string(), leave: false };
+    assert_eq!(interactions::parse_interaction(body).unwrap(), Interaction::Action(join.clone()));
+    let direct = r#"{"type":2,"user":{"id":"7","username":"sam"},"data":{"name":"roster","options":[{"name":"leave","type":5,"value":true}]}}"#;
+    let leave = Action::Roster { user_id: "7".to_string(), name: "sam".to_string(), position: String::new(), leave: true };
+    assert_eq!(interactions::parse_interaction(direct).unwrap(), Interaction::Action(leave.clone()));
+
+    let store = common::temp_store("roster-command");
+    let request = request(serde_json::json!({}));
+    assert_eq!(interactions::action_reply(&join, &store, &request, None, now()).await, "You're on the roster as Jo, goalie.");
+    let players = roster::roster(&store, "11007").await.unwrap();
+    assert_eq!(players.players["42"].position, Position::Goalie);
+    assert_eq!(interactions::action_reply(&leave, &store, &request, None, now()).await, "You weren't on the roster.");
+    let wing = Action::Roster { user_id: "42".to_string(), name: "Jo".to_string(), position: "wing".to_string(), leave: false };
+    assert!(interactions::action_reply(&wing, &store, &request, None, now()).await.starts_with("Unknown position"));
+}
+
+#[tokio::test]
+async fn rsvp_records_the_answer_for_the_next_or_named_game() {
+    let json = std::fs::read_to_string("tests/sample_response.json").unwrap();
+    let ds = DaySmart::from_json(&json).unwrap();
+    let store = common::temp_store("rsvp-command");
+    let request = request(serde_json::json!({}));
+    let body = r#"{"type":2,"member":{"user":{"id":"42"}},"data":{"name":"rsvp","options":[{"name":"answer","type":3,"value":"yes"}]}}"#;
+    let Interaction::Action(yes) = interactions::parse_interaction(body).unwrap() else {
+        panic!("rsvp should parse as an action");
+    };
+    assert!(yes.needs_schedule());
+
+    assert_eq!(interactions::action_reply(&yes, &store, &request, Some(&ds), now()).await, "Got it: you're in for Sun Sep 21.");
+    let next = ds.next_game(interactions::NEXT_GAME_DAYS, now()).unwrap().id.to_string();
+    assert_eq!(roster::rsvps(&store, "11007", &next).await.unwrap()["42"], Rsvp::Yes);
+
+    let out = Action::Rsvp { user_id: "42".to_string(), answer: "no".to_string(), date: Some("2025-09-28".to_string()) };
+    assert_eq!(interactions::action_reply(&out, &store, &request, Some(&ds), now()).await, "Got it: you're out for Sun Sep 28.");
+    let later = ds.games_on("2025-09-28".parse().unwrap())[0].id.to_string();
+    assert_eq!(roster::rsvps(&store, "11007", &later).await.unwrap()["42"], Rsvp::No);
+    let maybe = Action::Rsvp { user_id: "42".to_string(), answer: "maybe".to_string(), date: None };
+    assert!(interactions::action_reply(&maybe, &store, &request, Some(&ds), now()).await.starts_with("Unknown answer"));
+}
+
+#[tokio::test]
+async fn rsvp_for_a_pickup_day_is_kept_under_the_date_with_fee_split() {
+    let ds = DaySmart::from_json(&std::fs::read_to_string("tests/sample_response.json").unwrap()).unwrap();
+    let store = common::temp_store("rsvp-pickup");
+    let pickup = Action::Rsvp { user_id: "42".to_string(), answer: "yes".to_string(), date: Some("2025-10-01".to_string()) };
+
+    let reply = interactions::action_reply(&pickup, &store, &request(serde_json::json!({})), Some(&ds), now()).await;
+    assert_eq!(reply, "There is no game on Wed Oct 1.");
+
+    let request = request(serde_json::json!({ "fee_split": { "ice_cost": 168 } }));
+    assert_eq!(interactions::action_reply(&pickup, &store, &request, Some(&ds), now()).await, "Got it: you're in for Wed Oct 1.");
+    assert_eq!(roster::rsvps(&store, "11007", "2025-10-01").await.unwrap()["42"], Rsvp::Yes);
+    // Without DaySmart too
+    assert_eq!(interactions::action_reply(&pickup, &store, &request, None, now()).await, "Got it: you're in for Wed Oct 1.");
+}
@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+
+use chrono::{TimeZone, Utc};
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::jersey::JerseyConfig;
+
+fn dark_at_home(opponents: BTreeMap<String, String>) -> JerseyConfig {
+    JerseyConfig { home_jersey: "Dark".to_string(), away_jersey: "Light".to_string(), opponents }
+}
+
+#[test]
+fn color_follows_home_away_then_opponent_overrides() {
+    let config: JerseyConfig = serde_json::from_str(r#"{ "opponents": { "Seal Team Sticks": "Teal" } }"#).unwrap();
+    assert_eq!(config.home_jersey, "Light");
+    assert_eq!(config.color(true, Some("Blackbirds")), "Light");
+    assert_eq!(config.color(false, None), "Dark");
+    assert_eq!(config.color(true, Some(" seal team sticks")), "Teal");
+    assert_eq!(config.color(false, Some("Seal Team Sticks")), "Teal");
+}
+
+#[test]
+fn reminders_use_configured_jerseys() {
+    let json = std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json");
+    // The 2025-09-21 game is a home game
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+
+    let swapped = DaySmart::from_json(&json).unwrap().with_jerseys(dark_at_home(BTreeMap::new()));
+    let msg = swapped.get_next_game_message(3, now).expect("expected a game within window");
+    assert!(msg.contains(":shirt: Dark Jerseys"), "message was: {}", msg);
+    assert!(swapped.to_benchapp_csv(now, 1).contains("Dark Jerseys; Locker Room: LR11"));
+
+    let game = swapped.next_game(3, now).unwrap();
+    let opponent = swapped.reminder_vars(&game).opponent;
+    let overridden = DaySmart::from_json(&json).unwrap().with_jerseys(dark_at_home(BTreeMap::from([(opponent.clone(), "Red".to_string())])));
+    let msg = overridden.get_next_game_message(3, now).unwrap();
+    assert!(msg.contains(":shirt: Red Jerseys"), "opponent {} message was: {}", opponent, msg);
+}
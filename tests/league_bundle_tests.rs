@@ -0,0 +1,52 @@
+use std::io::Read;
+
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::league_bundle::{self, LEAGUE_CALENDAR};
+
+/// The sample team document recast as its league's document, with one more game between two
+/// other teams.
+fn league_document() -> String {
+    let mut doc: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/sample_response.json").unwrap()).unwrap();
+    doc["data"] = serde_json::json!({ "id": "2407", "type": "leagues", "attributes": { "name": "Kraken Hockey League" } });
+    let included = doc["included"].as_array_mut().unwrap();
+    included.retain(|item| item["type"] != "leagues");
+    included.push(serde_json::json!({ "id": "11007", "type": "teams", "attributes": { "name": "Yacht Flippers" } }));
+    included.push(serde_json::json!({
+        "id": "315000",
+        "type": "events",
+        "attributes": { "event_type_id": "g", "hteam_id": 10882, "vteam_id": 10997, "resource_id": 7, "start_gmt": "2025-10-05T20:00:00", "end_gmt": "2025-10-05T21:15:00" }
+    }));
+    doc.to_string()
+}
+
+#[test]
+fn bundles_a_calendar_per_team_and_one_for_the_league() {
+    let league = DaySmart::from_league_json(&league_document()).expect("league document parses");
+    assert_eq!(league.league().map(|l| l.name.as_str()), Some("Kraken Hockey League"));
+
+    let files = league_bundle::calendars(&league);
+    let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["blackbirds.ics", "seal-team-sticks.ics", "yacht-flippers.ics", LEAGUE_CALENDAR]);
+
+    let (_, yacht) = &files[2];
+    assert_eq!(yacht.matches("BEGIN:VEVENT").count(), 2);
+    assert!(yacht.contains("vs Seal Team Sticks") && yacht.contains("vs Blackbirds"), "calendar was: {}", yacht);
+
+    let (_, combined) = &files[3];
+    assert_eq!(combined.matches("BEGIN:VEVENT").count(), 3);
+    assert!(combined.contains("Blackbirds @ Seal Team Sticks"), "calendar was: {}", combined);
+
+    let zip = league_bundle::zip_files(&files).unwrap();
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip)).unwrap();
+    assert_eq!(archive.len(), 4);
+    let mut content = String::new();
+    archive.by_name(LEAGUE_CALENDAR).unwrap().read_to_string(&mut content).unwrap();
+    assert_eq!(&content, combined);
+}
+
+#[test]
+fn file_stems_are_lower_case_words() {
+    assert_eq!(league_bundle::file_stem("Seal Team Sticks"), "seal-team-sticks");
+    assert_eq!(league_bundle::file_stem("  Puck Dynasty (D3) "), "puck-dynasty-d3");
+    assert_eq!(league_bundle::file_stem("!!!"), "team");
+}
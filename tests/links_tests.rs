@@ -0,0 +1,32 @@
+use hockey_reminder_lambda_rust::discord_bot::{link_components, LinkButton};
+use hockey_reminder_lambda_rust::links::{self, LinksConfig};
+
+#[test]
+fn buttons_prefer_configured_urls_and_skip_missing_ones() {
+    let mut config = LinksConfig { standings_url: Some("https://example.invalid/standings".to_string()), ..Default::default() };
+    config.directions.insert("Olympic View Arena".to_string(), "https://maps.invalid/ova".to_string());
+
+    let buttons = links::reminder_buttons(&config, Some("Olympic View Arena"), None);
+    let labels: Vec<&str> = buttons.iter().map(|b| b.label.as_str()).collect();
+    assert_eq!(labels, vec!["Directions", "League standings"]);
+    assert_eq!(buttons[0].url, "https://maps.invalid/ova");
+
+    // Unlisted rinks get a maps search; the hosted feed stands in for the schedule page
+    let buttons = links::reminder_buttons(&config, Some("Starbucks Rink 1"), Some("https://cdn.invalid/feeds/t/11007.ics".to_string()));
+    assert_eq!(buttons[0].url, "https://www.google.com/maps/search/?api=1&query=Starbucks%20Rink%201");
+    assert_eq!(buttons[1], LinkButton { label: "Full schedule".to_string(), url: "https://cdn.invalid/feeds/t/11007.ics".to_string() });
+}
+
+#[test]
+fn components_group_link_buttons_into_rows_of_five() {
+    let buttons: Vec<LinkButton> =
+        (0..6).map(|i| LinkButton { label: format!("b{}", i), url: format!("https://example.invalid/{}", i) }).collect();
+    let components = link_components(&buttons);
+    let rows = components.as_array().unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["type"], 1);
+    assert_eq!(rows[0]["components"].as_array().unwrap().len(), 5);
+    assert_eq!(rows[1]["components"][0]["style"], 5);
+    assert_eq!(rows[1]["components"][0]["url"], "https://example.invalid/5");
+    assert!(link_components(&[]).as_array().unwrap().is_empty());
+}
@@ -0,0 +1,41 @@
+use chrono::{TimeZone, Utc};
+use hockey_reminder_lambda_rust::destination::DeliveryReport;
+use hockey_reminder_lambda_rust::handler::Workflow;
+use hockey_reminder_lambda_rust::metrics;
+use hockey_reminder_lambda_rust::summary::{Outcome, WorkflowReport};
+
+fn delivery(error: Option<&str>) -> DeliveryReport {
+    DeliveryReport { workflow: Some(Workflow::Daysmart), destination: "discord https://discord.com/api/webhooks/1/REDACTED".to_string(), error: error.map(str::to_string), error_info: None }
+}
+
+#[test]
+fn documents_carry_run_and_per_workflow_metrics() {
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 14, 0, 0).unwrap();
+    let mut posted = WorkflowReport::new(Workflow::Daysmart, Outcome::Done, "DaySmart message posted");
+    posted.duration_ms = 840;
+    let failed = WorkflowReport::new(Workflow::Ical, Outcome::Failed, "Ical: timed out");
+    let docs = metrics::documents("11007", now, Some(3), &[posted, failed], &[delivery(None), delivery(Some("HTTP 404"))]);
+
+    assert_eq!(docs.len(), 3);
+    let run = &docs[0];
+    assert_eq!(run["_aws"]["Timestamp"], now.timestamp_millis());
+    assert_eq!(run["_aws"]["CloudWatchMetrics"][0]["Namespace"], metrics::NAMESPACE);
+    assert_eq!(run["_aws"]["CloudWatchMetrics"][0]["Dimensions"], serde_json::json!([["TeamId"]]));
+    assert_eq!((run["TeamId"].as_str(), run["games_found"].as_u64(), run["messages_posted"].as_u64()), (Some("11007"), Some(3), Some(1)));
+
+    let workflow = &docs[1];
+    assert_eq!(workflow["_aws"]["CloudWatchMetrics"][0]["Dimensions"], serde_json::json!([["TeamId", "Workflow"]]));
+    let names: Vec<&str> = workflow["_aws"]["CloudWatchMetrics"][0]["Metrics"].as_array().unwrap().iter().filter_map(|m| m["Name"].as_str()).collect();
+    assert_eq!(names, ["workflow_duration_ms", "workflow_failures"]);
+    assert_eq!((workflow["Workflow"].as_str(), workflow["workflow_duration_ms"].as_u64(), workflow["workflow_failures"].as_u64()), (Some("daysmart"), Some(840), Some(0)));
+    assert_eq!((docs[2]["Workflow"].as_str(), docs[2]["workflow_failures"].as_u64()), (Some("ical"), Some(1)));
+}
+
+#[test]
+fn games_found_is_left_out_when_daysmart_was_not_fetched() {
+    let docs = metrics::documents("11007", Utc::now(), None, &[], &[]);
+    assert_eq!(docs.len(), 1);
+    assert!(docs[0].get("games_found").is_none());
+    let names = docs[0]["_aws"]["CloudWatchMetrics"][0]["Metrics"].as_array().unwrap().len();
+    assert_eq!(names, 1);
+}
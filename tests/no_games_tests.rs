@@ -0,0 +1,46 @@
+mod common;
+
+use chrono::NaiveDate;
+
+use hockey_reminder_lambda_rust::destination::Publisher;
+use hockey_reminder_lambda_rust::no_games::{self, NoGamesNoticeConfig, NoGamesState};
+use hockey_reminder_lambda_rust::summary::Outcome;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 12, d).unwrap()
+}
+
+#[test]
+fn notices_slow_from_daily_to_weekly() {
+    let mut state = NoGamesState::default();
+    let mut sent_on = Vec::new();
+    for d in 1..=31 {
+        if state.is_due(day(d), 7) {
+            state.record(day(d));
+            sent_on.push(d);
+        }
+    }
+    assert_eq!(sent_on, vec![1, 2, 4, 8, 15, 22, 29]);
+}
+
+#[test]
+fn notice_says_when_the_next_one_comes() {
+    assert!(no_games::notice_message(5, 1).ends_with("next update tomorrow."));
+    assert!(no_games::notice_message(5, 4).contains("in the next 5 days"));
+    assert!(no_games::notice_message(5, 4).ends_with("next update in 4 days."));
+}
+
+#[tokio::test]
+async fn notify_persists_and_reset_starts_over() {
+    let store = common::temp_store("nogames");
+    let config = NoGamesNoticeConfig::default();
+
+    let first = no_games::notify(&Publisher::default(), &store, "11007", &config, day(1), 5).await;
+    assert_eq!(first, (Outcome::Done, "DaySmart: no upcoming games (notice posted)".to_string()));
+    let again = no_games::notify(&Publisher::default(), &store, "11007", &config, day(1), 5).await;
+    assert_eq!(again, (Outcome::Skipped, "DaySmart: no upcoming games (skipped)".to_string()));
+
+    no_games::reset(&store, "11007").await.unwrap();
+    let after_reset = no_games::notify(&Publisher::default(), &store, "11007", &config, day(1), 5).await;
+    assert_eq!(after_reset, (Outcome::Done, "DaySmart: no upcoming games (notice posted)".to_string()));
+}
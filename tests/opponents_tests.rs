@@ -0,0 +1,43 @@
+use chrono::{TimeZone, Utc};
+use hockey_reminder_lambda_rust::benchapp_api::games_from_daysmart_with;
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::opponents::BenchappNames;
+
+#[test]
+fn csv_names_are_read_from_the_name_column() {
+    let csv = "\u{feff}Id,\"Team Name\",Notes\n1,\"Yacht Flippers, The\",x\n2,Blackbirds,\n\n3,,blank\n";
+    let names = BenchappNames::from_csv(csv).unwrap();
+    assert_eq!(names.names(), ["Yacht Flippers, The", "Blackbirds"]);
+
+    let headerless = BenchappNames::from_csv("Players\nKraken\n").unwrap();
+    assert_eq!(headerless.names(), ["Kraken"]);
+    assert!(BenchappNames::from_csv("").is_err());
+}
+
+#[test]
+fn names_match_exactly_then_by_unique_words() {
+    let names = BenchappNames::new(vec!["St. Louis Blues".to_string(), "Kraken".to_string(), "Ice Dogs".to_string(), "Sea Dogs".to_string()]);
+    assert_eq!(names.resolve("st louis  blues"), Some("St. Louis Blues"));
+    assert_eq!(names.resolve("Seattle Kraken"), Some("Kraken"));
+    // "Dogs" fits both Ice Dogs and Sea Dogs
+    assert_eq!(names.resolve("Dogs"), None);
+    assert_eq!(names.rename("Sharks"), "Sharks");
+}
+
+#[test]
+fn daysmart_exports_use_benchapp_names() {
+    let json = std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json");
+    let ds = DaySmart::from_json(&json).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 1, 0, 0, 0).unwrap();
+    let names = BenchappNames::new(vec!["YACHT FLIPPERS (BenchApp)".to_string()]);
+
+    let csv = ds.to_benchapp_csv_with(now, 4, &names);
+    assert!(csv.contains("\"YACHT FLIPPERS (BenchApp)\""), "csv was: {}", csv);
+    assert_eq!(ds.to_benchapp_csv_with(now, 4, &BenchappNames::default()), ds.to_benchapp_csv(now, 4));
+
+    let games = ds.upcoming_games(60, now);
+    let plain = games_from_daysmart_with(&ds, &games, &BenchappNames::default());
+    let opponent = plain[0].opponent.clone();
+    let renamed = games_from_daysmart_with(&ds, &games, &BenchappNames::new(vec![opponent.to_uppercase()]));
+    assert_eq!(renamed[0].opponent, opponent.to_uppercase());
+}
@@ -0,0 +1,41 @@
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::handler::Request;
+use hockey_reminder_lambda_rust::preview;
+
+#[test]
+fn preview_lists_games_in_the_horizon_earliest_first() {
+    let json = std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json");
+    let ds = DaySmart::from_json(&json).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 0, 0, 0).unwrap();
+
+    let games = preview::games_ahead(&ds, 30, now);
+    assert!(games.len() > 1);
+    assert!(games.windows(2).all(|pair| pair[0].start <= pair[1].start));
+    let first = &games[0];
+    assert_eq!(first.game_id, 312149);
+    assert_eq!(first.start, "2025-09-22T03:45:00+00:00");
+    assert_eq!(first.local_start, "2025-09-21T20:45:00-07:00");
+    assert_eq!((first.opponent.as_str(), first.home, first.jersey.as_str()), ("Seal Team Sticks", true, "Light"));
+    assert_eq!(first.rink, "Starbucks Rink 1");
+    assert_eq!(first.locker_room.as_deref(), Some("LR11"));
+
+    assert_eq!(preview::games_ahead(&ds, 1, now).len(), 0);
+}
+
+#[test]
+fn preview_horizon_must_be_positive() {
+    let mut json = serde_json::json!({
+        "mode": "test",
+        "discord_hook_url": "prod",
+        "team_id": "123",
+        "company": "acme",
+        "preview_horizon_days": 30
+    });
+    let req: Request = serde_json::from_value(json.clone()).unwrap();
+    assert!(req.validate().is_ok());
+    json["preview_horizon_days"] = serde_json::json!(0);
+    let req: Request = serde_json::from_value(json).unwrap();
+    assert!(req.validate().is_err());
+}
@@ -0,0 +1,40 @@
+use chrono_tz::{America, Tz};
+use hockey_reminder_lambda_rust::render::{plain_text, RenderProfile};
+
+#[test]
+fn plain_text_strips_emoji_and_discord_markup() {
+    let reminder = ":hockey: Kraken Hockey League Game :goal:\nSun Sep 21, 2025\n8:45 PM at Starbucks Rink 1\nLocker Room: LR11\n:shirt: Light Jerseys";
+    assert_eq!(
+        plain_text(reminder, Tz::UTC),
+        "Kraken Hockey League Game\nSun Sep 21, 2025\n8:45 PM at Starbucks Rink 1\nLocker Room: LR11\nLight Jerseys"
+    );
+
+    let change = ":warning: **Schedule change**\n    Time: Sun 8:45 PM → **Sun 9:30 PM**";
+    assert_eq!(plain_text(change, Tz::UTC), "Schedule change\n    Time: Sun 8:45 PM → Sun 9:30 PM");
+
+    // Unicode emoji, lines of nothing but emoji, and URLs
+    assert_eq!(plain_text("🏒 Game on ✅\n:trophy:\nhttps://example.com/a:b", Tz::UTC), "Game on\nhttps://example.com/a:b");
+}
+
+#[test]
+fn plain_text_keeps_code_block_contents_aligned() {
+    let standings = ":bar_chart: Standings: Div 5\n```\nTeam          W  L\nYacht Flippers 5  1\n```";
+    assert_eq!(plain_text(standings, Tz::UTC), "Standings: Div 5\nTeam          W  L\nYacht Flippers 5  1");
+}
+
+#[test]
+fn discord_profile_leaves_messages_as_written() {
+    let message = ":calendar_spiral: **Week ahead**";
+    assert_eq!(RenderProfile::Discord.render(message, Tz::UTC), message);
+    assert_eq!(RenderProfile::PlainText.render(message, Tz::UTC), "Week ahead");
+}
+
+#[test]
+fn plain_text_writes_discord_timestamps_out_in_the_given_timezone() {
+    let reminder = ":hockey: Game\n<t:1758512700:D> (<t:1758512700:R>)\n<t:1758512700:t> at Starbucks Rink 1";
+    assert_eq!(plain_text(reminder, Tz::UTC), "Game\nSeptember 22, 2025 (September 22, 2025 3:45 AM UTC)\n3:45 AM UTC at Starbucks Rink 1");
+    assert_eq!(plain_text("<t:1758512700> <t:1758512700:F>", Tz::UTC), "September 22, 2025 3:45 AM UTC Monday, September 22, 2025 3:45 AM UTC");
+    assert_eq!(plain_text(reminder, America::Los_Angeles), "Game\nSeptember 21, 2025 (September 21, 2025 8:45 PM PDT)\n8:45 PM PDT at Starbucks Rink 1");
+    // Anything else in angle brackets is left alone
+    assert_eq!(plain_text("<t:soon> <t:1758512700:X> <https://example.com>", Tz::UTC), "<t:soon> <t:1758512700:X> <https://example.com>");
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use hockey_reminder_lambda_rust::roster::{self, Attendance, Player, Position, Roster, Rsvp, Rsvps};
+
+fn player(name: &str, position: Position) -> Player {
+    Player { name: name.to_string(), position }
+}
+
+#[test]
+fn attendance_counts_only_explicit_answers() {
+    let mut roster = Roster::default();
+    roster.players.insert("1".to_string(), player("Lee", Position::Skater));
+    roster.players.insert("2".to_string(), player("Kim", Position::Skater));
+    roster.players.insert("3".to_string(), player("Sam", Position::Defense));
+    roster.players.insert("4".to_string(), player("Alex", Position::Defense));
+    roster.players.insert("5".to_string(), player("Jo", Position::Goalie));
+
+    let mut answers = Rsvps::new();
+    answers.insert("2".to_string(), Rsvp::No);
+    answers.insert("3".to_string(), Rsvp::Yes);
+    answers.insert("99".to_string(), Rsvp::Yes);
+
+    // Sam on D, user 99 as an unregistered skater; Lee, Alex and Jo haven't answered
+    let counts = roster::attendance(&roster, &answers);
+    assert_eq!(counts, Attendance { skaters: 1, defense: 1, goalies: 0, unanswered: 3 });
+    assert_eq!(counts.total(), 2);
+    assert_eq!(counts.line(), "Confirmed: 1 skater, 1 D, 0 G (3 haven't answered)");
+    assert_eq!(Attendance { skaters: 2, defense: 0, goalies: 1, unanswered: 1 }.line(), "Confirmed: 2 skaters, 0 D, 1 G (1 hasn't answered)");
+    assert_eq!(Attendance { skaters: 2, defense: 0, goalies: 1, unanswered: 0 }.line(), "Confirmed: 2 skaters, 0 D, 1 G");
+}
+
+#[tokio::test]
+async fn registry_and_answers_persist_in_state() {
+    let store = common::temp_store("roster");
+
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 12, 0, 0).unwrap();
+    assert!(roster::register(&store, "11007", "1", player("Lee", Position::Skater)).await.unwrap());
+    assert!(!roster::register(&store, "11007", "1", player("Lee", Position::Defense)).await.unwrap(), "re-registering updates");
+    assert!(roster::register(&store, "11007", "5", player("Jo", Position::Goalie)).await.unwrap());
+    assert!(!roster::unregister(&store, "11007", "2").await.unwrap());
+
+    roster::respond(&store, "11007", "1234", "5", Rsvp::Yes, now).await.unwrap();
+    roster::respond(&store, "11007", "1234", "1", Rsvp::Yes, now).await.unwrap();
+    roster::respond(&store, "11007", "1234", "1", Rsvp::No, now).await.unwrap();
+
+    let players = roster::roster(&store, "11007").await.unwrap();
+    assert_eq!(players.players["1"], player("Lee", Position::Defense));
+    let answers = roster::rsvps(&store, "11007", "1234").await.unwrap();
+    assert_eq!(roster::attendance(&players, &answers), Attendance { skaters: 0, defense: 0, goalies: 1, unanswered: 0 });
+    assert!(roster::rsvps(&store, "11007", "9999").await.unwrap().is_empty());
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use hockey_reminder_lambda_rust::rotation::{self, RotationConfig, RotationState};
+
+fn players() -> Vec<String> {
+    vec!["Lee".to_string(), "Kim".to_string(), "Sam".to_string()]
+}
+
+#[test]
+fn assigns_round_robin_and_keeps_existing_assignments() {
+    let mut state = RotationState::default();
+    assert_eq!(state.assign("1", &players()).as_deref(), Some("Lee"));
+    assert_eq!(state.assign("2", &players()).as_deref(), Some("Kim"));
+    // Re-asking for a game returns the same player and does not advance the pointer
+    assert_eq!(state.assign("1", &players()).as_deref(), Some("Lee"));
+    assert_eq!(state.assign("3", &players()).as_deref(), Some("Sam"));
+    assert_eq!(state.assign("4", &players()).as_deref(), Some("Lee"));
+    assert!(RotationState::default().assign("1", &[]).is_none());
+}
+
+#[test]
+fn swap_exchanges_assignees() {
+    let mut state = RotationState::default();
+    state.assign("1", &players());
+    state.assign("2", &players());
+    state.swap("1", "2").unwrap();
+    assert_eq!(state.assignments.get("1").map(String::as_str), Some("Kim"));
+    assert_eq!(state.assignments.get("2").map(String::as_str), Some("Lee"));
+    assert!(state.swap("1", "99").is_err());
+}
+
+#[tokio::test]
+async fn assignee_persists_rotation_pointer() {
+    let store = common::temp_store("rotation");
+    let config = RotationConfig { players: players(), key: None };
+
+    let first = rotation::assignee(&store, "11007", "scorekeeper", &config, "312149").await.unwrap();
+    let second = rotation::assignee(&store, "11007", "scorekeeper", &config, "314460").await.unwrap();
+    let again = rotation::assignee(&store, "11007", "scorekeeper", &config, "312149").await.unwrap();
+    assert_eq!(first.as_deref(), Some("Lee"));
+    assert_eq!(second.as_deref(), Some("Kim"));
+    assert_eq!(again, first);
+}
+
+#[tokio::test]
+async fn rotations_keep_independent_pointers() {
+    let store = common::temp_store("rotation2");
+    let scorekeeper = RotationConfig { players: players(), key: None };
+    let beverages = RotationConfig { players: vec!["Ash".to_string(), "Bo".to_string()], key: None };
+
+    rotation::assignee(&store, "11007", "scorekeeper", &scorekeeper, "1").await.unwrap();
+    rotation::assignee(&store, "11007", "scorekeeper", &scorekeeper, "2").await.unwrap();
+    // The beverage rotation starts from its own first player despite scorekeeper having advanced
+    let drinks = rotation::assignee(&store, "11007", "beverages", &beverages, "2").await.unwrap();
+    assert_eq!(drinks.as_deref(), Some("Ash"));
+    let sk = rotation::assignee(&store, "11007", "scorekeeper", &scorekeeper, "2").await.unwrap();
+    assert_eq!(sk.as_deref(), Some("Kim"));
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use hockey_reminder_lambda_rust::discord::PostedMessage;
+use hockey_reminder_lambda_rust::discord_bot::{DiscordBot, Reactor};
+use hockey_reminder_lambda_rust::roster::{Player, Position, Roster};
+use hockey_reminder_lambda_rust::rsvp_reactions::{self, ReactionCount, RsvpReactionsConfig};
+
+fn reactor(id: &str, name: &str) -> Reactor {
+    Reactor { user_id: id.to_string(), name: name.to_string() }
+}
+
+fn roster() -> Roster {
+    let mut roster = Roster::default();
+    roster.players.insert("1".to_string(), Player { name: "Sam".to_string(), position: Position::Goalie });
+    roster.players.insert("2".to_string(), Player { name: "Alex".to_string(), position: Position::Defense });
+    roster
+}
+
+#[test]
+fn count_uses_roster_positions_by_user_id_and_lets_a_cross_win() {
+    let yes = [reactor("1", "sam"), reactor("2", "Alex"), reactor("3", "Jordan"), reactor("4", "Riley")];
+    let no = [reactor("4", "Riley"), reactor("5", "Casey")];
+    let count = rsvp_reactions::count(&yes, &no, &roster());
+    assert_eq!(count, ReactionCount { skaters: 2, goalies: 1, out: 2 });
+}
+
+#[test]
+fn summary_warns_below_minimums() {
+    let count = ReactionCount { skaters: 9, goalies: 1, out: 1 };
+    let text = rsvp_reactions::summary(&count, "Sun Sep 28 vs Blackbirds", &RsvpReactionsConfig::default());
+    assert!(text.starts_with(":white_check_mark: 9 skaters, 1 goalie confirmed for Sun Sep 28 vs Blackbirds\n:x: 1 out\n"), "{}", text);
+    assert!(text.contains(":warning: Short of skaters: 9 of 10 needed"), "{}", text);
+    assert!(!text.contains("Short of goalies"), "{}", text);
+}
+
+#[test]
+fn summary_without_shortfall_has_no_warning() {
+    let count = ReactionCount { skaters: 12, goalies: 2, out: 0 };
+    let text = rsvp_reactions::summary(&count, "Sat Sep 20 practice", &RsvpReactionsConfig { min_skaters: 10, min_goalies: 1 });
+    assert!(text.starts_with(":white_check_mark: 12 skaters, 2 goalies confirmed for Sat Sep 20 practice\n"), "{}", text);
+    assert!(!text.contains(":warning:") && !text.contains(":x:"), "{}", text);
+}
+
+#[tokio::test]
+async fn reactors_skip_bots_and_prefer_display_names() {
+    let users = r#"[{"id":"1","username":"sam99","global_name":"Sam"},{"id":"2","username":"jordan"},{"id":"3","username":"hockeybot","bot":true}]"#;
+    let (url, rx) = common::capture("/api/v10", vec![common::json_response(users)]);
+
+    let bot = DiscordBot::new("token".to_string()).with_api_base(&url);
+    let reactors = bot.reactors("100", "200", rsvp_reactions::YES_EMOJI).await.unwrap();
+    assert_eq!(reactors, vec![reactor("1", "Sam"), reactor("2", "jordan")]);
+    assert_eq!(rx.recv().unwrap().lines().next().unwrap(), "GET /api/v10/channels/100/messages/200/reactions/%E2%9C%85?limit=100 HTTP/1.1");
+}
+
+#[tokio::test]
+async fn reactions_are_added_to_the_reminder_yes_first() {
+    let (url, rx) = common::capture("/api/v10", vec![common::NO_CONTENT, common::NO_CONTENT]);
+    let bot = DiscordBot::new("token".to_string()).with_api_base(&url);
+    let message = PostedMessage { id: "200".to_string(), channel_id: Some("100".to_string()) };
+    rsvp_reactions::add_reactions(&bot, &message).await.unwrap();
+    let lines: Vec<String> = rx.iter().take(2).map(|request| request.lines().next().unwrap().to_string()).collect();
+    assert_eq!(
+        lines,
+        [
+            "PUT /api/v10/channels/100/messages/200/reactions/%E2%9C%85/@me HTTP/1.1",
+            "PUT /api/v10/channels/100/messages/200/reactions/%E2%9D%8C/@me HTTP/1.1"
+        ]
+    );
+
+    // Nothing to react to without the channel
+    let unknown = PostedMessage { id: "200".to_string(), channel_id: None };
+    rsvp_reactions::add_reactions(&bot, &unknown).await.unwrap();
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::discord_bot::DiscordBot;
+use hockey_reminder_lambda_rust::scheduled_events::{self, EventRecord, EventRecords, ScheduledEventsConfig};
+
+fn load_sample() -> String {
+    std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json")
+}
+
+/// Answer every request like Discord creating a scheduled event, and hand back the requests.
+fn discord_api() -> (String, std::sync::mpsc::Receiver<String>) {
+    common::capture("/api/v10", vec![common::json_response(r#"{"id":"777"}"#); 10])
+}
+
+#[tokio::test]
+async fn each_upcoming_game_gets_one_scheduled_event() {
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 27, 0, 0, 0).unwrap();
+    let config = ScheduledEventsConfig { guild_id: "42".to_string(), days_ahead: 7 };
+    let (api, received) = discord_api();
+    let bot = DiscordBot::new("token".to_string()).with_api_base(&api);
+    let mut records = EventRecords::new();
+
+    let summary = scheduled_events::sync(&bot, &mut records, &config, &ds, now).await;
+    assert_eq!(summary.to_string(), "1 created, 0 updated, 0 deleted, 0 unchanged");
+    let request = received.recv().unwrap();
+    assert!(request.starts_with("POST /api/v10/guilds/42/scheduled-events HTTP/1.1"), "request was: {}", request);
+    assert!(request.contains("authorization: Bot token"), "request was: {}", request);
+    let body: serde_json::Value = serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+    assert_eq!(body["name"], "Sun Sep 28 vs Blackbirds");
+    assert_eq!(body["entity_type"], 3);
+    assert!(body["entity_metadata"]["location"].as_str().unwrap().starts_with("Olympic View Arena"), "body was: {}", body);
+    assert!(body["description"].as_str().unwrap().contains("Dark jerseys"), "body was: {}", body);
+    let (game_id, record) = records.iter().next().unwrap();
+    assert_eq!((record.event_id.as_str(), record.name.as_str()), ("777", "Sun Sep 28 vs Blackbirds"));
+
+    // Already created: nothing to do on the next run
+    let game_id = *game_id;
+    assert_eq!(scheduled_events::sync(&bot, &mut records, &config, &ds, now).await.unchanged, 1);
+    assert!(received.try_recv().is_err());
+
+    // A game whose start no longer matches its event is moved
+    records.get_mut(&game_id).unwrap().start -= chrono::Duration::hours(1);
+    assert_eq!(scheduled_events::sync(&bot, &mut records, &config, &ds, now).await.updated, 1);
+    let request = received.recv().unwrap();
+    assert!(request.starts_with("PATCH /api/v10/guilds/42/scheduled-events/777 HTTP/1.1"), "request was: {}", request);
+    assert_eq!(records[&game_id].start, ds.upcoming_game(game_id, now).unwrap().dt);
+}
+
+#[tokio::test]
+async fn events_of_games_dropped_from_the_schedule_are_deleted() {
+    let ds = DaySmart::from_json(&load_sample()).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 27, 0, 0, 0).unwrap();
+    let config = ScheduledEventsConfig { guild_id: "42".to_string(), days_ahead: 7 };
+    let (api, received) = discord_api();
+    let bot = DiscordBot::new("token".to_string()).with_api_base(&api);
+    let record = |event_id: &str, start| EventRecord { event_id: event_id.to_string(), created_at: now, name: "Gone".to_string(), start };
+    let mut records = EventRecords::from([
+        (1, record("555", now + chrono::Duration::days(2))),
+        // Long past: only forgotten
+        (2, record("556", now - chrono::Duration::days(30))),
+    ]);
+
+    let summary = scheduled_events::sync(&bot, &mut records, &config, &ds, now).await;
+    assert_eq!((summary.created, summary.deleted), (1, 1));
+    let requests: Vec<String> = received.try_iter().collect();
+    assert!(requests.iter().any(|r| r.starts_with("DELETE /api/v10/guilds/42/scheduled-events/555 HTTP/1.1")), "requests were: {:?}", requests);
+    assert!(!requests.iter().any(|r| r.contains("/556")), "requests were: {:?}", requests);
+    assert_eq!(records.len(), 1);
+    assert!(!records.contains_key(&1) && !records.contains_key(&2));
+}
+
+#[tokio::test]
+async fn records_are_kept_per_team() {
+    let store = common::temp_store("event-records");
+    let now = Utc.with_ymd_and_hms(2025, 9, 27, 0, 0, 0).unwrap();
+    let records = EventRecords::from([(7, EventRecord { event_id: "777".to_string(), created_at: now, name: "Game".to_string(), start: now })]);
+    scheduled_events::save_records(&store, "11007", &records).await.unwrap();
+    assert_eq!(scheduled_events::records(&store, "11007").await.unwrap(), records);
+    assert!(scheduled_events::records(&store, "11008").await.unwrap().is_empty());
+}
+
+#[test]
+fn guild_id_must_be_a_discord_id() {
+    let config: ScheduledEventsConfig = serde_json::from_value(serde_json::json!({ "guild_id": "my server" })).unwrap();
+    assert_eq!(config.days_ahead, 14);
+    assert!(config.validate().is_err());
+    assert!(ScheduledEventsConfig { guild_id: "42".to_string(), days_ahead: 14 }.validate().is_ok());
+    assert!(ScheduledEventsConfig { guild_id: "42".to_string(), days_ahead: 10_000 }.validate().is_err());
+}
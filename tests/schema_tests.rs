@@ -0,0 +1,36 @@
+use hockey_reminder_lambda_rust::handler::{response_schema, RESPONSE_SCHEMA_VERSION};
+use hockey_reminder_lambda_rust::preview::game_schema;
+
+/// The published schemas must match the Rust types. Run with UPDATE_SCHEMA=1 to regenerate them.
+#[test]
+fn published_schemas_are_current() {
+    for (published_path, schema) in [("schema/response.schema.json", response_schema()), ("schema/game.schema.json", game_schema())] {
+        let generated = serde_json::to_string_pretty(&schema).unwrap() + "\n";
+        if std::env::var_os("UPDATE_SCHEMA").is_some() {
+            std::fs::write(published_path, &generated).unwrap();
+        }
+        let published = std::fs::read_to_string(published_path).unwrap_or_default();
+        assert!(published == generated, "{} is out of date; regenerate with UPDATE_SCHEMA=1 cargo test --test schema_tests", published_path);
+    }
+}
+
+#[test]
+fn game_schema_requires_the_game_fields() {
+    let schema = serde_json::to_value(game_schema()).unwrap();
+    let required: Vec<&str> = schema["required"].as_array().unwrap().iter().filter_map(|field| field.as_str()).collect();
+    for field in ["game_id", "start", "local_start", "opponent", "home", "rink", "jersey", "playoff"] {
+        assert!(required.contains(&field), "{} should be required: {}", field, schema);
+    }
+    assert!(!required.contains(&"locker_room"));
+}
+
+#[test]
+fn response_schema_requires_known_workflow_names_and_outcomes() {
+    let schema = serde_json::to_value(response_schema()).unwrap();
+    let text = schema.to_string();
+    assert!(schema["properties"]["schema_version"].is_object(), "schema was: {}", text);
+    for name in ["daysmart", "ics_publish", "drop_in", "canary", "done", "skipped", "failed"] {
+        assert!(text.contains(&format!("\"{}\"", name)), "schema should list {}: {}", name, text);
+    }
+    assert_eq!(RESPONSE_SCHEMA_VERSION, 1);
+}
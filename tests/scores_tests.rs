@@ -0,0 +1,28 @@
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::scores;
+
+#[tokio::test]
+async fn remembers_posted_recaps_per_team() {
+    let store = common::temp_store("scores-posted");
+    let now = Utc.with_ymd_and_hms(2025, 10, 2, 15, 0, 0).unwrap();
+    assert!(scores::posted(&store, "11007").await.unwrap().is_empty());
+
+    scores::record_posted(&store, "11007", &[41, 42], now).await.unwrap();
+    let posted = scores::posted(&store, "11007").await.unwrap();
+    assert_eq!(posted.keys().copied().collect::<Vec<_>>(), vec![41, 42]);
+    assert!(scores::posted(&store, "11008").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn forgets_recaps_posted_over_a_month_ago() {
+    let store = common::temp_store("scores-prune");
+    let then = Utc.with_ymd_and_hms(2025, 9, 1, 15, 0, 0).unwrap();
+    scores::record_posted(&store, "11007", &[41], then).await.unwrap();
+
+    scores::record_posted(&store, "11007", &[42], then + Duration::days(31)).await.unwrap();
+    let posted = scores::posted(&store, "11007").await.unwrap();
+    assert_eq!(posted.keys().copied().collect::<Vec<_>>(), vec![42]);
+}
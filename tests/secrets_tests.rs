@@ -0,0 +1,88 @@
+mod common;
+
+use std::sync::mpsc;
+
+use hockey_reminder_lambda_rust::handler::Request;
+use hockey_reminder_lambda_rust::secrets::{Secret, SecretRef, SecretsClient};
+
+/// Answer one request like the Parameters and Secrets extension would, passing the raw request back.
+fn serve(body: &str) -> (String, mpsc::Receiver<String>) {
+    common::capture("", vec![common::json_response(body)])
+}
+
+#[tokio::test]
+async fn resolves_json_secrets_with_the_session_token_and_caches_them() {
+    let secret = r#"{"SecretString": "{\"discord_hook_url\": \"https://discord.test/hook\"}"}"#;
+    let (url, requests) = serve(secret);
+    let client = SecretsClient::new(url, "session-token".to_string());
+    let arn = SecretRef::SecretsManager("arn:aws:secretsmanager:us-east-1:1:secret:cache-test".to_string());
+
+    assert_eq!(client.resolve(&arn).await.unwrap(), "https://discord.test/hook");
+    let request = requests.recv().unwrap();
+    assert!(request.starts_with("GET /secretsmanager/get?secretId=arn%3Aaws%3Asecretsmanager"));
+    assert!(request.to_ascii_lowercase().contains("x-aws-parameters-secrets-token: session-token"));
+
+    // The server only answers once, so the second lookup has to come from the cache
+    assert_eq!(client.resolve(&arn).await.unwrap(), "https://discord.test/hook");
+}
+
+#[tokio::test]
+async fn resolves_plain_secrets_and_parameters() {
+    let (url, _) = serve(r#"{"SecretString": "https://discord.test/plain\n"}"#);
+    let client = SecretsClient::new(url, String::new());
+    let plain = SecretRef::SecretsManager("plain-secret".to_string());
+    assert_eq!(client.resolve(&plain).await.unwrap(), "https://discord.test/plain");
+
+    let (url, requests) = serve(r#"{"Parameter": {"Name": "/hockey/hook", "Value": "https://discord.test/param"}}"#);
+    let client = SecretsClient::new(url, String::new());
+    let parameter = SecretRef::Parameter("/hockey/hook".to_string());
+    assert_eq!(client.resolve(&parameter).await.unwrap(), "https://discord.test/param");
+    assert!(requests.recv().unwrap().contains("withDecryption=true"));
+}
+
+#[tokio::test]
+async fn empty_values_are_errors() {
+    let (url, _) = serve(r#"{"SecretString": ""}"#);
+    let client = SecretsClient::new(url, String::new());
+    assert!(client.resolve(&SecretRef::SecretsManager("empty-secret".to_string())).await.is_err());
+}
+
+#[tokio::test]
+async fn request_webhooks_and_tokens_can_be_references() {
+    let (url, requests) = serve(r#"{"Parameter": {"Name": "/hockey/bot_token", "Value": "bot-token-from-ssm"}}"#);
+    let client = SecretsClient::new(url, String::new());
+    let mut request: Request = serde_json::from_value(serde_json::json!({
+        "mode": "production",
+        "discord_hook_url": "https://discord.test/inline",
+        "bot_token": { "parameter": "/hockey/bot_token" },
+        "team_id": "123",
+        "company": "acme"
+    }))
+    .unwrap();
+    assert_eq!(request.bot_token, Some(Secret::Stored(SecretRef::Parameter("/hockey/bot_token".to_string()))));
+    assert!(!format!("{:?}", request).contains("discord.test/inline"));
+
+    request.resolve_secrets(&client).await.unwrap();
+    assert_eq!(request.bot_token.as_ref().map(Secret::value), Some("bot-token-from-ssm"));
+    assert_eq!(request.discord_hook_url.value(), "https://discord.test/inline");
+    assert!(requests.recv().unwrap().contains("name=%2Fhockey%2Fbot_token"));
+}
+
+#[tokio::test]
+async fn webhook_kept_in_a_secret_is_resolved_into_discord_hook_url() {
+    let (url, requests) = serve(r#"{"SecretString": "https://discord.test/from-arn"}"#);
+    let client = SecretsClient::new(url, String::new());
+    let mut request: Request = serde_json::from_value(serde_json::json!({
+        "mode": "production",
+        "discord_hook_secret_arn": "arn:aws:secretsmanager:us-east-1:1:secret:resolve-test",
+        "team_id": "123",
+        "company": "acme"
+    }))
+    .unwrap();
+
+    request.resolve_secrets(&client).await.unwrap();
+    assert_eq!(request.discord_hook_url.value(), "https://discord.test/from-arn");
+    assert_eq!(request.discord_hook_secret(), None);
+    assert!(request.validate().is_ok(), "still one webhook source");
+    assert!(requests.recv().unwrap().contains("secretId=arn%3Aaws%3Asecretsmanager"));
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use hockey_reminder_lambda_rust::daysmart::View;
+use hockey_reminder_lambda_rust::display::Placeholders;
+use hockey_reminder_lambda_rust::http::HttpPolicy;
+use hockey_reminder_lambda_rust::jersey::JerseyConfig;
+use hockey_reminder_lambda_rust::sources::Sources;
+
+#[tokio::test]
+async fn fetch_skips_sources_no_workflow_needs() {
+    // Neither source requested: nothing is fetched and no timing is reported
+    let sources = Sources::fetch(None, None, None, Placeholders::default(), JerseyConfig::default(), View::default(), HttpPolicy::default(), None, None);
+    assert!(sources.daysmart().await.is_none());
+    assert!(sources.ical().await.is_none());
+    assert!(sources.fetches().is_empty());
+}
+
+#[tokio::test]
+async fn slow_source_is_reported_as_pending() {
+    let policy = HttpPolicy { connect_timeout_ms: 1_000, read_timeout_ms: 10_000, retries: 0, backoff_ms: 0 };
+    let sources = Sources::fetch(None, Some(common::silent_server()), None, Placeholders::default(), JerseyConfig::default(), View::default(), policy, None, None);
+
+    let waited = tokio::time::timeout(std::time::Duration::from_millis(200), sources.ical()).await;
+    assert!(waited.is_err(), "the silent feed should not finish");
+    let fetches = sources.fetches();
+    assert_eq!(fetches.len(), 1);
+    assert_eq!(fetches[0].source, "ical");
+    assert!(!fetches[0].ok);
+}
@@ -0,0 +1,69 @@
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::standings::{self, format_table, Standing};
+
+/// A league document of three teams: Yeti 2-0, Beavers and Sharks 0-1-1 each, a playoff game and
+/// a game still to be played.
+fn league() -> DaySmart {
+    let game = |id: &str, home: i64, visitor: i64, score: Option<(i64, i64)>, event_type: &str| {
+        serde_json::json!({
+            "id": id,
+            "type": "events",
+            "attributes": {
+                "event_type_id": event_type,
+                "hteam_id": home,
+                "vteam_id": visitor,
+                "start_gmt": "2025-10-05T20:00:00",
+                "end_gmt": "2025-10-05T21:15:00",
+                "home_score": score.map(|s| s.0),
+                "visiting_score": score.map(|s| s.1)
+            }
+        })
+    };
+    let team = |id: &str, name: &str| serde_json::json!({ "id": id, "type": "teams", "attributes": { "name": name } });
+    let document = serde_json::json!({
+        "data": { "id": "2407", "type": "leagues", "attributes": { "name": "Kraken Hockey League" }, "relationships": { "playoffEvents": { "data": [{ "type": "events", "id": "4" }] } } },
+        "included": [
+            team("10", "Sharks"),
+            team("20", "Yeti"),
+            team("30", "Beavers"),
+            game("1", 20, 10, Some((4, 2)), "g"),
+            game("2", 30, 20, Some((1, 3)), "g"),
+            game("3", 10, 30, Some((2, 2)), "g"),
+            game("4", 30, 20, Some((6, 0)), "g"),
+            game("5", 10, 20, None, "g"),
+        ]
+    });
+    DaySmart::from_league_json(&document.to_string()).expect("league document parses")
+}
+
+fn rows() -> Vec<Standing> {
+    standings::from_league(&league())
+}
+
+#[test]
+fn standings_are_counted_from_scored_regular_season_games() {
+    let rows = rows();
+    let names: Vec<&str> = rows.iter().map(|r| r.team_name.as_str()).collect();
+    assert_eq!(names, vec!["Yeti", "Beavers", "Sharks"]);
+    assert_eq!((rows[0].games_played, rows[0].wins, rows[0].points), (2, 2, 4));
+    // The playoff win and the unplayed game don't count
+    assert_eq!((rows[1].games_played, rows[1].losses, rows[1].ties, rows[1].points), (2, 1, 1, 1));
+}
+
+#[test]
+fn table_marks_our_team_and_summarizes_its_position() {
+    let rows = rows();
+    let table = format_table("Kraken Hockey League", &rows, Some(30));
+    assert!(table.starts_with(":bar_chart: Standings: Kraken Hockey League\n```"), "table was: {}", table);
+    assert!(table.contains(">  2 Beavers"), "table was: {}", table);
+    assert!(table.contains("   1 Yeti"), "table was: {}", table);
+    assert!(table.ends_with("```\nBeavers: 2nd of 3, 0-1-1 (1 pts)"), "table was: {}", table);
+}
+
+#[test]
+fn table_without_our_team_has_no_summary_line() {
+    let rows = rows();
+    let table = format_table("Kraken Hockey League", &rows, Some(99));
+    assert!(!table.contains('>'), "table was: {}", table);
+    assert!(table.ends_with("```"), "table was: {}", table);
+}
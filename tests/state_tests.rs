@@ -0,0 +1,77 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use hockey_reminder_lambda_rust::state::StateConfig;
+
+#[tokio::test]
+async fn file_store_round_trips_and_deletes() {
+    let store = common::temp_store("state");
+    let missing: Option<Vec<String>> = store.get("things/11007").await.unwrap();
+    assert!(missing.is_none());
+
+    store.put("things/11007", &vec!["a".to_string(), "b".to_string()]).await.unwrap();
+    let loaded: Option<Vec<String>> = store.get("things/11007").await.unwrap();
+    assert_eq!(loaded, Some(vec!["a".to_string(), "b".to_string()]));
+
+    store.delete("things/11007").await.unwrap();
+    store.delete("things/11007").await.expect("deleting a missing key is fine");
+    let gone: Option<Vec<String>> = store.get("things/11007").await.unwrap();
+    assert!(gone.is_none());
+}
+
+#[tokio::test]
+async fn expiring_records_are_cleaned_up_once_expired() {
+    let store = common::temp_store("state");
+    let now = Utc::now();
+    store.put_expiring("posted/11007/1", &"old".to_string(), now - Duration::days(1)).await.unwrap();
+    store.put_expiring("posted/11007/2", &"current".to_string(), now + Duration::days(1)).await.unwrap();
+    store.put("roster/11007", &vec!["Lee".to_string()]).await.unwrap();
+
+    // Until cleaned up, expiring records read like any other
+    assert_eq!(store.get::<String>("posted/11007/1").await.unwrap(), Some("old".to_string()));
+    assert_eq!(store.get::<String>("posted/11007/2").await.unwrap(), Some("current".to_string()));
+
+    assert_eq!(store.remove_expired(now).await.unwrap(), Some(1));
+    assert_eq!(store.get::<String>("posted/11007/1").await.unwrap(), None);
+    assert_eq!(store.remove_expired(now).await.unwrap(), Some(0));
+    // Records without an expiry stay, and the live expiring one is untouched
+    assert_eq!(store.get::<Vec<String>>("roster/11007").await.unwrap(), Some(vec!["Lee".to_string()]));
+    assert_eq!(store.remove_expired(now + Duration::days(2)).await.unwrap(), Some(1));
+    assert_eq!(store.get::<String>("posted/11007/2").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn file_store_keys_cannot_escape_directory() {
+    let store = common::temp_store("state");
+    store.put("../../escape", &1).await.unwrap();
+    let loaded: Option<i32> = store.get("escape").await.unwrap();
+    assert_eq!(loaded, Some(1), "parent segments should be dropped from keys");
+}
+
+#[test]
+fn state_config_is_tagged_by_backend() {
+    let cfg: StateConfig = serde_json::from_value(serde_json::json!({ "backend": "s3", "bucket": "b" })).unwrap();
+    assert!(matches!(cfg, StateConfig::S3 { ref prefix, .. } if prefix == "state"));
+    let cfg: StateConfig = serde_json::from_value(serde_json::json!({ "backend": "file", "dir": "/tmp/x" })).unwrap();
+    assert!(matches!(cfg, StateConfig::File { .. }));
+    let cfg: StateConfig = serde_json::from_value(serde_json::json!({ "backend": "dynamodb", "table": "hockey-state" })).unwrap();
+    assert!(matches!(cfg, StateConfig::Dynamodb { ref table } if table == "hockey-state"));
+}
+
+#[tokio::test]
+async fn read_only_store_reads_but_never_writes() {
+    let store = common::temp_store("state");
+    store.put("things/11007", &"kept".to_string()).await.unwrap();
+
+    let read_only = store.clone().read_only();
+    let loaded: Option<String> = read_only.get("things/11007").await.unwrap();
+    assert_eq!(loaded.as_deref(), Some("kept"));
+    read_only.put("things/11007", &"changed".to_string()).await.unwrap();
+    read_only.put_expiring("things/other", &"new".to_string(), Utc::now()).await.unwrap();
+    read_only.delete("things/11007").await.unwrap();
+
+    let loaded: Option<String> = store.get("things/11007").await.unwrap();
+    assert_eq!(loaded.as_deref(), Some("kept"));
+    let other: Option<String> = store.get("things/other").await.unwrap();
+    assert!(other.is_none());
+}
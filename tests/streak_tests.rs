@@ -0,0 +1,25 @@
+use hockey_reminder_lambda_rust::streak::{current_streak, streak_line, GameResult, Streak, StreakConfig};
+
+use GameResult::{Loss, Tie, Win};
+
+#[test]
+fn current_streak_counts_matching_results_from_the_latest_game() {
+    assert_eq!(current_streak(&[]), None);
+    assert_eq!(current_streak(&[Loss, Win, Win, Win]), Some(Streak { result: Win, length: 3 }));
+    assert_eq!(current_streak(&[Win, Tie]), Some(Streak { result: Tie, length: 1 }));
+}
+
+#[test]
+fn streak_line_respects_minimum_length_and_losing_streak_setting() {
+    let config = StreakConfig::default();
+    assert_eq!(streak_line(current_streak(&[Win, Win, Win]), &config).as_deref(), Some(":fire: Won 3 straight"));
+    assert_eq!(streak_line(current_streak(&[Loss, Win]), &config), None);
+    assert_eq!(
+        streak_line(current_streak(&[Loss]), &StreakConfig { min_length: 1, hide_losing: false }).as_deref(),
+        Some(":chart_with_downwards_trend: Lost the last game")
+    );
+
+    let morale = StreakConfig { hide_losing: true, ..StreakConfig::default() };
+    assert_eq!(streak_line(current_streak(&[Loss, Loss]), &morale), None);
+    assert_eq!(streak_line(current_streak(&[Tie, Tie]), &morale).as_deref(), Some(":handshake: Tied 2 straight"));
+}
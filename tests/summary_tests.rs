@@ -0,0 +1,98 @@
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::handler::Workflow;
+use hockey_reminder_lambda_rust::sources::FetchReport;
+use hockey_reminder_lambda_rust::destination::DeliveryReport;
+use hockey_reminder_lambda_rust::summary::{format_error_report, format_run_summary, Outcome, RunSummary, WorkflowReport};
+
+#[test]
+fn run_summary_lists_each_workflow_and_fetch() {
+    let reports = vec![
+        WorkflowReport::new(Workflow::Daysmart, Outcome::Done, "DaySmart message posted"),
+        WorkflowReport::new(Workflow::DropIn, Outcome::Skipped, "Drop-in: not configured (skipped)"),
+        WorkflowReport::new(Workflow::Ical, Outcome::Failed, "BenchApp post failed: HTTP 500"),
+    ];
+    let fetches = vec![
+        FetchReport { source: "daysmart".to_string(), duration_ms: 412, ok: true, quarantined: Vec::new() },
+        FetchReport { source: "ical".to_string(), duration_ms: 1203, ok: false, quarantined: Vec::new() },
+    ];
+    let text = format_run_summary(&RunSummary {
+        team_id: "11007",
+        now: Utc.with_ymd_and_hms(2025, 9, 27, 15, 0, 0).unwrap(),
+        timezone: chrono_tz::America::Los_Angeles,
+        test_mode: false,
+        games_found: Some(2),
+        days_ahead: 5,
+        reports: &reports,
+        fetches: &fetches,
+        warnings: &[],
+    });
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], ":bar_chart: Run summary for team 11007 (Sat Sep 27, 8:00 AM PDT)");
+    assert_eq!(lines[1], "Games found: 2 in the next 5 days");
+    assert_eq!(lines[2], ":white_check_mark: daysmart: DaySmart message posted");
+    assert_eq!(lines[3], ":fast_forward: drop_in: Drop-in: not configured (skipped)");
+    assert_eq!(lines[4], ":x: ical: BenchApp post failed: HTTP 500");
+    assert_eq!(lines[5], "Fetches: daysmart 412 ms, ical 1203 ms (failed)");
+}
+
+#[test]
+fn run_summary_marks_test_mode_and_empty_runs() {
+    let text = format_run_summary(&RunSummary {
+        team_id: "11007",
+        now: Utc.with_ymd_and_hms(2025, 9, 27, 15, 0, 0).unwrap(),
+        timezone: chrono_tz::America::Los_Angeles,
+        test_mode: true,
+        games_found: None,
+        days_ahead: 5,
+        reports: &[],
+        fetches: &[],
+        warnings: &[],
+    });
+    assert_eq!(text, ":bar_chart: Run summary for team 11007 (Sat Sep 27, 8:00 AM PDT) [test mode]\nNo workflows executed");
+}
+
+#[test]
+fn run_summary_ends_with_schedule_warnings() {
+    let warnings = vec!["Game 7 at Rink 1 is listed for Sun Sep 28 3:00 AM, outside its hours (06:00–23:30); not announced until DaySmart is corrected".to_string()];
+    let text = format_run_summary(&RunSummary {
+        team_id: "11007",
+        now: Utc.with_ymd_and_hms(2025, 9, 27, 15, 0, 0).unwrap(),
+        timezone: chrono_tz::America::Los_Angeles,
+        test_mode: false,
+        games_found: Some(1),
+        days_ahead: 5,
+        reports: &[],
+        fetches: &[],
+        warnings: &warnings,
+    });
+    assert_eq!(text.lines().last(), Some(format!(":warning: {}", warnings[0]).as_str()));
+}
+
+#[test]
+fn error_report_lists_failed_workflows_fetches_and_posts() {
+    let reports = vec![
+        WorkflowReport::new(Workflow::Daysmart, Outcome::Done, "DaySmart message posted"),
+        WorkflowReport::new(Workflow::Ical, Outcome::Failed, "Ical: timed out"),
+        WorkflowReport::panicked(Workflow::Scores, "bad document".to_string()),
+    ];
+    let fetches = vec![
+        FetchReport { source: "daysmart".to_string(), duration_ms: 412, ok: true, quarantined: Vec::new() },
+        FetchReport { source: "ical".to_string(), duration_ms: 3000, ok: false, quarantined: Vec::new() },
+    ];
+    let deliveries = vec![DeliveryReport {
+        workflow: Some(Workflow::Daysmart),
+        destination: "slack https://hooks.slack.com/services/REDACTED".to_string(),
+        error: Some("HTTP 404".to_string()),
+        error_info: None,
+    }];
+    let text = format_error_report("11007", &reports, &fetches, &deliveries).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], ":rotating_light: Team 11007: 4 problems in the last run");
+    assert_eq!(lines[1], "• ical: Ical: timed out");
+    assert_eq!(lines[2], "• scores: Scores: panicked: bad document");
+    assert_eq!(lines[3], "• ical fetch failed after 3000 ms");
+    assert_eq!(lines[4], "• post to slack https://hooks.slack.com/services/REDACTED failed: HTTP 404");
+
+    assert_eq!(format_error_report("11007", &reports[..1], &fetches[..1], &[]), None);
+}
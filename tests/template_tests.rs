@@ -0,0 +1,44 @@
+use hockey_reminder_lambda_rust::template;
+
+#[test]
+fn malformed_templates_are_rejected() {
+    assert!(template::validate("{{home}} vs {{visitor}}").is_ok());
+    assert!(template::validate("{{#if playoff}}unclosed").is_err());
+}
+
+fn vars(full: bool) -> template::ReminderVars {
+    let some = |text: &str| full.then(|| text.to_string());
+    template::ReminderVars {
+        header: ":hockey: Kraken Hockey League Game :goal:".to_string(),
+        date: "Sun Sep 21, 2025".to_string(),
+        time: "8:45 PM".to_string(),
+        arrive_by: some("8:15 PM"),
+        arrive_minutes_early: full.then_some(30),
+        timestamp: 1758512700,
+        arena: "Starbucks Rink 1".to_string(),
+        address: some("10601 5th Ave NE, Seattle, WA 98125"),
+        maps_url: some("https://www.google.com/maps/search/?api=1&query=x"),
+        home: "Yacht Flippers".to_string(),
+        visitor: "Seal Team Sticks".to_string(),
+        opponent: "Seal Team Sticks".to_string(),
+        opponent_decided: full,
+        jersey: "Light".to_string(),
+        locker_room: some("LR11"),
+        playoff: full,
+        practice: false,
+        notes: if full { vec!["Wear alternate jerseys".to_string()] } else { Vec::new() },
+        venue_note: some("Park in the north lot"),
+    }
+}
+
+// format_game_message relies on the built-in wordings rendering for any game
+#[test]
+fn built_in_templates_render_with_and_without_optional_values() {
+    for wording in [template::DEFAULT_REMINDER_TEMPLATE, template::DEFAULT_PRACTICE_TEMPLATE] {
+        assert!(template::validate(wording).is_ok());
+        let full = template::render(wording, &vars(true)).unwrap();
+        assert!(full.contains("Arrive by 8:15 PM") && full.contains("LR11") && full.contains("Park in the north lot"), "rendered: {}", full);
+        let bare = template::render(wording, &vars(false)).unwrap();
+        assert!(!bare.contains("Arrive by") && !bare.contains("Locker Room") && !bare.contains(":memo:"), "rendered: {}", bare);
+    }
+}
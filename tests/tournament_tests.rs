@@ -0,0 +1,70 @@
+use chrono::{TimeZone, Utc};
+
+use hockey_reminder_lambda_rust::daysmart::DaySmart;
+use hockey_reminder_lambda_rust::model::game::GameInfo;
+use hockey_reminder_lambda_rust::tournament::{self, TournamentConfig};
+
+fn game(id: i64, y: i32, mo: u32, d: u32, h: u32) -> GameInfo {
+    GameInfo {
+        id,
+        dt: Utc.with_ymd_and_hms(y, mo, d, h, 0, 0).unwrap(),
+        h_id: None,
+        v_id: None,
+        res_id: None,
+        area_id: None,
+        home_locker_res_id: None,
+        away_locker_res_id: None,
+        is_playoff: false,
+        is_practice: false,
+        score: None,
+        end: None,
+    }
+}
+
+#[test]
+fn detects_three_games_within_three_local_days() {
+    let tz = chrono_tz::America::Los_Angeles;
+    let config = TournamentConfig::default();
+    // Fri 7 PM, Sat 10 AM, Sun 1 PM Pacific, plus a game the following week
+    let games = vec![game(4, 2025, 10, 3, 2), game(1, 2025, 9, 27, 2), game(2, 2025, 9, 27, 17), game(3, 2025, 9, 28, 20)];
+    let weekend = tournament::detect(&games, &config, tz).expect("tournament");
+    assert_eq!(weekend.iter().map(|g| g.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    // Two games in the window is just a busy weekend
+    assert!(tournament::detect(&games[..3], &TournamentConfig { min_games: 3, ..config.clone() }, tz).is_none());
+    assert!(tournament::detect(&[], &config, tz).is_none());
+}
+
+#[test]
+fn formats_gaps_between_games() {
+    assert_eq!(tournament::format_gap(chrono::Duration::minutes(150)), "2h 30m");
+    assert_eq!(tournament::format_gap(chrono::Duration::hours(15)), "15h");
+    assert_eq!(tournament::format_gap(chrono::Duration::minutes(45)), "45m");
+}
+
+#[test]
+fn itinerary_lists_games_breaks_and_notes_with_combined_ics() {
+    let json = std::fs::read_to_string("tests/sample_response.json").expect("failed to read sample_response.json");
+    let ds = DaySmart::from_json(&json).expect("from_json failed");
+    let now = Utc.with_ymd_and_hms(2025, 9, 1, 0, 0, 0).unwrap();
+    // The sample only has two games a week apart; the itinerary formatting doesn't care
+    let games = ds.upcoming_games(60, now);
+    assert_eq!(games.len(), 2);
+
+    let mut config = TournamentConfig { hotel: Some("Hotel: Marriott Everett, conf #123".to_string()), ..Default::default() };
+    config.rink_notes.insert("Starbucks Rink 1".to_string(), "Park in the north lot".to_string());
+    let msg = ds.format_itinerary(&games, &config);
+    assert!(msg.starts_with(":trophy: Tournament weekend: 2 games"), "message was: {}", msg);
+    assert_eq!(msg.matches(" break").count(), 1, "message was: {}", msg);
+    assert!(msg.contains(":hotel: Hotel: Marriott Everett"), "message was: {}", msg);
+    assert!(msg.contains(":hourglass: 162h 30m break"), "message was: {}", msg);
+    assert!(msg.contains("Starbucks Rink 1: Park in the north lot"), "message was: {}", msg);
+    // A rink played at again after another one still gets its note once
+    let back_again = [games[0].clone(), games[1].clone(), games[0].clone()];
+    let msg = ds.format_itinerary(&back_again, &config);
+    assert_eq!(msg.matches("Park in the north lot").count(), 1, "message was: {}", msg);
+
+    let ics = ds.to_ics(&games);
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    assert!(ics.contains(&format!("UID:daysmart-{}", games[0].id)));
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{serve, temp_store, NO_CONTENT};
+use hockey_reminder_lambda_rust::discord::Discord;
+use hockey_reminder_lambda_rust::secrets::{SecretRef, SecretsClient};
+use hockey_reminder_lambda_rust::summary::Outcome;
+use hockey_reminder_lambda_rust::webhook_rotation;
+
+const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Length: 7\r\nConnection: close\r\n\r\nUnknown";
+
+#[tokio::test]
+async fn rotation_verifies_stores_and_notifies_the_old_webhook() {
+    let store = temp_store("webhook");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 12, 0, 0).unwrap();
+    let (old_url, old_hits) = serve("/webhook", vec![NOT_FOUND]);
+    let (new_url, new_hits) = serve("/webhook", vec![NO_CONTENT]);
+    let new_hook = SecretRef::Parameter("/hockey/rotation-test".to_string());
+
+    let (outcome, detail) = webhook_rotation::rotate(&store, "11007", &Discord::new(old_url.clone()), &Discord::new(new_url.clone()), &new_hook, now).await;
+    // A revoked old webhook doesn't stop the cutover
+    assert_eq!(outcome, Outcome::Done, "detail was: {}", detail);
+    assert!(detail.contains("old channel not reachable"), "detail was: {}", detail);
+    assert_eq!((old_hits.load(std::sync::atomic::Ordering::SeqCst), new_hits.load(std::sync::atomic::Ordering::SeqCst)), (1, 1));
+
+    // Only the reference is stored, never the URL
+    let stored = webhook_rotation::stored(&store, "11007").await.unwrap().expect("rotation stored");
+    assert_eq!((&stored.new_hook, stored.rotated_at), (&new_hook, now));
+    let raw = std::fs::read_dir(store.dir()).unwrap().map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap_or_default()).collect::<String>();
+    assert!(!raw.contains(&new_url) && !raw.contains(&old_url), "state was: {}", raw);
+
+    let parameter = format!(r#"{{"Parameter": {{"Name": "/hockey/rotation-test", "Value": "{}"}}}}"#, new_url);
+    let (secrets_url, _) = serve("", vec![common::json_response(&parameter)]);
+    let secrets = SecretsClient::new(secrets_url, String::new());
+    assert_eq!(webhook_rotation::effective_hook_url(&store, &secrets, "11007", &old_url).await, new_url);
+    assert_eq!(webhook_rotation::effective_hook_url(&store, &secrets, "other", &old_url).await, old_url);
+    // Changing discord_hook_url after the rotation wins over it
+    assert_eq!(webhook_rotation::effective_hook_url(&store, &secrets, "11007", "https://discord.test/newer").await, "https://discord.test/newer");
+}
+
+#[test]
+fn rotation_takes_the_new_webhook_as_a_secret_or_parameter() {
+    use hockey_reminder_lambda_rust::webhook_rotation::WebhookRotationConfig;
+
+    let config: WebhookRotationConfig = serde_json::from_str(r#"{ "new_hook_parameter": "/hockey/new_hook" }"#).unwrap();
+    assert!(config.validate().is_ok());
+    assert_eq!(config.new_hook(), Some(SecretRef::Parameter("/hockey/new_hook".to_string())));
+    let neither: WebhookRotationConfig = serde_json::from_str("{}").unwrap();
+    assert!(neither.validate().is_err());
+}
+
+#[tokio::test]
+async fn rotation_keeps_the_old_webhook_when_the_new_one_fails() {
+    let store = temp_store("webhook-fail");
+    let now = Utc.with_ymd_and_hms(2025, 9, 20, 12, 0, 0).unwrap();
+    let (new_url, _) = serve("/webhook", vec![NOT_FOUND]);
+    let old = Discord::new("http://127.0.0.1:9/old".to_string());
+
+    let new_hook = SecretRef::Parameter("/hockey/new_hook".to_string());
+    let (outcome, detail) = webhook_rotation::rotate(&store, "11007", &old, &Discord::new(new_url), &new_hook, now).await;
+    assert_eq!(outcome, Outcome::Failed, "detail was: {}", detail);
+    assert_eq!(webhook_rotation::stored(&store, "11007").await.unwrap(), None);
+
+    let (outcome, _) = webhook_rotation::rotate(&store, "11007", &old, &old.clone(), &new_hook, now).await;
+    assert_eq!(outcome, Outcome::Skipped);
+}
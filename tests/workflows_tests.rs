@@ -0,0 +1,379 @@
+mod common;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use hockey_reminder_lambda_rust::clock::SequentialIds;
+use hockey_reminder_lambda_rust::daysmart::{self, DaySmart};
+use hockey_reminder_lambda_rust::dedup;
+use hockey_reminder_lambda_rust::destination::{DeliveryLog, Publisher};
+use hockey_reminder_lambda_rust::discord::Discord;
+use hockey_reminder_lambda_rust::dropin::DropInSlot;
+use hockey_reminder_lambda_rust::handler::{self, Request};
+use hockey_reminder_lambda_rust::notifier::Notifier;
+use hockey_reminder_lambda_rust::sources::Sources;
+use hockey_reminder_lambda_rust::summary::Outcome;
+use hockey_reminder_lambda_rust::template;
+use hockey_reminder_lambda_rust::workflows::{self, drop_in::Sessions, reminder::Schedule, Context, DropIn, Halt, Reminder, StepFuture, Workflow};
+
+fn context(request: serde_json::Value) -> Arc<Context> {
+    let request: Request = serde_json::from_value(request).unwrap();
+    Arc::new(Context {
+        request: Arc::new(request),
+        sources: Sources::default(),
+        state: None,
+        now: Utc.with_ymd_and_hms(2025, 9, 20, 15, 0, 0).unwrap(),
+        timezone: None,
+        local_tz: chrono_tz::America::Los_Angeles,
+        http_policy: Default::default(),
+        ids: Arc::new(SequentialIds::default()),
+        hook_url: "http://127.0.0.1:9/unused".to_string(),
+        deadline: None,
+    })
+}
+
+fn base_request() -> serde_json::Value {
+    serde_json::json!({ "mode": "test", "discord_hook_url": "http://127.0.0.1:9/unused", "team_id": "123", "company": "acme" })
+}
+
+/// Records posts without sending them.
+fn publisher() -> (Publisher, DeliveryLog) {
+    let log = DeliveryLog::default();
+    let publisher = Publisher::new(Some(Arc::new(Discord::new("http://127.0.0.1:9/unused".to_string())))).with_log(log.clone()).dry_run();
+    (publisher, log)
+}
+
+/// A workflow whose steps halt where the request's `notes` says.
+struct Mock {
+    halt_at: String,
+}
+
+impl Workflow for Mock {
+    type Input = u32;
+    type Output = String;
+
+    const KIND: handler::Workflow = handler::Workflow::Scores;
+
+    fn prepare(ctx: &Context) -> Result<Self, Halt> {
+        let halt_at = ctx.request.notes.clone().unwrap_or_default();
+        if halt_at == "prepare" {
+            return Err(Halt::new(Outcome::Skipped, "Mock: not configured (skipped)"));
+        }
+        Ok(Mock { halt_at })
+    }
+
+    fn fetch<'a>(&'a self, _ctx: &'a Context) -> StepFuture<'a, u32> {
+        Box::pin(async move {
+            match self.halt_at.as_str() {
+                "fetch" => Err(Halt::new(Outcome::Failed, "Mock fetch failed")),
+                _ => Ok(3),
+            }
+        })
+    }
+
+    fn render(&self, _ctx: &Context, games: u32) -> Result<String, Halt> {
+        match self.halt_at.as_str() {
+            "render" => Err(Halt::new(Outcome::Skipped, "Mock: nothing to say (skipped)")),
+            _ => Ok(format!("{} games", games)),
+        }
+    }
+
+    fn deliver<'a>(&'a self, _ctx: &'a Context, publisher: &'a Publisher, message: String) -> StepFuture<'a, String> {
+        Box::pin(async move {
+            publisher.post(&message).await.map_err(|e| Halt::failed(format!("Mock post failed: {}", e), e))?;
+            Ok("Mock posted".to_string())
+        })
+    }
+}
+
+async fn run_mock(halt_at: &str) -> Result<(Outcome, String), Halt> {
+    let mut request = base_request();
+    request["notes"] = serde_json::json!(halt_at);
+    let (publisher, _) = publisher();
+    let report = workflows::launch::<Mock>(context(request), publisher)?.await;
+    assert_eq!(report.workflow, handler::Workflow::Scores);
+    Ok((report.outcome, report.detail))
+}
+
+#[tokio::test]
+async fn engine_runs_the_steps_in_order_and_reports_where_they_halt() {
+    assert_eq!(run_mock("").await.unwrap(), (Outcome::Done, "Mock posted".to_string()));
+    assert_eq!(run_mock("prepare").await.unwrap_err(), Halt::new(Outcome::Skipped, "Mock: not configured (skipped)"));
+    assert_eq!(run_mock("fetch").await.unwrap(), (Outcome::Failed, "Mock fetch failed".to_string()));
+    assert_eq!(run_mock("render").await.unwrap(), (Outcome::Skipped, "Mock: nothing to say (skipped)".to_string()));
+}
+
+#[tokio::test]
+async fn deliver_posts_the_rendered_message() {
+    let (publisher, log) = publisher();
+    let run = workflows::launch::<Mock>(context(base_request()), publisher).unwrap();
+    run.await;
+    let posts = log.lock().unwrap().clone();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0].content, "3 games");
+}
+
+#[test]
+fn unconfigured_workflows_halt_in_prepare() {
+    let ctx = context(base_request());
+    for kind in [
+        handler::Workflow::Canary,
+        handler::Workflow::IcsPublish,
+        handler::Workflow::DropIn,
+        handler::Workflow::StateCleanup,
+        handler::Workflow::Ical,
+        handler::Workflow::Feed,
+        handler::Workflow::FeedRotate,
+        handler::Workflow::BenchappApi,
+        handler::Workflow::WebhookRotate,
+        handler::Workflow::Explain,
+    ] {
+        let (publisher, _) = publisher();
+        match workflows::start(kind, &ctx, publisher) {
+            Err(halt) => assert_eq!(halt.outcome, Outcome::Skipped, "{:?}: {}", kind, halt.detail),
+            Ok(_) => panic!("{:?} should halt without its settings", kind),
+        }
+    }
+    // The reminder needs nothing beyond the team
+    assert!(workflows::start(handler::Workflow::Daysmart, &ctx, publisher().0).is_ok());
+}
+
+/// Never finishes, or panics, as the request's `notes` says.
+struct Stuck;
+
+impl Workflow for Stuck {
+    type Input = ();
+    type Output = ();
+
+    const KIND: handler::Workflow = handler::Workflow::Scores;
+
+    fn prepare(_ctx: &Context) -> Result<Self, Halt> {
+        Ok(Stuck)
+    }
+
+    fn fetch<'a>(&'a self, ctx: &'a Context) -> StepFuture<'a, ()> {
+        Box::pin(async move {
+            if ctx.request.notes.as_deref() == Some("panic") {
+                panic!("bad document");
+            }
+            futures::future::pending().await
+        })
+    }
+
+    fn render(&self, _ctx: &Context, _input: ()) -> Result<(), Halt> {
+        Ok(())
+    }
+
+    fn deliver<'a>(&'a self, _ctx: &'a Context, _publisher: &'a Publisher, _output: ()) -> StepFuture<'a, String> {
+        Box::pin(async { Ok("Stuck finished".to_string()) })
+    }
+}
+
+#[tokio::test]
+async fn runner_reports_halts_first_then_runs_in_order() {
+    let mut request = base_request();
+    request["explain_game_id"] = serde_json::json!(42);
+    let ctx = context(request);
+    let planned = vec![(handler::Workflow::Explain, publisher().0), (handler::Workflow::Canary, publisher().0)];
+    let reports = workflows::run_all(&ctx, planned).await;
+    let kinds: Vec<_> = reports.iter().map(|r| (r.workflow, r.outcome)).collect();
+    assert_eq!(kinds, vec![(handler::Workflow::Canary, Outcome::Skipped), (handler::Workflow::Explain, Outcome::Skipped)]);
+    assert_eq!(reports[1].detail, "DaySmart: not fetched (skipped)");
+}
+
+#[tokio::test]
+async fn supervised_runs_report_timeouts_and_panics_as_failures() {
+    let deadline = Some(tokio::time::Instant::now() + std::time::Duration::from_millis(50));
+    let run = workflows::launch::<Stuck>(context(base_request()), publisher().0).unwrap();
+    let timed_out = workflows::supervise(handler::Workflow::Scores, run, deadline).await;
+    assert_eq!((timed_out.outcome, timed_out.detail.as_str()), (Outcome::Failed, "Scores: timed out"));
+    assert!(timed_out.duration_ms >= 50, "duration {} ms", timed_out.duration_ms);
+
+    let mut request = base_request();
+    request["notes"] = serde_json::json!("panic");
+    let run = workflows::launch::<Stuck>(context(request), publisher().0).unwrap();
+    let panicked = workflows::supervise(handler::Workflow::Scores, run, None).await;
+    assert_eq!((panicked.outcome, panicked.detail.as_str()), (Outcome::Failed, "Scores: panicked: bad document"));
+    assert_eq!(panicked.panic.as_deref(), Some("bad document"));
+}
+
+#[test]
+fn drop_in_renders_only_unseen_sessions() {
+    let mut request = base_request();
+    request["dropin"] = serde_json::json!({});
+    let ctx = context(request);
+    let drop_in = DropIn::prepare(&ctx).unwrap();
+    let slot = |id: &str, hour| DropInSlot {
+        id: id.to_string(),
+        dt: Utc.with_ymd_and_hms(2025, 9, 22, hour, 0, 0).unwrap(),
+        name: Some("Stick & Puck".to_string()),
+        rink: None,
+    };
+    let seen: BTreeSet<String> = ["1".to_string()].into();
+
+    let announcement = drop_in.render(&ctx, Sessions { matching: vec![slot("1", 3), slot("2", 4)], seen: seen.clone() }).unwrap();
+    assert_eq!(announcement.count, 1);
+    assert_eq!(announcement.now_seen, ["1".to_string(), "2".to_string()].into());
+
+    let nothing_new = drop_in.render(&ctx, Sessions { matching: vec![slot("1", 3)], seen }).err().unwrap();
+    assert_eq!(nothing_new.outcome, Outcome::Skipped);
+}
+
+#[test]
+fn reminder_renders_a_thread_only_for_a_single_game() {
+    let ds = Arc::new(DaySmart::from_json(&std::fs::read_to_string("tests/sample_response.json").unwrap()).unwrap());
+    let render = |extra: serde_json::Value| {
+        let mut request = base_request();
+        request["game_threads"] = serde_json::json!(true);
+        request["days_ahead"] = serde_json::json!(30);
+        request.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        let schedule = Schedule { day_smart: ds.clone(), pending: None, names: Default::default() };
+        Reminder.render(&context(request), schedule).ok().unwrap()
+    };
+
+    let single = render(serde_json::json!({}));
+    assert_eq!(single.parts.len(), 1);
+    assert!(single.single_game);
+    assert!(single.thread_name.is_some());
+    assert_eq!(single.filename, "games.csv");
+
+    let digest = render(serde_json::json!({ "announce_all": true }));
+    assert!(digest.parts.len() > 1, "{} parts", digest.parts.len());
+    assert!(!digest.single_game);
+    assert_eq!(digest.thread_name, None);
+}
+
+#[test]
+fn announce_all_digests_every_game_in_the_window() {
+    let ds = Arc::new(DaySmart::from_json(&std::fs::read_to_string("tests/sample_response.json").unwrap()).unwrap());
+    let digest = |days_ahead: i64| {
+        let mut request = base_request();
+        request["announce_all"] = serde_json::json!(true);
+        request["days_ahead"] = serde_json::json!(days_ahead);
+        let schedule = Schedule { day_smart: ds.clone(), pending: None, names: Default::default() };
+        let draft = Reminder.render(&context(request), schedule).ok().unwrap();
+        daysmart::games_digest(draft.parts.into_iter().map(|(_, message)| message).collect())
+    };
+
+    // 9/21 and 9/28 are both within ten days
+    let both = digest(10).expect("expected games within window");
+    assert!(both.starts_with(":calendar_spiral: 2 upcoming games\n\n"), "digest was: {}", both);
+    assert_eq!(both.matches(":shirt:").count(), 2, "digest was: {}", both);
+    let first = both.find("Sun Sep 21").expect("9/21 game listed");
+    let second = both.find("Sun Sep 28").expect("9/28 game listed");
+    assert!(first < second, "games should be in order: {}", both);
+
+    // A single game reads like the next-game reminder
+    assert_eq!(digest(3), ds.get_next_game_message(3, Utc.with_ymd_and_hms(2025, 9, 20, 15, 0, 0).unwrap()));
+}
+
+#[test]
+fn reminder_words_games_by_the_message_template() {
+    let ds = Arc::new(DaySmart::from_json(&std::fs::read_to_string("tests/sample_response.json").unwrap()).unwrap());
+    let render = |message_template: Option<&str>| {
+        let mut request = base_request();
+        request["days_ahead"] = serde_json::json!(5);
+        request["message_template"] = serde_json::json!(message_template);
+        let schedule = Schedule { day_smart: ds.clone(), pending: None, names: Default::default() };
+        Reminder.render(&context(request), schedule).ok().unwrap().parts
+    };
+
+    let custom = render(Some("{{opponent}} & friends at {{arena}}. Bring {{jersey}} jerseys."));
+    // No HTML escaping: the text goes to Discord as-is
+    assert_eq!(custom[0].1, "Seal Team Sticks & friends at Starbucks Rink 1. Bring Light jerseys.");
+
+    let built_in = render(None);
+    assert_eq!(render(Some(template::DEFAULT_REMINDER_TEMPLATE))[0].1, built_in[0].1);
+    assert_eq!(Some(built_in[0].1.clone()), ds.get_next_game_message(5, Utc.with_ymd_and_hms(2025, 9, 20, 15, 0, 0).unwrap()));
+}
+
+#[tokio::test]
+async fn later_reminder_offsets_post_into_the_game_thread() {
+    let store = common::temp_store("game-thread");
+    let (url, received) = common::capture(
+        "/webhook",
+        vec![common::json_response(r#"{"id":"555","channel_id":"555"}"#), common::json_response(r#"{"id":"556","channel_id":"555"}"#)],
+    );
+    let mut request = base_request();
+    request["game_threads"] = serde_json::json!(true);
+    request["reminder_offsets_hours"] = serde_json::json!([72, 24]);
+    let request: Request = serde_json::from_value(request).unwrap();
+    let ds = Arc::new(DaySmart::from_json(&std::fs::read_to_string("tests/sample_response.json").unwrap()).unwrap());
+    // The Sun Sep 28 game starts at 22:15 UTC
+    let run = |now| {
+        let ctx = Context {
+            request: Arc::new(request.clone()),
+            sources: Sources::default(),
+            state: Some(store.clone()),
+            now,
+            timezone: None,
+            local_tz: chrono_tz::America::Los_Angeles,
+            http_policy: Default::default(),
+            ids: Arc::new(SequentialIds::default()),
+            hook_url: url.clone(),
+            deadline: None,
+        };
+        let publisher = Publisher::new(Some(Arc::new(Discord::new(url.clone()))));
+        let ds = ds.clone();
+        async move {
+            let draft = Reminder.render(&ctx, Schedule { day_smart: ds, pending: None, names: Default::default() }).ok().unwrap();
+            Reminder.deliver(&ctx, &publisher, draft).await.unwrap()
+        }
+    };
+
+    run(Utc.with_ymd_and_hms(2025, 9, 26, 0, 0, 0).unwrap()).await;
+    let first = received.recv().unwrap();
+    assert!(first.starts_with("POST /webhook?wait=true HTTP/1.1"), "request was: {}", first);
+    assert!(first.contains("Sun Sep 28 vs Blackbirds"), "the first reminder opens the thread");
+
+    run(Utc.with_ymd_and_hms(2025, 9, 28, 0, 0, 0).unwrap()).await;
+    let second = received.recv().unwrap();
+    assert!(second.starts_with("POST /webhook?wait=true&thread_id=555 HTTP/1.1"), "request was: {}", second);
+    assert!(!second.contains("thread_name"), "no second thread for the game");
+
+    let game_id = ds.games_on("2025-09-28".parse().unwrap())[0].id.to_string();
+    let record = dedup::posted(&store, "123", &game_id).await.unwrap().unwrap();
+    assert_eq!(record.message_id.as_deref(), Some("556"));
+    assert_eq!(record.first_message().map(|message| message.id).as_deref(), Some("555"));
+}
+
+#[tokio::test]
+async fn a_failed_extra_destination_does_not_repeat_the_main_post() {
+    let store = common::temp_store("extra-destination");
+    let (primary, received) = common::capture("/webhook", vec![common::json_response(r#"{"id":"555"}"#)]);
+    let bad_request = "HTTP/1.1 400 Bad Request\r\nContent-Length: 3\r\nConnection: close\r\n\r\nbad";
+    let (extra, _) = common::serve("/webhook", vec![bad_request, bad_request]);
+    let request: Request = serde_json::from_value(base_request()).unwrap();
+    let ds = Arc::new(DaySmart::from_json(&std::fs::read_to_string("tests/sample_response.json").unwrap()).unwrap());
+    let run = || {
+        let ctx = Context {
+            request: Arc::new(request.clone()),
+            sources: Sources::default(),
+            state: Some(store.clone()),
+            now: Utc.with_ymd_and_hms(2025, 9, 20, 15, 0, 0).unwrap(),
+            timezone: None,
+            local_tz: chrono_tz::America::Los_Angeles,
+            http_policy: Default::default(),
+            ids: Arc::new(SequentialIds::default()),
+            hook_url: primary.clone(),
+            deadline: None,
+        };
+        let publisher = Publisher::new(Some(Arc::new(Discord::new(primary.clone()))))
+            .with_notifiers([Arc::new(Discord::new(extra.clone())) as Arc<dyn Notifier>]);
+        let ds = ds.clone();
+        async move {
+            let draft = Reminder.render(&ctx, Schedule { day_smart: ds, pending: None, names: Default::default() }).ok().unwrap();
+            Reminder.deliver(&ctx, &publisher, draft).await
+        }
+    };
+
+    let first = run().await.expect_err("the extra destination's failure is reported");
+    assert_eq!(first.outcome, Outcome::Failed);
+    assert!(first.detail.starts_with("DaySmart message posted; other destinations failed:"), "detail was: {}", first.detail);
+    assert!(dedup::pending(&store, "123").await.unwrap().is_none(), "nothing queued to re-announce");
+
+    let second = run().await.expect_err("an unchanged reminder is skipped");
+    assert_eq!((second.outcome, second.detail.as_str()), (Outcome::Skipped, "DaySmart: reminder already posted (skipped)"));
+    assert_eq!(received.try_iter().count(), 1, "the main webhook got the reminder once");
+}
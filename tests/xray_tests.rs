@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::time::Duration;
+
+use hockey_reminder_lambda_rust::clock::SequentialIds;
+use hockey_reminder_lambda_rust::xray::{self, TraceHeader, XrayLayer};
+use tracing::{error, info_span};
+use tracing_subscriber::layer::SubscriberExt;
+
+const HEADER: &str = "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1";
+
+fn daemon() -> UdpSocket {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.set_read_timeout(Some(Duration::from_millis(200))).unwrap();
+    socket
+}
+
+fn receive(socket: &UdpSocket) -> Option<serde_json::Value> {
+    let mut buf = [0u8; 8192];
+    let len = socket.recv(&mut buf).ok()?;
+    let packet = std::str::from_utf8(&buf[..len]).unwrap();
+    let (header, document) = packet.split_once('\n').unwrap();
+    assert_eq!(header, r#"{"format": "json", "version": 1}"#);
+    Some(serde_json::from_str(document).unwrap())
+}
+
+#[test]
+fn trace_header_is_parsed() {
+    let header = TraceHeader::parse(HEADER).unwrap();
+    assert_eq!(header.root, "1-5759e988-bd862e3fe1be46a994272793");
+    assert_eq!(header.parent.as_deref(), Some("53995c3f42cd8ad8"));
+    assert!(header.sampled);
+    assert!(!TraceHeader::parse("Root=1-abc;Sampled=0").unwrap().sampled);
+    assert_eq!(TraceHeader::parse("Parent=53995c3f42cd8ad8"), None);
+}
+
+#[test]
+fn nested_spans_are_sent_as_subsegments_with_their_failures() {
+    let socket = daemon();
+    let layer = XrayLayer::new(socket.local_addr().unwrap().to_string()).with_trace_header(HEADER).with_ids(Arc::new(SequentialIds::starting_at(1)));
+    tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+        let fetch = info_span!("daysmart_fetch");
+        let _entered = fetch.enter();
+        let get = info_span!("get_text", url = "https://example.invalid/events", status = tracing::field::Empty);
+        get.in_scope(|| {
+            get.record("status", 503u64);
+            error!(error = "HTTP 503: unavailable", "GET failed");
+        });
+    });
+
+    let get = receive(&socket).unwrap();
+    let fetch = receive(&socket).unwrap();
+    assert_eq!((fetch["name"].as_str(), fetch["id"].as_str()), (Some("daysmart_fetch"), Some("0000000000000001")));
+    assert_eq!(fetch["parent_id"], "53995c3f42cd8ad8");
+    assert_eq!(fetch["trace_id"], "1-5759e988-bd862e3fe1be46a994272793");
+    assert!(fetch.get("fault").is_none());
+
+    assert_eq!((get["name"].as_str(), get["parent_id"].as_str()), (Some("get_text"), Some("0000000000000001")));
+    assert_eq!(get["http"]["response"]["status"], 503);
+    assert_eq!(get["metadata"]["fields"]["url"], "https://example.invalid/events");
+    assert_eq!(get["fault"], true);
+    assert_eq!(get["cause"]["exceptions"][0]["message"], "HTTP 503: unavailable");
+    assert!(get["end_time"].as_f64().unwrap() >= get["start_time"].as_f64().unwrap());
+}
+
+#[test]
+fn unsampled_traces_are_not_sent() {
+    let socket = daemon();
+    let layer = XrayLayer::new(socket.local_addr().unwrap().to_string()).with_trace_header("Root=1-5759e988-bd862e3fe1be46a994272793;Sampled=0");
+    tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+        let _entered = info_span!("discord_post").entered();
+    });
+    assert!(receive(&socket).is_none());
+}
+
+#[test]
+fn client_errors_and_throttles_are_not_faults() {
+    let trace = TraceHeader::parse(HEADER).unwrap();
+    let status = |code: u64| BTreeMap::from([("status".to_string(), serde_json::json!(code))]);
+    let rejected = xray::subsegment("discord_post", "2", &trace, None, (1.0, 2.0), &status(404), Some("HTTP 404"));
+    assert_eq!((rejected.get("error"), rejected.get("fault")), (Some(&serde_json::json!(true)), None));
+    let limited = xray::subsegment("discord_post", "2", &trace, None, (1.0, 2.0), &status(429), Some("rate limited"));
+    assert_eq!(limited["throttle"], true);
+}